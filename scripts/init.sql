@@ -1,8 +1,10 @@
 -- Database initialization script for Docker
 -- This script creates the initial database schema
 
--- Enable pgvector extension for embeddings
-CREATE EXTENSION IF NOT EXISTS vector;
+-- pgvector is optional: when the server ships it, the extension and the
+-- cards.answer_embedding column are created below; otherwise the schema is
+-- created without them and the app runs with answer embeddings disabled
+-- (set REQUIRE_PGVECTOR=true to refuse to start in that case)
 
 -- Create ENUM for card state
 CREATE TYPE card_state AS ENUM ('new', 'learning', 'review', 'relearning');
@@ -13,6 +15,10 @@ CREATE TABLE IF NOT EXISTS users (
     email VARCHAR(255) NOT NULL UNIQUE,
     name VARCHAR(255) NOT NULL,
     password_hash VARCHAR(255),
+    utc_offset_minutes INTEGER NOT NULL DEFAULT 0,
+    day_cutoff_hour INTEGER NOT NULL DEFAULT 4,
+    daily_review_goal INTEGER,
+    ai_validation_enabled BOOLEAN NOT NULL DEFAULT TRUE,
     created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
 );
@@ -25,6 +31,17 @@ CREATE TABLE IF NOT EXISTS decks (
     user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
     name VARCHAR(255) NOT NULL,
     description TEXT,
+    scheduler_overrides JSONB NOT NULL DEFAULT '{}'::jsonb,
+    fsrs_weights REAL[],
+    default_tags TEXT[] NOT NULL DEFAULT '{}',
+    total_new_limit INTEGER,
+    new_cards_per_day INTEGER,
+    ignored_answer_prefixes TEXT[] NOT NULL DEFAULT '{}',
+    answer_normalization JSONB,
+    language TEXT,
+    rtl BOOLEAN NOT NULL DEFAULT FALSE,
+    review_mode TEXT NOT NULL DEFAULT 'typed',
+    deleted_at TIMESTAMP WITH TIME ZONE,
     created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
 );
@@ -38,7 +55,7 @@ CREATE TABLE IF NOT EXISTS cards (
     deck_id UUID REFERENCES decks(id) ON DELETE SET NULL,
     question TEXT NOT NULL,
     answer TEXT NOT NULL,
-    answer_embedding vector(1536),
+    embedding_status TEXT NOT NULL DEFAULT 'none',
     fsrs_state JSONB NOT NULL DEFAULT '{
         "stability": 0.0,
         "difficulty": 0.0,
@@ -49,13 +66,35 @@ CREATE TABLE IF NOT EXISTS cards (
         "state": "New",
         "last_review": null
     }'::jsonb,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    required_keywords TEXT[] NOT NULL DEFAULT '{}',
+    answer_type TEXT NOT NULL DEFAULT 'text',
+    suspended BOOLEAN NOT NULL DEFAULT FALSE,
+    not_before TIMESTAMP WITH TIME ZONE,
+    position INTEGER NOT NULL DEFAULT 0,
     created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
 );
 
+-- Answer embeddings, only where pgvector is available
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM pg_available_extensions WHERE name = 'vector') THEN
+        CREATE EXTENSION IF NOT EXISTS vector;
+        ALTER TABLE cards ADD COLUMN IF NOT EXISTS answer_embedding vector(1536);
+    ELSE
+        RAISE NOTICE 'pgvector is not available: cards.answer_embedding is not created';
+    END IF;
+END
+$$;
+
 CREATE INDEX IF NOT EXISTS idx_cards_user_id ON cards(user_id);
 CREATE INDEX IF NOT EXISTS idx_cards_deck_id ON cards(deck_id);
 CREATE INDEX IF NOT EXISTS idx_cards_fsrs_state ON cards USING GIN (fsrs_state);
+CREATE INDEX IF NOT EXISTS idx_cards_tags ON cards USING GIN (tags);
+CREATE INDEX IF NOT EXISTS idx_cards_fsrs_difficulty ON cards (((fsrs_state ->> 'difficulty')::real) DESC NULLS LAST);
+CREATE INDEX IF NOT EXISTS idx_cards_deck_created ON cards (deck_id, created_at, position, id);
+CREATE INDEX IF NOT EXISTS idx_cards_user_created ON cards (user_id, created_at, position, id);
 
 -- Create reviews table
 CREATE TABLE IF NOT EXISTS reviews (
@@ -78,6 +117,7 @@ CREATE TABLE IF NOT EXISTS review_logs (
     ai_score FLOAT4,
     fsrs_rating INT NOT NULL CHECK (fsrs_rating >= 1 AND fsrs_rating <= 4),
     validation_method VARCHAR(50) NOT NULL,
+    explanation TEXT,
     created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
 );
 
@@ -112,3 +152,16 @@ CREATE TABLE IF NOT EXISTS deck_stats (
 CREATE INDEX IF NOT EXISTS idx_user_stats_user_id ON user_stats(user_id);
 CREATE INDEX IF NOT EXISTS idx_deck_stats_deck_id ON deck_stats(deck_id);
 CREATE INDEX IF NOT EXISTS idx_deck_stats_user_id ON deck_stats(user_id);
+
+-- Domain events whose handler failed every retry (replayable by admins)
+CREATE TABLE IF NOT EXISTS dead_letter_events (
+    id UUID PRIMARY KEY,
+    handler VARCHAR(255) NOT NULL,
+    event JSONB NOT NULL,
+    error TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    replayed_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_dead_letter_events_pending ON dead_letter_events(created_at DESC) WHERE replayed_at IS NULL;
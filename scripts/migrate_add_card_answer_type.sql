@@ -0,0 +1,5 @@
+-- Migration: Add the answer type (text or order-independent list) to cards
+-- Run this against existing databases initialized before list answers
+
+ALTER TABLE cards
+    ADD COLUMN IF NOT EXISTS answer_type TEXT NOT NULL DEFAULT 'text';
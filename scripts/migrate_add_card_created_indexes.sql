@@ -0,0 +1,5 @@
+-- Migration: Index the (created_at, id) order of deck and user card lists
+-- Run this against existing databases initialized before these indexes
+
+CREATE INDEX IF NOT EXISTS idx_cards_deck_created ON cards (deck_id, created_at, id);
+CREATE INDEX IF NOT EXISTS idx_cards_user_created ON cards (user_id, created_at, id);
@@ -0,0 +1,5 @@
+-- Migration: Index FSRS difficulty for `order=difficulty` card lists
+-- Run this against existing databases initialized before difficulty ordering
+
+CREATE INDEX IF NOT EXISTS idx_cards_fsrs_difficulty
+    ON cards (((fsrs_state ->> 'difficulty')::real) DESC NULLS LAST);
@@ -0,0 +1,7 @@
+-- Migration: Track answer embedding progress per card
+-- Run this against existing databases initialized before embedding_status
+
+ALTER TABLE cards
+    ADD COLUMN IF NOT EXISTS embedding_status TEXT NOT NULL DEFAULT 'none';
+
+UPDATE cards SET embedding_status = 'ready' WHERE answer_embedding IS NOT NULL;
@@ -0,0 +1,5 @@
+-- Migration: Add per-card scheduling freeze date
+-- Run this against existing databases initialized before not_before
+
+ALTER TABLE cards
+    ADD COLUMN IF NOT EXISTS not_before TIMESTAMP WITH TIME ZONE;
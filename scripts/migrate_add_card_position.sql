@@ -0,0 +1,11 @@
+-- Migration: Add the card's position within its import, so cards created in
+-- the same instant keep their file order
+-- Run this against existing databases initialized before import positions
+
+ALTER TABLE cards
+    ADD COLUMN IF NOT EXISTS position INTEGER NOT NULL DEFAULT 0;
+
+DROP INDEX IF EXISTS idx_cards_deck_created;
+DROP INDEX IF EXISTS idx_cards_user_created;
+CREATE INDEX IF NOT EXISTS idx_cards_deck_created ON cards (deck_id, created_at, position, id);
+CREATE INDEX IF NOT EXISTS idx_cards_user_created ON cards (user_id, created_at, position, id);
@@ -0,0 +1,5 @@
+-- Migration: Add required answer keywords to cards
+-- Run this against existing databases initialized before required keywords
+
+ALTER TABLE cards
+    ADD COLUMN IF NOT EXISTS required_keywords TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,5 @@
+-- Migration: Add the suspended flag to cards
+-- Run this against existing databases initialized before batch card actions
+
+ALTER TABLE cards
+    ADD COLUMN IF NOT EXISTS suspended BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,14 @@
+-- Migration: Add the dead-letter table for domain events whose handler failed every retry
+-- Run this against existing databases initialized before dead_letter_events
+
+CREATE TABLE IF NOT EXISTS dead_letter_events (
+    id UUID PRIMARY KEY,
+    handler VARCHAR(255) NOT NULL,
+    event JSONB NOT NULL,
+    error TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    replayed_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_dead_letter_events_pending ON dead_letter_events(created_at DESC) WHERE replayed_at IS NULL;
@@ -0,0 +1,5 @@
+-- Migration: Add per-deck answer normalization pipelines
+-- Run this against existing databases initialized before answer_normalization
+
+ALTER TABLE decks
+    ADD COLUMN IF NOT EXISTS answer_normalization JSONB;
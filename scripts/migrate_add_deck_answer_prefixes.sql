@@ -0,0 +1,5 @@
+-- Migration: Add per-deck filler prefixes ignored in answers
+-- Run this against existing databases initialized before answer prefixes
+
+ALTER TABLE decks
+    ADD COLUMN IF NOT EXISTS ignored_answer_prefixes TEXT[] NOT NULL DEFAULT '{}';
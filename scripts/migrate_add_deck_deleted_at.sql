@@ -0,0 +1,5 @@
+-- Migration: Add deck trash (soft delete)
+-- Run this against existing databases initialized before deleted_at
+
+ALTER TABLE decks
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,5 @@
+-- Migration: Add optional per-deck FSRS weights
+-- Run this against existing databases initialized before custom FSRS weights
+
+ALTER TABLE decks
+    ADD COLUMN IF NOT EXISTS fsrs_weights REAL[];
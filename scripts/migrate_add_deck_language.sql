@@ -0,0 +1,6 @@
+-- Migration: Add deck language and right-to-left text direction
+-- Run this against existing databases initialized before language/rtl
+
+ALTER TABLE decks
+    ADD COLUMN IF NOT EXISTS language TEXT,
+    ADD COLUMN IF NOT EXISTS rtl BOOLEAN NOT NULL DEFAULT FALSE;
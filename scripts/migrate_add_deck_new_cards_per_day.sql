@@ -0,0 +1,5 @@
+-- Migration: Add a per-deck cap on the number of cards introduced per day
+-- Run this against existing databases initialized before new_cards_per_day
+
+ALTER TABLE decks
+    ADD COLUMN IF NOT EXISTS new_cards_per_day INTEGER;
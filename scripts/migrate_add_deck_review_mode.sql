@@ -0,0 +1,5 @@
+-- Migration: Add per-deck review mode (typed answers or self-graded ratings)
+-- Run this against existing databases initialized before review_mode
+
+ALTER TABLE decks
+    ADD COLUMN IF NOT EXISTS review_mode TEXT NOT NULL DEFAULT 'typed';
@@ -0,0 +1,5 @@
+-- Migration: Add a per-deck cap on the number of cards ever introduced
+-- Run this against existing databases initialized before total_new_limit
+
+ALTER TABLE decks
+    ADD COLUMN IF NOT EXISTS total_new_limit INTEGER;
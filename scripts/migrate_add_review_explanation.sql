@@ -0,0 +1,5 @@
+-- Migration: Store validator feedback on review logs
+-- Run this against existing databases initialized before review explanations
+
+ALTER TABLE review_logs
+    ADD COLUMN IF NOT EXISTS explanation TEXT;
@@ -0,0 +1,5 @@
+-- Migration: Add per-deck scheduler overrides
+-- Run this against existing databases initialized before deck-level scheduler settings
+
+ALTER TABLE decks
+    ADD COLUMN IF NOT EXISTS scheduler_overrides JSONB NOT NULL DEFAULT '{}'::jsonb;
@@ -0,0 +1,10 @@
+-- Migration: Add card tags and per-deck default tags
+-- Run this against existing databases initialized before tagging
+
+ALTER TABLE cards
+    ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX IF NOT EXISTS idx_cards_tags ON cards USING GIN (tags);
+
+ALTER TABLE decks
+    ADD COLUMN IF NOT EXISTS default_tags TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,5 @@
+-- Migration: Add the per-user switch for AI answer validation
+-- Run this against existing databases initialized before the setting existed
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS ai_validation_enabled BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,5 @@
+-- Migration: Add an optional per-user daily review goal
+-- Run this against existing databases initialized before daily_review_goal
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS daily_review_goal INTEGER;
@@ -0,0 +1,6 @@
+-- Migration: Add per-user study day settings (UTC offset and day cutoff hour)
+-- Run this against existing databases initialized before day_cutoff_hour
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS utc_offset_minutes INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS day_cutoff_hour INTEGER NOT NULL DEFAULT 4;
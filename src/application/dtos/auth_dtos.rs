@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::user_dtos::UserDto;
 
 /// Auth: Register request DTO
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct RegisterRequest {
     pub email: String,
     pub name: String,
@@ -11,14 +11,28 @@ pub struct RegisterRequest {
 }
 
 /// Auth: Login request DTO
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
 }
 
-/// Auth: Response DTO (register + login)
+/// Auth: Change email request DTO
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeEmailRequest {
+    pub new_email: String,
+    pub current_password: String,
+}
+
+/// Auth: Change password request DTO
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Auth: Response DTO (register + login)
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct AuthResponse {
     pub token: String,
     pub user: UserDto,
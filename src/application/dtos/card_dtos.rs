@@ -1,22 +1,204 @@
-use crate::domain::entities::FsrsState;
+use crate::domain::entities::{AnswerType, Card, CardState, DueStatus, EmbeddingStatus, FsrsState};
+use crate::shared::media_signature::{media_urls, MediaSigner};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Create Card DTO
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct CreateCardRequest {
     pub deck_id: Option<Uuid>,
     pub question: String,
     pub answer: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Terms an answer must contain to score above the deck's cap
+    #[serde(default)]
+    pub required_keywords: Vec<String>,
+    /// `list` grades the answer as a set of items in any order
+    #[serde(default)]
+    #[schema(value_type = String, example = "text")]
+    pub answer_type: AnswerType,
 }
 
-/// Card response DTO
+/// Update Card DTO - only fields present in the request are changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct UpdateCardRequest {
+    /// Keep the card from coming due before this time; `null` clears it
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub not_before: Option<Option<DateTime<Utc>>>,
+    /// `list` grades the answer as a set of items in any order
+    #[serde(default)]
+    #[schema(value_type = Option<String>, example = "list")]
+    pub answer_type: Option<AnswerType>,
+}
+
+/// Edit Card DTO - replaces the card's text; scheduling is kept
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct EditCardRequest {
+    pub question: String,
+    pub answer: String,
+    /// Kept as it is when omitted
+    #[serde(default)]
+    #[schema(value_type = Option<String>, example = "text")]
+    pub answer_type: Option<AnswerType>,
+}
+
+/// Deserialize a field that was present in the input, so an explicit `null`
+/// becomes `Some(None)` while an absent field stays `None`
+pub(crate) fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Which of the user's cards a batch action applies to; all criteria must match
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CardFilter {
+    pub tag: Option<String>,
+    pub deck_id: Option<Uuid>,
+    #[schema(value_type = Option<String>, example = "review")]
+    pub state: Option<CardState>,
+}
+
+/// Action applied by a batch request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchCardAction {
+    Suspend,
+    Unsuspend,
+    /// Forget review progress
+    Reset,
+    /// Make due again after `days` (default 0, i.e. now)
+    Reschedule,
+}
+
+/// Batch card action request DTO
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BatchCardActionRequest {
+    #[serde(default)]
+    pub filter: CardFilter,
+    pub action: BatchCardAction,
+    #[serde(default)]
+    pub days: Option<i32>,
+}
+
+/// Batch card action response DTO
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BatchCardActionResponse {
+    pub affected: usize,
+}
+
+/// Embedding backfill response DTO
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingBackfillDto {
+    /// Cards handed to the embedding worker
+    pub queued: usize,
+}
+
+/// Semantic card search request DTO
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SearchCardsRequest {
+    /// Free text matched against the meaning of the cards' answers
+    pub query: String,
+    /// Maximum number of cards returned (1-50, default 10)
+    pub limit: Option<i64>,
+}
+
+/// Semantic card search hit DTO
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CardSearchHitDto {
+    pub card: CardDto,
+    /// Cosine distance between the query and the card's answer embedding;
+    /// smaller is closer
+    pub distance: f32,
+}
+
+/// Tag usage DTO - a distinct tag and how many cards carry it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TagCountDto {
+    pub tag: String,
+    pub count: i64,
+}
+
+/// Card response DTO
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct CardDto {
     pub id: Uuid,
     pub user_id: Uuid,
     pub deck_id: Option<Uuid>,
     pub question: String,
     pub answer: String,
+    #[schema(value_type = Object)]
     pub fsrs_state: FsrsState,
+    pub tags: Vec<String>,
+    pub required_keywords: Vec<String>,
+    #[schema(value_type = String, example = "text")]
+    pub answer_type: AnswerType,
+    pub suspended: bool,
+    pub not_before: Option<DateTime<Utc>>,
+    /// Due status when the response was built
+    #[schema(value_type = String, example = "due")]
+    pub due_status: DueStatus,
+    pub due_at: DateTime<Utc>,
+    /// Whether the answer embedding is stored yet (`ready` cards are
+    /// searchable)
+    #[schema(value_type = String, example = "ready")]
+    pub embedding_status: EmbeddingStatus,
+    /// Images/audio linked from the question or answer, when the media
+    /// proxy is enabled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub media: Vec<MediaLinkDto>,
+}
+
+/// An external media URL and the signed proxy URL that serves it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct MediaLinkDto {
+    pub url: String,
+    pub proxy_url: String,
+}
+
+impl CardDto {
+    /// Attach signed proxy URLs for the media linked from the card
+    pub fn with_signed_media(mut self, signer: &MediaSigner) -> Self {
+        let mut urls = media_urls(&self.question);
+        urls.extend(media_urls(&self.answer));
+        let mut media: Vec<MediaLinkDto> = Vec::new();
+        for url in urls {
+            if !media.iter().any(|link| link.url == url) {
+                media.push(MediaLinkDto {
+                    url: url.to_string(),
+                    proxy_url: signer.proxy_url(url),
+                });
+            }
+        }
+        self.media = media;
+        self
+    }
+}
+
+impl From<Card> for CardDto {
+    fn from(card: Card) -> Self {
+        let due_status = card.due_status(Utc::now());
+        let due_at = card.due_at();
+        Self {
+            id: card.id,
+            user_id: card.user_id,
+            deck_id: card.deck_id,
+            question: card.question,
+            answer: card.answer,
+            fsrs_state: card.fsrs_state,
+            tags: card.tags,
+            required_keywords: card.required_keywords,
+            answer_type: card.answer_type,
+            suspended: card.suspended,
+            not_before: card.not_before,
+            due_status,
+            due_at,
+            embedding_status: card.embedding_status,
+            media: Vec::new(),
+        }
+    }
 }
@@ -1,20 +1,186 @@
+use crate::domain::answer_normalization::NormalizationStep;
+use crate::domain::entities::{Deck, ReviewMode};
+use crate::domain::scheduler_config::SchedulerOverrides;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Create Deck DTO
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct CreateDeckRequest {
     pub name: String,
     pub description: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub scheduler_overrides: Option<SchedulerOverrides>,
+    /// Tags applied to every card created in or imported into the deck
+    #[serde(default)]
+    pub default_tags: Vec<String>,
+    /// Cap on the number of cards ever introduced from the deck
+    #[serde(default)]
+    pub total_new_limit: Option<i32>,
+    /// Filler phrases (e.g. "the answer is") stripped from the start of answers
+    #[serde(default)]
+    pub ignored_answer_prefixes: Vec<String>,
+    /// Ordered answer normalization steps; omitted for the default pipeline
+    #[serde(default)]
+    #[schema(value_type = Option<Vec<Object>>)]
+    pub answer_normalization: Option<Vec<NormalizationStep>>,
+    /// Language tag of the deck's content, e.g. `ar` or `pt-BR`
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Right-to-left text; inferred from `language` when omitted
+    #[serde(default)]
+    pub rtl: Option<bool>,
+    /// `typed` (default) or `self_grade`
+    #[serde(default)]
+    #[schema(value_type = Option<String>, example = "typed")]
+    pub review_mode: Option<ReviewMode>,
+}
+
+/// Update Deck DTO - only fields present in the request are changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct UpdateDeckRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub scheduler_overrides: Option<SchedulerOverrides>,
+    /// Custom FSRS weights (17 or 19 values). An empty array clears them.
+    #[serde(default)]
+    pub fsrs_weights: Option<Vec<f32>>,
+    /// Replaces the deck's default tags. An empty array clears them.
+    #[serde(default)]
+    pub default_tags: Option<Vec<String>>,
+    /// Replaces the cap on cards ever introduced. Zero removes it.
+    #[serde(default)]
+    pub total_new_limit: Option<i32>,
+    /// Replaces the deck's ignored answer prefixes. An empty array clears them.
+    #[serde(default)]
+    pub ignored_answer_prefixes: Option<Vec<String>>,
+    /// Replaces the deck's answer normalization steps. An empty array restores
+    /// the default pipeline.
+    #[serde(default)]
+    #[schema(value_type = Option<Vec<Object>>)]
+    pub answer_normalization: Option<Vec<NormalizationStep>>,
+    /// Replaces the deck language. An empty string clears it.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Replaces the text direction; inferred from the language when only
+    /// that changes
+    #[serde(default)]
+    pub rtl: Option<bool>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>, example = "typed")]
+    pub review_mode: Option<ReviewMode>,
 }
 
 /// Deck response DTO
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct DeckDto {
     pub id: Uuid,
     pub user_id: Uuid,
     pub name: String,
     pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fsrs_weights: Option<Vec<f32>>,
+    pub default_tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_new_limit: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_cards_per_day: Option<i32>,
+    pub ignored_answer_prefixes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<Object>>)]
+    pub answer_normalization: Option<Vec<NormalizationStep>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    pub rtl: bool,
+    #[schema(value_type = String, example = "typed")]
+    pub review_mode: ReviewMode,
+    /// Set for decks in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
+
+impl From<Deck> for DeckDto {
+    fn from(deck: Deck) -> Self {
+        Self {
+            id: deck.id,
+            user_id: deck.user_id,
+            name: deck.name,
+            description: deck.description,
+            fsrs_weights: deck.fsrs_weights,
+            default_tags: deck.default_tags,
+            total_new_limit: deck.total_new_limit,
+            new_cards_per_day: deck.new_cards_per_day,
+            ignored_answer_prefixes: deck.ignored_answer_prefixes,
+            answer_normalization: deck.answer_normalization,
+            language: deck.language,
+            rtl: deck.rtl,
+            review_mode: deck.review_mode,
+            deleted_at: deck.deleted_at,
+            created_at: deck.created_at,
+            updated_at: deck.updated_at,
+        }
+    }
+}
+
+/// Study options of a deck, read and replaced as one bundle. On `PUT`,
+/// omitted fields take their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DeckOptions {
+    /// Probability of recall to aim for when a card comes due (0.7-0.99);
+    /// `null` uses the global default
+    #[serde(default)]
+    pub desired_retention: Option<f32>,
+    /// Cards introduced per study day of the deck owner; `null` for no limit
+    #[serde(default)]
+    pub new_cards_per_day: Option<i32>,
+    /// Longest interval the scheduler may pick; `null` uses the global default
+    #[serde(default)]
+    pub maximum_interval_days: Option<i32>,
+    #[serde(default)]
+    #[schema(value_type = String, example = "typed")]
+    pub review_mode: ReviewMode,
+    /// Filler phrases stripped from the start of answers
+    #[serde(default)]
+    pub ignored_answer_prefixes: Vec<String>,
+    /// Ordered answer normalization steps; `null` for the default pipeline
+    #[serde(default)]
+    #[schema(value_type = Option<Vec<Object>>)]
+    pub answer_normalization: Option<Vec<NormalizationStep>>,
+    /// Language tag of the deck's content, e.g. `ar` or `pt-BR`
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Right-to-left text; inferred from `language` when omitted
+    #[serde(default)]
+    pub rtl: Option<bool>,
+}
+
+impl From<&Deck> for DeckOptions {
+    fn from(deck: &Deck) -> Self {
+        Self {
+            desired_retention: deck.scheduler_overrides.desired_retention,
+            new_cards_per_day: deck.new_cards_per_day,
+            maximum_interval_days: deck.scheduler_overrides.maximum_interval_days,
+            review_mode: deck.review_mode,
+            ignored_answer_prefixes: deck.ignored_answer_prefixes.clone(),
+            answer_normalization: deck.answer_normalization.clone(),
+            language: deck.language.clone(),
+            rtl: Some(deck.rtl),
+        }
+    }
+}
+
+/// Related deck DTO - another deck of the user ranked by semantic overlap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedDeckDto {
+    pub deck_id: Uuid,
+    pub name: String,
+    /// Cosine similarity between the decks' answer-embedding centroids
+    pub similarity: f32,
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{domain::entities::DeadLetterEvent, shared::event_bus::DomainEvent};
+
+/// Dead-lettered event DTO - an event whose handler failed every retry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetterDto {
+    pub id: Uuid,
+    pub handler: String,
+    pub event: DomainEvent,
+    pub error: String,
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+    pub replayed_at: Option<DateTime<Utc>>,
+}
+
+impl From<DeadLetterEvent> for DeadLetterDto {
+    fn from(dead_letter: DeadLetterEvent) -> Self {
+        Self {
+            id: dead_letter.id,
+            handler: dead_letter.handler,
+            event: dead_letter.event,
+            error: dead_letter.error,
+            attempts: dead_letter.attempts,
+            created_at: dead_letter.created_at,
+            replayed_at: dead_letter.replayed_at,
+        }
+    }
+}
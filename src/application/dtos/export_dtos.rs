@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::entities::{Card, Deck, ReviewLog};
+
+/// Lifecycle of a data-export job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportJobStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+/// Export job DTO — returned when an export is enqueued or polled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportJobDto {
+    pub job_id: Uuid,
+    pub user_id: Uuid,
+    pub status: ExportJobStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Full user data export — the JSON document served by the download endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserDataExport {
+    pub user_id: Uuid,
+    pub exported_at: DateTime<Utc>,
+    pub decks: Vec<Deck>,
+    pub cards: Vec<Card>,
+    pub review_logs: Vec<ReviewLog>,
+}
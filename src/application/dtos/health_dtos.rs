@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::domain::ports::{ComponentHealth, ComponentStatus};
+
+/// Detailed health DTO - overall status with one entry per component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetailedHealthDto {
+    pub status: ComponentStatus,
+    pub uptime_secs: u64,
+    pub components: BTreeMap<String, ComponentHealth>,
+}
@@ -1,18 +1,128 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
-/// Import result DTO — returned after TSV or Anki import
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Import result DTO — returned after TSV, CSV or XML import
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ImportResult {
+    /// Deck the cards were imported into
+    pub deck_id: Uuid,
     pub cards_imported: u32,
     pub cards_skipped: u32,
+    /// Rows whose question and answer (trimmed, case-insensitive) match an
+    /// existing card; never imported and not part of `cards_skipped`
+    #[serde(default)]
+    pub cards_duplicate: u32,
+    /// `cards_skipped` broken down by reason (`missing_field`, `empty`,
+    /// `duplicate`, `over_limit`, ...); the counts add up to `cards_skipped`
+    #[serde(default)]
+    pub skip_reasons: HashMap<String, u32>,
+    /// Per-deck breakdown for TSV imports with a deck column; `deck_id` is
+    /// then the first deck named in the file unless one was given
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub decks: Vec<DeckImportCount>,
 }
 
-/// Anki import result DTO — returned after .apkg import (includes created deck info)
+/// Cards an import added to one deck
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DeckImportCount {
+    pub deck_id: Uuid,
+    pub deck_name: String,
+    pub cards_imported: u32,
+}
+
+/// Options shared by the file imports
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ImportOptions {
+    /// Skip cards whose front matches an existing card (ignoring case and
+    /// whitespace) in the target deck, or among all the user's cards for
+    /// imports that create a new deck
+    #[serde(default)]
+    pub skip_duplicates: bool,
+    /// TSV only: read each row's deck name from a third column
+    #[serde(default)]
+    pub deck_column: bool,
+}
+
+/// Layout of a CSV import file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvFormat {
+    /// Field separator, e.g. `b','` or `b';'`
+    pub delimiter: u8,
+    /// Whether the first row is a header (e.g. `front,back`) to skip
+    pub has_header: bool,
+}
+
+impl Default for CsvFormat {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            has_header: false,
+        }
+    }
+}
+
+/// JSON import file — cards with optional review history
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonImportFile {
+    pub cards: Vec<JsonImportCard>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonImportCard {
+    pub question: String,
+    pub answer: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Past reviews; the card's FSRS state is rebuilt by replaying them
+    #[serde(default)]
+    pub reviews: Vec<JsonImportReview>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonImportReview {
+    #[serde(default)]
+    pub user_answer: String,
+    pub score: f32,
+    /// FSRS rating, 1 (Again) to 4 (Easy)
+    pub rating: i32,
+    pub reviewed_at: DateTime<Utc>,
+}
+
+/// JSON import result DTO
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct JsonImportResult {
+    pub cards_imported: u32,
+    pub cards_skipped: u32,
+    #[serde(default)]
+    pub skip_reasons: HashMap<String, u32>,
+    pub reviews_imported: u32,
+}
+
+/// Import preview DTO — how a delimited file would map to card fields
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ImportInspection {
+    pub delimiter: String,
+    pub column_count: usize,
+    pub total_rows: usize,
+    pub sample_rows: Vec<Vec<String>>,
+    /// Guessed column index holding the card front (question)
+    pub front_column: Option<usize>,
+    /// Guessed column index holding the card back (answer)
+    pub back_column: Option<usize>,
+}
+
+/// Anki import result DTO — returned after .apkg import (includes created deck info)
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct AnkiImportResult {
     pub deck_id: Uuid,
     pub deck_name: String,
     pub cards_imported: u32,
     pub cards_skipped: u32,
+    /// See [`ImportResult::cards_duplicate`]
+    #[serde(default)]
+    pub cards_duplicate: u32,
+    #[serde(default)]
+    pub skip_reasons: HashMap<String, u32>,
 }
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// What one maintenance run removed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PruneResultDto {
+    /// Study sessions past their expiry
+    pub study_sessions: usize,
+    /// Decks that sat in the trash longer than the retention window
+    pub trashed_decks: u64,
+}
@@ -1,15 +1,27 @@
 pub mod auth_dtos;
 pub mod card_dtos;
 pub mod deck_dtos;
+pub mod event_dtos;
+pub mod export_dtos;
+pub mod health_dtos;
 pub mod import_dtos;
+pub mod maintenance_dtos;
+pub mod pagination_dtos;
 pub mod review_dtos;
+pub mod scheduler_dtos;
 pub mod stats_dtos;
 pub mod user_dtos;
 
 pub use auth_dtos::*;
 pub use card_dtos::*;
 pub use deck_dtos::*;
+pub use event_dtos::*;
+pub use export_dtos::*;
+pub use health_dtos::*;
 pub use import_dtos::*;
+pub use maintenance_dtos::*;
+pub use pagination_dtos::*;
 pub use review_dtos::*;
+pub use scheduler_dtos::*;
 pub use stats_dtos::*;
 pub use user_dtos::*;
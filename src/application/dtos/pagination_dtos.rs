@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// One page of a list endpoint, with the number of matching rows overall so
+/// clients can render page controls
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Rows matching the query across all pages
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl<T> Page<T> {
+    /// Apply `f` to every item, keeping the paging fields
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            limit: self.limit,
+            offset: self.offset,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::domain::{answer_diff::DiffSegment, entities::ReviewMode};
+
 /// Review Card DTO - for submitting a review with user answer
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReviewCardRequest {
@@ -14,13 +16,21 @@ pub struct LegacyReviewCardRequest {
 }
 
 /// Review response DTO with AI validation results
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ReviewResponseDto {
     pub card_id: Uuid,
     pub ai_score: f32,
     pub fsrs_rating: i32,
     pub validation_method: String,
     pub next_review_in_days: i32,
+    /// Score `validation_method` needed to accept the answer (embedding
+    /// similarity threshold); absent for methods without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
+    /// Word diff from the submitted answer to the expected one, when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<Object>>)]
+    pub diff: Option<Vec<DiffSegment>>,
 }
 
 /// Legacy Review response DTO
@@ -30,3 +40,63 @@ pub struct ReviewDto {
     pub card_id: Uuid,
     pub grade: i32,
 }
+
+/// Review history entry DTO - one past grading of a card
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ReviewHistoryEntryDto {
+    pub id: Uuid,
+    pub user_answer: String,
+    pub ai_score: f32,
+    pub validation_method: String,
+    pub fsrs_rating: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// One answer to grade in a validation preview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateAnswerItem {
+    pub question: String,
+    pub expected: String,
+    pub answer: String,
+}
+
+/// AI grading of one previewed answer; nothing is stored or scheduled.
+/// `error` is set instead of the score when the validator failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationPreviewDto {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation_method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A study session: a deck's due cards frozen into a queue at creation time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudySessionDto {
+    pub session_id: Uuid,
+    pub deck_id: Uuid,
+    pub total: usize,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    /// Whether cards are answered by typing or by self-grading
+    pub review_mode: ReviewMode,
+}
+
+/// Next card of a study session; `card` is `None` once the queue is exhausted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudySessionNextDto {
+    pub session_id: Uuid,
+    pub card: Option<super::CardDto>,
+    /// 1-based position of `card` in the queue
+    pub position: usize,
+    pub remaining: usize,
+    pub reviewed: usize,
+    pub total: usize,
+}
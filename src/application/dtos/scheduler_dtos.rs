@@ -0,0 +1,37 @@
+use crate::domain::{
+    entities::ReviewMode,
+    scheduler_config::{SchedulerConfig, SchedulerOverrides},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Effective scheduler configuration for a deck
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerConfigDto {
+    pub deck_id: Uuid,
+    /// Configuration the scheduler uses after merging deck overrides and
+    /// FSRS weights onto the defaults
+    pub resolved: SchedulerConfig,
+    /// How the requesting user's reviews are graded: `self_grade` when the
+    /// deck is self-graded or the user turned AI validation off
+    pub review_mode: ReviewMode,
+    /// Values explicitly set on the deck
+    pub deck_overrides: SchedulerOverrides,
+    /// FSRS weights set on the deck, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deck_fsrs_weights: Option<Vec<f32>>,
+}
+
+/// FSRS weights fitted to a user's review history. Nothing is applied; the
+/// weights can be set on a deck as `fsrs_weights`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FsrsOptimizationDto {
+    /// Suggested FSRS-4.5 weights (w0..w16)
+    pub weights: Vec<f32>,
+    /// Reviews the weights were fitted on
+    pub review_count: usize,
+    /// Mean log-loss of the default weights on those reviews
+    pub default_loss: f32,
+    /// Mean log-loss of the suggested weights
+    pub loss: f32,
+}
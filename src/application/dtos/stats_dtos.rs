@@ -1,8 +1,9 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// User statistics response DTO
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct UserStatsDto {
     pub user_id: Uuid,
     pub total_reviews: i32,
@@ -13,7 +14,7 @@ pub struct UserStatsDto {
 }
 
 /// Deck statistics response DTO
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct DeckStatsDto {
     pub deck_id: Uuid,
     pub deck_name: String,
@@ -24,3 +25,55 @@ pub struct DeckStatsDto {
     pub accuracy_percentage: f64,
     pub last_active_date: Option<String>, // ISO 8601 date string
 }
+
+/// Number of cards currently due for a user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DueCountDto {
+    pub user_id: Uuid,
+    pub due_count: i64,
+}
+
+/// One range of AI scores and how many reviews scored in it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBucketDto {
+    /// Inclusive lower bound
+    pub lower: f32,
+    /// Exclusive upper bound, except for the last bucket which includes 1.0
+    pub upper: f32,
+    pub count: i64,
+}
+
+/// Distribution of AI review scores, for tuning rating thresholds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreHistogramDto {
+    pub user_id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deck_id: Option<Uuid>,
+    pub total: i64,
+    pub buckets: Vec<ScoreBucketDto>,
+}
+
+/// Reviews done on the user's current study day against their daily goal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoalProgressDto {
+    pub user_id: Uuid,
+    /// The current study day, in the user's timezone and day cutoff
+    pub date: NaiveDate,
+    pub reviews_today: i64,
+    pub daily_review_goal: Option<i32>,
+    /// Reviews still needed to meet the goal; `None` without a goal
+    pub remaining: Option<i64>,
+    /// Always false without a goal
+    pub goal_met: bool,
+}
+
+/// Reviews per local day of week and hour, to show when a user studies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityHeatmapDto {
+    pub user_id: Uuid,
+    /// Offset from UTC the reviews were bucketed in (the user's preference)
+    pub utc_offset_minutes: i32,
+    pub total: i64,
+    /// 7 rows (Monday first) of 24 hourly counts
+    pub counts: Vec<Vec<i64>>,
+}
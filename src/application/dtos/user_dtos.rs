@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::card_dtos::deserialize_present;
+use crate::domain::entities::UserPreferences;
+
 /// Create User DTO
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateUserRequest {
@@ -9,9 +12,38 @@ pub struct CreateUserRequest {
 }
 
 /// User response DTO
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct UserDto {
     pub id: Uuid,
     pub email: String,
     pub name: String,
 }
+
+/// Update Preferences DTO - only fields present in the request are changed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct UpdatePreferencesRequest {
+    pub utc_offset_minutes: Option<i32>,
+    pub day_cutoff_hour: Option<i32>,
+    /// `null` removes the goal
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub daily_review_goal: Option<Option<i32>>,
+    pub ai_validation_enabled: Option<bool>,
+}
+
+impl UpdatePreferencesRequest {
+    /// `preferences` with the fields of this request replaced
+    pub fn apply(self, preferences: UserPreferences) -> UserPreferences {
+        UserPreferences {
+            utc_offset_minutes: self
+                .utc_offset_minutes
+                .unwrap_or(preferences.utc_offset_minutes),
+            day_cutoff_hour: self.day_cutoff_hour.unwrap_or(preferences.day_cutoff_hour),
+            daily_review_goal: self
+                .daily_review_goal
+                .unwrap_or(preferences.daily_review_goal),
+            ai_validation_enabled: self
+                .ai_validation_enabled
+                .unwrap_or(preferences.ai_validation_enabled),
+        }
+    }
+}
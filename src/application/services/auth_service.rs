@@ -1,19 +1,31 @@
 use crate::domain::repositories::UserRepository;
+use crate::shared::event_bus::EventBus;
 use std::sync::Arc;
 
-/// Auth service - handles registration and login
+/// Auth service - handles registration, login and credential changes
 pub struct AuthService {
     pub register: Arc<super::super::use_cases::RegisterUserUseCase>,
     pub login: Arc<super::super::use_cases::LoginUserUseCase>,
+    pub change_email: Arc<super::super::use_cases::ChangeEmailUseCase>,
+    pub change_password: Arc<super::super::use_cases::ChangePasswordUseCase>,
 }
 
 impl AuthService {
-    pub fn new(user_repo: Arc<dyn UserRepository>) -> Self {
+    pub fn new(user_repo: Arc<dyn UserRepository>, event_bus: Arc<EventBus>) -> Self {
         Self {
             register: Arc::new(super::super::use_cases::RegisterUserUseCase::new(
                 user_repo.clone(),
+                event_bus,
+            )),
+            login: Arc::new(super::super::use_cases::LoginUserUseCase::new(
+                user_repo.clone(),
+            )),
+            change_email: Arc::new(super::super::use_cases::ChangeEmailUseCase::new(
+                user_repo.clone(),
+            )),
+            change_password: Arc::new(super::super::use_cases::ChangePasswordUseCase::new(
+                user_repo,
             )),
-            login: Arc::new(super::super::use_cases::LoginUserUseCase::new(user_repo)),
         }
     }
 }
@@ -1,32 +1,105 @@
 use crate::{
-    domain::{entities::Card, repositories::CardRepository},
-    shared::event_bus::{DomainEvent, EventBus},
+    domain::{
+        entities::{Card, Deck, UserPreferences},
+        repositories::{
+            CardListOptions, CardRepository, CardSelection, DeckRepository, UserRepository,
+        },
+    },
+    shared::{
+        access::{Access, OwnershipPolicy},
+        content_policy::ContentPolicy,
+        event_bus::{DomainEvent, EventBus},
+        quota::UserQuotas,
+    },
     AppResult,
 };
 use std::sync::Arc;
 use uuid::Uuid;
 
-use super::super::dtos::{CardDto, CreateCardRequest};
+use super::super::dtos::{
+    BatchCardAction, BatchCardActionRequest, BatchCardActionResponse, CardDto, CreateCardRequest,
+    Page, TagCountDto, UpdateCardRequest,
+};
 
 /// Card service - handles card (flashcard) operations
 pub struct CardService {
     card_repo: Arc<dyn CardRepository>,
+    deck_repo: Arc<dyn DeckRepository>,
+    user_repo: Option<Arc<dyn UserRepository>>,
     event_bus: Arc<EventBus>,
+    ownership_policy: OwnershipPolicy,
+    quotas: UserQuotas,
+    content_policy: ContentPolicy,
 }
 
 impl CardService {
-    pub fn new(card_repo: Arc<dyn CardRepository>, event_bus: Arc<EventBus>) -> Self {
+    pub fn new(
+        card_repo: Arc<dyn CardRepository>,
+        deck_repo: Arc<dyn DeckRepository>,
+        event_bus: Arc<EventBus>,
+    ) -> Self {
         Self {
             card_repo,
+            deck_repo,
+            user_repo: None,
             event_bus,
+            ownership_policy: OwnershipPolicy::default(),
+            quotas: UserQuotas::default(),
+            content_policy: ContentPolicy::default(),
         }
     }
 
+    /// How cards owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    /// Per-user card limit enforced on creation
+    pub fn with_quotas(mut self, quotas: UserQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    /// Daily new-card limits follow the deck owner's study day (UTC offset
+    /// and day cutoff hour) instead of the UTC calendar day
+    pub fn with_user_repository(mut self, user_repo: Arc<dyn UserRepository>) -> Self {
+        self.user_repo = Some(user_repo);
+        self
+    }
+
+    /// Cards the policy refuses are rejected
+    pub fn with_content_policy(mut self, content_policy: ContentPolicy) -> Self {
+        self.content_policy = content_policy;
+        self
+    }
+
+    /// Create a card. Cards created in a deck also get the deck's default tags.
     pub async fn create_card(&self, user_id: Uuid, req: CreateCardRequest) -> AppResult<CardDto> {
-        let mut card = Card::new(user_id, req.question, req.answer);
+        if self.quotas.max_cards.is_some() {
+            self.quotas
+                .check_new_card(self.card_repo.count_by_user(user_id).await?)?;
+        }
+        self.content_policy
+            .check(&req.question, &req.answer)
+            .map_err(|e| crate::AppError::ValidationError(e.to_string()))?;
+        let mut card = Card::new(user_id, req.question, req.answer)
+            .with_tags(&req.tags)
+            .with_required_keywords(&req.required_keywords)
+            .with_answer_type(req.answer_type);
         let deck_id = req.deck_id;
         if let Some(deck_id) = deck_id {
-            card = card.with_deck(deck_id);
+            // Only the user's own decks; a card in someone else's deck would
+            // skew that deck's stats
+            let deck = self.ownership_policy.authorize(
+                self.deck_repo.find_by_id(deck_id).await?,
+                |deck| deck.user_id,
+                user_id,
+                Access::Write,
+                "Deck",
+                deck_id,
+            )?;
+            card = card.with_deck(deck_id).with_tags(&deck.default_tags);
         }
         let card_id = self.card_repo.create(&card).await?;
 
@@ -40,75 +113,816 @@ impl CardService {
 
         Ok(CardDto {
             id: card_id,
-            user_id: card.user_id,
-            deck_id: card.deck_id,
-            question: card.question,
-            answer: card.answer,
-            fsrs_state: card.fsrs_state,
+            ..CardDto::from(card)
         })
     }
 
     pub async fn get_user_cards(
         &self,
         user_id: Uuid,
-        limit: Option<i64>,
-        offset: Option<i64>,
-        exclude_card_ids: Option<Vec<Uuid>>,
-    ) -> AppResult<Vec<CardDto>> {
+        options: CardListOptions,
+    ) -> AppResult<Page<CardDto>> {
+        let total = self
+            .card_repo
+            .count_by_user_matching(user_id, options.clone())
+            .await?;
+        let (limit, offset) = (options.limit, options.offset.unwrap_or(0));
         let cards = self
             .card_repo
-            .find_by_user_paginated(user_id, limit, offset, exclude_card_ids)
+            .find_by_user_paginated(user_id, options)
             .await?;
 
-        Ok(cards
-            .into_iter()
-            .map(|card| CardDto {
-                id: card.id,
-                user_id: card.user_id,
-                deck_id: card.deck_id,
-                question: card.question,
-                answer: card.answer,
-                fsrs_state: card.fsrs_state,
-            })
-            .collect())
+        Ok(Page {
+            limit: limit.unwrap_or(cards.len() as i64),
+            items: cards.into_iter().map(CardDto::from).collect(),
+            total,
+            offset,
+        })
     }
 
+    /// One page of the user's cards that are due for review at `now`, most
+    /// overdue first
+    pub async fn get_due_cards(
+        &self,
+        user_id: Uuid,
+        now: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Vec<CardDto>> {
+        let cards = self
+            .card_repo
+            .find_due_by_user(user_id, now, limit, offset)
+            .await?;
+        Ok(cards.into_iter().map(CardDto::from).collect())
+    }
+
+    /// Cards of a deck in study order, with new cards capped as described
+    /// at [`new_card_limit`]. A deck in the trash is not found, like a
+    /// missing one.
     pub async fn get_deck_cards(
         &self,
         deck_id: Uuid,
-        limit: Option<i64>,
-        offset: Option<i64>,
-        exclude_card_ids: Option<Vec<Uuid>>,
-    ) -> AppResult<Vec<CardDto>> {
+        user_id: Uuid,
+        mut options: CardListOptions,
+    ) -> AppResult<Page<CardDto>> {
+        let deck = self.ownership_policy.authorize(
+            self.deck_repo.find_by_id(deck_id).await?,
+            |deck| deck.user_id,
+            user_id,
+            Access::Read,
+            "Deck",
+            deck_id,
+        )?;
+        options.new_card_limit = new_card_limit(
+            self.card_repo.as_ref(),
+            self.user_repo.as_deref(),
+            &deck,
+            chrono::Utc::now(),
+        )
+        .await?;
+
+        let total = self
+            .card_repo
+            .count_by_deck_matching(deck_id, options.clone())
+            .await?;
+        let (limit, offset) = (options.limit, options.offset.unwrap_or(0));
         let cards = self
             .card_repo
-            .find_by_deck_paginated(deck_id, limit, offset, exclude_card_ids)
+            .find_by_deck_paginated(deck_id, options)
             .await?;
 
-        Ok(cards
+        Ok(Page {
+            limit: limit.unwrap_or(cards.len() as i64),
+            items: cards.into_iter().map(CardDto::from).collect(),
+            total,
+            offset,
+        })
+    }
+
+    /// A random card for free practice, from `deck_id` when given
+    pub async fn get_random_card(
+        &self,
+        user_id: Uuid,
+        deck_id: Option<Uuid>,
+    ) -> AppResult<CardDto> {
+        self.card_repo
+            .find_random(user_id, deck_id)
+            .await?
+            .map(CardDto::from)
+            .ok_or_else(|| crate::AppError::NotFound("No cards available to practice".to_string()))
+    }
+
+    /// Distinct tags on the user's cards with usage counts, most used first
+    pub async fn get_user_tags(&self, user_id: Uuid) -> AppResult<Vec<TagCountDto>> {
+        let counts = self.card_repo.count_tags_by_user(user_id).await?;
+
+        Ok(counts
             .into_iter()
-            .map(|card| CardDto {
-                id: card.id,
-                user_id: card.user_id,
-                deck_id: card.deck_id,
-                question: card.question,
-                answer: card.answer,
-                fsrs_state: card.fsrs_state,
-            })
+            .map(|(tag, count)| TagCountDto { tag, count })
             .collect())
     }
 
-    pub async fn delete_card(&self, card_id: Uuid, user_id: Uuid) -> AppResult<()> {
-        let card = self.card_repo.find_by_id(card_id).await?.ok_or_else(|| {
-            crate::AppError::NotFound(format!("Card with id {} not found", card_id))
-        })?;
+    pub async fn get_card(&self, card_id: Uuid, user_id: Uuid) -> AppResult<CardDto> {
+        let card = self.ownership_policy.authorize(
+            self.card_repo.find_by_id(card_id).await?,
+            |card| card.user_id,
+            user_id,
+            Access::Read,
+            "Card",
+            card_id,
+        )?;
 
-        if card.user_id != user_id {
-            return Err(crate::AppError::AuthorizationError(
-                "Cannot delete card belonging to another user".to_string(),
+        Ok(CardDto::from(card))
+    }
+
+    /// Apply `req.action` to every card of the user matching `req.filter`,
+    /// which must set at least one criterion. Changes apply all or none: a
+    /// card saved (e.g. reviewed) after it was loaded fails the whole batch
+    /// with `AppError::Conflict`.
+    pub async fn batch_action(
+        &self,
+        user_id: Uuid,
+        req: BatchCardActionRequest,
+    ) -> AppResult<BatchCardActionResponse> {
+        let selection = CardSelection {
+            tag: req
+                .filter
+                .tag
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty()),
+            deck_id: req.filter.deck_id,
+            state: req.filter.state,
+        };
+        if selection.is_empty() {
+            return Err(crate::AppError::ValidationError(
+                "filter must set at least one of tag, deck_id or state".to_string(),
             ));
         }
 
+        let now = chrono::Utc::now();
+        let updates: Vec<(Card, chrono::DateTime<chrono::Utc>)> = self
+            .card_repo
+            .find_matching(user_id, &selection)
+            .await?
+            .into_iter()
+            .map(|mut card| {
+                let loaded_at = card.updated_at;
+                match req.action {
+                    BatchCardAction::Suspend => card.suspended = true,
+                    BatchCardAction::Unsuspend => card.suspended = false,
+                    BatchCardAction::Reset => card.reset(),
+                    BatchCardAction::Reschedule => card.reschedule(req.days.unwrap_or(0)),
+                }
+                card.updated_at = now;
+                (card, loaded_at)
+            })
+            .collect();
+        self.card_repo.update_many_if_unchanged(&updates).await?;
+
+        Ok(BatchCardActionResponse {
+            affected: updates.len(),
+        })
+    }
+
+    /// Apply the fields set in `req` to one of the user's cards
+    pub async fn update_card(
+        &self,
+        card_id: Uuid,
+        user_id: Uuid,
+        req: UpdateCardRequest,
+    ) -> AppResult<CardDto> {
+        let mut card = self.ownership_policy.authorize(
+            self.card_repo.find_by_id(card_id).await?,
+            |card| card.user_id,
+            user_id,
+            Access::Write,
+            "Card",
+            card_id,
+        )?;
+
+        if let Some(not_before) = req.not_before {
+            card.not_before = not_before;
+        }
+        if let Some(answer_type) = req.answer_type {
+            card.answer_type = answer_type;
+        }
+        card.updated_at = chrono::Utc::now();
+
+        self.card_repo.update(&card).await?;
+
+        Ok(CardDto::from(card))
+    }
+
+    pub async fn delete_card(&self, card_id: Uuid, user_id: Uuid) -> AppResult<()> {
+        self.ownership_policy.authorize(
+            self.card_repo.find_by_id(card_id).await?,
+            |card| card.user_id,
+            user_id,
+            Access::Write,
+            "Card",
+            card_id,
+        )?;
+
         self.card_repo.delete(card_id).await
     }
 }
+
+/// How many `New` cards of `deck` may still be served at `now`: what is left
+/// of its `total_new_limit` and of its `new_cards_per_day` for the deck
+/// owner's current study day, whichever is smaller. `None` when the deck has
+/// neither limit. Without a user repository the study day is the UTC day
+/// starting at the default cutoff hour.
+pub(crate) async fn new_card_limit(
+    card_repo: &dyn CardRepository,
+    user_repo: Option<&dyn UserRepository>,
+    deck: &Deck,
+    now: chrono::DateTime<chrono::Utc>,
+) -> AppResult<Option<i64>> {
+    let mut new_card_limit: Option<i64> = None;
+    if let Some(limit) = deck.total_new_limit {
+        let introduced = card_repo.count_introduced_by_deck(deck.id).await?;
+        new_card_limit = Some((limit as i64 - introduced).max(0));
+    }
+    if let Some(limit) = deck.new_cards_per_day {
+        let preferences = match user_repo {
+            Some(repo) => repo
+                .find_by_id(deck.user_id)
+                .await?
+                .map(|user| user.preferences)
+                .unwrap_or_default(),
+            None => UserPreferences::default(),
+        };
+        let introduced = card_repo
+            .count_introduced_by_deck_since(deck.id, preferences.review_day_start(now))
+            .await?;
+        let remaining = (limit as i64 - introduced).max(0);
+        new_card_limit = Some(new_card_limit.map_or(remaining, |limit| limit.min(remaining)));
+    }
+    Ok(new_card_limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        application::{dtos::CardFilter, use_cases::test_support::SingleUserRepository},
+        domain::entities::{CardState, User},
+    };
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+
+    struct InMemoryCardRepo {
+        cards: std::sync::Mutex<Vec<Card>>,
+        /// Card saved again right after `find_matching`, like a review
+        /// landing in the middle of a batch action
+        reviewed_after_find: Option<Uuid>,
+    }
+
+    impl InMemoryCardRepo {
+        fn new(cards: Vec<Card>) -> Self {
+            Self {
+                cards: std::sync::Mutex::new(cards),
+                reviewed_after_find: None,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl CardRepository for InMemoryCardRepo {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Card>> {
+            let cards = self.cards.lock().unwrap();
+            Ok(cards.iter().find(|c| c.id == id).cloned())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Card>> {
+            let cards = self.cards.lock().unwrap();
+            Ok(cards
+                .iter()
+                .filter(|c| c.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_deck(&self, deck_id: Uuid) -> AppResult<Vec<Card>> {
+            let cards = self.cards.lock().unwrap();
+            Ok(cards
+                .iter()
+                .filter(|c| c.deck_id == Some(deck_id))
+                .cloned()
+                .collect())
+        }
+        async fn update(&self, card: &Card) -> AppResult<()> {
+            let mut cards = self.cards.lock().unwrap();
+            if let Some(existing) = cards.iter_mut().find(|c| c.id == card.id) {
+                *existing = card.clone();
+            }
+            Ok(())
+        }
+        async fn find_matching(
+            &self,
+            user_id: Uuid,
+            selection: &CardSelection,
+        ) -> AppResult<Vec<Card>> {
+            let mut cards = self.cards.lock().unwrap();
+            let found = cards
+                .iter()
+                .filter(|c| c.user_id == user_id && selection.matches(c))
+                .cloned()
+                .collect();
+            if let Some(card) = cards
+                .iter_mut()
+                .find(|c| Some(c.id) == self.reviewed_after_find)
+            {
+                card.updated_at += chrono::Duration::seconds(1);
+            }
+            Ok(found)
+        }
+        async fn update_many_if_unchanged(
+            &self,
+            updates: &[(Card, chrono::DateTime<chrono::Utc>)],
+        ) -> AppResult<()> {
+            let mut cards = self.cards.lock().unwrap();
+            for (card, loaded_at) in updates {
+                if cards
+                    .iter()
+                    .any(|c| c.id == card.id && c.updated_at != *loaded_at)
+                {
+                    return Err(crate::AppError::Conflict(format!(
+                        "Card {} was changed concurrently; retry",
+                        card.id
+                    )));
+                }
+            }
+            for (card, _) in updates {
+                if let Some(existing) = cards.iter_mut().find(|c| c.id == card.id) {
+                    *existing = card.clone();
+                }
+            }
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct FixedDeckRepo {
+        deck: Option<Deck>,
+    }
+
+    #[async_trait]
+    impl DeckRepository for FixedDeckRepo {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            Ok(deck.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            Ok(self.deck.clone().filter(|deck| deck.id == id))
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(vec![])
+        }
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    fn service(card: Card, policy: OwnershipPolicy) -> CardService {
+        CardService::new(
+            Arc::new(InMemoryCardRepo::new(vec![card])),
+            Arc::new(FixedDeckRepo { deck: None }),
+            Arc::new(EventBus::new()),
+        )
+        .with_ownership_policy(policy)
+    }
+
+    #[tokio::test]
+    async fn test_unowned_card_is_not_found_under_privacy_policy() {
+        let card = Card::new(Uuid::new_v4(), "Q".to_string(), "A".to_string());
+        let card_id = card.id;
+        let svc = service(card, OwnershipPolicy::HideReads);
+
+        let unowned = svc.get_card(card_id, Uuid::new_v4()).await;
+        let missing = svc.get_card(Uuid::new_v4(), Uuid::new_v4()).await;
+
+        assert!(matches!(unowned, Err(crate::AppError::NotFound(_))));
+        assert!(matches!(missing, Err(crate::AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unowned_card_is_forbidden_under_explicit_policy() {
+        let card = Card::new(Uuid::new_v4(), "Q".to_string(), "A".to_string());
+        let card_id = card.id;
+        let svc = service(card, OwnershipPolicy::Explicit);
+
+        let result = svc.get_card(card_id, Uuid::new_v4()).await;
+
+        assert!(matches!(
+            result,
+            Err(crate::AppError::AuthorizationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_owner_can_read_card() {
+        let card = Card::new(Uuid::new_v4(), "Q".to_string(), "A".to_string());
+        let (card_id, owner) = (card.id, card.user_id);
+        let svc = service(card, OwnershipPolicy::HideAll);
+
+        let result = svc.get_card(card_id, owner).await.unwrap();
+
+        assert_eq!(result.id, card_id);
+    }
+
+    #[tokio::test]
+    async fn test_another_users_deck_cards_are_not_found_under_privacy_policy() {
+        let owner = Uuid::new_v4();
+        let deck = Deck::new(owner, "Theirs".to_string(), None);
+        let deck_id = deck.id;
+        let card = Card::new(owner, "Q".to_string(), "A".to_string()).with_deck(deck_id);
+        let svc = CardService::new(
+            Arc::new(InMemoryCardRepo::new(vec![card])),
+            Arc::new(FixedDeckRepo { deck: Some(deck) }),
+            Arc::new(EventBus::new()),
+        )
+        .with_ownership_policy(OwnershipPolicy::HideAll);
+
+        let unowned = svc
+            .get_deck_cards(deck_id, Uuid::new_v4(), CardListOptions::default())
+            .await;
+        let owned = svc
+            .get_deck_cards(deck_id, owner, CardListOptions::default())
+            .await
+            .unwrap();
+
+        assert!(matches!(unowned, Err(crate::AppError::NotFound(_))));
+        assert_eq!(owned.items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_user_cards_page_reports_total_of_all_matches() {
+        let user_id = Uuid::new_v4();
+        let cards: Vec<Card> = (0..5)
+            .map(|i| Card::new(user_id, format!("Q{}", i), "A".to_string()))
+            .collect();
+        let svc = CardService::new(
+            Arc::new(InMemoryCardRepo::new(cards.clone())),
+            Arc::new(FixedDeckRepo { deck: None }),
+            Arc::new(EventBus::new()),
+        );
+
+        let page = svc
+            .get_user_cards(
+                user_id,
+                CardListOptions {
+                    limit: Some(2),
+                    offset: Some(3),
+                    exclude_card_ids: Some(vec![cards[0].id]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let ids: Vec<Uuid> = page.items.iter().map(|card| card.id).collect();
+        assert_eq!(ids, vec![cards[4].id]);
+        assert_eq!((page.total, page.limit, page.offset), (4, 2, 3));
+    }
+
+    #[tokio::test]
+    async fn test_deck_cards_page_reports_total_of_all_matches() {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Spanish".to_string(), None);
+        let deck_id = deck.id;
+        let cards: Vec<Card> = (0..5)
+            .map(|i| Card::new(user_id, format!("Q{}", i), "A".to_string()).with_deck(deck_id))
+            .collect();
+        let svc = CardService::new(
+            Arc::new(InMemoryCardRepo::new(cards)),
+            Arc::new(FixedDeckRepo { deck: Some(deck) }),
+            Arc::new(EventBus::new()),
+        );
+
+        let page = svc
+            .get_deck_cards(
+                deck_id,
+                user_id,
+                CardListOptions {
+                    limit: Some(2),
+                    offset: Some(4),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(page.items.len(), 1);
+        assert_eq!((page.total, page.limit, page.offset), (5, 2, 4));
+    }
+
+    #[tokio::test]
+    async fn test_batch_suspend_by_tag() {
+        let user_id = Uuid::new_v4();
+        let tagged =
+            Card::new(user_id, "hola".to_string(), "hello".to_string()).with_tags(["verbs"]);
+        let also_tagged =
+            Card::new(user_id, "comer".to_string(), "to eat".to_string()).with_tags(["verbs"]);
+        let untagged = Card::new(user_id, "casa".to_string(), "house".to_string());
+        let other_user =
+            Card::new(Uuid::new_v4(), "ser".to_string(), "to be".to_string()).with_tags(["verbs"]);
+        let repo = Arc::new(InMemoryCardRepo::new(vec![
+            tagged,
+            also_tagged,
+            untagged,
+            other_user,
+        ]));
+        let svc = CardService::new(
+            repo.clone(),
+            Arc::new(FixedDeckRepo { deck: None }),
+            Arc::new(EventBus::new()),
+        );
+
+        let result = svc
+            .batch_action(
+                user_id,
+                BatchCardActionRequest {
+                    filter: CardFilter {
+                        tag: Some("verbs".to_string()),
+                        ..Default::default()
+                    },
+                    action: BatchCardAction::Suspend,
+                    days: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.affected, 2);
+        let cards = repo.cards.lock().unwrap();
+        let suspended: Vec<_> = cards.iter().filter(|c| c.suspended).collect();
+        assert_eq!(suspended.len(), 2);
+        assert!(suspended
+            .iter()
+            .all(|c| c.user_id == user_id && c.tags.contains(&"verbs".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_batch_without_filter_is_rejected() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "hola".to_string(), "hello".to_string());
+        let repo = Arc::new(InMemoryCardRepo::new(vec![card]));
+        let svc = CardService::new(
+            repo.clone(),
+            Arc::new(FixedDeckRepo { deck: None }),
+            Arc::new(EventBus::new()),
+        );
+
+        let result = svc
+            .batch_action(
+                user_id,
+                BatchCardActionRequest {
+                    filter: CardFilter {
+                        tag: Some("  ".to_string()),
+                        ..Default::default()
+                    },
+                    action: BatchCardAction::Suspend,
+                    days: None,
+                },
+            )
+            .await;
+
+        assert!(matches!(result, Err(crate::AppError::ValidationError(_))));
+        assert!(!repo.cards.lock().unwrap()[0].suspended);
+    }
+
+    #[tokio::test]
+    async fn test_batch_fails_whole_when_a_card_is_reviewed_meanwhile() {
+        let user_id = Uuid::new_v4();
+        let first =
+            Card::new(user_id, "hola".to_string(), "hello".to_string()).with_tags(["verbs"]);
+        let reviewed =
+            Card::new(user_id, "comer".to_string(), "to eat".to_string()).with_tags(["verbs"]);
+        let repo = Arc::new(InMemoryCardRepo {
+            reviewed_after_find: Some(reviewed.id),
+            ..InMemoryCardRepo::new(vec![first, reviewed])
+        });
+        let svc = CardService::new(
+            repo.clone(),
+            Arc::new(FixedDeckRepo { deck: None }),
+            Arc::new(EventBus::new()),
+        );
+
+        let result = svc
+            .batch_action(
+                user_id,
+                BatchCardActionRequest {
+                    filter: CardFilter {
+                        tag: Some("verbs".to_string()),
+                        ..Default::default()
+                    },
+                    action: BatchCardAction::Suspend,
+                    days: None,
+                },
+            )
+            .await;
+
+        assert!(matches!(result, Err(crate::AppError::Conflict(_))));
+        assert!(repo.cards.lock().unwrap().iter().all(|c| !c.suspended));
+    }
+
+    #[tokio::test]
+    async fn test_total_new_limit_stops_serving_new_cards() {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Spanish".to_string(), None).with_total_new_limit(Some(2));
+        let deck_id = deck.id;
+        let created = chrono::Utc::now() - chrono::Duration::days(10);
+
+        let mut introduced =
+            Card::new(user_id, "hola".to_string(), "hello".to_string()).with_deck(deck_id);
+        introduced.fsrs_state.state = CardState::Review;
+        let new_cards: Vec<Card> = ["casa", "perro", "gato"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, word)| {
+                let mut card =
+                    Card::new(user_id, word.to_string(), word.to_string()).with_deck(deck_id);
+                card.created_at = created + chrono::Duration::hours(i as i64);
+                card
+            })
+            .collect();
+        let oldest_new = new_cards[0].id;
+        let mut cards = vec![introduced.clone()];
+        cards.extend(new_cards);
+
+        let repo = Arc::new(InMemoryCardRepo::new(cards));
+        let svc = CardService::new(
+            repo.clone(),
+            Arc::new(FixedDeckRepo { deck: Some(deck) }),
+            Arc::new(EventBus::new()),
+        );
+        let page = CardListOptions {
+            limit: Some(50),
+            ..Default::default()
+        };
+
+        let served = svc
+            .get_deck_cards(deck_id, user_id, page.clone())
+            .await
+            .unwrap();
+        let ids: Vec<Uuid> = served.items.iter().map(|c| c.id).collect();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(served.total, 2);
+        assert!(ids.contains(&introduced.id) && ids.contains(&oldest_new));
+
+        // Once the second card is introduced the cap is reached, even though
+        // the page still has room
+        {
+            let mut cards = repo.cards.lock().unwrap();
+            let card = cards.iter_mut().find(|c| c.id == oldest_new).unwrap();
+            card.fsrs_state.state = CardState::Learning;
+        }
+        let served = svc.get_deck_cards(deck_id, user_id, page).await.unwrap();
+        assert_eq!(served.items.len(), 2);
+        assert!(served
+            .items
+            .iter()
+            .all(|c| c.fsrs_state.state != CardState::New));
+    }
+
+    #[tokio::test]
+    async fn test_new_cards_per_day_follows_the_owners_study_day() {
+        // UTC+2 with the day starting at 4am local, so the study day of
+        // March 10th starts at 02:00 UTC
+        let mut user = User::new("late@example.com".to_string(), "Late".to_string());
+        user.preferences.utc_offset_minutes = 120;
+        user.preferences.day_cutoff_hour = 4;
+        let mut deck = Deck::new(user.id, "Spanish".to_string(), None);
+        deck.new_cards_per_day = Some(1);
+        let now = chrono::Utc.with_ymd_and_hms(2026, 3, 10, 10, 0, 0).unwrap();
+
+        // Introduced at 03:00 local, before the cutoff: yesterday's quota
+        let mut late_night =
+            Card::new(user.id, "hola".to_string(), "hello".to_string()).with_deck(deck.id);
+        late_night.fsrs_state.state = CardState::Learning;
+        late_night.fsrs_state.reps = 1;
+        late_night.fsrs_state.last_review =
+            Some(chrono::Utc.with_ymd_and_hms(2026, 3, 10, 1, 0, 0).unwrap());
+        let card_repo = InMemoryCardRepo::new(vec![late_night]);
+        let user_repo = SingleUserRepository(user);
+
+        let limit = new_card_limit(&card_repo, Some(&user_repo), &deck, now)
+            .await
+            .unwrap();
+
+        assert_eq!(limit, Some(1));
+    }
+
+    fn create_request(deck_id: Option<Uuid>) -> CreateCardRequest {
+        CreateCardRequest {
+            question: "hola".to_string(),
+            answer: "hello".to_string(),
+            deck_id,
+            tags: vec![],
+            required_keywords: vec![],
+            answer_type: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_card_in_missing_deck_is_not_found() {
+        let svc = CardService::new(
+            Arc::new(InMemoryCardRepo::new(vec![])),
+            Arc::new(FixedDeckRepo { deck: None }),
+            Arc::new(EventBus::new()),
+        );
+
+        let result = svc
+            .create_card(Uuid::new_v4(), create_request(Some(Uuid::new_v4())))
+            .await;
+
+        assert!(matches!(result, Err(crate::AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_card_in_another_users_deck_is_forbidden() {
+        let deck = Deck::new(Uuid::new_v4(), "Theirs".to_string(), None);
+        let deck_id = deck.id;
+        let svc = CardService::new(
+            Arc::new(InMemoryCardRepo::new(vec![])),
+            Arc::new(FixedDeckRepo { deck: Some(deck) }),
+            Arc::new(EventBus::new()),
+        );
+
+        let result = svc
+            .create_card(Uuid::new_v4(), create_request(Some(deck_id)))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::AppError::AuthorizationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_card_in_own_deck_gets_deck_tags() {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Mine".to_string(), None)
+            .with_default_tags(vec!["spanish".to_string()]);
+        let deck_id = deck.id;
+        let svc = CardService::new(
+            Arc::new(InMemoryCardRepo::new(vec![])),
+            Arc::new(FixedDeckRepo { deck: Some(deck) }),
+            Arc::new(EventBus::new()),
+        );
+
+        let card = svc
+            .create_card(user_id, create_request(Some(deck_id)))
+            .await
+            .unwrap();
+
+        assert_eq!(card.deck_id, Some(deck_id));
+        assert_eq!(card.tags, vec!["spanish".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_random_card_comes_from_requested_deck() {
+        let user_id = Uuid::new_v4();
+        let deck_id = Uuid::new_v4();
+        let in_deck =
+            Card::new(user_id, "hola".to_string(), "hello".to_string()).with_deck(deck_id);
+        let mut suspended =
+            Card::new(user_id, "adiós".to_string(), "bye".to_string()).with_deck(deck_id);
+        suspended.suspended = true;
+        let other_deck =
+            Card::new(user_id, "casa".to_string(), "house".to_string()).with_deck(Uuid::new_v4());
+        let no_deck = Card::new(user_id, "ser".to_string(), "to be".to_string());
+        let svc = CardService::new(
+            Arc::new(InMemoryCardRepo::new(vec![
+                in_deck.clone(),
+                suspended,
+                other_deck,
+                no_deck,
+            ])),
+            Arc::new(FixedDeckRepo { deck: None }),
+            Arc::new(EventBus::new()),
+        );
+
+        for _ in 0..20 {
+            let card = svc.get_random_card(user_id, Some(deck_id)).await.unwrap();
+            assert_eq!(card.id, in_deck.id);
+        }
+        assert!(svc.get_random_card(user_id, None).await.is_ok());
+        assert!(matches!(
+            svc.get_random_card(Uuid::new_v4(), None).await,
+            Err(crate::AppError::NotFound(_))
+        ));
+    }
+}
@@ -1,63 +1,547 @@
 use crate::{
-    domain::{entities::Deck, repositories::DeckRepository},
+    domain::{
+        entities::Deck,
+        fsrs,
+        language::{parse_language_tag, resolve_rtl, LanguageError},
+        repositories::DeckRepository,
+    },
+    shared::{
+        access::{Access, OwnershipPolicy},
+        quota::UserQuotas,
+    },
     AppResult,
 };
 use std::sync::Arc;
 use uuid::Uuid;
 
-use super::super::dtos::{CreateDeckRequest, DeckDto};
+use super::super::dtos::{CreateDeckRequest, DeckDto, DeckOptions, Page, UpdateDeckRequest};
 
 /// Deck service - handles deck operations
 pub struct DeckService {
     deck_repo: Arc<dyn DeckRepository>,
+    ownership_policy: OwnershipPolicy,
+    quotas: UserQuotas,
 }
 
 impl DeckService {
     pub fn new(deck_repo: Arc<dyn DeckRepository>) -> Self {
-        Self { deck_repo }
+        Self {
+            deck_repo,
+            ownership_policy: OwnershipPolicy::default(),
+            quotas: UserQuotas::default(),
+        }
+    }
+
+    /// How decks owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    /// Per-user deck limit enforced on creation
+    pub fn with_quotas(mut self, quotas: UserQuotas) -> Self {
+        self.quotas = quotas;
+        self
     }
 
     pub async fn create_deck(&self, user_id: Uuid, req: CreateDeckRequest) -> AppResult<DeckDto> {
-        let deck = Deck::new(user_id, req.name, req.description);
+        if self.quotas.max_decks.is_some() {
+            self.quotas
+                .check_new_deck(self.deck_repo.count_by_user(user_id).await?)?;
+        }
+        let mut deck = Deck::new(user_id, req.name, req.description);
+        if let Some(overrides) = req.scheduler_overrides {
+            overrides
+                .validate()
+                .map_err(|e| crate::AppError::ValidationError(e.to_string()))?;
+            deck = deck.with_scheduler_overrides(overrides);
+        }
+        deck = deck.with_default_tags(normalize_tags(req.default_tags));
+        if let Some(limit) = req.total_new_limit {
+            if limit <= 0 {
+                return Err(crate::AppError::ValidationError(
+                    "total_new_limit must be positive".to_string(),
+                ));
+            }
+            deck = deck.with_total_new_limit(Some(limit));
+        }
+        deck = deck
+            .with_ignored_answer_prefixes(normalize_tags(req.ignored_answer_prefixes))
+            .with_answer_normalization(req.answer_normalization.filter(|steps| !steps.is_empty()));
+        let language = match req.language {
+            Some(language) => parse_language_tag(&language).map_err(validation_error)?,
+            None => None,
+        };
+        let rtl = resolve_rtl(language.as_deref(), req.rtl).map_err(validation_error)?;
+        deck = deck
+            .with_language(language, rtl)
+            .with_review_mode(req.review_mode.unwrap_or_default());
         let deck_id = self.deck_repo.create(&deck).await?;
 
         Ok(DeckDto {
             id: deck_id,
-            user_id: deck.user_id,
-            name: deck.name,
-            description: deck.description,
-            created_at: deck.created_at,
-            updated_at: deck.updated_at,
+            ..DeckDto::from(deck)
         })
     }
 
-    pub async fn get_user_decks(&self, user_id: Uuid) -> AppResult<Vec<DeckDto>> {
-        let decks = self.deck_repo.find_by_user(user_id).await?;
+    /// One page of the user's decks, newest first
+    pub async fn get_user_decks(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Page<DeckDto>> {
+        let total = self.deck_repo.count_by_user(user_id).await?;
+        let decks = self
+            .deck_repo
+            .find_by_user_paginated(user_id, limit, offset)
+            .await?;
 
-        Ok(decks
-            .into_iter()
-            .map(|deck| DeckDto {
-                id: deck.id,
-                user_id: deck.user_id,
-                name: deck.name,
-                description: deck.description,
-                created_at: deck.created_at,
-                updated_at: deck.updated_at,
-            })
-            .collect())
+        Ok(Page {
+            items: decks.into_iter().map(DeckDto::from).collect(),
+            total,
+            limit,
+            offset,
+        })
     }
 
-    pub async fn delete_deck(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<()> {
-        let deck = self.deck_repo.find_by_id(deck_id).await?.ok_or_else(|| {
-            crate::AppError::NotFound(format!("Deck with id {} not found", deck_id))
-        })?;
+    pub async fn update_deck(
+        &self,
+        deck_id: Uuid,
+        user_id: Uuid,
+        req: UpdateDeckRequest,
+    ) -> AppResult<DeckDto> {
+        let mut deck = self.ownership_policy.authorize(
+            self.deck_repo.find_by_id(deck_id).await?,
+            |deck| deck.user_id,
+            user_id,
+            Access::Write,
+            "Deck",
+            deck_id,
+        )?;
+
+        if let Some(name) = req.name {
+            deck.name = name;
+        }
+        if let Some(description) = req.description {
+            deck.description = Some(description);
+        }
+        if let Some(overrides) = req.scheduler_overrides {
+            overrides
+                .validate()
+                .map_err(|e| crate::AppError::ValidationError(e.to_string()))?;
+            deck.scheduler_overrides = overrides;
+        }
+        if let Some(weights) = req.fsrs_weights {
+            deck.fsrs_weights = if weights.is_empty() {
+                None
+            } else {
+                fsrs::validate_weights(&weights)
+                    .map_err(|e| crate::AppError::ValidationError(e.to_string()))?;
+                Some(weights)
+            };
+        }
+        if let Some(tags) = req.default_tags {
+            deck.default_tags = normalize_tags(tags);
+        }
+        if let Some(limit) = req.total_new_limit {
+            if limit < 0 {
+                return Err(crate::AppError::ValidationError(
+                    "total_new_limit must not be negative".to_string(),
+                ));
+            }
+            deck.total_new_limit = (limit > 0).then_some(limit);
+        }
+        if let Some(prefixes) = req.ignored_answer_prefixes {
+            deck.ignored_answer_prefixes = normalize_tags(prefixes);
+        }
+        if let Some(steps) = req.answer_normalization {
+            deck.answer_normalization = (!steps.is_empty()).then_some(steps);
+        }
+        let language_changed = req.language.is_some();
+        if let Some(language) = req.language {
+            deck.language = parse_language_tag(&language).map_err(validation_error)?;
+        }
+        if language_changed || req.rtl.is_some() {
+            deck.rtl = resolve_rtl(deck.language.as_deref(), req.rtl).map_err(validation_error)?;
+        }
+        if let Some(review_mode) = req.review_mode {
+            deck.review_mode = review_mode;
+        }
+        deck.updated_at = chrono::Utc::now();
+
+        self.deck_repo.update(&deck).await?;
+
+        Ok(DeckDto::from(deck))
+    }
+
+    pub async fn get_deck_options(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<DeckOptions> {
+        let deck = self.ownership_policy.authorize(
+            self.deck_repo.find_by_id(deck_id).await?,
+            |deck| deck.user_id,
+            user_id,
+            Access::Read,
+            "Deck",
+            deck_id,
+        )?;
+
+        Ok(DeckOptions::from(&deck))
+    }
+
+    /// Replace the deck's study options. The whole bundle is validated
+    /// before anything is saved, so an invalid field leaves the deck as it
+    /// was.
+    pub async fn update_deck_options(
+        &self,
+        deck_id: Uuid,
+        user_id: Uuid,
+        options: DeckOptions,
+    ) -> AppResult<DeckOptions> {
+        let mut deck = self.ownership_policy.authorize(
+            self.deck_repo.find_by_id(deck_id).await?,
+            |deck| deck.user_id,
+            user_id,
+            Access::Write,
+            "Deck",
+            deck_id,
+        )?;
 
-        if deck.user_id != user_id {
-            return Err(crate::AppError::AuthorizationError(
-                "Cannot delete deck belonging to another user".to_string(),
+        let mut overrides = deck.scheduler_overrides.clone();
+        overrides.desired_retention = options.desired_retention;
+        overrides.maximum_interval_days = options.maximum_interval_days;
+        overrides
+            .validate()
+            .map_err(|e| crate::AppError::ValidationError(e.to_string()))?;
+        if options.new_cards_per_day.is_some_and(|limit| limit < 0) {
+            return Err(crate::AppError::ValidationError(
+                "new_cards_per_day must not be negative".to_string(),
             ));
         }
+        let language = match options.language {
+            Some(language) => parse_language_tag(&language).map_err(validation_error)?,
+            None => None,
+        };
+        let rtl = resolve_rtl(language.as_deref(), options.rtl).map_err(validation_error)?;
+
+        deck.scheduler_overrides = overrides;
+        deck.new_cards_per_day = options.new_cards_per_day;
+        deck.review_mode = options.review_mode;
+        deck.ignored_answer_prefixes = normalize_tags(options.ignored_answer_prefixes);
+        deck.answer_normalization = options
+            .answer_normalization
+            .filter(|steps| !steps.is_empty());
+        deck.language = language;
+        deck.rtl = rtl;
+        deck.updated_at = chrono::Utc::now();
+
+        self.deck_repo.update(&deck).await?;
+
+        Ok(DeckOptions::from(&deck))
+    }
+
+    /// Move the deck to the trash; its cards are kept but hidden until the
+    /// deck is restored
+    pub async fn delete_deck(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<()> {
+        let mut deck = self.ownership_policy.authorize(
+            self.deck_repo.find_by_id(deck_id).await?,
+            |deck| deck.user_id,
+            user_id,
+            Access::Write,
+            "Deck",
+            deck_id,
+        )?;
+
+        let now = chrono::Utc::now();
+        deck.deleted_at = Some(now);
+        deck.updated_at = now;
+        self.deck_repo.update(&deck).await
+    }
+}
+
+fn validation_error(err: LanguageError) -> crate::AppError {
+    crate::AppError::ValidationError(err.to_string())
+}
+
+/// Trim tags and drop blanks and duplicates, keeping the first occurrence
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !normalized.iter().any(|t| t == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    struct InMemoryDeckRepo {
+        decks: Mutex<Vec<Deck>>,
+    }
+
+    #[async_trait]
+    impl DeckRepository for InMemoryDeckRepo {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            self.decks.lock().unwrap().push(deck.clone());
+            Ok(deck.id)
+        }
+
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            Ok(self
+                .decks
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|d| d.id == id)
+                .cloned())
+        }
+
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(self
+                .decks
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|d| d.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn update(&self, deck: &Deck) -> AppResult<()> {
+            let mut decks = self.decks.lock().unwrap();
+            if let Some(existing) = decks.iter_mut().find(|d| d.id == deck.id) {
+                *existing = deck.clone();
+            }
+            Ok(())
+        }
+
+        async fn delete(&self, id: Uuid) -> AppResult<()> {
+            self.decks.lock().unwrap().retain(|d| d.id != id);
+            Ok(())
+        }
+    }
+
+    fn service_with_deck() -> (DeckService, Arc<InMemoryDeckRepo>, Deck) {
+        let deck = Deck::new(Uuid::new_v4(), "Spanish".to_string(), None);
+        let repo = Arc::new(InMemoryDeckRepo {
+            decks: Mutex::new(vec![deck.clone()]),
+        });
+        (DeckService::new(repo.clone()), repo, deck)
+    }
+
+    #[tokio::test]
+    async fn test_user_decks_are_paginated() {
+        let user_id = Uuid::new_v4();
+        let decks: Vec<Deck> = (0..4)
+            .map(|i| Deck::new(user_id, format!("Deck {}", i), None))
+            .collect();
+        let service = DeckService::new(Arc::new(InMemoryDeckRepo {
+            decks: Mutex::new(decks.clone()),
+        }));
+
+        let page = service.get_user_decks(user_id, 2, 1).await.unwrap();
+
+        let ids: Vec<Uuid> = page.items.iter().map(|deck| deck.id).collect();
+        assert_eq!(ids, vec![decks[1].id, decks[2].id]);
+        assert_eq!((page.total, page.limit, page.offset), (4, 2, 1));
+
+        let past_end = service.get_user_decks(user_id, 2, 10).await.unwrap();
+        assert!(past_end.items.is_empty());
+        assert_eq!(past_end.total, 4);
+    }
+
+    #[tokio::test]
+    async fn test_update_deck_sets_fsrs_weights() {
+        let (service, repo, deck) = service_with_deck();
+
+        let result = service
+            .update_deck(
+                deck.id,
+                deck.user_id,
+                UpdateDeckRequest {
+                    fsrs_weights: Some(fsrs::DEFAULT_WEIGHTS.to_vec()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.fsrs_weights.as_ref().map(Vec::len), Some(17));
+        let stored = repo.find_by_id(deck.id).await.unwrap().unwrap();
+        assert!(stored.fsrs_weights.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_update_deck_rejects_wrong_length_weights() {
+        let (service, _repo, deck) = service_with_deck();
+
+        let result = service
+            .update_deck(
+                deck.id,
+                deck.user_id,
+                UpdateDeckRequest {
+                    fsrs_weights: Some(vec![0.5; 10]),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert!(matches!(result, Err(crate::AppError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_deck_past_quota_is_rejected() {
+        let (service, repo, deck) = service_with_deck();
+        let service = service.with_quotas(UserQuotas {
+            max_decks: Some(2),
+            max_cards: None,
+        });
+        let request = || CreateDeckRequest {
+            name: "French".to_string(),
+            description: None,
+            scheduler_overrides: None,
+            default_tags: vec![],
+            total_new_limit: None,
+            ignored_answer_prefixes: vec![],
+            answer_normalization: None,
+            language: None,
+            rtl: None,
+            review_mode: None,
+        };
+
+        assert!(service.create_deck(deck.user_id, request()).await.is_ok());
+        let result = service.create_deck(deck.user_id, request()).await;
+
+        assert!(matches!(result, Err(crate::AppError::QuotaExceeded(_))));
+        assert_eq!(repo.count_by_user(deck.user_id).await.unwrap(), 2);
+        // Other users have their own quota
+        assert!(service.create_deck(Uuid::new_v4(), request()).await.is_ok());
+    }
+
+    fn create_request(language: Option<&str>, rtl: Option<bool>) -> CreateDeckRequest {
+        CreateDeckRequest {
+            name: "Vocabulary".to_string(),
+            description: None,
+            scheduler_overrides: None,
+            default_tags: vec![],
+            total_new_limit: None,
+            ignored_answer_prefixes: vec![],
+            answer_normalization: None,
+            language: language.map(String::from),
+            rtl,
+            review_mode: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_arabic_deck_defaults_to_rtl() {
+        let (service, repo, deck) = service_with_deck();
+
+        let created = service
+            .create_deck(deck.user_id, create_request(Some("ar"), None))
+            .await
+            .unwrap();
+
+        assert_eq!(created.language.as_deref(), Some("ar"));
+        assert!(created.rtl);
+        assert!(repo.find_by_id(created.id).await.unwrap().unwrap().rtl);
+    }
+
+    #[tokio::test]
+    async fn test_deck_rtl_must_match_language() {
+        let (service, _repo, deck) = service_with_deck();
+
+        let result = service
+            .create_deck(deck.user_id, create_request(Some("ar"), Some(false)))
+            .await;
+        assert!(matches!(result, Err(crate::AppError::ValidationError(_))));
+
+        // Changing only the language re-infers the direction
+        let updated = service
+            .update_deck(
+                deck.id,
+                deck.user_id,
+                UpdateDeckRequest {
+                    language: Some("he".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(updated.rtl);
+
+        let result = service
+            .update_deck(
+                deck.id,
+                deck.user_id,
+                UpdateDeckRequest {
+                    rtl: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(crate::AppError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_deck_options_round_trip() {
+        let (service, repo, deck) = service_with_deck();
+        let options = DeckOptions {
+            desired_retention: Some(0.85),
+            new_cards_per_day: Some(15),
+            maximum_interval_days: Some(365),
+            review_mode: crate::domain::entities::ReviewMode::SelfGrade,
+            ignored_answer_prefixes: vec!["the answer is".to_string()],
+            answer_normalization: Some(vec![
+                crate::domain::answer_normalization::NormalizationStep::Lowercase,
+            ]),
+            language: Some("ar".to_string()),
+            rtl: Some(true),
+        };
+
+        let updated = service
+            .update_deck_options(deck.id, deck.user_id, options.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(updated, options);
+        assert_eq!(
+            service
+                .get_deck_options(deck.id, deck.user_id)
+                .await
+                .unwrap(),
+            options
+        );
+        let stored = repo.find_by_id(deck.id).await.unwrap().unwrap();
+        assert_eq!(stored.scheduler_overrides.desired_retention, Some(0.85));
+        assert_eq!(stored.new_cards_per_day, Some(15));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_retention_rejects_whole_options_bundle() {
+        let (service, repo, deck) = service_with_deck();
+
+        let result = service
+            .update_deck_options(
+                deck.id,
+                deck.user_id,
+                DeckOptions {
+                    desired_retention: Some(1.5),
+                    new_cards_per_day: Some(15),
+                    language: Some("ar".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await;
 
-        self.deck_repo.delete(deck_id).await
+        assert!(matches!(result, Err(crate::AppError::ValidationError(_))));
+        let stored = repo.find_by_id(deck.id).await.unwrap().unwrap();
+        assert_eq!(stored.new_cards_per_day, None);
+        assert_eq!(stored.language, None);
     }
 }
@@ -1,11 +1,14 @@
 use crate::{
-    domain::{entities::User, repositories::UserRepository},
-    AppResult,
+    domain::{
+        entities::{User, UserPreferences},
+        repositories::UserRepository,
+    },
+    AppError, AppResult,
 };
 use std::sync::Arc;
 use uuid::Uuid;
 
-use super::super::dtos::{CreateUserRequest, UserDto};
+use super::super::dtos::{CreateUserRequest, UpdatePreferencesRequest, UserDto};
 
 /// User service - handles user-related operations
 /// SOLID: Single Responsibility - only handles user operations
@@ -33,7 +36,7 @@ impl UserService {
             .user_repo
             .find_by_id(user_id)
             .await?
-            .ok_or_else(|| crate::AppError::NotFound("User not found".to_string()))?;
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
         Ok(UserDto {
             id: user.id,
@@ -41,4 +44,101 @@ impl UserService {
             name: user.name,
         })
     }
+    pub async fn get_preferences(&self, user_id: Uuid) -> AppResult<UserPreferences> {
+        self.user_repo
+            .find_by_id(user_id)
+            .await?
+            .map(|user| user.preferences)
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+    }
+
+    /// Change the fields present in `update`, keeping the others
+    pub async fn update_preferences(
+        &self,
+        user_id: Uuid,
+        update: UpdatePreferencesRequest,
+    ) -> AppResult<UserPreferences> {
+        let mut user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        let preferences = update.apply(user.preferences);
+        preferences
+            .validate()
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        user.preferences = preferences;
+        user.updated_at = chrono::Utc::now();
+        self.user_repo.update(&user).await?;
+        Ok(preferences)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    struct InMemoryUserRepository(Mutex<User>);
+
+    #[async_trait]
+    impl UserRepository for InMemoryUserRepository {
+        async fn create(&self, user: &User) -> AppResult<Uuid> {
+            Ok(user.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<User>> {
+            let user = self.0.lock().unwrap();
+            Ok((user.id == id).then(|| user.clone()))
+        }
+        async fn find_by_email(&self, _email: &str) -> AppResult<Option<User>> {
+            Ok(None)
+        }
+        async fn update(&self, user: &User) -> AppResult<()> {
+            *self.0.lock().unwrap() = user.clone();
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_preferences_keeps_omitted_fields() {
+        let mut user = User::new("a@example.com".to_string(), "A".to_string());
+        user.preferences.utc_offset_minutes = 120;
+        user.preferences.daily_review_goal = Some(50);
+        let user_id = user.id;
+        let service = UserService::new(Arc::new(InMemoryUserRepository(Mutex::new(user))));
+
+        let updated = service
+            .update_preferences(
+                user_id,
+                serde_json::from_str(r#"{"ai_validation_enabled": false}"#).unwrap(),
+            )
+            .await
+            .unwrap();
+        let cleared = service
+            .update_preferences(
+                user_id,
+                serde_json::from_str(r#"{"daily_review_goal": null}"#).unwrap(),
+            )
+            .await
+            .unwrap();
+        let invalid = service
+            .update_preferences(
+                user_id,
+                serde_json::from_str(r#"{"day_cutoff_hour": 24}"#).unwrap(),
+            )
+            .await;
+
+        assert_eq!(updated.utc_offset_minutes, 120);
+        assert_eq!(updated.daily_review_goal, Some(50));
+        assert!(!updated.ai_validation_enabled);
+        assert_eq!(cleared.daily_review_goal, None);
+        assert!(!cleared.ai_validation_enabled);
+        assert!(matches!(invalid, Err(AppError::ValidationError(_))));
+        assert_eq!(service.get_preferences(user_id).await.unwrap(), cleared);
+    }
 }
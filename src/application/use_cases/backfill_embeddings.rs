@@ -0,0 +1,201 @@
+//! BackfillEmbeddings use case - queue embeddings for cards that never got one
+//! or whose embedding failed
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::{
+        dtos::EmbeddingBackfillDto,
+        use_cases::{
+            import_tsv::spawn_embedding_worker,
+            review_card::{ai_validation_enabled, AI_DISABLED},
+        },
+    },
+    domain::{
+        entities::EmbeddingStatus,
+        ports::EmbeddingService,
+        repositories::{CardRepository, UserRepository},
+    },
+    AppError, AppResult,
+};
+
+/// Statuses the backfill picks up
+pub(crate) const BACKFILL_STATUSES: [EmbeddingStatus; 2] =
+    [EmbeddingStatus::None, EmbeddingStatus::Failed];
+
+/// Use case for (re)generating a user's missing answer embeddings
+pub struct BackfillEmbeddingsUseCase {
+    card_repository: Arc<dyn CardRepository>,
+    embedding_service: Arc<dyn EmbeddingService>,
+    user_repository: Option<Arc<dyn UserRepository>>,
+}
+
+impl BackfillEmbeddingsUseCase {
+    pub fn new(
+        card_repository: Arc<dyn CardRepository>,
+        embedding_service: Arc<dyn EmbeddingService>,
+    ) -> Self {
+        Self {
+            card_repository,
+            embedding_service,
+            user_repository: None,
+        }
+    }
+
+    /// Refuse users who turned AI validation off in their preferences
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    /// Mark the user's `None`/`Failed` cards `Pending` and hand them to the
+    /// embedding worker, which runs in the background
+    pub async fn execute(&self, user_id: Uuid) -> AppResult<EmbeddingBackfillDto> {
+        if !ai_validation_enabled(self.user_repository.as_deref(), user_id).await? {
+            return Err(AppError::ValidationError(AI_DISABLED.to_string()));
+        }
+        let cards = self
+            .card_repository
+            .find_by_embedding_status(user_id, &BACKFILL_STATUSES)
+            .await?;
+        for card in &cards {
+            self.card_repository
+                .set_embedding_status(card.id, EmbeddingStatus::Pending)
+                .await?;
+        }
+
+        let queued = cards.len();
+        if queued > 0 {
+            spawn_embedding_worker(
+                cards
+                    .into_iter()
+                    .map(|card| (card.id, card.answer))
+                    .collect(),
+                self.card_repository.clone(),
+                self.embedding_service.clone(),
+            );
+        }
+        Ok(EmbeddingBackfillDto { queued })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::use_cases::test_support::SingleUserRepository;
+    use crate::domain::entities::Card;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    struct StubCardRepo(Mutex<Vec<Card>>);
+
+    #[async_trait]
+    impl CardRepository for StubCardRepo {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Card>> {
+            Ok(self.0.lock().unwrap().iter().find(|c| c.id == id).cloned())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|c| c.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn set_embedding_status(&self, id: Uuid, status: EmbeddingStatus) -> AppResult<()> {
+            for card in self.0.lock().unwrap().iter_mut().filter(|c| c.id == id) {
+                card.embedding_status = status;
+            }
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Never answers, so queued cards stay `Pending` for the assertions
+    struct PendingEmbeddingService;
+
+    #[async_trait]
+    impl EmbeddingService for PendingEmbeddingService {
+        async fn generate_embedding(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queues_missing_and_failed_embeddings() {
+        let user_id = Uuid::new_v4();
+        let card = |status| {
+            let mut card = Card::new(user_id, "Q".to_string(), "A".to_string());
+            card.embedding_status = status;
+            card
+        };
+        let repo = Arc::new(StubCardRepo(Mutex::new(vec![
+            card(EmbeddingStatus::None),
+            card(EmbeddingStatus::Failed),
+            card(EmbeddingStatus::Ready),
+            card(EmbeddingStatus::Pending),
+        ])));
+        let use_case =
+            BackfillEmbeddingsUseCase::new(repo.clone(), Arc::new(PendingEmbeddingService));
+
+        let result = use_case.execute(user_id).await.unwrap();
+
+        assert_eq!(result.queued, 2);
+        let statuses: Vec<_> = repo
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|c| c.embedding_status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                EmbeddingStatus::Pending,
+                EmbeddingStatus::Pending,
+                EmbeddingStatus::Ready,
+                EmbeddingStatus::Pending,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refuses_users_who_turned_ai_off() {
+        let users = Arc::new(SingleUserRepository::ai_opted_out());
+        let user_id = users.user_id();
+        let mut card = Card::new(user_id, "Q".to_string(), "A".to_string());
+        card.embedding_status = EmbeddingStatus::None;
+        let repo = Arc::new(StubCardRepo(Mutex::new(vec![card])));
+        let use_case =
+            BackfillEmbeddingsUseCase::new(repo.clone(), Arc::new(PendingEmbeddingService))
+                .with_user_repository(users);
+
+        let result = use_case.execute(user_id).await;
+
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+        assert_eq!(
+            repo.0.lock().unwrap()[0].embedding_status,
+            EmbeddingStatus::None
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! ChangeEmail use case - update a user's email after re-checking their password.
+
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::{ChangeEmailRequest, UserDto},
+    domain::{repositories::UserRepository, value_objects::Password},
+    shared::error::{AppError, AppResult},
+};
+
+pub struct ChangeEmailUseCase {
+    user_repo: Arc<dyn UserRepository>,
+}
+
+impl ChangeEmailUseCase {
+    pub fn new(user_repo: Arc<dyn UserRepository>) -> Self {
+        Self { user_repo }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, req: ChangeEmailRequest) -> AppResult<UserDto> {
+        let mut user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User with id {} not found", user_id)))?;
+
+        let hash = user.password_hash.as_deref().ok_or_else(|| {
+            AppError::AuthenticationError("Current password is incorrect".to_string())
+        })?;
+        if !Password::candidate(req.current_password).verify(hash)? {
+            return Err(AppError::AuthenticationError(
+                "Current password is incorrect".to_string(),
+            ));
+        }
+
+        let new_email = req.new_email.trim().to_string();
+        if !is_valid_email(&new_email) {
+            return Err(AppError::ValidationError(format!(
+                "Invalid email address: {}",
+                new_email
+            )));
+        }
+
+        if let Some(existing) = self.user_repo.find_by_email(&new_email).await? {
+            if existing.id != user_id {
+                return Err(AppError::Conflict(
+                    "An account with this email already exists".to_string(),
+                ));
+            }
+        }
+
+        user.email = new_email;
+        user.updated_at = chrono::Utc::now();
+        self.user_repo.update(&user).await?;
+
+        Ok(UserDto {
+            id: user.id,
+            email: user.email,
+            name: user.name,
+        })
+    }
+}
+
+/// Minimal shape check: one `@` with a non-empty local part and a dotted domain
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !email.chars().any(char::is_whitespace)
+        && !domain.contains('@')
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|part| !part.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::User;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    struct InMemoryUserRepo {
+        users: Mutex<Vec<User>>,
+    }
+
+    #[async_trait]
+    impl UserRepository for InMemoryUserRepo {
+        async fn create(&self, user: &User) -> AppResult<Uuid> {
+            self.users.lock().unwrap().push(user.clone());
+            Ok(user.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<User>> {
+            Ok(self
+                .users
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|u| u.id == id)
+                .cloned())
+        }
+        async fn find_by_email(&self, email: &str) -> AppResult<Option<User>> {
+            Ok(self
+                .users
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|u| u.email == email)
+                .cloned())
+        }
+        async fn update(&self, user: &User) -> AppResult<()> {
+            let mut users = self.users.lock().unwrap();
+            if let Some(existing) = users.iter_mut().find(|u| u.id == user.id) {
+                *existing = user.clone();
+            }
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    fn setup() -> (ChangeEmailUseCase, Arc<InMemoryUserRepo>, User) {
+        let alice = User::new_with_password(
+            "alice@example.com".to_string(),
+            "Alice".to_string(),
+            Password::candidate("correctpassword".to_string())
+                .hash()
+                .unwrap(),
+        );
+        let bob = User::new("bob@example.com".to_string(), "Bob".to_string());
+        let repo = Arc::new(InMemoryUserRepo {
+            users: Mutex::new(vec![alice.clone(), bob]),
+        });
+        (ChangeEmailUseCase::new(repo.clone()), repo, alice)
+    }
+
+    fn request(new_email: &str, current_password: &str) -> ChangeEmailRequest {
+        ChangeEmailRequest {
+            new_email: new_email.to_string(),
+            current_password: current_password.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_change_email_success() {
+        let (uc, repo, alice) = setup();
+
+        let result = uc
+            .execute(
+                alice.id,
+                request("alice@new.example.com", "correctpassword"),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.email, "alice@new.example.com");
+        let stored = repo.find_by_id(alice.id).await.unwrap().unwrap();
+        assert_eq!(stored.email, "alice@new.example.com");
+    }
+
+    #[tokio::test]
+    async fn test_change_email_wrong_password_rejected() {
+        let (uc, repo, alice) = setup();
+
+        let result = uc
+            .execute(alice.id, request("alice@new.example.com", "wrongpassword"))
+            .await;
+
+        assert!(matches!(result, Err(AppError::AuthenticationError(_))));
+        let stored = repo.find_by_id(alice.id).await.unwrap().unwrap();
+        assert_eq!(stored.email, "alice@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_change_email_duplicate_returns_conflict() {
+        let (uc, _repo, alice) = setup();
+
+        let result = uc
+            .execute(alice.id, request("bob@example.com", "correctpassword"))
+            .await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[test]
+    fn test_is_valid_email() {
+        assert!(is_valid_email("user@example.com"));
+        assert!(!is_valid_email("user.example.com"));
+        assert!(!is_valid_email("@example.com"));
+        assert!(!is_valid_email("user@localhost"));
+        assert!(!is_valid_email("user@@example.com"));
+        assert!(!is_valid_email("us er@example.com"));
+    }
+}
@@ -0,0 +1,141 @@
+//! ChangePassword use case - rotate a logged-in user's password.
+
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::ChangePasswordRequest,
+    domain::{repositories::UserRepository, value_objects::Password},
+    shared::error::{AppError, AppResult},
+};
+
+pub struct ChangePasswordUseCase {
+    user_repo: Arc<dyn UserRepository>,
+}
+
+impl ChangePasswordUseCase {
+    pub fn new(user_repo: Arc<dyn UserRepository>) -> Self {
+        Self { user_repo }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, req: ChangePasswordRequest) -> AppResult<()> {
+        let mut user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User with id {} not found", user_id)))?;
+
+        let hash = user.password_hash.as_deref().ok_or_else(|| {
+            AppError::AuthenticationError("Current password is incorrect".to_string())
+        })?;
+        if !Password::candidate(req.current_password).verify(hash)? {
+            return Err(AppError::AuthenticationError(
+                "Current password is incorrect".to_string(),
+            ));
+        }
+
+        let new_password = Password::new(req.new_password)?;
+
+        user.password_hash = Some(new_password.hash()?);
+        user.updated_at = chrono::Utc::now();
+        self.user_repo.update(&user).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::User;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    struct SingleUserRepo {
+        user: Mutex<User>,
+    }
+
+    #[async_trait]
+    impl UserRepository for SingleUserRepo {
+        async fn create(&self, user: &User) -> AppResult<Uuid> {
+            Ok(user.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<User>> {
+            let user = self.user.lock().unwrap();
+            Ok((user.id == id).then(|| user.clone()))
+        }
+        async fn find_by_email(&self, _email: &str) -> AppResult<Option<User>> {
+            Ok(None)
+        }
+        async fn update(&self, user: &User) -> AppResult<()> {
+            *self.user.lock().unwrap() = user.clone();
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    fn setup() -> (ChangePasswordUseCase, Arc<SingleUserRepo>, Uuid) {
+        let user = User::new_with_password(
+            "alice@example.com".to_string(),
+            "Alice".to_string(),
+            Password::new("oldpassword".to_string())
+                .unwrap()
+                .hash()
+                .unwrap(),
+        );
+        let user_id = user.id;
+        let repo = Arc::new(SingleUserRepo {
+            user: Mutex::new(user),
+        });
+        (ChangePasswordUseCase::new(repo.clone()), repo, user_id)
+    }
+
+    fn verifies(password: &str, hash: &str) -> bool {
+        Password::candidate(password.to_string())
+            .verify(hash)
+            .unwrap()
+    }
+
+    fn request(current_password: &str, new_password: &str) -> ChangePasswordRequest {
+        ChangePasswordRequest {
+            current_password: current_password.to_string(),
+            new_password: new_password.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_change_password_rotates_hash() {
+        let (uc, repo, user_id) = setup();
+
+        uc.execute(user_id, request("oldpassword", "newpassword"))
+            .await
+            .unwrap();
+
+        let hash = repo.user.lock().unwrap().password_hash.clone().unwrap();
+        assert!(verifies("newpassword", &hash));
+        assert!(!verifies("oldpassword", &hash));
+    }
+
+    #[tokio::test]
+    async fn test_change_password_wrong_current_password() {
+        let (uc, repo, user_id) = setup();
+
+        let result = uc
+            .execute(user_id, request("wrongpassword", "newpassword"))
+            .await;
+
+        assert!(matches!(result, Err(AppError::AuthenticationError(_))));
+        let hash = repo.user.lock().unwrap().password_hash.clone().unwrap();
+        assert!(verifies("oldpassword", &hash));
+    }
+
+    #[tokio::test]
+    async fn test_change_password_enforces_policy() {
+        let (uc, _repo, user_id) = setup();
+
+        let result = uc.execute(user_id, request("oldpassword", "short")).await;
+
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+}
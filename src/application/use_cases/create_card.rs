@@ -4,9 +4,14 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
-    domain::{entities::Card, ports::EmbeddingService, repositories::CardRepository},
+    application::use_cases::review_card::ai_validation_enabled,
+    domain::{
+        entities::Card,
+        ports::EmbeddingService,
+        repositories::{CardRepository, UserRepository},
+    },
     shared::{
-        error::AppResult,
+        error::{AppError, AppResult},
         event_bus::{DomainEvent, EventBus},
     },
 };
@@ -20,6 +25,8 @@ where
     card_repository: Arc<R>,
     embedding_service: Arc<E>,
     event_bus: Arc<EventBus>,
+    require_embedding: bool,
+    user_repository: Option<Arc<dyn UserRepository>>,
 }
 
 impl<R, E> CreateCardUseCase<R, E>
@@ -36,9 +43,25 @@ where
             card_repository,
             embedding_service,
             event_bus,
+            require_embedding: false,
+            user_repository: None,
         }
     }
 
+    /// Fail the create with `ExternalApiError` instead of storing the card
+    /// without an embedding when none can be generated
+    pub fn with_require_embedding(mut self, require_embedding: bool) -> Self {
+        self.require_embedding = require_embedding;
+        self
+    }
+
+    /// Answers of users who turned AI validation off are not embedded, even
+    /// when an embedding is required
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
     /// Execute the use case: create a card and generate its answer embedding
     pub async fn execute(
         &self,
@@ -55,16 +78,34 @@ where
             card = card.with_deck(deck_id);
         }
 
-        // Generate embedding for the answer
-        match self.embedding_service.generate_embedding(&answer).await {
-            Ok(embedding) => {
-                card = card.with_embedding(embedding);
-                tracing::info!("Generated embedding for card answer");
+        // Generate embedding for the answer, unless the user turned AI off
+        let failure = if !ai_validation_enabled(self.user_repository.as_deref(), user_id).await? {
+            None
+        } else {
+            match self.embedding_service.generate_embedding(&answer).await {
+                Ok(embedding) => match card.clone().with_embedding(embedding) {
+                    Ok(with_embedding) => {
+                        card = with_embedding;
+                        tracing::info!("Generated embedding for card answer");
+                        None
+                    }
+                    Err(e) => Some(format!("invalid embedding: {}", e)),
+                },
+                Err(e) => Some(e.to_string()),
             }
-            Err(e) => {
-                tracing::warn!("Failed to generate embedding: {}, continuing without it", e);
-                // Continue without embedding - it's not critical for card creation
+        };
+        if let Some(failure) = failure {
+            if self.require_embedding {
+                return Err(AppError::ExternalApiError(format!(
+                    "Failed to generate embedding: {}",
+                    failure
+                )));
             }
+            // Continue without embedding - it's not critical for card creation
+            tracing::warn!(
+                "Failed to generate embedding: {}, continuing without it",
+                failure
+            );
         }
 
         // Save to repository
@@ -86,15 +127,28 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::application::use_cases::test_support::SingleUserRepository;
     use async_trait::async_trait;
 
     struct MockCardRepository {
         card_id: Uuid,
+        created: std::sync::atomic::AtomicUsize,
+    }
+
+    impl MockCardRepository {
+        fn new(card_id: Uuid) -> Self {
+            Self {
+                card_id,
+                created: Default::default(),
+            }
+        }
     }
 
     #[async_trait]
     impl CardRepository for MockCardRepository {
         async fn create(&self, _card: &Card) -> AppResult<Uuid> {
+            self.created
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
             Ok(self.card_id)
         }
 
@@ -135,7 +189,7 @@ mod tests {
     impl EmbeddingService for MockEmbeddingService {
         async fn generate_embedding(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
             if self.should_succeed {
-                Ok(vec![0.1, 0.2, 0.3, 0.4, 0.5])
+                Ok(vec![0.1; crate::domain::embedding::EMBEDDING_DIMENSIONS])
             } else {
                 Err(anyhow::anyhow!("Embedding service failed"))
             }
@@ -145,9 +199,7 @@ mod tests {
     #[tokio::test]
     async fn test_create_card_with_embedding_success() {
         let expected_card_id = Uuid::new_v4();
-        let card_repo = Arc::new(MockCardRepository {
-            card_id: expected_card_id,
-        });
+        let card_repo = Arc::new(MockCardRepository::new(expected_card_id));
         let embedding_service = Arc::new(MockEmbeddingService {
             should_succeed: true,
         });
@@ -168,9 +220,7 @@ mod tests {
     #[tokio::test]
     async fn test_create_card_without_deck() {
         let expected_card_id = Uuid::new_v4();
-        let card_repo = Arc::new(MockCardRepository {
-            card_id: expected_card_id,
-        });
+        let card_repo = Arc::new(MockCardRepository::new(expected_card_id));
         let embedding_service = Arc::new(MockEmbeddingService {
             should_succeed: true,
         });
@@ -190,14 +240,12 @@ mod tests {
     #[tokio::test]
     async fn test_create_card_embedding_failure_continues() {
         let expected_card_id = Uuid::new_v4();
-        let card_repo = Arc::new(MockCardRepository {
-            card_id: expected_card_id,
-        });
+        let card_repo = Arc::new(MockCardRepository::new(expected_card_id));
         let embedding_service = Arc::new(MockEmbeddingService {
             should_succeed: false,
         });
         let event_bus = Arc::new(EventBus::new());
-        let use_case = CreateCardUseCase::new(card_repo, embedding_service, event_bus);
+        let use_case = CreateCardUseCase::new(card_repo.clone(), embedding_service, event_bus);
 
         let user_id = Uuid::new_v4();
         let question = "Test question".to_string();
@@ -208,5 +256,79 @@ mod tests {
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), expected_card_id);
+        assert_eq!(
+            card_repo.created.load(std::sync::atomic::Ordering::SeqCst),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_card_require_embedding_fails_without_creating() {
+        let card_repo = Arc::new(MockCardRepository::new(Uuid::new_v4()));
+        let embedding_service = Arc::new(MockEmbeddingService {
+            should_succeed: false,
+        });
+        let use_case = CreateCardUseCase::new(
+            card_repo.clone(),
+            embedding_service,
+            Arc::new(EventBus::new()),
+        )
+        .with_require_embedding(true);
+
+        let result = use_case
+            .execute(
+                Uuid::new_v4(),
+                None,
+                "Test question".to_string(),
+                "Test answer".to_string(),
+            )
+            .await;
+
+        assert!(matches!(result, Err(AppError::ExternalApiError(_))));
+        assert_eq!(
+            card_repo.created.load(std::sync::atomic::Ordering::SeqCst),
+            0
+        );
+
+        let working = CreateCardUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockEmbeddingService {
+                should_succeed: true,
+            }),
+            Arc::new(EventBus::new()),
+        )
+        .with_require_embedding(true);
+        assert!(working
+            .execute(Uuid::new_v4(), None, "Q".to_string(), "A".to_string())
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_card_skips_embedding_for_ai_opted_out_user() {
+        let users = Arc::new(SingleUserRepository::ai_opted_out());
+        let user_id = users.user_id();
+        let card_repo = Arc::new(MockCardRepository::new(Uuid::new_v4()));
+        // Would fail the create if the answer were sent to it
+        let embedding_service = Arc::new(MockEmbeddingService {
+            should_succeed: false,
+        });
+        let use_case = CreateCardUseCase::new(
+            card_repo.clone(),
+            embedding_service,
+            Arc::new(EventBus::new()),
+        )
+        .with_require_embedding(true)
+        .with_user_repository(users);
+
+        let result = use_case
+            .execute(user_id, None, "Q".to_string(), "A".to_string())
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(
+            card_repo.created.load(std::sync::atomic::Ordering::SeqCst),
+            1
+        );
     }
 }
@@ -0,0 +1,69 @@
+//! DeadLetters use case - list events whose handler failed every retry and
+//! replay them once the cause is fixed
+
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::DeadLetterDto,
+    domain::repositories::DeadLetterRepository,
+    shared::{
+        error::{AppError, AppResult},
+        event_bus::EventBus,
+    },
+};
+
+/// Use case for inspecting and replaying dead-lettered domain events
+pub struct DeadLettersUseCase {
+    dead_letter_repository: Arc<dyn DeadLetterRepository>,
+    event_bus: Arc<EventBus>,
+}
+
+impl DeadLettersUseCase {
+    pub fn new(
+        dead_letter_repository: Arc<dyn DeadLetterRepository>,
+        event_bus: Arc<EventBus>,
+    ) -> Self {
+        Self {
+            dead_letter_repository,
+            event_bus,
+        }
+    }
+
+    /// Events not replayed yet, newest first
+    pub async fn list(&self, limit: i64, offset: i64) -> AppResult<Vec<DeadLetterDto>> {
+        let dead_letters = self
+            .dead_letter_repository
+            .list_pending(limit, offset)
+            .await?;
+        Ok(dead_letters.into_iter().map(DeadLetterDto::from).collect())
+    }
+
+    /// Run the failed handler on the event again. The event stays
+    /// dead-lettered when the handler fails again.
+    pub async fn replay(&self, id: Uuid) -> AppResult<DeadLetterDto> {
+        let mut dead_letter = self
+            .dead_letter_repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Dead-lettered event {} not found", id)))?;
+        if dead_letter.replayed_at.is_some() {
+            return Err(AppError::Conflict(format!(
+                "Dead-lettered event {} was already replayed",
+                id
+            )));
+        }
+
+        self.event_bus
+            .replay(&dead_letter.handler, dead_letter.event.clone())
+            .await?;
+
+        let replayed_at = Utc::now();
+        self.dead_letter_repository
+            .mark_replayed(id, replayed_at)
+            .await?;
+        dead_letter.replayed_at = Some(replayed_at);
+        Ok(DeadLetterDto::from(dead_letter))
+    }
+}
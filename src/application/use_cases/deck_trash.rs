@@ -0,0 +1,323 @@
+//! DeckTrash use case - list decks moved to the trash and restore them
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::DeckDto,
+    domain::repositories::{CardRepository, DeckRepository},
+    shared::{
+        error::{AppError, AppResult},
+        quota::UserQuotas,
+    },
+};
+
+/// Use case for the trash of soft-deleted decks
+pub struct DeckTrashUseCase {
+    deck_repository: Arc<dyn DeckRepository>,
+    card_repository: Option<Arc<dyn CardRepository>>,
+    quotas: UserQuotas,
+}
+
+impl DeckTrashUseCase {
+    pub fn new(deck_repository: Arc<dyn DeckRepository>) -> Self {
+        Self {
+            deck_repository,
+            card_repository: None,
+            quotas: UserQuotas::default(),
+        }
+    }
+
+    /// Per-user deck and card limits enforced on restore, since trashed decks
+    /// and their cards don't count against them. The card limit needs the
+    /// card repository.
+    pub fn with_quotas(
+        mut self,
+        quotas: UserQuotas,
+        card_repository: Arc<dyn CardRepository>,
+    ) -> Self {
+        self.quotas = quotas;
+        self.card_repository = Some(card_repository);
+        self
+    }
+
+    /// The user's trashed decks, most recently deleted first
+    pub async fn list(&self, user_id: Uuid) -> AppResult<Vec<DeckDto>> {
+        let decks = self.deck_repository.find_deleted_by_user(user_id).await?;
+        Ok(decks.into_iter().map(DeckDto::from).collect())
+    }
+
+    /// Take the deck out of the trash; its cards show up again with it.
+    /// Fails with `AppError::QuotaExceeded` when the user has since reached
+    /// the deck limit or the deck's cards would exceed the card limit.
+    pub async fn restore(&self, user_id: Uuid, deck_id: Uuid) -> AppResult<DeckDto> {
+        let mut deck = self
+            .deck_repository
+            .find_deleted_by_user(user_id)
+            .await?
+            .into_iter()
+            .find(|deck| deck.id == deck_id)
+            .ok_or_else(|| AppError::NotFound(format!("Deck {} is not in the trash", deck_id)))?;
+
+        if self.quotas.max_decks.is_some() {
+            self.quotas
+                .check_new_deck(self.deck_repository.count_by_user(user_id).await?)?;
+        }
+        if let (Some(max), Some(card_repository)) = (self.quotas.max_cards, &self.card_repository) {
+            let cards = card_repository.count_by_user(user_id).await?
+                + card_repository.count_by_deck(deck_id).await?;
+            if cards > max {
+                return Err(AppError::QuotaExceeded(format!(
+                    "Restoring the deck would exceed the card limit of {} per user",
+                    max
+                )));
+            }
+        }
+
+        deck.deleted_at = None;
+        deck.updated_at = chrono::Utc::now();
+        self.deck_repository.update(&deck).await?;
+        Ok(DeckDto::from(deck))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        application::{services::DeckService, use_cases::StudySessionUseCase},
+        domain::{
+            entities::{Card, Deck},
+            repositories::CardRepository,
+        },
+    };
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Hides trashed decks like `PgDeckRepository`
+    #[derive(Default)]
+    struct InMemoryDeckRepo {
+        decks: Mutex<Vec<Deck>>,
+    }
+
+    #[async_trait]
+    impl DeckRepository for InMemoryDeckRepo {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            self.decks.lock().unwrap().push(deck.clone());
+            Ok(deck.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            Ok(self
+                .decks
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|d| d.id == id && d.deleted_at.is_none())
+                .cloned())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(self
+                .decks
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|d| d.user_id == user_id && d.deleted_at.is_none())
+                .cloned()
+                .collect())
+        }
+        async fn find_deleted_by_user(&self, user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(self
+                .decks
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|d| d.user_id == user_id && d.deleted_at.is_some())
+                .cloned()
+                .collect())
+        }
+        async fn update(&self, deck: &Deck) -> AppResult<()> {
+            for d in self.decks.lock().unwrap().iter_mut() {
+                if d.id == deck.id {
+                    *d = deck.clone();
+                }
+            }
+            Ok(())
+        }
+        async fn delete(&self, id: Uuid) -> AppResult<()> {
+            self.decks.lock().unwrap().retain(|d| d.id != id);
+            Ok(())
+        }
+    }
+
+    struct InMemoryCardRepo {
+        cards: Vec<Card>,
+    }
+
+    #[async_trait]
+    impl CardRepository for InMemoryCardRepo {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Card>> {
+            Ok(self.cards.iter().find(|c| c.id == id).cloned())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(self
+                .cards
+                .iter()
+                .filter(|c| c.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn count_by_user(&self, user_id: Uuid) -> AppResult<i64> {
+            // Like `PgCardRepository`, only this test's deck can be trashed
+            Ok(self
+                .cards
+                .iter()
+                .filter(|c| c.user_id == user_id && c.deck_id.is_none())
+                .count() as i64)
+        }
+        async fn find_by_deck(&self, deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(self
+                .cards
+                .iter()
+                .filter(|c| c.deck_id == Some(deck_id))
+                .cloned()
+                .collect())
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_trashed_deck_and_cards_hidden_until_restored() {
+        let deck = Deck::new(Uuid::new_v4(), "Spanish".to_string(), None);
+        let user_id = deck.user_id;
+        let deck_repo = Arc::new(InMemoryDeckRepo::default());
+        deck_repo.create(&deck).await.unwrap();
+        let card_repo = Arc::new(InMemoryCardRepo {
+            cards: vec![
+                Card::new(user_id, "Hola".to_string(), "Hello".to_string()).with_deck(deck.id)
+            ],
+        });
+        let decks = DeckService::new(deck_repo.clone());
+        let study = StudySessionUseCase::new(card_repo, deck_repo.clone());
+        let trash = DeckTrashUseCase::new(deck_repo.clone());
+
+        decks.delete_deck(deck.id, user_id).await.unwrap();
+
+        assert!(decks
+            .get_user_decks(user_id, 50, 0)
+            .await
+            .unwrap()
+            .items
+            .is_empty());
+        assert!(matches!(
+            study.start(user_id, deck.id).await,
+            Err(AppError::NotFound(_))
+        ));
+        let trashed = trash.list(user_id).await.unwrap();
+        assert_eq!(trashed.len(), 1);
+        assert!(trashed[0].deleted_at.is_some());
+
+        let restored = trash.restore(user_id, deck.id).await.unwrap();
+
+        assert!(restored.deleted_at.is_none());
+        assert_eq!(
+            decks
+                .get_user_decks(user_id, 50, 0)
+                .await
+                .unwrap()
+                .items
+                .len(),
+            1
+        );
+        assert!(trash.list(user_id).await.unwrap().is_empty());
+        let session = study.start(user_id, deck.id).await.unwrap();
+        assert_eq!(session.total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_restore_requires_a_trashed_deck_of_the_user() {
+        let deck = Deck::new(Uuid::new_v4(), "Spanish".to_string(), None);
+        let deck_repo = Arc::new(InMemoryDeckRepo::default());
+        deck_repo.create(&deck).await.unwrap();
+        let trash = DeckTrashUseCase::new(deck_repo.clone());
+
+        // Not in the trash
+        let result = trash.restore(deck.user_id, deck.id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        DeckService::new(deck_repo)
+            .delete_deck(deck.id, deck.user_id)
+            .await
+            .unwrap();
+        let result = trash.restore(Uuid::new_v4(), deck.id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_restore_respects_deck_and_card_quotas() {
+        let user_id = Uuid::new_v4();
+        let trashed = Deck::new(user_id, "Old".to_string(), None);
+        let deck_repo = Arc::new(InMemoryDeckRepo::default());
+        deck_repo.create(&trashed).await.unwrap();
+        let card_repo = Arc::new(InMemoryCardRepo {
+            cards: vec![
+                Card::new(user_id, "Hola".to_string(), "Hello".to_string()).with_deck(trashed.id),
+                Card::new(user_id, "Casa".to_string(), "House".to_string()),
+            ],
+        });
+        let decks = DeckService::new(deck_repo.clone());
+        decks.delete_deck(trashed.id, user_id).await.unwrap();
+        let other = Deck::new(user_id, "New".to_string(), None);
+        deck_repo.create(&other).await.unwrap();
+
+        let deck_limited = DeckTrashUseCase::new(deck_repo.clone()).with_quotas(
+            UserQuotas {
+                max_decks: Some(1),
+                max_cards: None,
+            },
+            card_repo.clone(),
+        );
+        assert!(matches!(
+            deck_limited.restore(user_id, trashed.id).await,
+            Err(AppError::QuotaExceeded(_))
+        ));
+
+        let card_limited = DeckTrashUseCase::new(deck_repo.clone()).with_quotas(
+            UserQuotas {
+                max_decks: Some(2),
+                max_cards: Some(1),
+            },
+            card_repo.clone(),
+        );
+        assert!(matches!(
+            card_limited.restore(user_id, trashed.id).await,
+            Err(AppError::QuotaExceeded(_))
+        ));
+        assert_eq!(
+            deck_repo.find_deleted_by_user(user_id).await.unwrap().len(),
+            1
+        );
+
+        let within_limits = DeckTrashUseCase::new(deck_repo.clone()).with_quotas(
+            UserQuotas {
+                max_decks: Some(2),
+                max_cards: Some(2),
+            },
+            card_repo,
+        );
+        assert!(within_limits.restore(user_id, trashed.id).await.is_ok());
+    }
+}
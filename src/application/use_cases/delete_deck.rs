@@ -5,7 +5,8 @@ use crate::domain::repositories::DeckRepository;
 use crate::AppResult;
 
 /// Use case for deleting a deck
-/// Note: Cards in the deck will have their deck_id set to NULL (ON DELETE SET NULL)
+/// Note: the deck is moved to the trash (`deleted_at`); its cards are kept but
+/// hidden until it is restored with `DeckTrashUseCase`
 pub struct DeleteDeckUseCase {
     deck_repository: Arc<dyn DeckRepository>,
 }
@@ -20,9 +21,11 @@ impl DeleteDeckUseCase {
         let deck = self.deck_repository.find_by_id(deck_id).await?;
 
         match deck {
-            Some(d) if d.user_id == user_id => {
-                self.deck_repository.delete(deck_id).await?;
-                Ok(())
+            Some(mut d) if d.user_id == user_id => {
+                let now = chrono::Utc::now();
+                d.deleted_at = Some(now);
+                d.updated_at = now;
+                self.deck_repository.update(&d).await
             }
             Some(_) => Err(crate::AppError::AuthorizationError(
                 "Cannot delete deck belonging to another user".to_string(),
@@ -114,9 +117,9 @@ mod tests {
 
         assert!(result.is_ok());
 
-        // Verify deck was deleted
-        let found = repo.find_by_id(deck_id).await.unwrap();
-        assert!(found.is_none());
+        // Verify deck was moved to the trash
+        let found = repo.find_by_id(deck_id).await.unwrap().unwrap();
+        assert!(found.deleted_at.is_some());
     }
 
     #[tokio::test]
@@ -152,7 +155,7 @@ mod tests {
         ));
 
         // Verify deck was NOT deleted
-        let found = repo.find_by_id(deck_id).await.unwrap();
-        assert!(found.is_some());
+        let found = repo.find_by_id(deck_id).await.unwrap().unwrap();
+        assert!(found.deleted_at.is_none());
     }
 }
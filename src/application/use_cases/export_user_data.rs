@@ -0,0 +1,402 @@
+//! ExportUserData use case - build a JSON export of a user's data in the background
+
+use bytes::Bytes;
+use chrono::Utc;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::{ExportJobDto, ExportJobStatus, UserDataExport},
+    domain::repositories::{CardRepository, DeckRepository, ReviewLogRepository},
+    shared::{
+        access::{Access, OwnershipPolicy},
+        error::{AppError, AppResult},
+    },
+};
+
+struct ExportJob {
+    info: ExportJobDto,
+    payload: Option<Bytes>,
+}
+
+/// In-memory registry of export jobs.
+///
+/// Only the latest job per user is retained, so at most one export payload per
+/// user is held in memory.
+#[derive(Default)]
+struct ExportJobStore {
+    jobs: Mutex<HashMap<Uuid, ExportJob>>,
+}
+
+impl ExportJobStore {
+    /// Register a new pending job, or fail with `Conflict` if the user already
+    /// has one pending
+    fn begin(&self, user_id: Uuid) -> AppResult<ExportJobDto> {
+        let mut jobs = self.jobs.lock().unwrap();
+        if jobs
+            .values()
+            .any(|job| job.info.user_id == user_id && job.info.status == ExportJobStatus::Pending)
+        {
+            return Err(AppError::Conflict(
+                "An export is already in progress for this user".to_string(),
+            ));
+        }
+        jobs.retain(|_, job| job.info.user_id != user_id);
+
+        let info = ExportJobDto {
+            job_id: Uuid::new_v4(),
+            user_id,
+            status: ExportJobStatus::Pending,
+            error: None,
+            created_at: Utc::now(),
+            completed_at: None,
+        };
+        jobs.insert(
+            info.job_id,
+            ExportJob {
+                info: info.clone(),
+                payload: None,
+            },
+        );
+        Ok(info)
+    }
+
+    fn finish(&self, job_id: Uuid, result: AppResult<Vec<u8>>) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(job) = jobs.get_mut(&job_id) else {
+            return;
+        };
+        job.info.completed_at = Some(Utc::now());
+        match result {
+            Ok(payload) => {
+                job.info.status = ExportJobStatus::Completed;
+                job.payload = Some(Bytes::from(payload));
+            }
+            Err(e) => {
+                tracing::error!("Export job {} failed: {}", job_id, e);
+                job.info.status = ExportJobStatus::Failed;
+                job.info.error = Some(e.to_string());
+            }
+        }
+    }
+
+    fn get(
+        &self,
+        job_id: Uuid,
+        user_id: Uuid,
+        policy: OwnershipPolicy,
+    ) -> AppResult<(ExportJobDto, Option<Bytes>)> {
+        let jobs = self.jobs.lock().unwrap();
+        let job = policy.authorize(
+            jobs.get(&job_id),
+            |job| job.info.user_id,
+            user_id,
+            Access::Read,
+            "Export job",
+            job_id,
+        )?;
+        Ok((job.info.clone(), job.payload.clone()))
+    }
+}
+
+/// Use case for exporting all decks, cards and review logs of a user.
+///
+/// `start` enqueues the export and returns immediately; the JSON document is
+/// built on a background task and fetched with `download` once completed.
+pub struct ExportUserDataUseCase {
+    deck_repository: Arc<dyn DeckRepository>,
+    card_repository: Arc<dyn CardRepository>,
+    review_log_repository: Arc<dyn ReviewLogRepository>,
+    jobs: Arc<ExportJobStore>,
+    ownership_policy: OwnershipPolicy,
+}
+
+impl ExportUserDataUseCase {
+    pub fn new(
+        deck_repository: Arc<dyn DeckRepository>,
+        card_repository: Arc<dyn CardRepository>,
+        review_log_repository: Arc<dyn ReviewLogRepository>,
+    ) -> Self {
+        Self {
+            deck_repository,
+            card_repository,
+            review_log_repository,
+            jobs: Arc::new(ExportJobStore::default()),
+            ownership_policy: OwnershipPolicy::default(),
+        }
+    }
+
+    /// How export jobs owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    /// Enqueue an export for `user_id`. Returns `Conflict` while a previous
+    /// export for the same user is still pending.
+    pub fn start(&self, user_id: Uuid) -> AppResult<ExportJobDto> {
+        let job = self.jobs.begin(user_id)?;
+
+        let deck_repository = self.deck_repository.clone();
+        let card_repository = self.card_repository.clone();
+        let review_log_repository = self.review_log_repository.clone();
+        let jobs = self.jobs.clone();
+        let job_id = job.job_id;
+
+        let build = tokio::spawn(async move {
+            build_export(
+                user_id,
+                deck_repository.as_ref(),
+                card_repository.as_ref(),
+                review_log_repository.as_ref(),
+            )
+            .await
+        });
+        // Awaited separately so a panicking build still marks the job failed
+        // instead of leaving it pending, which would block new exports
+        tokio::spawn(async move {
+            let result = build.await.unwrap_or_else(|e| {
+                Err(AppError::InternalError(format!(
+                    "Export task did not complete: {}",
+                    e
+                )))
+            });
+            jobs.finish(job_id, result);
+        });
+
+        Ok(job)
+    }
+
+    pub fn status(&self, job_id: Uuid, user_id: Uuid) -> AppResult<ExportJobDto> {
+        self.jobs
+            .get(job_id, user_id, self.ownership_policy)
+            .map(|(info, _)| info)
+    }
+
+    /// JSON payload of a completed export; cloning it shares the buffer
+    pub fn download(&self, job_id: Uuid, user_id: Uuid) -> AppResult<Bytes> {
+        let (info, payload) = self.jobs.get(job_id, user_id, self.ownership_policy)?;
+        match (info.status, payload) {
+            (ExportJobStatus::Completed, Some(payload)) => Ok(payload),
+            (ExportJobStatus::Failed, _) => Err(AppError::InternalError(format!(
+                "Export job {} failed",
+                job_id
+            ))),
+            _ => Err(AppError::Conflict(format!(
+                "Export job {} is not ready yet",
+                job_id
+            ))),
+        }
+    }
+}
+
+async fn build_export(
+    user_id: Uuid,
+    deck_repository: &dyn DeckRepository,
+    card_repository: &dyn CardRepository,
+    review_log_repository: &dyn ReviewLogRepository,
+) -> AppResult<Vec<u8>> {
+    let export = UserDataExport {
+        user_id,
+        exported_at: Utc::now(),
+        decks: deck_repository.find_by_user(user_id).await?,
+        cards: card_repository.find_by_user(user_id).await?,
+        review_logs: review_log_repository.find_by_user(user_id).await?,
+    };
+    Ok(serde_json::to_vec(&export)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{Card, Deck, ReviewLog};
+    use async_trait::async_trait;
+    use tokio::sync::Notify;
+
+    struct SingleDeckRepository;
+
+    #[async_trait]
+    impl DeckRepository for SingleDeckRepository {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            Ok(deck.id)
+        }
+
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Deck>> {
+            Ok(None)
+        }
+
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(vec![Deck::new(user_id, "Spanish".to_string(), None)])
+        }
+
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Card repository that blocks `find_by_user` until released, keeping the
+    /// export job pending, and then panics if `panics` is set
+    #[derive(Default)]
+    struct GatedCardRepository {
+        release: Notify,
+        panics: bool,
+    }
+
+    #[async_trait]
+    impl CardRepository for GatedCardRepository {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Card>> {
+            Ok(None)
+        }
+
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Card>> {
+            self.release.notified().await;
+            assert!(!self.panics, "card repository failed");
+            Ok(vec![Card::new(
+                user_id,
+                "hola".to_string(),
+                "hello".to_string(),
+            )])
+        }
+
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct EmptyReviewLogRepository;
+
+    #[async_trait]
+    impl ReviewLogRepository for EmptyReviewLogRepository {
+        async fn create(&self, review_log: &ReviewLog) -> AppResult<Uuid> {
+            Ok(review_log.id)
+        }
+
+        async fn find_by_card(&self, _card_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(vec![])
+        }
+
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(vec![])
+        }
+    }
+
+    fn use_case(card_repository: Arc<GatedCardRepository>) -> ExportUserDataUseCase {
+        ExportUserDataUseCase::new(
+            Arc::new(SingleDeckRepository),
+            card_repository,
+            Arc::new(EmptyReviewLogRepository),
+        )
+    }
+
+    async fn wait_until_finished(use_case: &ExportUserDataUseCase, job_id: Uuid, user_id: Uuid) {
+        for _ in 0..100 {
+            if use_case.status(job_id, user_id).unwrap().status != ExportJobStatus::Pending {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+        panic!("export job did not finish");
+    }
+
+    #[tokio::test]
+    async fn test_second_concurrent_export_returns_conflict() {
+        let cards = Arc::new(GatedCardRepository::default());
+        let use_case = use_case(cards.clone());
+        let user_id = Uuid::new_v4();
+
+        let first = use_case.start(user_id).unwrap();
+        assert_eq!(first.status, ExportJobStatus::Pending);
+
+        let second = use_case.start(user_id);
+        assert!(matches!(second, Err(AppError::Conflict(_))));
+
+        // Other users are not blocked
+        assert!(use_case.start(Uuid::new_v4()).is_ok());
+
+        cards.release.notify_waiters();
+    }
+
+    #[tokio::test]
+    async fn test_completed_export_can_be_downloaded() {
+        let cards = Arc::new(GatedCardRepository::default());
+        let use_case = use_case(cards.clone());
+        let user_id = Uuid::new_v4();
+
+        let job = use_case.start(user_id).unwrap();
+        assert!(matches!(
+            use_case.download(job.job_id, user_id),
+            Err(AppError::Conflict(_))
+        ));
+
+        cards.release.notify_one();
+        wait_until_finished(&use_case, job.job_id, user_id).await;
+
+        let payload = use_case.download(job.job_id, user_id).unwrap();
+        let export: UserDataExport = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(export.user_id, user_id);
+        assert_eq!(export.decks.len(), 1);
+        assert_eq!(export.cards.len(), 1);
+
+        // A finished export no longer blocks a new one
+        assert!(use_case.start(user_id).is_ok());
+        cards.release.notify_one();
+    }
+
+    #[tokio::test]
+    async fn test_export_job_owned_by_another_user() {
+        let cards = Arc::new(GatedCardRepository::default());
+        let use_case = use_case(cards.clone());
+
+        let job = use_case.start(Uuid::new_v4()).unwrap();
+        let result = use_case.status(job.job_id, Uuid::new_v4());
+
+        assert!(matches!(result, Err(AppError::AuthorizationError(_))));
+        cards.release.notify_one();
+    }
+
+    #[tokio::test]
+    async fn test_panicking_export_fails_and_unblocks_new_exports() {
+        let cards = Arc::new(GatedCardRepository {
+            panics: true,
+            ..Default::default()
+        });
+        let use_case = use_case(cards.clone());
+        let user_id = Uuid::new_v4();
+
+        let job = use_case.start(user_id).unwrap();
+        cards.release.notify_one();
+        wait_until_finished(&use_case, job.job_id, user_id).await;
+
+        let status = use_case.status(job.job_id, user_id).unwrap();
+        assert_eq!(status.status, ExportJobStatus::Failed);
+        assert!(use_case.start(user_id).is_ok());
+        cards.release.notify_one();
+    }
+}
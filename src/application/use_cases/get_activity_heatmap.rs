@@ -0,0 +1,156 @@
+//! GetActivityHeatmap use case - when a user reviews, by local day of week
+//! and hour
+
+use chrono::{Datelike, Timelike};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::ActivityHeatmapDto,
+    domain::repositories::{ReviewLogRepository, UserRepository},
+    AppResult,
+};
+
+/// Use case for counting a user's reviews in a 7x24 day-of-week/hour grid
+pub struct GetActivityHeatmapUseCase {
+    review_log_repository: Arc<dyn ReviewLogRepository>,
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl GetActivityHeatmapUseCase {
+    pub fn new(
+        review_log_repository: Arc<dyn ReviewLogRepository>,
+        user_repository: Arc<dyn UserRepository>,
+    ) -> Self {
+        Self {
+            review_log_repository,
+            user_repository,
+        }
+    }
+
+    /// Bucket every review of `user_id` by the local weekday (Monday first)
+    /// and hour it happened at, using the user's UTC offset
+    pub async fn execute(&self, user_id: Uuid) -> AppResult<ActivityHeatmapDto> {
+        let preferences = self
+            .user_repository
+            .find_by_id(user_id)
+            .await?
+            .map(|user| user.preferences)
+            .unwrap_or_default();
+
+        let mut counts = vec![vec![0i64; 24]; 7];
+        let logs = self.review_log_repository.find_by_user(user_id).await?;
+        for log in &logs {
+            let local = preferences.local_time(log.created_at);
+            let day = local.weekday().num_days_from_monday() as usize;
+            counts[day][local.hour() as usize] += 1;
+        }
+
+        Ok(ActivityHeatmapDto {
+            user_id,
+            utc_offset_minutes: preferences.utc_offset_minutes,
+            total: logs.len() as i64,
+            counts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{ReviewLog, User, UserPreferences};
+    use async_trait::async_trait;
+    use chrono::{TimeZone, Utc};
+
+    struct StubReviewLogRepo(Vec<ReviewLog>);
+
+    #[async_trait]
+    impl ReviewLogRepository for StubReviewLogRepo {
+        async fn create(&self, log: &ReviewLog) -> AppResult<Uuid> {
+            Ok(log.id)
+        }
+        async fn find_by_card(&self, _card_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(vec![])
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|l| l.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+    }
+
+    struct StubUserRepo(User);
+
+    #[async_trait]
+    impl UserRepository for StubUserRepo {
+        async fn create(&self, user: &User) -> AppResult<Uuid> {
+            Ok(user.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<User>> {
+            Ok(Some(self.0.clone()).filter(|user| user.id == id))
+        }
+        async fn find_by_email(&self, _email: &str) -> AppResult<Option<User>> {
+            Ok(None)
+        }
+        async fn update(&self, _user: &User) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    fn log_at(user_id: Uuid, at: chrono::DateTime<Utc>) -> ReviewLog {
+        let mut log = ReviewLog::new(
+            Uuid::new_v4(),
+            user_id,
+            String::new(),
+            String::new(),
+            1.0,
+            "exact".to_string(),
+            4,
+        );
+        log.created_at = at;
+        log
+    }
+
+    #[tokio::test]
+    async fn test_reviews_land_in_local_weekday_and_hour() {
+        let mut user = User::new("a@example.com".to_string(), "A".to_string());
+        user.preferences = UserPreferences {
+            utc_offset_minutes: 120,
+            ..Default::default()
+        };
+        let user_id = user.id;
+        // 2024-01-01 is a Monday
+        let logs = vec![
+            // Monday 09:30 UTC -> Monday 11:30 local
+            log_at(user_id, Utc.with_ymd_and_hms(2024, 1, 1, 9, 30, 0).unwrap()),
+            log_at(user_id, Utc.with_ymd_and_hms(2024, 1, 8, 9, 5, 0).unwrap()),
+            // Sunday 23:00 UTC -> Monday 01:00 local
+            log_at(user_id, Utc.with_ymd_and_hms(2024, 1, 7, 23, 0, 0).unwrap()),
+            // Saturday 21:59 UTC -> Saturday 23:59 local
+            log_at(
+                user_id,
+                Utc.with_ymd_and_hms(2024, 1, 6, 21, 59, 0).unwrap(),
+            ),
+        ];
+        let use_case = GetActivityHeatmapUseCase::new(
+            Arc::new(StubReviewLogRepo(logs)),
+            Arc::new(StubUserRepo(user)),
+        );
+
+        let heatmap = use_case.execute(user_id).await.unwrap();
+
+        assert_eq!(heatmap.total, 4);
+        assert_eq!(heatmap.counts.len(), 7);
+        assert!(heatmap.counts.iter().all(|row| row.len() == 24));
+        assert_eq!(heatmap.counts[0][11], 2);
+        assert_eq!(heatmap.counts[0][1], 1);
+        assert_eq!(heatmap.counts[5][23], 1);
+        assert_eq!(heatmap.counts[6].iter().sum::<i64>(), 0);
+    }
+}
@@ -0,0 +1,252 @@
+//! GetCardHistory use case - past gradings of a card, newest first
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::ReviewHistoryEntryDto,
+    domain::repositories::{CardRepository, ReviewLogRepository},
+    shared::access::{Access, OwnershipPolicy},
+    AppResult,
+};
+
+/// Use case for listing the review logs of one of the user's cards
+pub struct GetCardHistoryUseCase {
+    card_repository: Arc<dyn CardRepository>,
+    review_log_repository: Arc<dyn ReviewLogRepository>,
+    ownership_policy: OwnershipPolicy,
+}
+
+impl GetCardHistoryUseCase {
+    pub fn new(
+        card_repository: Arc<dyn CardRepository>,
+        review_log_repository: Arc<dyn ReviewLogRepository>,
+    ) -> Self {
+        Self {
+            card_repository,
+            review_log_repository,
+            ownership_policy: OwnershipPolicy::default(),
+        }
+    }
+
+    /// How cards owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    pub async fn execute(
+        &self,
+        card_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<Vec<ReviewHistoryEntryDto>> {
+        self.ownership_policy.authorize(
+            self.card_repository.find_by_id(card_id).await?,
+            |card| card.user_id,
+            user_id,
+            Access::Read,
+            "Card",
+            card_id,
+        )?;
+
+        let mut logs = self.review_log_repository.find_by_card(card_id).await?;
+        logs.sort_by_key(|log| std::cmp::Reverse(log.created_at));
+
+        Ok(logs
+            .into_iter()
+            .map(|log| ReviewHistoryEntryDto {
+                id: log.id,
+                user_answer: log.user_answer,
+                ai_score: log.ai_score,
+                validation_method: log.validation_method,
+                fsrs_rating: log.fsrs_rating,
+                explanation: log.explanation,
+                created_at: log.created_at,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        application::use_cases::ReviewCardUseCase,
+        domain::{
+            entities::{Card, ReviewLog},
+            ports::{AIValidator, ValidationMethod, ValidationResult},
+        },
+        shared::event_bus::EventBus,
+    };
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    struct MockCardRepository {
+        card: Card,
+    }
+
+    #[async_trait]
+    impl CardRepository for MockCardRepository {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Card>> {
+            Ok((self.card.id == id).then(|| self.card.clone()))
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryReviewLogRepository {
+        logs: Mutex<Vec<ReviewLog>>,
+    }
+
+    #[async_trait]
+    impl ReviewLogRepository for InMemoryReviewLogRepository {
+        async fn create(&self, log: &ReviewLog) -> AppResult<Uuid> {
+            self.logs.lock().unwrap().push(log.clone());
+            Ok(log.id)
+        }
+        async fn find_by_card(&self, card_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            let logs = self.logs.lock().unwrap();
+            Ok(logs
+                .iter()
+                .filter(|l| l.card_id == card_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(vec![])
+        }
+    }
+
+    struct ExplainingValidator;
+
+    #[async_trait]
+    impl AIValidator for ExplainingValidator {
+        async fn validate(
+            &self,
+            _expected: &str,
+            _actual: &str,
+            _question: &str,
+        ) -> anyhow::Result<ValidationResult> {
+            Ok(ValidationResult {
+                score: 0.6,
+                method: ValidationMethod::Llm,
+                explanation: Some("Right idea, but the article is missing".to_string()),
+                threshold: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validator_explanation_is_stored_and_listed() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "the house".to_string(), "la casa".to_string());
+        let card_id = card.id;
+        let cards = Arc::new(MockCardRepository { card });
+        let logs = Arc::new(InMemoryReviewLogRepository::default());
+
+        ReviewCardUseCase::new(
+            cards.clone(),
+            logs.clone(),
+            Arc::new(ExplainingValidator),
+            Arc::new(EventBus::new()),
+        )
+        .execute(card_id, user_id, "casa".to_string())
+        .await
+        .unwrap();
+
+        let history = GetCardHistoryUseCase::new(cards, logs)
+            .execute(card_id, user_id)
+            .await
+            .unwrap();
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].user_answer, "casa");
+        assert_eq!(history[0].validation_method, "llm");
+        assert_eq!(
+            history[0].explanation.as_deref(),
+            Some("Right idea, but the article is missing")
+        );
+    }
+
+    fn log(card_id: Uuid, user_id: Uuid, answer: &str, score: f32, minutes_ago: i64) -> ReviewLog {
+        let mut log = ReviewLog::new(
+            card_id,
+            user_id,
+            answer.to_string(),
+            "la casa".to_string(),
+            score,
+            "embedding".to_string(),
+            if score >= 0.8 { 3 } else { 1 },
+        );
+        log.created_at = chrono::Utc::now() - chrono::Duration::minutes(minutes_ago);
+        log
+    }
+
+    #[tokio::test]
+    async fn test_history_lists_logs_newest_first() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "the house".to_string(), "la casa".to_string());
+        let card_id = card.id;
+        let logs = InMemoryReviewLogRepository::default();
+        for entry in [
+            log(card_id, user_id, "casa", 0.6, 20),
+            log(card_id, user_id, "la casa", 1.0, 1),
+            log(card_id, user_id, "el casa", 0.7, 10),
+            log(Uuid::new_v4(), user_id, "otra", 0.1, 5),
+        ] {
+            logs.create(&entry).await.unwrap();
+        }
+
+        let history =
+            GetCardHistoryUseCase::new(Arc::new(MockCardRepository { card }), Arc::new(logs))
+                .execute(card_id, user_id)
+                .await
+                .unwrap();
+
+        let answers: Vec<&str> = history.iter().map(|h| h.user_answer.as_str()).collect();
+        assert_eq!(answers, ["la casa", "el casa", "casa"]);
+        assert_eq!(history[0].ai_score, 1.0);
+        assert_eq!(history[0].fsrs_rating, 3);
+        assert_eq!(history[2].fsrs_rating, 1);
+        assert_eq!(history[0].validation_method, "embedding");
+        assert!(history[0].created_at > history[1].created_at);
+    }
+
+    #[tokio::test]
+    async fn test_history_of_another_users_card_is_rejected() {
+        let card = Card::new(Uuid::new_v4(), "Q".to_string(), "A".to_string());
+        let card_id = card.id;
+
+        let result = GetCardHistoryUseCase::new(
+            Arc::new(MockCardRepository { card }),
+            Arc::new(InMemoryReviewLogRepository::default()),
+        )
+        .execute(card_id, Uuid::new_v4())
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::AppError::AuthorizationError(_))
+        ));
+    }
+}
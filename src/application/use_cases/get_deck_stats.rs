@@ -4,6 +4,7 @@ use uuid::Uuid;
 use crate::{
     application::dtos::DeckStatsDto,
     domain::repositories::{DeckRepository, DeckStatsRepository},
+    shared::access::{Access, OwnershipPolicy},
     AppResult,
 };
 
@@ -11,6 +12,7 @@ use crate::{
 pub struct GetDeckStatsUseCase {
     deck_stats_repository: Arc<dyn DeckStatsRepository>,
     deck_repository: Arc<dyn DeckRepository>,
+    ownership_policy: OwnershipPolicy,
 }
 
 impl GetDeckStatsUseCase {
@@ -21,18 +23,26 @@ impl GetDeckStatsUseCase {
         Self {
             deck_stats_repository,
             deck_repository,
+            ownership_policy: OwnershipPolicy::default(),
         }
     }
 
-    pub async fn execute(&self, deck_id: Uuid) -> AppResult<DeckStatsDto> {
+    /// How decks owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    pub async fn execute(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<DeckStatsDto> {
         // Get the deck to get its name
-        let deck = self
-            .deck_repository
-            .find_by_id(deck_id)
-            .await?
-            .ok_or_else(|| {
-                crate::AppError::NotFound(format!("Deck with id {} not found", deck_id))
-            })?;
+        let deck = self.ownership_policy.authorize(
+            self.deck_repository.find_by_id(deck_id).await?,
+            |deck| deck.user_id,
+            user_id,
+            Access::Read,
+            "Deck",
+            deck_id,
+        )?;
 
         // Get or create stats for this deck
         let stats = self
@@ -116,15 +126,6 @@ mod tests {
                 .unwrap_or_else(|| DeckStats::new(deck_id, user_id)))
         }
 
-        async fn update_after_review(
-            &self,
-            _deck_id: Uuid,
-            _is_correct: bool,
-            _review_date: chrono::NaiveDate,
-        ) -> AppResult<()> {
-            Ok(())
-        }
-
         async fn increment_card_count(&self, _deck_id: Uuid) -> AppResult<()> {
             Ok(())
         }
@@ -154,7 +155,7 @@ mod tests {
         let stats_repo = Arc::new(MockDeckStatsRepository::with_stats(stats));
 
         let use_case = GetDeckStatsUseCase::new(stats_repo, deck_repo);
-        let result = use_case.execute(deck_id).await.unwrap();
+        let result = use_case.execute(deck_id, user_id).await.unwrap();
 
         assert_eq!(result.deck_name, "Spanish Vocabulary");
         assert_eq!(result.total_cards, 50);
@@ -176,9 +177,27 @@ mod tests {
         });
 
         let use_case = GetDeckStatsUseCase::new(stats_repo, deck_repo);
-        let result = use_case.execute(deck_id).await;
+        let result = use_case.execute(deck_id, Uuid::new_v4()).await;
 
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), crate::AppError::NotFound(_)));
     }
+
+    #[tokio::test]
+    async fn test_get_deck_stats_of_another_user_is_not_found_under_privacy_policy() {
+        let owner = Uuid::new_v4();
+        let deck = Deck::new(owner, "Theirs".to_string(), None);
+        let deck_id = deck.id;
+
+        let deck_repo = Arc::new(MockDeckRepository::with_deck(deck));
+        let stats_repo = Arc::new(MockDeckStatsRepository {
+            stats: Mutex::new(None),
+        });
+
+        let use_case = GetDeckStatsUseCase::new(stats_repo, deck_repo)
+            .with_ownership_policy(OwnershipPolicy::HideAll);
+        let result = use_case.execute(deck_id, Uuid::new_v4()).await;
+
+        assert!(matches!(result, Err(crate::AppError::NotFound(_))));
+    }
 }
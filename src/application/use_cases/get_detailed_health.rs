@@ -0,0 +1,123 @@
+//! GetDetailedHealth use case - per-component status of the service
+
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::{
+    application::dtos::DetailedHealthDto,
+    domain::ports::{ComponentHealth, ComponentStatus, HealthCheck},
+};
+
+/// Time a single check may take before its component is reported down
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Use case for running every registered health check
+pub struct GetDetailedHealthUseCase {
+    checks: Vec<Arc<dyn HealthCheck>>,
+    started_at: Instant,
+}
+
+impl GetDetailedHealthUseCase {
+    pub fn new(checks: Vec<Arc<dyn HealthCheck>>) -> Self {
+        Self {
+            checks,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Run all checks. The overall status is `ok` when every component is ok,
+    /// `down` when a critical component is down, and `degraded` otherwise.
+    pub async fn execute(&self) -> DetailedHealthDto {
+        let mut components = BTreeMap::new();
+        let mut status = ComponentStatus::Ok;
+
+        for check in &self.checks {
+            let health = tokio::time::timeout(CHECK_TIMEOUT, check.check())
+                .await
+                .unwrap_or_else(|_| ComponentHealth::down("Health check timed out"));
+
+            status = match health.status {
+                ComponentStatus::Ok => status,
+                ComponentStatus::Down if check.critical() => ComponentStatus::Down,
+                _ => status.max(ComponentStatus::Degraded),
+            };
+            components.insert(check.name().to_string(), health);
+        }
+
+        DetailedHealthDto {
+            status,
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            components,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct FixedCheck {
+        name: &'static str,
+        critical: bool,
+        health: ComponentHealth,
+    }
+
+    #[async_trait]
+    impl HealthCheck for FixedCheck {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn critical(&self) -> bool {
+            self.critical
+        }
+
+        async fn check(&self) -> ComponentHealth {
+            self.health.clone()
+        }
+    }
+
+    fn check(name: &'static str, critical: bool, health: ComponentHealth) -> Arc<dyn HealthCheck> {
+        Arc::new(FixedCheck {
+            name,
+            critical,
+            health,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_mixed_statuses_report_degraded() {
+        let use_case = GetDetailedHealthUseCase::new(vec![
+            check("database", true, ComponentHealth::ok()),
+            check("migrations", false, ComponentHealth::degraded("behind")),
+            check("openai", false, ComponentHealth::down("unreachable")),
+        ]);
+
+        let health = use_case.execute().await;
+
+        assert_eq!(health.status, ComponentStatus::Degraded);
+        assert_eq!(health.components.len(), 3);
+        assert_eq!(health.components["database"].status, ComponentStatus::Ok);
+        assert_eq!(
+            health.components["migrations"].status,
+            ComponentStatus::Degraded
+        );
+        assert_eq!(health.components["openai"].status, ComponentStatus::Down);
+    }
+
+    #[tokio::test]
+    async fn test_critical_component_down_reports_down() {
+        let use_case = GetDetailedHealthUseCase::new(vec![
+            check(
+                "database",
+                true,
+                ComponentHealth::down("connection refused"),
+            ),
+            check("openai", false, ComponentHealth::ok()),
+        ]);
+
+        assert_eq!(use_case.execute().await.status, ComponentStatus::Down);
+    }
+}
@@ -0,0 +1,195 @@
+//! GetDueCount use case - how many cards are due, once or as a periodic feed
+
+use chrono::{DateTime, Utc};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::{application::dtos::DueCountDto, domain::repositories::CardRepository, AppResult};
+
+const DEFAULT_STREAM_INTERVAL_SECS: u64 = 30;
+
+/// Use case for counting a user's due cards
+pub struct GetDueCountUseCase {
+    card_repository: Arc<dyn CardRepository>,
+    stream_interval: Duration,
+}
+
+impl GetDueCountUseCase {
+    pub fn new(card_repository: Arc<dyn CardRepository>) -> Self {
+        Self {
+            card_repository,
+            stream_interval: Duration::from_secs(DEFAULT_STREAM_INTERVAL_SECS),
+        }
+    }
+
+    /// How often `watch` pushes a fresh count
+    pub fn with_stream_interval(mut self, stream_interval: Duration) -> Self {
+        self.stream_interval = stream_interval;
+        self
+    }
+
+    /// Push interval from `DUE_COUNT_STREAM_INTERVAL_SECS` (default 30s)
+    pub fn stream_interval_from_env() -> Duration {
+        let secs = std::env::var("DUE_COUNT_STREAM_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_STREAM_INTERVAL_SECS);
+        Duration::from_secs(secs)
+    }
+
+    pub async fn execute(&self, user_id: Uuid) -> AppResult<DueCountDto> {
+        let due_count = self
+            .card_repository
+            .count_due_by_user(user_id, Utc::now())
+            .await?;
+
+        Ok(DueCountDto { user_id, due_count })
+    }
+
+    /// Count due cards immediately and then every stream interval, until
+    /// `until` (e.g. when the caller's token expires).
+    ///
+    /// The counting task stops as soon as the receiver is dropped, e.g. when
+    /// an SSE client disconnects, or at `until`, which closes the receiver.
+    pub fn watch(
+        &self,
+        user_id: Uuid,
+        until: DateTime<Utc>,
+    ) -> mpsc::Receiver<AppResult<DueCountDto>> {
+        let (tx, rx) = mpsc::channel(1);
+        let use_case = Self {
+            card_repository: self.card_repository.clone(),
+            stream_interval: self.stream_interval,
+        };
+        let lifetime = (until - Utc::now()).to_std().unwrap_or_default();
+
+        tokio::spawn(async move {
+            let expiry = tokio::time::sleep(lifetime);
+            tokio::pin!(expiry);
+            let mut ticker = tokio::time::interval(use_case.stream_interval);
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = &mut expiry => break,
+                    _ = tx.closed() => break,
+                    _ = ticker.tick() => {}
+                }
+                if tx.send(use_case.execute(user_id).await).await.is_err() {
+                    break;
+                }
+            }
+            tracing::debug!("Due count stream for user {} closed", user_id);
+        });
+
+        rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::Card;
+    use async_trait::async_trait;
+
+    struct MockCardRepository {
+        cards: Vec<Card>,
+    }
+
+    #[async_trait]
+    impl CardRepository for MockCardRepository {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Card>> {
+            Ok(None)
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(self
+                .cards
+                .iter()
+                .filter(|c| c.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_first_streamed_count_is_sent_immediately() {
+        let user_id = Uuid::new_v4();
+        let new_card = Card::new(user_id, "hola".to_string(), "hello".to_string());
+        let mut scheduled = Card::new(user_id, "casa".to_string(), "house".to_string());
+        scheduled.reschedule(5);
+        let mut suspended = Card::new(user_id, "perro".to_string(), "dog".to_string());
+        suspended.suspended = true;
+        let use_case = GetDueCountUseCase::new(Arc::new(MockCardRepository {
+            cards: vec![new_card, scheduled, suspended],
+        }))
+        .with_stream_interval(Duration::from_secs(3600));
+
+        let mut events = use_case.watch(user_id, Utc::now() + chrono::Duration::hours(1));
+        let first = tokio::time::timeout(Duration::from_secs(1), events.recv())
+            .await
+            .expect("initial count was not pushed on connect")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(first.user_id, user_id);
+        assert_eq!(first.due_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_stream_closes_at_its_end_time() {
+        let user_id = Uuid::new_v4();
+        let use_case = GetDueCountUseCase::new(Arc::new(MockCardRepository { cards: vec![] }))
+            .with_stream_interval(Duration::from_millis(10));
+
+        let mut events = use_case.watch(user_id, Utc::now() + chrono::Duration::milliseconds(50));
+        let mut received = 0;
+        let closed = tokio::time::timeout(Duration::from_secs(1), async {
+            while events.recv().await.is_some() {
+                received += 1;
+            }
+        })
+        .await;
+
+        assert!(closed.is_ok(), "stream outlived its end time");
+        assert!(received >= 1);
+    }
+
+    #[tokio::test]
+    async fn test_card_with_future_not_before_is_not_due_until_then() {
+        let user_id = Uuid::new_v4();
+        let not_before = Utc::now() + chrono::Duration::days(3);
+        let mut frozen = Card::new(user_id, "hola".to_string(), "hello".to_string());
+        frozen.not_before = Some(not_before);
+        let repo = MockCardRepository {
+            cards: vec![frozen],
+        };
+
+        let before = repo.count_due_by_user(user_id, Utc::now()).await.unwrap();
+        let after = repo
+            .count_due_by_user(user_id, not_before + chrono::Duration::seconds(1))
+            .await
+            .unwrap();
+
+        assert_eq!(before, 0);
+        assert_eq!(after, 1);
+    }
+}
@@ -0,0 +1,197 @@
+//! GetGoalProgress use case - reviews done today against the user's daily goal
+
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::GoalProgressDto,
+    domain::repositories::{ReviewLogRepository, UserRepository},
+    AppResult,
+};
+
+/// Use case for tracking progress towards a user's daily review goal
+pub struct GetGoalProgressUseCase {
+    review_log_repository: Arc<dyn ReviewLogRepository>,
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl GetGoalProgressUseCase {
+    pub fn new(
+        review_log_repository: Arc<dyn ReviewLogRepository>,
+        user_repository: Arc<dyn UserRepository>,
+    ) -> Self {
+        Self {
+            review_log_repository,
+            user_repository,
+        }
+    }
+
+    pub async fn execute(&self, user_id: Uuid) -> AppResult<GoalProgressDto> {
+        self.progress_at(user_id, Utc::now()).await
+    }
+
+    /// Progress on the study day containing `now`, using the user's UTC
+    /// offset and day cutoff to decide which reviews count as today's
+    pub async fn progress_at(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> AppResult<GoalProgressDto> {
+        let preferences = self
+            .user_repository
+            .find_by_id(user_id)
+            .await?
+            .map(|user| user.preferences)
+            .unwrap_or_default();
+
+        let today = preferences.review_date(now);
+        let reviews_today = self
+            .review_log_repository
+            .find_by_user(user_id)
+            .await?
+            .iter()
+            .filter(|log| preferences.review_date(log.created_at) == today)
+            .count() as i64;
+
+        let goal = preferences.daily_review_goal;
+        Ok(GoalProgressDto {
+            user_id,
+            date: today,
+            reviews_today,
+            daily_review_goal: goal,
+            remaining: goal.map(|goal| (goal as i64 - reviews_today).max(0)),
+            goal_met: goal.is_some_and(|goal| reviews_today >= goal as i64),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{ReviewLog, User, UserPreferences};
+    use async_trait::async_trait;
+    use chrono::{NaiveDate, TimeZone};
+
+    struct StubReviewLogRepo(Vec<ReviewLog>);
+
+    #[async_trait]
+    impl ReviewLogRepository for StubReviewLogRepo {
+        async fn create(&self, log: &ReviewLog) -> AppResult<Uuid> {
+            Ok(log.id)
+        }
+        async fn find_by_card(&self, _card_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(vec![])
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|l| l.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+    }
+
+    struct StubUserRepo(User);
+
+    #[async_trait]
+    impl UserRepository for StubUserRepo {
+        async fn create(&self, user: &User) -> AppResult<Uuid> {
+            Ok(user.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<User>> {
+            Ok(Some(self.0.clone()).filter(|user| user.id == id))
+        }
+        async fn find_by_email(&self, _email: &str) -> AppResult<Option<User>> {
+            Ok(None)
+        }
+        async fn update(&self, _user: &User) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    fn log_at(user_id: Uuid, at: DateTime<Utc>) -> ReviewLog {
+        let mut log = ReviewLog::new(
+            Uuid::new_v4(),
+            user_id,
+            String::new(),
+            String::new(),
+            1.0,
+            "exact".to_string(),
+            4,
+        );
+        log.created_at = at;
+        log
+    }
+
+    fn setup(
+        goal: Option<i32>,
+        logs: impl Fn(Uuid) -> Vec<ReviewLog>,
+    ) -> (Uuid, GetGoalProgressUseCase) {
+        let mut user = User::new("a@example.com".to_string(), "A".to_string());
+        // UTC+2, day starts at 4am local
+        user.preferences = UserPreferences {
+            utc_offset_minutes: 120,
+            day_cutoff_hour: 4,
+            daily_review_goal: goal,
+            ..Default::default()
+        };
+        let user_id = user.id;
+        let use_case = GetGoalProgressUseCase::new(
+            Arc::new(StubReviewLogRepo(logs(user_id))),
+            Arc::new(StubUserRepo(user)),
+        );
+        (user_id, use_case)
+    }
+
+    fn reviews(user_id: Uuid) -> Vec<ReviewLog> {
+        vec![
+            // 01:00 local on March 10th: still March 9th's study day
+            log_at(user_id, Utc.with_ymd_and_hms(2026, 3, 9, 23, 0, 0).unwrap()),
+            // 05:00 and 23:30 local on March 10th
+            log_at(user_id, Utc.with_ymd_and_hms(2026, 3, 10, 3, 0, 0).unwrap()),
+            log_at(
+                user_id,
+                Utc.with_ymd_and_hms(2026, 3, 10, 21, 30, 0).unwrap(),
+            ),
+            // 03:00 local on March 11th: still March 10th's study day
+            log_at(user_id, Utc.with_ymd_and_hms(2026, 3, 11, 1, 0, 0).unwrap()),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_counts_reviews_of_the_local_study_day() {
+        let (user_id, use_case) = setup(Some(5), reviews);
+        // 12:00 local on March 10th
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 10, 0, 0).unwrap();
+
+        let progress = use_case.progress_at(user_id, now).await.unwrap();
+
+        assert_eq!(progress.date, NaiveDate::from_ymd_opt(2026, 3, 10).unwrap());
+        assert_eq!(progress.reviews_today, 3);
+        assert_eq!(progress.remaining, Some(2));
+        assert!(!progress.goal_met);
+
+        let (user_id, use_case) = setup(Some(3), reviews);
+        let progress = use_case.progress_at(user_id, now).await.unwrap();
+        assert_eq!(progress.remaining, Some(0));
+        assert!(progress.goal_met);
+    }
+
+    #[tokio::test]
+    async fn test_no_goal_is_never_met() {
+        let (user_id, use_case) = setup(None, reviews);
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 10, 0, 0).unwrap();
+
+        let progress = use_case.progress_at(user_id, now).await.unwrap();
+
+        assert_eq!(progress.reviews_today, 3);
+        assert_eq!(progress.daily_review_goal, None);
+        assert_eq!(progress.remaining, None);
+        assert!(!progress.goal_met);
+    }
+}
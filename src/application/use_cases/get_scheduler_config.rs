@@ -0,0 +1,209 @@
+//! GetSchedulerConfig use case - resolve the scheduler configuration used for a deck
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::{dtos::SchedulerConfigDto, use_cases::review_card::self_graded_reason},
+    domain::{
+        entities::ReviewMode,
+        repositories::{DeckRepository, UserRepository},
+        scheduler_config::SchedulerConfig,
+    },
+    shared::access::{Access, OwnershipPolicy},
+    AppResult,
+};
+
+/// Use case for inspecting the effective scheduler configuration of a deck
+pub struct GetSchedulerConfigUseCase {
+    deck_repository: Arc<dyn DeckRepository>,
+    user_repository: Option<Arc<dyn UserRepository>>,
+    defaults: SchedulerConfig,
+    ownership_policy: OwnershipPolicy,
+}
+
+impl GetSchedulerConfigUseCase {
+    pub fn new(deck_repository: Arc<dyn DeckRepository>, defaults: SchedulerConfig) -> Self {
+        Self {
+            deck_repository,
+            user_repository: None,
+            defaults,
+            ownership_policy: OwnershipPolicy::default(),
+        }
+    }
+
+    /// How decks owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    /// Apply the requesting user's `ai_validation_enabled` preference to the
+    /// reported review mode, as reviews do
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    /// The configuration a review of a card in `deck_id` by `user_id` would
+    /// use. A trashed deck is reported as not found.
+    pub async fn execute(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<SchedulerConfigDto> {
+        let deck = self.ownership_policy.authorize(
+            self.deck_repository
+                .find_by_id(deck_id)
+                .await?
+                .filter(|deck| deck.deleted_at.is_none()),
+            |deck| deck.user_id,
+            user_id,
+            Access::Read,
+            "Deck",
+            deck_id,
+        )?;
+
+        let self_graded_by =
+            self_graded_reason(deck.review_mode, self.user_repository.as_deref(), user_id).await?;
+
+        Ok(SchedulerConfigDto {
+            deck_id,
+            resolved: deck.scheduler_config(&self.defaults),
+            review_mode: if self_graded_by.is_some() {
+                ReviewMode::SelfGrade
+            } else {
+                ReviewMode::Typed
+            },
+            deck_overrides: deck.scheduler_overrides,
+            deck_fsrs_weights: deck.fsrs_weights,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::use_cases::test_support::SingleUserRepository;
+    use crate::domain::{entities::Deck, fsrs, scheduler_config::SchedulerOverrides};
+    use async_trait::async_trait;
+
+    struct MockDeckRepository {
+        deck: Option<Deck>,
+    }
+
+    #[async_trait]
+    impl DeckRepository for MockDeckRepository {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            Ok(deck.id)
+        }
+
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Deck>> {
+            Ok(self.deck.clone())
+        }
+
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(vec![])
+        }
+
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deck_overrides_shadow_defaults() {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Spanish".to_string(), None).with_scheduler_overrides(
+            SchedulerOverrides {
+                good_threshold: Some(0.8),
+                maximum_interval_days: Some(90),
+                ..Default::default()
+            },
+        );
+        let deck_id = deck.id;
+        let repo = Arc::new(MockDeckRepository { deck: Some(deck) });
+        let defaults = SchedulerConfig::default();
+        let use_case = GetSchedulerConfigUseCase::new(repo, defaults.clone());
+
+        let result = use_case.execute(deck_id, user_id).await.unwrap();
+
+        assert_eq!(result.resolved.good_threshold, 0.8);
+        assert_eq!(result.resolved.maximum_interval_days, 90);
+        assert_eq!(result.resolved.easy_threshold, defaults.easy_threshold);
+        assert_eq!(result.resolved.hard_threshold, defaults.hard_threshold);
+        assert_eq!(result.deck_overrides.good_threshold, Some(0.8));
+    }
+
+    #[tokio::test]
+    async fn test_deck_fsrs_weights_replace_the_defaults() {
+        let user_id = Uuid::new_v4();
+        let mut weights = fsrs::DEFAULT_WEIGHTS.to_vec();
+        weights[2] = 12.0;
+        let mut deck = Deck::new(user_id, "Spanish".to_string(), None);
+        deck.fsrs_weights = Some(weights.clone());
+        let deck_id = deck.id;
+        let repo = Arc::new(MockDeckRepository { deck: Some(deck) });
+        let use_case = GetSchedulerConfigUseCase::new(repo, SchedulerConfig::default());
+
+        let result = use_case.execute(deck_id, user_id).await.unwrap();
+
+        assert_eq!(result.resolved.fsrs_weights, Some(weights.clone()));
+        assert_eq!(result.deck_fsrs_weights, Some(weights));
+        assert_eq!(result.review_mode, ReviewMode::Typed);
+    }
+
+    #[tokio::test]
+    async fn test_users_who_turned_ai_off_self_grade() {
+        let users = Arc::new(SingleUserRepository::ai_opted_out());
+        let user_id = users.user_id();
+        let deck = Deck::new(user_id, "Spanish".to_string(), None);
+        let deck_id = deck.id;
+        let repo = Arc::new(MockDeckRepository { deck: Some(deck) });
+        let use_case = GetSchedulerConfigUseCase::new(repo, SchedulerConfig::default())
+            .with_user_repository(users);
+
+        let result = use_case.execute(deck_id, user_id).await.unwrap();
+
+        assert_eq!(result.review_mode, ReviewMode::SelfGrade);
+    }
+
+    #[tokio::test]
+    async fn test_trashed_deck_not_found() {
+        let user_id = Uuid::new_v4();
+        let mut deck = Deck::new(user_id, "Spanish".to_string(), None);
+        deck.deleted_at = Some(chrono::Utc::now());
+        let deck_id = deck.id;
+        let repo = Arc::new(MockDeckRepository { deck: Some(deck) });
+        let use_case = GetSchedulerConfigUseCase::new(repo, SchedulerConfig::default());
+
+        let result = use_case.execute(deck_id, user_id).await;
+
+        assert!(matches!(result, Err(crate::AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_deck_not_found() {
+        let repo = Arc::new(MockDeckRepository { deck: None });
+        let use_case = GetSchedulerConfigUseCase::new(repo, SchedulerConfig::default());
+
+        let result = use_case.execute(Uuid::new_v4(), Uuid::new_v4()).await;
+
+        assert!(matches!(result, Err(crate::AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_deck_owned_by_another_user() {
+        let deck = Deck::new(Uuid::new_v4(), "Spanish".to_string(), None);
+        let deck_id = deck.id;
+        let repo = Arc::new(MockDeckRepository { deck: Some(deck) });
+        let use_case = GetSchedulerConfigUseCase::new(repo, SchedulerConfig::default());
+
+        let result = use_case.execute(deck_id, Uuid::new_v4()).await;
+
+        assert!(matches!(
+            result,
+            Err(crate::AppError::AuthorizationError(_))
+        ));
+    }
+}
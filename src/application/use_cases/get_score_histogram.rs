@@ -0,0 +1,298 @@
+//! GetScoreHistogram use case - how AI review scores are distributed, for
+//! tuning the rating thresholds
+
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::{ScoreBucketDto, ScoreHistogramDto},
+    domain::repositories::{
+        CardRepository, DeckRepository, ReviewLogRepository, ScoreHistogramFilter,
+    },
+    shared::{
+        access::{Access, OwnershipPolicy},
+        error::AppError,
+    },
+    AppResult,
+};
+
+/// Bucket count used when the request doesn't give one
+pub const DEFAULT_BUCKETS: usize = 10;
+const MAX_BUCKETS: usize = 100;
+
+/// Reviews covered by a histogram request
+#[derive(Debug, Clone, Default)]
+pub struct ScoreHistogramQuery {
+    pub deck_id: Option<Uuid>,
+    pub buckets: Option<usize>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Use case for counting a user's reviews per AI score range
+pub struct GetScoreHistogramUseCase {
+    card_repository: Arc<dyn CardRepository>,
+    deck_repository: Arc<dyn DeckRepository>,
+    review_log_repository: Arc<dyn ReviewLogRepository>,
+    ownership_policy: OwnershipPolicy,
+}
+
+impl GetScoreHistogramUseCase {
+    pub fn new(
+        card_repository: Arc<dyn CardRepository>,
+        deck_repository: Arc<dyn DeckRepository>,
+        review_log_repository: Arc<dyn ReviewLogRepository>,
+    ) -> Self {
+        Self {
+            card_repository,
+            deck_repository,
+            review_log_repository,
+            ownership_policy: OwnershipPolicy::default(),
+        }
+    }
+
+    /// How decks owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        query: ScoreHistogramQuery,
+    ) -> AppResult<ScoreHistogramDto> {
+        let buckets = query.buckets.unwrap_or(DEFAULT_BUCKETS);
+        if !(1..=MAX_BUCKETS).contains(&buckets) {
+            return Err(AppError::ValidationError(format!(
+                "buckets must be between 1 and {}",
+                MAX_BUCKETS
+            )));
+        }
+        if let (Some(from), Some(to)) = (query.from, query.to) {
+            if from >= to {
+                return Err(AppError::ValidationError(
+                    "from must be before to".to_string(),
+                ));
+            }
+        }
+
+        let card_ids = match query.deck_id {
+            Some(deck_id) => {
+                self.ownership_policy.authorize(
+                    self.deck_repository.find_by_id(deck_id).await?,
+                    |deck| deck.user_id,
+                    user_id,
+                    Access::Read,
+                    "Deck",
+                    deck_id,
+                )?;
+                let cards = self.card_repository.find_by_deck(deck_id).await?;
+                Some(cards.into_iter().map(|card| card.id).collect())
+            }
+            None => None,
+        };
+
+        let counts = self
+            .review_log_repository
+            .score_histogram(
+                user_id,
+                ScoreHistogramFilter {
+                    card_ids,
+                    from: query.from,
+                    to: query.to,
+                },
+                buckets,
+            )
+            .await?;
+
+        let width = 1.0 / buckets as f32;
+        Ok(ScoreHistogramDto {
+            user_id,
+            deck_id: query.deck_id,
+            total: counts.iter().sum(),
+            buckets: counts
+                .into_iter()
+                .enumerate()
+                .map(|(i, count)| ScoreBucketDto {
+                    lower: i as f32 * width,
+                    upper: (i + 1) as f32 * width,
+                    count,
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{Card, Deck, ReviewLog};
+    use async_trait::async_trait;
+
+    struct StubCardRepo(Vec<Card>);
+
+    #[async_trait]
+    impl CardRepository for StubCardRepo {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Card>> {
+            Ok(self.0.iter().find(|c| c.id == id).cloned())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|c| c.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_deck(&self, deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|c| c.deck_id == Some(deck_id))
+                .cloned()
+                .collect())
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct StubDeckRepo(Deck);
+
+    #[async_trait]
+    impl DeckRepository for StubDeckRepo {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            Ok(deck.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            Ok(Some(self.0.clone()).filter(|deck| deck.id == id))
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(vec![self.0.clone()])
+        }
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct StubReviewLogRepo(Vec<ReviewLog>);
+
+    #[async_trait]
+    impl ReviewLogRepository for StubReviewLogRepo {
+        async fn create(&self, log: &ReviewLog) -> AppResult<Uuid> {
+            Ok(log.id)
+        }
+        async fn find_by_card(&self, card_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|l| l.card_id == card_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|l| l.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn log(card: &Card, score: f32) -> ReviewLog {
+        ReviewLog::new(
+            card.id,
+            card.user_id,
+            String::new(),
+            String::new(),
+            score,
+            "llm".to_string(),
+            3,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_scores_land_in_expected_buckets() {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Spanish".to_string(), None);
+        let in_deck = Card::new(user_id, "Q1".to_string(), "A1".to_string()).with_deck(deck.id);
+        let elsewhere = Card::new(user_id, "Q2".to_string(), "A2".to_string());
+        let logs = vec![
+            log(&in_deck, 0.0),
+            log(&in_deck, 0.05),
+            log(&in_deck, 0.42),
+            log(&in_deck, 0.95),
+            log(&in_deck, 1.0),
+            log(&elsewhere, 0.5),
+        ];
+        let deck_id = deck.id;
+        let use_case = GetScoreHistogramUseCase::new(
+            Arc::new(StubCardRepo(vec![in_deck, elsewhere])),
+            Arc::new(StubDeckRepo(deck)),
+            Arc::new(StubReviewLogRepo(logs)),
+        );
+
+        let all = use_case
+            .execute(user_id, ScoreHistogramQuery::default())
+            .await
+            .unwrap();
+        let counts: Vec<i64> = all.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![2, 0, 0, 0, 1, 1, 0, 0, 0, 2]);
+        assert_eq!(all.total, 6);
+
+        let deck_only = use_case
+            .execute(
+                user_id,
+                ScoreHistogramQuery {
+                    deck_id: Some(deck_id),
+                    buckets: Some(4),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let counts: Vec<i64> = deck_only.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![2, 1, 0, 2]);
+        assert_eq!(deck_only.buckets[1].lower, 0.25);
+    }
+
+    #[tokio::test]
+    async fn test_bucket_count_is_validated() {
+        let user_id = Uuid::new_v4();
+        let use_case = GetScoreHistogramUseCase::new(
+            Arc::new(StubCardRepo(vec![])),
+            Arc::new(StubDeckRepo(Deck::new(user_id, "D".to_string(), None))),
+            Arc::new(StubReviewLogRepo(vec![])),
+        );
+
+        let result = use_case
+            .execute(
+                user_id,
+                ScoreHistogramQuery {
+                    buckets: Some(0),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+}
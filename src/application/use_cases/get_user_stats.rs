@@ -66,6 +66,7 @@ mod tests {
         async fn update_after_review(
             &self,
             _user_id: Uuid,
+            _deck_id: Option<Uuid>,
             _is_correct: bool,
             _review_date: chrono::NaiveDate,
         ) -> AppResult<()> {
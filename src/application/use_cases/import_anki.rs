@@ -4,33 +4,65 @@
 //! a SQLite database. We extract notes from it, strip HTML, create a new deck,
 //! and bulk-insert the cards.
 
-use std::sync::Arc;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use bytes::Bytes;
 use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::SqlitePool;
+use tempfile::TempPath;
 use uuid::Uuid;
 
 use crate::{
-    application::dtos::AnkiImportResult,
+    application::dtos::{AnkiImportResult, ImportOptions},
     domain::{
         entities::{Card, Deck},
         ports::EmbeddingService,
-        repositories::{CardRepository, DeckRepository, DeckStatsRepository},
+        repositories::{CardRepository, DeckRepository, DeckStatsRepository, UserRepository},
+    },
+    shared::{
+        content_policy::ContentPolicy,
+        error::{AppError, AppResult},
+        quota::UserQuotas,
     },
-    shared::error::{AppError, AppResult},
 };
 
-use super::import_tsv::spawn_embedding_worker;
+use super::import_tsv::{
+    card_import_limit, pending_embedding, spawn_embedding_worker, DuplicateFilter, DuplicatePairs,
+    SkipReason, SkippedRows,
+};
+use super::review_card::ai_validation_enabled;
 
 const MAX_FILE_BYTES: usize = 10 * 1024 * 1024; // 10 MB
 const MAX_CARDS: usize = 2_000;
 
+/// Which note fields become the card front and back.
+///
+/// Anki notes can have any number of fields; many note types keep the answer
+/// in the third field or later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnkiFieldMapping {
+    pub front_index: usize,
+    pub back_index: usize,
+}
+
+impl Default for AnkiFieldMapping {
+    fn default() -> Self {
+        Self {
+            front_index: 0,
+            back_index: 1,
+        }
+    }
+}
+
 pub struct ImportAnkiUseCase {
     card_repo: Arc<dyn CardRepository>,
     deck_repo: Arc<dyn DeckRepository>,
     deck_stats_repo: Arc<dyn DeckStatsRepository>,
     embedding_service: Arc<dyn EmbeddingService>,
+    quotas: UserQuotas,
+    content_policy: ContentPolicy,
+    user_repository: Option<Arc<dyn UserRepository>>,
+    tmp_dir: Option<PathBuf>,
 }
 
 impl ImportAnkiUseCase {
@@ -45,22 +77,80 @@ impl ImportAnkiUseCase {
             deck_repo,
             deck_stats_repo,
             embedding_service,
+            quotas: UserQuotas::default(),
+            content_policy: ContentPolicy::default(),
+            user_repository: None,
+            tmp_dir: None,
         }
     }
 
-    pub async fn execute(&self, user_id: Uuid, file_bytes: Bytes) -> AppResult<AnkiImportResult> {
+    /// Directory for the extracted collection database (the system temp
+    /// directory when `None`). The file is removed when the import ends.
+    pub fn with_tmp_dir(mut self, tmp_dir: Option<PathBuf>) -> Self {
+        self.tmp_dir = tmp_dir;
+        self
+    }
+
+    /// Per-user deck and card limits. The import fails when no deck can be
+    /// created; notes past the card limit are skipped.
+    pub fn with_quotas(mut self, quotas: UserQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    /// Cards the policy refuses are skipped
+    pub fn with_content_policy(mut self, content_policy: ContentPolicy) -> Self {
+        self.content_policy = content_policy;
+        self
+    }
+
+    /// Users who turned AI validation off get no embeddings
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    /// Import the archive. With `mapping` set, every note uses those field
+    /// indices; otherwise they are derived from each note model's card template,
+    /// falling back to fields 0/1.
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        file_bytes: Bytes,
+        mapping: Option<AnkiFieldMapping>,
+        options: ImportOptions,
+    ) -> AppResult<AnkiImportResult> {
         if file_bytes.len() > MAX_FILE_BYTES {
             return Err(AppError::ValidationError(
                 "File exceeds the 10 MB size limit".to_string(),
             ));
         }
 
+        if self.quotas.max_decks.is_some() {
+            self.quotas
+                .check_new_deck(self.deck_repo.count_by_user(user_id).await?)?;
+        }
+        let card_limit =
+            card_import_limit(&self.quotas, self.card_repo.as_ref(), user_id, MAX_CARDS).await?;
+        let embed = ai_validation_enabled(self.user_repository.as_deref(), user_id).await?;
+        // The deck is new, so duplicates are checked against all the user's cards
+        let existing = self.card_repo.find_by_user(user_id).await?;
+        let mut known_pairs = DuplicatePairs::from_cards(&existing);
+        let mut duplicates = options
+            .skip_duplicates
+            .then(|| DuplicateFilter::from_cards(&existing));
+
         let raw = file_bytes.to_vec();
 
-        // Unzip is synchronous — extract the collection DB bytes in a blocking thread
-        let tmp_path = tokio::task::spawn_blocking(move || extract_collection_to_tempfile(raw))
-            .await
-            .map_err(|e| AppError::InternalError(format!("Anki unzip task panicked: {}", e)))??;
+        // Unzip is synchronous — extract the collection DB bytes in a blocking thread.
+        // The file is deleted when `tmp_path` drops, including on error paths.
+        let tmp_dir = self.tmp_dir.clone();
+        let tmp_path =
+            tokio::task::spawn_blocking(move || extract_collection_to_tempfile(raw, tmp_dir))
+                .await
+                .map_err(|e| {
+                    AppError::InternalError(format!("Anki unzip task panicked: {}", e))
+                })??;
 
         // Open the SQLite collection file with sqlx (async, read-only)
         let opts = SqliteConnectOptions::new()
@@ -72,36 +162,64 @@ impl ImportAnkiUseCase {
         })?;
 
         let deck_name = extract_deck_name(&pool).await;
+        let model_mappings = if mapping.is_some() {
+            HashMap::new()
+        } else {
+            extract_model_mappings(&pool).await
+        };
 
         // Fetch up to MAX_CARDS + 1 rows to detect truncation
-        let rows: Vec<(String,)> =
-            sqlx::query_as(&format!("SELECT flds FROM notes LIMIT {}", MAX_CARDS + 1))
-                .fetch_all(&pool)
-                .await
-                .map_err(|e| {
-                    AppError::InternalError(format!("Failed to query Anki notes: {}", e))
-                })?;
+        let rows: Vec<(i64, String)> = sqlx::query_as(&format!(
+            "SELECT mid, flds FROM notes LIMIT {}",
+            MAX_CARDS + 1
+        ))
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| AppError::InternalError(format!("Failed to query Anki notes: {}", e)))?;
 
         pool.close().await;
+        drop(tmp_path);
 
         let mut pairs: Vec<(String, String)> = Vec::new();
-        let mut skipped: u32 = 0;
+        let mut skipped = SkippedRows::default();
+        let mut cards_duplicate = 0u32;
 
-        for (flds,) in &rows {
-            if pairs.len() >= MAX_CARDS {
-                skipped += 1;
+        for (mid, flds) in &rows {
+            if pairs.len() >= card_limit {
+                skipped.record(SkipReason::OverLimit);
                 continue;
             }
-            let parts: Vec<&str> = flds.splitn(3, '\x1f').collect();
-            if parts.len() < 2 {
-                tracing::warn!("Skipping Anki note with fewer than 2 fields");
-                skipped += 1;
+            let fields = mapping
+                .or_else(|| model_mappings.get(mid).copied())
+                .unwrap_or_default();
+            let parts: Vec<&str> = flds.split('\x1f').collect();
+            let (Some(front), Some(back)) =
+                (parts.get(fields.front_index), parts.get(fields.back_index))
+            else {
+                tracing::warn!(
+                    "Skipping Anki note with {} fields (mapping {:?})",
+                    parts.len(),
+                    fields
+                );
+                skipped.record(SkipReason::MissingField);
                 continue;
-            }
-            let front = strip_html(parts[0]);
-            let back = strip_html(parts[1]);
+            };
+            let front = strip_html(front);
+            let back = strip_html(back);
             if front.is_empty() || back.is_empty() {
-                skipped += 1;
+                skipped.record(SkipReason::Empty);
+                continue;
+            }
+            if self.content_policy.check(&front, &back).is_err() {
+                skipped.record(SkipReason::ContentPolicy);
+                continue;
+            }
+            if known_pairs.is_duplicate(&front, &back) {
+                cards_duplicate += 1;
+                continue;
+            }
+            if duplicates.as_mut().is_some_and(|d| d.is_duplicate(&front)) {
+                skipped.record(SkipReason::Duplicate);
                 continue;
             }
             pairs.push((front, back));
@@ -116,13 +234,21 @@ impl ImportAnkiUseCase {
                 deck_id,
                 deck_name,
                 cards_imported: 0,
-                cards_skipped: skipped,
+                cards_skipped: skipped.total(),
+                cards_duplicate,
+                skip_reasons: skipped.into_reasons(),
             });
         }
 
         let cards: Vec<Card> = pairs
             .iter()
-            .map(|(front, back)| Card::new(user_id, front.clone(), back.clone()).with_deck(deck_id))
+            .enumerate()
+            .map(|(position, (front, back))| {
+                let card = Card::new(user_id, front.clone(), back.clone())
+                    .with_deck(deck_id)
+                    .with_position(position as i32);
+                pending_embedding(card, embed)
+            })
             .collect();
 
         let card_ids = self.card_repo.bulk_create(&cards).await?;
@@ -132,28 +258,35 @@ impl ImportAnkiUseCase {
             .add_to_card_count(deck_id, imported as i32)
             .await?;
 
-        spawn_embedding_worker(
-            cards
-                .into_iter()
-                .zip(card_ids)
-                .map(|(c, id)| (id, c.answer))
-                .collect(),
-            self.card_repo.clone(),
-            self.embedding_service.clone(),
-        );
+        if embed {
+            spawn_embedding_worker(
+                cards
+                    .into_iter()
+                    .zip(card_ids)
+                    .map(|(c, id)| (id, c.answer))
+                    .collect(),
+                self.card_repo.clone(),
+                self.embedding_service.clone(),
+            );
+        }
 
         Ok(AnkiImportResult {
             deck_id,
             deck_name,
             cards_imported: imported,
-            cards_skipped: skipped,
+            cards_skipped: skipped.total(),
+            cards_duplicate,
+            skip_reasons: skipped.into_reasons(),
         })
     }
 }
 
 /// Unzip the .apkg and write `collection.anki21` / `collection.anki2` to a temp file.
 /// Returns the path to the temp file.
-fn extract_collection_to_tempfile(file_bytes: Vec<u8>) -> AppResult<std::path::PathBuf> {
+fn extract_collection_to_tempfile(
+    file_bytes: Vec<u8>,
+    tmp_dir: Option<PathBuf>,
+) -> AppResult<TempPath> {
     use std::io::{Cursor, Read, Write};
 
     let cursor = Cursor::new(file_bytes);
@@ -187,22 +320,22 @@ fn extract_collection_to_tempfile(file_bytes: Vec<u8>) -> AppResult<std::path::P
         .map_err(|e| AppError::InternalError(format!("Failed to read collection bytes: {}", e)))?;
     drop(entry);
 
-    let mut tmp = tempfile::Builder::new()
-        .suffix(".db")
-        .tempfile()
-        .map_err(|e| AppError::InternalError(format!("Failed to create temp file: {}", e)))?;
+    let mut builder = tempfile::Builder::new();
+    builder.suffix(".db");
+    let mut tmp = match tmp_dir {
+        Some(dir) => builder.tempfile_in(dir),
+        None => builder.tempfile(),
+    }
+    .map_err(|e| AppError::InternalError(format!("Failed to create temp file: {}", e)))?;
 
     tmp.write_all(&db_bytes)
         .map_err(|e| AppError::InternalError(format!("Failed to write temp file: {}", e)))?;
     tmp.flush()
         .map_err(|e| AppError::InternalError(format!("Failed to flush temp file: {}", e)))?;
 
-    // Keep the file on disk (persist it) so sqlx can open it
-    let (_, path) = tmp
-        .keep()
-        .map_err(|e| AppError::InternalError(format!("Failed to persist temp file: {}", e)))?;
-
-    Ok(path)
+    // Close the handle but keep the path so sqlx can open the file; the
+    // returned guard deletes it when dropped
+    Ok(tmp.into_temp_path())
 }
 
 /// Extract the first non-"Default" deck name from the `col` table.
@@ -241,7 +374,83 @@ async fn extract_deck_name(pool: &SqlitePool) -> String {
     "Imported Deck".to_string()
 }
 
+/// Derive a front/back field mapping for each note model from the `col.models`
+/// JSON: the front is the first field referenced by the question template and
+/// the back is the first other field referenced by the answer template.
+async fn extract_model_mappings(pool: &SqlitePool) -> HashMap<i64, AnkiFieldMapping> {
+    let mut mappings = HashMap::new();
+
+    let Ok((json_str,)) = sqlx::query_as::<_, (String,)>("SELECT models FROM col LIMIT 1")
+        .fetch_one(pool)
+        .await
+    else {
+        return mappings;
+    };
+    let Ok(models) = serde_json::from_str::<serde_json::Value>(&json_str) else {
+        return mappings;
+    };
+
+    for (mid, model) in models.as_object().into_iter().flatten() {
+        let Ok(mid) = mid.parse::<i64>() else {
+            continue;
+        };
+        if let Some(mapping) = mapping_from_model(model) {
+            mappings.insert(mid, mapping);
+        }
+    }
+
+    mappings
+}
+
+fn mapping_from_model(model: &serde_json::Value) -> Option<AnkiFieldMapping> {
+    let field_ord: HashMap<&str, usize> = model
+        .get("flds")?
+        .as_array()?
+        .iter()
+        .enumerate()
+        .filter_map(|(i, f)| {
+            let name = f.get("name")?.as_str()?;
+            let ord = f
+                .get("ord")
+                .and_then(|o| o.as_u64())
+                .map_or(i, |o| o as usize);
+            Some((name, ord))
+        })
+        .collect();
+
+    let template = model.get("tmpls")?.as_array()?.first()?;
+    let qfmt = template.get("qfmt")?.as_str()?;
+    let afmt = template.get("afmt")?.as_str()?;
+
+    let front_index = template_fields(qfmt)
+        .into_iter()
+        .find_map(|name| field_ord.get(name).copied())?;
+    let back_index = template_fields(afmt)
+        .into_iter()
+        .filter_map(|name| field_ord.get(name).copied())
+        .find(|ord| *ord != front_index)?;
+
+    Some(AnkiFieldMapping {
+        front_index,
+        back_index,
+    })
+}
+
+/// Field names referenced by `{{...}}` in a card template, in order. Section
+/// markers (`#`, `^`, `/`), `FrontSide` and filter prefixes (`text:`) are skipped.
+fn template_fields(template: &str) -> Vec<&str> {
+    template
+        .split("{{")
+        .skip(1)
+        .filter_map(|rest| rest.split_once("}}").map(|(inner, _)| inner.trim()))
+        .filter(|inner| !inner.starts_with(['#', '^', '/', '!']))
+        .map(|inner| inner.rsplit(':').next().unwrap_or(inner).trim())
+        .filter(|name| *name != "FrontSide")
+        .collect()
+}
+
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
     use async_trait::async_trait;
@@ -315,14 +524,6 @@ mod tests {
         async fn get_or_create(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<DeckStats> {
             Ok(DeckStats::new(deck_id, user_id))
         }
-        async fn update_after_review(
-            &self,
-            _deck_id: Uuid,
-            _is_correct: bool,
-            _review_date: chrono::NaiveDate,
-        ) -> AppResult<()> {
-            Ok(())
-        }
         async fn increment_card_count(&self, _deck_id: Uuid) -> AppResult<()> {
             Ok(())
         }
@@ -355,6 +556,20 @@ mod tests {
     /// Build a minimal `.apkg` (ZIP containing a SQLite DB) in memory.
     /// The SQLite DB has a `notes` table with the given `(front, back)` pairs.
     fn build_test_apkg(notes: &[(&str, &str)], deck_name: Option<&str>) -> Vec<u8> {
+        let flds: Vec<String> = notes
+            .iter()
+            .map(|(front, back)| format!("{}\x1f{}", front, back))
+            .collect();
+        build_test_apkg_raw(&flds, deck_name, None)
+    }
+
+    /// Like `build_test_apkg`, but with raw `\x1f`-joined field strings and an
+    /// optional `col.models` JSON (notes all use model id 1).
+    fn build_test_apkg_raw(
+        notes: &[String],
+        deck_name: Option<&str>,
+        models_json: Option<&str>,
+    ) -> Vec<u8> {
         // 1. Create an in-memory SQLite DB via a temp file
         let tmp = tempfile::Builder::new()
             .suffix(".db")
@@ -377,28 +592,32 @@ mod tests {
                     .create_if_missing(true);
                 let pool = sqlx::SqlitePool::connect_with(opts).await.unwrap();
 
-                sqlx::query("CREATE TABLE notes (id INTEGER PRIMARY KEY, flds TEXT NOT NULL)")
-                    .execute(&pool)
-                    .await
-                    .unwrap();
+                sqlx::query(
+                    "CREATE TABLE notes (id INTEGER PRIMARY KEY, mid INTEGER NOT NULL, flds TEXT NOT NULL)",
+                )
+                .execute(&pool)
+                .await
+                .unwrap();
 
-                for (front, back) in notes {
-                    let flds = format!("{}\x1f{}", front, back);
-                    sqlx::query("INSERT INTO notes (flds) VALUES (?)")
+                for flds in notes {
+                    sqlx::query("INSERT INTO notes (mid, flds) VALUES (1, ?)")
                         .bind(flds)
                         .execute(&pool)
                         .await
                         .unwrap();
                 }
 
-                if let Some(name) = deck_name {
-                    let decks_json = format!(r#"{{"1":{{"id":1,"name":"{}","usn":0}}}}"#, name);
-                    sqlx::query("CREATE TABLE col (id INTEGER PRIMARY KEY, decks TEXT)")
+                if deck_name.is_some() || models_json.is_some() {
+                    let decks_json = deck_name.map(|name| {
+                        format!(r#"{{"1":{{"id":1,"name":"{}","usn":0}}}}"#, name)
+                    });
+                    sqlx::query("CREATE TABLE col (id INTEGER PRIMARY KEY, decks TEXT, models TEXT)")
                         .execute(&pool)
                         .await
                         .unwrap();
-                    sqlx::query("INSERT INTO col (id, decks) VALUES (1, ?)")
+                    sqlx::query("INSERT INTO col (id, decks, models) VALUES (1, ?, ?)")
                         .bind(decks_json)
+                        .bind(models_json)
                         .execute(&pool)
                         .await
                         .unwrap();
@@ -431,7 +650,12 @@ mod tests {
     async fn test_import_anki_file_too_large() {
         let big = vec![0u8; MAX_FILE_BYTES + 1];
         let result = make_use_case()
-            .execute(Uuid::new_v4(), Bytes::from(big))
+            .execute(
+                Uuid::new_v4(),
+                Bytes::from(big),
+                None,
+                ImportOptions::default(),
+            )
             .await;
         assert!(matches!(result, Err(AppError::ValidationError(_))));
     }
@@ -439,7 +663,12 @@ mod tests {
     #[tokio::test]
     async fn test_import_anki_invalid_zip() {
         let result = make_use_case()
-            .execute(Uuid::new_v4(), Bytes::from("not a zip at all"))
+            .execute(
+                Uuid::new_v4(),
+                Bytes::from("not a zip at all"),
+                None,
+                ImportOptions::default(),
+            )
             .await;
         assert!(matches!(result, Err(AppError::ValidationError(_))));
     }
@@ -458,7 +687,12 @@ mod tests {
             zip.finish().unwrap();
         }
         let result = make_use_case()
-            .execute(Uuid::new_v4(), Bytes::from(zip_buf))
+            .execute(
+                Uuid::new_v4(),
+                Bytes::from(zip_buf),
+                None,
+                ImportOptions::default(),
+            )
             .await;
         assert!(matches!(result, Err(AppError::ValidationError(_))));
     }
@@ -468,7 +702,12 @@ mod tests {
         let notes = vec![("Hello", "Hola"), ("World", "Mundo"), ("Cat", "Gato")];
         let apkg = build_test_apkg(&notes, Some("Spanish Basics"));
         let result = make_use_case()
-            .execute(Uuid::new_v4(), Bytes::from(apkg))
+            .execute(
+                Uuid::new_v4(),
+                Bytes::from(apkg),
+                None,
+                ImportOptions::default(),
+            )
             .await;
         assert!(result.is_ok(), "{:?}", result.err());
         let r = result.unwrap();
@@ -477,12 +716,37 @@ mod tests {
         assert_eq!(r.deck_name, "Spanish Basics");
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_anki_removes_temp_file() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let use_case = make_use_case().with_tmp_dir(Some(tmp_dir.path().to_path_buf()));
+        let apkg = build_test_apkg(&[("Hello", "Hola")], None);
+
+        let result = use_case
+            .execute(
+                Uuid::new_v4(),
+                Bytes::from(apkg),
+                None,
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.cards_imported, 1);
+        assert_eq!(std::fs::read_dir(tmp_dir.path()).unwrap().count(), 0);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_import_anki_strips_html() {
         let notes = vec![("<b>Bold front</b>", "<i>Italic back</i>")];
         let apkg = build_test_apkg(&notes, None);
         let result = make_use_case()
-            .execute(Uuid::new_v4(), Bytes::from(apkg))
+            .execute(
+                Uuid::new_v4(),
+                Bytes::from(apkg),
+                None,
+                ImportOptions::default(),
+            )
             .await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().cards_imported, 1);
@@ -493,13 +757,176 @@ mod tests {
     async fn test_import_anki_empty_deck() {
         let apkg = build_test_apkg(&[], Some("Empty"));
         let result = make_use_case()
-            .execute(Uuid::new_v4(), Bytes::from(apkg))
+            .execute(
+                Uuid::new_v4(),
+                Bytes::from(apkg),
+                None,
+                ImportOptions::default(),
+            )
             .await;
         assert!(result.is_ok());
         let r = result.unwrap();
         assert_eq!(r.cards_imported, 0);
     }
 
+    struct RecordingCardRepo {
+        cards: std::sync::Mutex<Vec<Card>>,
+    }
+
+    #[async_trait]
+    impl CardRepository for RecordingCardRepo {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            self.cards.lock().unwrap().extend_from_slice(cards);
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Card>> {
+            Ok(None)
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Card>> {
+            let cards = self.cards.lock().unwrap();
+            Ok(cards
+                .iter()
+                .filter(|c| c.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    fn make_recording_use_case() -> (ImportAnkiUseCase, Arc<RecordingCardRepo>) {
+        let card_repo = Arc::new(RecordingCardRepo {
+            cards: std::sync::Mutex::new(Vec::new()),
+        });
+        let use_case = ImportAnkiUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        );
+        (use_case, card_repo)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_anki_twice_reports_duplicates() {
+        let notes = vec![("Hello", "Hola"), ("World", "Mundo"), ("hello ", " HOLA")];
+        let apkg = build_test_apkg(&notes, Some("Spanish Basics"));
+        let (use_case, card_repo) = make_recording_use_case();
+        let user_id = Uuid::new_v4();
+
+        let first = use_case
+            .execute(
+                user_id,
+                Bytes::from(apkg.clone()),
+                None,
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+        let second = use_case
+            .execute(user_id, Bytes::from(apkg), None, ImportOptions::default())
+            .await
+            .unwrap();
+
+        // "hello "/" HOLA" repeats the first note once trimmed and lowercased
+        assert_eq!(first.cards_imported, 2);
+        assert_eq!(first.cards_duplicate, 1);
+        assert_eq!(second.cards_imported, 0);
+        assert_eq!(second.cards_duplicate, 3);
+        assert_eq!(second.cards_skipped, 0);
+        assert_eq!(card_repo.cards.lock().unwrap().len(), 2);
+    }
+
+    const THREE_FIELD_MODEL: &str = r#"{"1":{"id":1,"name":"Vocab",
+        "flds":[{"name":"Word","ord":0},{"name":"Reading","ord":1},{"name":"Meaning","ord":2}],
+        "tmpls":[{"name":"Card 1","qfmt":"{{Word}}","afmt":"{{FrontSide}}<hr id=answer>{{Meaning}}<br>{{hint:Reading}}"}]}}"#;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_anki_three_fields_uses_model_template() {
+        let notes = vec!["食べる\x1fたべる\x1fto eat".to_string()];
+        let apkg = build_test_apkg_raw(&notes, Some("Japanese"), Some(THREE_FIELD_MODEL));
+        let (use_case, card_repo) = make_recording_use_case();
+
+        let result = use_case
+            .execute(
+                Uuid::new_v4(),
+                Bytes::from(apkg),
+                None,
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.cards_imported, 1);
+        let cards = card_repo.cards.lock().unwrap();
+        assert_eq!(cards[0].question, "食べる");
+        assert_eq!(cards[0].answer, "to eat");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_anki_explicit_mapping_overrides_template() {
+        let notes = vec!["食べる\x1fたべる\x1fto eat".to_string()];
+        let apkg = build_test_apkg_raw(&notes, None, Some(THREE_FIELD_MODEL));
+        let (use_case, card_repo) = make_recording_use_case();
+
+        use_case
+            .execute(
+                Uuid::new_v4(),
+                Bytes::from(apkg),
+                Some(AnkiFieldMapping {
+                    front_index: 0,
+                    back_index: 1,
+                }),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        let cards = card_repo.cards.lock().unwrap();
+        assert_eq!(cards[0].answer, "たべる");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_anki_mapping_out_of_range_skips_note() {
+        let apkg = build_test_apkg(&[("Hello", "Hola")], None);
+        let result = make_use_case()
+            .execute(
+                Uuid::new_v4(),
+                Bytes::from(apkg),
+                Some(AnkiFieldMapping {
+                    front_index: 0,
+                    back_index: 2,
+                }),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.cards_imported, 0);
+        assert_eq!(result.cards_skipped, 1);
+    }
+
+    #[test]
+    fn test_template_fields_skips_sections_and_frontside() {
+        assert_eq!(
+            template_fields("{{FrontSide}}<hr>{{#Notes}}{{text:Meaning}}{{/Notes}}"),
+            vec!["Meaning"]
+        );
+    }
+
     #[test]
     fn test_strip_html_removes_tags() {
         assert_eq!(strip_html("<b>Bold</b>"), "Bold");
@@ -516,7 +943,7 @@ mod tests {
 }
 
 /// Strip HTML tags using `ammonia` (allow no tags → only text content remains).
-fn strip_html(html: &str) -> String {
+pub(crate) fn strip_html(html: &str) -> String {
     ammonia::Builder::new()
         .tags(std::collections::HashSet::new())
         .clean(html)
@@ -0,0 +1,401 @@
+//! ImportCsv use case — import cards from a comma- or semicolon-separated
+//! file into an existing deck.
+//!
+//! The first column is the question and the second the answer; further
+//! columns are ignored. Fields may be quoted to contain the delimiter, quotes
+//! (doubled) or line breaks.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::{CsvFormat, ImportOptions, ImportResult},
+    domain::{
+        entities::Card,
+        ports::EmbeddingService,
+        repositories::{CardRepository, DeckRepository, DeckStatsRepository, UserRepository},
+    },
+    shared::{
+        access::{Access, OwnershipPolicy},
+        content_policy::ContentPolicy,
+        error::{AppError, AppResult},
+        quota::UserQuotas,
+    },
+};
+
+use super::import_tsv::{
+    card_import_limit, pending_embedding, spawn_embedding_worker, DuplicateFilter, SkipReason,
+    SkippedRows, MAX_FILE_BYTES,
+};
+use super::review_card::ai_validation_enabled;
+
+const MAX_CARDS: usize = 2_000;
+
+pub struct ImportCsvUseCase {
+    card_repo: Arc<dyn CardRepository>,
+    deck_repo: Arc<dyn DeckRepository>,
+    deck_stats_repo: Arc<dyn DeckStatsRepository>,
+    embedding_service: Arc<dyn EmbeddingService>,
+    ownership_policy: OwnershipPolicy,
+    quotas: UserQuotas,
+    content_policy: ContentPolicy,
+    user_repository: Option<Arc<dyn UserRepository>>,
+}
+
+impl ImportCsvUseCase {
+    pub fn new(
+        card_repo: Arc<dyn CardRepository>,
+        deck_repo: Arc<dyn DeckRepository>,
+        deck_stats_repo: Arc<dyn DeckStatsRepository>,
+        embedding_service: Arc<dyn EmbeddingService>,
+    ) -> Self {
+        Self {
+            card_repo,
+            deck_repo,
+            deck_stats_repo,
+            embedding_service,
+            ownership_policy: OwnershipPolicy::default(),
+            quotas: UserQuotas::default(),
+            content_policy: ContentPolicy::default(),
+            user_repository: None,
+        }
+    }
+
+    /// How decks owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    /// Per-user card limit; cards past it are skipped
+    pub fn with_quotas(mut self, quotas: UserQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    /// Cards the policy refuses are skipped
+    pub fn with_content_policy(mut self, content_policy: ContentPolicy) -> Self {
+        self.content_policy = content_policy;
+        self
+    }
+
+    /// Users who turned AI validation off get no embeddings
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        deck_id: Uuid,
+        file_bytes: Bytes,
+        format: CsvFormat,
+        options: ImportOptions,
+    ) -> AppResult<ImportResult> {
+        if file_bytes.len() > MAX_FILE_BYTES {
+            return Err(AppError::ValidationError(
+                "File exceeds the 10 MB size limit".to_string(),
+            ));
+        }
+
+        let text = std::str::from_utf8(&file_bytes)
+            .map_err(|_| AppError::ValidationError("File is not valid UTF-8".to_string()))?;
+        let (pairs, mut skipped) = parse_rows(text, format)?;
+
+        let deck = self.ownership_policy.authorize(
+            self.deck_repo.find_by_id(deck_id).await?,
+            |deck| deck.user_id,
+            user_id,
+            Access::Write,
+            "Deck",
+            deck_id,
+        )?;
+        let card_limit =
+            card_import_limit(&self.quotas, self.card_repo.as_ref(), user_id, MAX_CARDS).await?;
+        let embed = ai_validation_enabled(self.user_repository.as_deref(), user_id).await?;
+        let mut duplicates = match options.skip_duplicates {
+            true => Some(DuplicateFilter::for_deck(self.card_repo.as_ref(), deck_id).await?),
+            false => None,
+        };
+
+        let mut cards: Vec<Card> = Vec::new();
+        for (front, back) in pairs {
+            if self.content_policy.check(&front, &back).is_err() {
+                skipped.record(SkipReason::ContentPolicy);
+                continue;
+            }
+            if duplicates.as_mut().is_some_and(|d| d.is_duplicate(&front)) {
+                skipped.record(SkipReason::Duplicate);
+                continue;
+            }
+            if cards.len() >= card_limit {
+                skipped.record(SkipReason::OverLimit);
+                continue;
+            }
+            let position = cards.len() as i32;
+            let card = Card::new(user_id, front, back)
+                .with_deck(deck_id)
+                .with_tags(&deck.default_tags)
+                .with_position(position);
+            cards.push(pending_embedding(card, embed));
+        }
+
+        if cards.is_empty() {
+            return Ok(ImportResult {
+                deck_id,
+                cards_imported: 0,
+                cards_skipped: skipped.total(),
+                cards_duplicate: 0,
+                skip_reasons: skipped.into_reasons(),
+                decks: Vec::new(),
+            });
+        }
+
+        let card_ids = self.card_repo.bulk_create(&cards).await?;
+        let imported = card_ids.len() as u32;
+
+        self.deck_stats_repo
+            .add_to_card_count(deck_id, imported as i32)
+            .await?;
+
+        if embed {
+            spawn_embedding_worker(
+                cards
+                    .into_iter()
+                    .zip(card_ids)
+                    .map(|(c, id)| (id, c.answer))
+                    .collect(),
+                self.card_repo.clone(),
+                self.embedding_service.clone(),
+            );
+        }
+
+        Ok(ImportResult {
+            deck_id,
+            cards_imported: imported,
+            cards_skipped: skipped.total(),
+            cards_duplicate: 0,
+            skip_reasons: skipped.into_reasons(),
+            decks: Vec::new(),
+        })
+    }
+}
+
+/// Extract `(front, back)` pairs from the rows, counting rows with a missing
+/// or empty question or answer as skipped
+fn parse_rows(text: &str, format: CsvFormat) -> AppResult<(Vec<(String, String)>, SkippedRows)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(format.delimiter)
+        .has_headers(format.has_header)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+
+    let mut pairs = Vec::new();
+    let mut skipped = SkippedRows::default();
+    for record in reader.records() {
+        let record =
+            record.map_err(|e| AppError::ValidationError(format!("Invalid CSV: {}", e)))?;
+        match (record.get(0), record.get(1)) {
+            (Some(front), Some(back)) if !front.is_empty() && !back.is_empty() => {
+                pairs.push((front.to_string(), back.to_string()))
+            }
+            (Some(_), Some(_)) => {
+                tracing::warn!("Skipping CSV row with an empty front or back: {:?}", record);
+                skipped.record(SkipReason::Empty);
+            }
+            _ => {
+                tracing::warn!("Skipping CSV row (missing back): {:?}", record);
+                skipped.record(SkipReason::MissingField);
+            }
+        }
+    }
+
+    Ok((pairs, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    use crate::domain::entities::{Deck, DeckStats};
+
+    #[derive(Default)]
+    struct MockCardRepo {
+        created: Mutex<Vec<Card>>,
+    }
+
+    #[async_trait]
+    impl CardRepository for MockCardRepo {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            self.created.lock().unwrap().extend_from_slice(cards);
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Card>> {
+            Ok(None)
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct MockDeckRepo {
+        deck: Deck,
+    }
+
+    #[async_trait]
+    impl DeckRepository for MockDeckRepo {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            Ok(deck.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            Ok((self.deck.id == id).then(|| self.deck.clone()))
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(vec![])
+        }
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct MockDeckStatsRepo;
+
+    #[async_trait]
+    impl DeckStatsRepository for MockDeckStatsRepo {
+        async fn get_or_create(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<DeckStats> {
+            Ok(DeckStats::new(deck_id, user_id))
+        }
+        async fn increment_card_count(&self, _deck_id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+        async fn decrement_card_count(&self, _deck_id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+        async fn add_to_card_count(&self, _deck_id: Uuid, _count: i32) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct MockEmbeddingService;
+
+    #[async_trait]
+    impl EmbeddingService for MockEmbeddingService {
+        async fn generate_embedding(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+            Ok(vec![0.1, 0.2, 0.3])
+        }
+    }
+
+    fn make_use_case(deck: Deck, card_repo: Arc<MockCardRepo>) -> ImportCsvUseCase {
+        ImportCsvUseCase::new(
+            card_repo,
+            Arc::new(MockDeckRepo { deck }),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        )
+    }
+
+    async fn import(csv: &str, format: CsvFormat) -> (ImportResult, Vec<(String, String)>) {
+        let deck = Deck::new(Uuid::new_v4(), "Spanish".to_string(), None);
+        let card_repo = Arc::new(MockCardRepo::default());
+        let result = make_use_case(deck.clone(), card_repo.clone())
+            .execute(
+                deck.user_id,
+                deck.id,
+                Bytes::from(csv.to_string()),
+                format,
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+        let pairs = card_repo
+            .created
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|c| (c.question.clone(), c.answer.clone()))
+            .collect();
+        (result, pairs)
+    }
+
+    fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+        expected
+            .iter()
+            .map(|(q, a)| (q.to_string(), a.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_quoted_fields_keep_embedded_delimiters_and_quotes() {
+        let csv =
+            "\"hello, friend\",\"hola, amigo\"\n\"say \"\"hi\"\"\",\"di \"\"hola\"\"\"\nno back\n";
+
+        let (result, created) = import(csv, CsvFormat::default()).await;
+
+        assert_eq!(result.cards_imported, 2);
+        assert_eq!(result.skip_reasons.get("missing_field"), Some(&1));
+        assert_eq!(
+            created,
+            pairs(&[
+                ("hello, friend", "hola, amigo"),
+                ("say \"hi\"", "di \"hola\"")
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_semicolon_delimiter() {
+        let format = CsvFormat {
+            delimiter: b';',
+            has_header: false,
+        };
+
+        let (_, created) = import("cat;el gato, the cat\ndog ; el perro\n", format).await;
+
+        assert_eq!(
+            created,
+            pairs(&[("cat", "el gato, the cat"), ("dog", "el perro")])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_header_row_is_skipped_only_when_requested() {
+        let csv = "front,back\ncat,gato\n";
+
+        let (_, with_header) = import(
+            csv,
+            CsvFormat {
+                has_header: true,
+                ..Default::default()
+            },
+        )
+        .await;
+        let (_, without_header) = import(csv, CsvFormat::default()).await;
+
+        assert_eq!(with_header, pairs(&[("cat", "gato")]));
+        assert_eq!(without_header, pairs(&[("front", "back"), ("cat", "gato")]));
+    }
+}
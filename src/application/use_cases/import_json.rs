@@ -0,0 +1,467 @@
+//! ImportJson use case — import cards together with their review history into
+//! an existing deck, e.g. when migrating from another app.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::{ImportOptions, JsonImportCard, JsonImportFile, JsonImportResult},
+    domain::{
+        entities::{Card, ReviewLog},
+        fsrs::Scheduler,
+        ports::EmbeddingService,
+        repositories::{
+            CardRepository, DeckRepository, DeckStatsRepository, ReviewLogRepository,
+            UserRepository,
+        },
+    },
+    shared::{
+        access::{Access, OwnershipPolicy},
+        content_policy::ContentPolicy,
+        error::{AppError, AppResult},
+        quota::UserQuotas,
+    },
+};
+
+use super::import_tsv::{
+    card_import_limit, pending_embedding, spawn_embedding_worker, DuplicateFilter, SkipReason,
+    SkippedRows, MAX_FILE_BYTES,
+};
+use super::review_card::ai_validation_enabled;
+
+const MAX_CARDS: usize = 2_000;
+/// `validation_method` recorded on imported review logs
+const IMPORTED_METHOD: &str = "imported";
+
+pub struct ImportJsonUseCase {
+    card_repo: Arc<dyn CardRepository>,
+    deck_repo: Arc<dyn DeckRepository>,
+    deck_stats_repo: Arc<dyn DeckStatsRepository>,
+    review_log_repo: Arc<dyn ReviewLogRepository>,
+    embedding_service: Arc<dyn EmbeddingService>,
+    ownership_policy: OwnershipPolicy,
+    quotas: UserQuotas,
+    content_policy: ContentPolicy,
+    user_repository: Option<Arc<dyn UserRepository>>,
+}
+
+impl ImportJsonUseCase {
+    pub fn new(
+        card_repo: Arc<dyn CardRepository>,
+        deck_repo: Arc<dyn DeckRepository>,
+        deck_stats_repo: Arc<dyn DeckStatsRepository>,
+        review_log_repo: Arc<dyn ReviewLogRepository>,
+        embedding_service: Arc<dyn EmbeddingService>,
+    ) -> Self {
+        Self {
+            card_repo,
+            deck_repo,
+            deck_stats_repo,
+            review_log_repo,
+            embedding_service,
+            ownership_policy: OwnershipPolicy::default(),
+            quotas: UserQuotas::default(),
+            content_policy: ContentPolicy::default(),
+            user_repository: None,
+        }
+    }
+
+    /// How decks owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    /// Per-user card limit; cards past it are skipped
+    pub fn with_quotas(mut self, quotas: UserQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    /// Cards the policy refuses are skipped
+    pub fn with_content_policy(mut self, content_policy: ContentPolicy) -> Self {
+        self.content_policy = content_policy;
+        self
+    }
+
+    /// Users who turned AI validation off get no embeddings
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    /// Import the cards in `file_bytes`. Each card's review history is stored
+    /// as review logs with the original timestamps, and its FSRS state is
+    /// rebuilt by replaying the ratings in order with the deck's scheduler.
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        deck_id: Uuid,
+        file_bytes: Bytes,
+        options: ImportOptions,
+    ) -> AppResult<JsonImportResult> {
+        if file_bytes.len() > MAX_FILE_BYTES {
+            return Err(AppError::ValidationError(
+                "File exceeds the 10 MB size limit".to_string(),
+            ));
+        }
+
+        let file: JsonImportFile = serde_json::from_slice(&file_bytes)
+            .map_err(|e| AppError::ValidationError(format!("Invalid JSON import file: {}", e)))?;
+
+        let deck = self.ownership_policy.authorize(
+            self.deck_repo.find_by_id(deck_id).await?,
+            |deck| deck.user_id,
+            user_id,
+            Access::Write,
+            "Deck",
+            deck_id,
+        )?;
+        let scheduler = deck
+            .fsrs_weights
+            .as_deref()
+            .and_then(|weights| Scheduler::new(weights).ok())
+            .unwrap_or_default();
+        let card_limit =
+            card_import_limit(&self.quotas, self.card_repo.as_ref(), user_id, MAX_CARDS).await?;
+        let embed = ai_validation_enabled(self.user_repository.as_deref(), user_id).await?;
+        let mut duplicates = match options.skip_duplicates {
+            true => Some(DuplicateFilter::for_deck(self.card_repo.as_ref(), deck_id).await?),
+            false => None,
+        };
+
+        let mut cards: Vec<Card> = Vec::new();
+        let mut review_logs: Vec<ReviewLog> = Vec::new();
+        let mut skipped = SkippedRows::default();
+
+        for entry in file.cards {
+            if cards.len() >= card_limit {
+                skipped.record(SkipReason::OverLimit);
+                continue;
+            }
+            if let Err((kind, reason)) = validate_card(&entry).and_then(|()| {
+                self.content_policy
+                    .check(&entry.question, &entry.answer)
+                    .map_err(|reason| (SkipReason::ContentPolicy, reason))
+            }) {
+                tracing::warn!(
+                    "Skipping JSON import card ({}): {:?}",
+                    reason,
+                    entry.question
+                );
+                skipped.record(kind);
+                continue;
+            }
+            if duplicates
+                .as_mut()
+                .is_some_and(|d| d.is_duplicate(&entry.question))
+            {
+                skipped.record(SkipReason::Duplicate);
+                continue;
+            }
+
+            let mut reviews = entry.reviews;
+            reviews.sort_by_key(|review| review.reviewed_at);
+
+            let mut card = Card::new(
+                user_id,
+                entry.question.trim().to_string(),
+                entry.answer.trim().to_string(),
+            )
+            .with_deck(deck_id)
+            .with_tags(&entry.tags)
+            .with_tags(&deck.default_tags)
+            .with_position(cards.len() as i32);
+            card = pending_embedding(card, embed);
+            card.fsrs_state = scheduler.replay(reviews.iter().map(|r| (r.rating, r.reviewed_at)));
+
+            review_logs.extend(reviews.into_iter().map(|review| {
+                let mut log = ReviewLog::new(
+                    card.id,
+                    user_id,
+                    review.user_answer,
+                    card.answer.clone(),
+                    review.score,
+                    IMPORTED_METHOD.to_string(),
+                    review.rating,
+                );
+                log.created_at = review.reviewed_at;
+                log
+            }));
+            cards.push(card);
+        }
+
+        if cards.is_empty() {
+            return Ok(JsonImportResult {
+                cards_imported: 0,
+                cards_skipped: skipped.total(),
+                skip_reasons: skipped.into_reasons(),
+                reviews_imported: 0,
+            });
+        }
+
+        let card_ids = self.card_repo.bulk_create(&cards).await?;
+        let imported = card_ids.len() as u32;
+
+        for log in &review_logs {
+            self.review_log_repo.create(log).await?;
+        }
+
+        self.deck_stats_repo
+            .add_to_card_count(deck_id, imported as i32)
+            .await?;
+
+        if embed {
+            spawn_embedding_worker(
+                cards
+                    .into_iter()
+                    .zip(card_ids)
+                    .map(|(c, id)| (id, c.answer))
+                    .collect(),
+                self.card_repo.clone(),
+                self.embedding_service.clone(),
+            );
+        }
+
+        Ok(JsonImportResult {
+            cards_imported: imported,
+            cards_skipped: skipped.total(),
+            skip_reasons: skipped.into_reasons(),
+            reviews_imported: review_logs.len() as u32,
+        })
+    }
+}
+
+fn validate_card(card: &JsonImportCard) -> Result<(), (SkipReason, &'static str)> {
+    if card.question.trim().is_empty() {
+        return Err((SkipReason::Empty, "missing question"));
+    }
+    if card.answer.trim().is_empty() {
+        return Err((SkipReason::Empty, "missing answer"));
+    }
+    if card.reviews.iter().any(|r| !(1..=4).contains(&r.rating)) {
+        return Err((
+            SkipReason::InvalidReview,
+            "review rating must be between 1 and 4",
+        ));
+    }
+    if card.reviews.iter().any(|r| !(0.0..=1.0).contains(&r.score)) {
+        return Err((
+            SkipReason::InvalidReview,
+            "review score must be between 0 and 1",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::{Duration, TimeZone, Utc};
+    use std::sync::Mutex;
+
+    use crate::domain::entities::{Deck, DeckStats};
+
+    #[derive(Default)]
+    struct MockCardRepo {
+        created: Mutex<Vec<Card>>,
+    }
+
+    #[async_trait]
+    impl CardRepository for MockCardRepo {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            self.created.lock().unwrap().extend_from_slice(cards);
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Card>> {
+            Ok(None)
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct MockDeckRepo {
+        deck: Deck,
+    }
+
+    #[async_trait]
+    impl DeckRepository for MockDeckRepo {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            Ok(deck.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            Ok((self.deck.id == id).then(|| self.deck.clone()))
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(vec![])
+        }
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct MockDeckStatsRepo;
+
+    #[async_trait]
+    impl DeckStatsRepository for MockDeckStatsRepo {
+        async fn get_or_create(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<DeckStats> {
+            Ok(DeckStats::new(deck_id, user_id))
+        }
+        async fn increment_card_count(&self, _deck_id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+        async fn decrement_card_count(&self, _deck_id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+        async fn add_to_card_count(&self, _deck_id: Uuid, _count: i32) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockReviewLogRepo {
+        created: Mutex<Vec<ReviewLog>>,
+    }
+
+    #[async_trait]
+    impl ReviewLogRepository for MockReviewLogRepo {
+        async fn create(&self, review_log: &ReviewLog) -> AppResult<Uuid> {
+            self.created.lock().unwrap().push(review_log.clone());
+            Ok(review_log.id)
+        }
+        async fn find_by_card(&self, _card_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(vec![])
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(vec![])
+        }
+    }
+
+    struct MockEmbeddingService;
+
+    #[async_trait]
+    impl EmbeddingService for MockEmbeddingService {
+        async fn generate_embedding(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+            Ok(vec![0.1, 0.2, 0.3])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_replays_review_history() {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Migrated".to_string(), None);
+        let deck_id = deck.id;
+        let cards = Arc::new(MockCardRepo::default());
+        let logs = Arc::new(MockReviewLogRepo::default());
+        let use_case = ImportJsonUseCase::new(
+            cards.clone(),
+            Arc::new(MockDeckRepo { deck }),
+            Arc::new(MockDeckStatsRepo),
+            logs.clone(),
+            Arc::new(MockEmbeddingService),
+        );
+
+        let first = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+        let history = [
+            (3, first),
+            (1, first + Duration::days(4)),
+            (4, first + Duration::days(5)),
+        ];
+        // Reviews are listed out of order on purpose; they are replayed by date
+        let json = serde_json::json!({
+            "cards": [{
+                "question": "hola",
+                "answer": "hello",
+                "reviews": [
+                    { "user_answer": "hi", "score": 0.95, "rating": history[2].0, "reviewed_at": history[2].1 },
+                    { "user_answer": "hello", "score": 0.8, "rating": history[0].0, "reviewed_at": history[0].1 },
+                    { "user_answer": "bye", "score": 0.1, "rating": history[1].0, "reviewed_at": history[1].1 }
+                ]
+            }]
+        });
+
+        let result = use_case
+            .execute(
+                user_id,
+                deck_id,
+                Bytes::from(json.to_string()),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.cards_imported, 1);
+        assert_eq!(result.reviews_imported, 3);
+
+        let expected = Scheduler::default().replay(history);
+        let created = cards.created.lock().unwrap();
+        let state = &created[0].fsrs_state;
+        assert_eq!(state.reps, 3);
+        assert_eq!(state.lapses, 1);
+        assert_eq!(state.state, expected.state);
+        assert_eq!(state.stability, expected.stability);
+        assert_eq!(state.difficulty, expected.difficulty);
+        assert_eq!(state.scheduled_days, expected.scheduled_days);
+        assert_eq!(state.last_review, Some(history[2].1));
+
+        let logs = logs.created.lock().unwrap();
+        let timestamps: Vec<_> = logs.iter().map(|log| log.created_at).collect();
+        assert_eq!(timestamps, history.map(|(_, at)| at).to_vec());
+        assert!(logs.iter().all(|log| log.card_id == created[0].id));
+    }
+
+    #[tokio::test]
+    async fn test_import_skips_invalid_ratings() {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Migrated".to_string(), None);
+        let deck_id = deck.id;
+        let use_case = ImportJsonUseCase::new(
+            Arc::new(MockCardRepo::default()),
+            Arc::new(MockDeckRepo { deck }),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockReviewLogRepo::default()),
+            Arc::new(MockEmbeddingService),
+        );
+        let json = r#"{"cards": [
+            {"question": "a", "answer": "b"},
+            {"question": "c", "answer": "d",
+             "reviews": [{"score": 0.5, "rating": 7, "reviewed_at": "2024-01-01T00:00:00Z"}]}
+        ]}"#;
+
+        let result = use_case
+            .execute(
+                user_id,
+                deck_id,
+                Bytes::from(json),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.cards_imported, 1);
+        assert_eq!(result.cards_skipped, 1);
+        assert_eq!(result.reviews_imported, 0);
+    }
+}
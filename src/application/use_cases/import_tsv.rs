@@ -1,47 +1,112 @@
-//! ImportTsv use case — bulk import cards from a TSV file into an existing deck.
+//! ImportTsv use case — bulk import cards from a TSV file into an existing deck,
+//! into the deck named by a `#deck:` directive, or into per-row decks.
 
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use bytes::Bytes;
 use uuid::Uuid;
 
 use crate::{
-    application::dtos::ImportResult,
+    application::{
+        dtos::{DeckImportCount, ImportOptions, ImportResult},
+        use_cases::review_card::ai_validation_enabled,
+    },
     domain::{
-        entities::Card,
+        entities::{Card, Deck, EmbeddingStatus},
         ports::EmbeddingService,
-        repositories::{CardRepository, DeckStatsRepository},
+        repositories::{CardRepository, DeckRepository, DeckStatsRepository, UserRepository},
+    },
+    shared::{
+        access::{Access, OwnershipPolicy},
+        content_policy::ContentPolicy,
+        error::{AppError, AppResult},
+        quota::UserQuotas,
     },
-    shared::error::{AppError, AppResult},
 };
 
-const MAX_FILE_BYTES: usize = 10 * 1024 * 1024; // 10 MB
+pub(crate) const MAX_FILE_BYTES: usize = 10 * 1024 * 1024; // 10 MB
 const MAX_CARDS: usize = 2_000;
+/// Comment line naming the deck to import into, e.g. `#deck:Spanish`
+const DECK_DIRECTIVE: &str = "#deck:";
 
 pub struct ImportTsvUseCase {
     card_repo: Arc<dyn CardRepository>,
+    deck_repo: Arc<dyn DeckRepository>,
     deck_stats_repo: Arc<dyn DeckStatsRepository>,
     embedding_service: Arc<dyn EmbeddingService>,
+    ownership_policy: OwnershipPolicy,
+    quotas: UserQuotas,
+    content_policy: ContentPolicy,
+    user_repository: Option<Arc<dyn UserRepository>>,
 }
 
 impl ImportTsvUseCase {
     pub fn new(
         card_repo: Arc<dyn CardRepository>,
+        deck_repo: Arc<dyn DeckRepository>,
         deck_stats_repo: Arc<dyn DeckStatsRepository>,
         embedding_service: Arc<dyn EmbeddingService>,
     ) -> Self {
         Self {
             card_repo,
+            deck_repo,
             deck_stats_repo,
             embedding_service,
+            ownership_policy: OwnershipPolicy::default(),
+            quotas: UserQuotas::default(),
+            content_policy: ContentPolicy::default(),
+            user_repository: None,
         }
     }
 
+    /// How decks owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    /// Per-user card limit; cards past it are skipped
+    pub fn with_quotas(mut self, quotas: UserQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    /// Cards the policy refuses are skipped
+    pub fn with_content_policy(mut self, content_policy: ContentPolicy) -> Self {
+        self.content_policy = content_policy;
+        self
+    }
+
+    /// Users who turned AI validation off get no embeddings
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    /// Import into `deck_id`, or, when it is `None`, into the deck named by
+    /// the file's `#deck:` directive (created if the user has none by that
+    /// name).
+    ///
+    /// Every line is either ignored or a row. Blank lines (only whitespace,
+    /// including a trailing newline at the end of the file) and `#` comments
+    /// are ignored and never counted. Each row is imported or skipped exactly
+    /// once: `missing_field` when it has no tab (or no deck to go into),
+    /// `empty` when the front or back is blank, then `content_policy`,
+    /// `duplicate` and `over_limit` in that order.
+    ///
+    /// With a deck column (`options.deck_column`, or a `front\tback\tdeck`
+    /// header line) each row goes into the deck named in its third column,
+    /// found or created like the directive's; rows without one use the deck
+    /// above.
     pub async fn execute(
         &self,
         user_id: Uuid,
-        deck_id: Uuid,
+        deck_id: Option<Uuid>,
         file_bytes: Bytes,
+        options: ImportOptions,
     ) -> AppResult<ImportResult> {
         if file_bytes.len() > MAX_FILE_BYTES {
             return Err(AppError::ValidationError(
@@ -52,91 +117,438 @@ impl ImportTsvUseCase {
         let text = std::str::from_utf8(&file_bytes)
             .map_err(|_| AppError::ValidationError("File is not valid UTF-8".to_string()))?;
 
-        let mut cards: Vec<Card> = Vec::new();
-        let mut skipped: u32 = 0;
+        let has_header = has_deck_header(text);
+        let deck_column = options.deck_column || has_header;
+        let default_deck = match (deck_id, deck_directive(text)) {
+            (Some(deck_id), _) => Some(self.authorize_deck(user_id, deck_id).await?.id),
+            (None, Some(name)) => Some(self.find_or_create_deck(user_id, name).await?),
+            (None, None) => None,
+        };
+        if default_deck.is_none() && !deck_column {
+            return Err(no_deck_error());
+        }
 
-        for line in text.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
+        let card_limit =
+            card_import_limit(&self.quotas, self.card_repo.as_ref(), user_id, MAX_CARDS).await?;
+        let embed = ai_validation_enabled(self.user_repository.as_deref(), user_id).await?;
+        let mut targets = DeckTargets::default();
+        let mut cards: Vec<Card> = Vec::new();
+        let mut skipped = SkippedRows::default();
+        let mut duplicates = 0u32;
 
-            let mut parts = line.splitn(2, '\t');
-            let front = match parts.next() {
-                Some(f) if !f.trim().is_empty() => f.trim().to_string(),
-                _ => {
-                    tracing::warn!("Skipping TSV line (missing front): {:?}", line);
-                    skipped += 1;
-                    continue;
-                }
-            };
+        // Split the untrimmed line, so a blank first or last column stays an
+        // (empty) column instead of disappearing with the surrounding tabs
+        let lines = text
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .skip(usize::from(has_header));
+        for line in lines {
+            let mut parts = line.splitn(if deck_column { 3 } else { 2 }, '\t');
+            let front = parts.next().unwrap_or_default().trim().to_string();
             let back = match parts.next() {
-                Some(b) if !b.trim().is_empty() => b.trim().to_string(),
-                _ => {
+                Some(b) => b.trim().to_string(),
+                None => {
                     tracing::warn!("Skipping TSV line (missing back): {:?}", line);
-                    skipped += 1;
+                    skipped.record(SkipReason::MissingField);
                     continue;
                 }
             };
+            if front.is_empty() || back.is_empty() {
+                tracing::warn!("Skipping TSV line (empty front or back): {:?}", line);
+                skipped.record(SkipReason::Empty);
+                continue;
+            }
+
+            if let Err(reason) = self.content_policy.check(&front, &back) {
+                tracing::warn!("Skipping TSV line ({}): {:?}", reason, line);
+                skipped.record(SkipReason::ContentPolicy);
+                continue;
+            }
+
+            let deck_id = match parts.next().map(str::trim).filter(|name| !name.is_empty()) {
+                Some(name) => match targets.by_name.get(name) {
+                    Some(deck_id) => *deck_id,
+                    None => {
+                        let deck_id = self.find_or_create_deck(user_id, name).await?;
+                        targets.by_name.insert(name.to_string(), deck_id);
+                        deck_id
+                    }
+                },
+                None => match default_deck {
+                    Some(deck_id) => deck_id,
+                    None => {
+                        tracing::warn!("Skipping TSV line (missing deck): {:?}", line);
+                        skipped.record(SkipReason::MissingField);
+                        continue;
+                    }
+                },
+            };
+            let target = self
+                .deck_target(&mut targets, user_id, deck_id, options.skip_duplicates)
+                .await?;
+
+            if target.pairs.is_duplicate(&front, &back) {
+                duplicates += 1;
+                continue;
+            }
+            if target
+                .duplicates
+                .as_mut()
+                .is_some_and(|d| d.is_duplicate(&front))
+            {
+                skipped.record(SkipReason::Duplicate);
+                continue;
+            }
 
-            if cards.len() >= MAX_CARDS {
-                skipped += 1;
+            if cards.len() >= card_limit {
+                skipped.record(SkipReason::OverLimit);
                 continue;
             }
 
-            cards.push(Card::new(user_id, front, back).with_deck(deck_id));
+            target.imported += 1;
+            let position = cards.len() as i32;
+            let card = Card::new(user_id, front, back)
+                .with_deck(deck_id)
+                .with_tags(&target.default_tags)
+                .with_position(position);
+            cards.push(pending_embedding(card, embed));
         }
 
+        // Without a default deck, report the first deck the file named
+        let deck_id = match default_deck.or(targets.order.first().copied()) {
+            Some(deck_id) => deck_id,
+            None => return Err(no_deck_error()),
+        };
+        let decks = match deck_column {
+            true => targets.counts(),
+            false => Vec::new(),
+        };
+
         if cards.is_empty() {
             return Ok(ImportResult {
+                deck_id,
                 cards_imported: 0,
-                cards_skipped: skipped,
+                cards_skipped: skipped.total(),
+                cards_duplicate: duplicates,
+                skip_reasons: skipped.into_reasons(),
+                decks,
             });
         }
 
         let card_ids = self.card_repo.bulk_create(&cards).await?;
         let imported = card_ids.len() as u32;
 
-        // Update deck stats card count
-        self.deck_stats_repo
-            .add_to_card_count(deck_id, imported as i32)
-            .await?;
+        // Update deck stats card counts
+        for deck in targets.order.iter().filter_map(|id| targets.by_id.get(id)) {
+            if deck.imported > 0 {
+                self.deck_stats_repo
+                    .add_to_card_count(deck.deck_id, deck.imported as i32)
+                    .await?;
+            }
+        }
 
         // Spawn background task to generate embeddings without blocking the response
-        spawn_embedding_worker(
-            cards
-                .into_iter()
-                .zip(card_ids)
-                .map(|(c, id)| (id, c.answer))
-                .collect(),
-            self.card_repo.clone(),
-            self.embedding_service.clone(),
-        );
+        if embed {
+            spawn_embedding_worker(
+                cards
+                    .into_iter()
+                    .zip(card_ids)
+                    .map(|(c, id)| (id, c.answer))
+                    .collect(),
+                self.card_repo.clone(),
+                self.embedding_service.clone(),
+            );
+        }
 
         Ok(ImportResult {
+            deck_id,
             cards_imported: imported,
-            cards_skipped: skipped,
+            cards_skipped: skipped.total(),
+            cards_duplicate: duplicates,
+            skip_reasons: skipped.into_reasons(),
+            decks,
         })
     }
+
+    /// Import state for `deck_id`, loaded the first time a row targets it
+    async fn deck_target<'a>(
+        &self,
+        targets: &'a mut DeckTargets,
+        user_id: Uuid,
+        deck_id: Uuid,
+        skip_duplicates: bool,
+    ) -> AppResult<&'a mut DeckTarget> {
+        if !targets.by_id.contains_key(&deck_id) {
+            // Checked before the deck's cards are read into the duplicate
+            // filters, whose counts would otherwise reveal them
+            let deck = self.authorize_deck(user_id, deck_id).await?;
+            let existing = self.card_repo.find_by_deck(deck_id).await?;
+            let duplicates = skip_duplicates.then(|| DuplicateFilter::from_cards(&existing));
+            targets.order.push(deck_id);
+            targets.by_id.insert(
+                deck_id,
+                DeckTarget {
+                    deck_id,
+                    deck_name: deck.name,
+                    default_tags: deck.default_tags,
+                    pairs: DuplicatePairs::from_cards(&existing),
+                    duplicates,
+                    imported: 0,
+                },
+            );
+        }
+        Ok(targets
+            .by_id
+            .get_mut(&deck_id)
+            .expect("deck target inserted above"))
+    }
+
+    /// `deck_id`, if `user_id` may import into it
+    async fn authorize_deck(&self, user_id: Uuid, deck_id: Uuid) -> AppResult<Deck> {
+        self.ownership_policy.authorize(
+            self.deck_repo.find_by_id(deck_id).await?,
+            |deck| deck.user_id,
+            user_id,
+            Access::Write,
+            "Deck",
+            deck_id,
+        )
+    }
+
+    /// The user's deck called `name`, created when there is none
+    async fn find_or_create_deck(&self, user_id: Uuid, name: &str) -> AppResult<Uuid> {
+        let decks = self.deck_repo.find_by_user(user_id).await?;
+        if let Some(deck) = decks.into_iter().find(|deck| deck.name == name) {
+            return Ok(deck.id);
+        }
+        if self.quotas.max_decks.is_some() {
+            self.quotas
+                .check_new_deck(self.deck_repo.count_by_user(user_id).await?)?;
+        }
+        self.deck_repo
+            .create(&Deck::new(user_id, name.to_string(), None))
+            .await
+    }
+}
+
+fn no_deck_error() -> AppError {
+    AppError::ValidationError(
+        "No deck given: import into a deck or add a '#deck:<name>' line".to_string(),
+    )
+}
+
+/// Decks an import writes to, in the order the file first names them
+#[derive(Default)]
+struct DeckTargets {
+    by_id: HashMap<Uuid, DeckTarget>,
+    by_name: HashMap<String, Uuid>,
+    order: Vec<Uuid>,
+}
+
+impl DeckTargets {
+    fn counts(&self) -> Vec<DeckImportCount> {
+        self.order
+            .iter()
+            .filter_map(|id| self.by_id.get(id))
+            .map(|target| DeckImportCount {
+                deck_id: target.deck_id,
+                deck_name: target.deck_name.clone(),
+                cards_imported: target.imported,
+            })
+            .collect()
+    }
+}
+
+struct DeckTarget {
+    deck_id: Uuid,
+    deck_name: String,
+    default_tags: Vec<String>,
+    pairs: DuplicatePairs,
+    duplicates: Option<DuplicateFilter>,
+    imported: u32,
+}
+
+/// Whether the first row of `text` is a `front\tback\tdeck` header
+fn has_deck_header(text: &str) -> bool {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .is_some_and(|line| {
+            let columns: Vec<String> = line.split('\t').map(|c| c.trim().to_lowercase()).collect();
+            columns == ["front", "back", "deck"]
+        })
+}
+
+/// Deck name from the first `#deck:` directive of `text`, if any
+fn deck_directive(text: &str) -> Option<&str> {
+    text.lines()
+        .filter_map(|line| line.trim().strip_prefix(DECK_DIRECTIVE))
+        .map(str::trim)
+        .find(|name| !name.is_empty())
+}
+
+/// Maximum number of cards one import may create for `user_id`: `file_limit`,
+/// lowered to what is left of the user's card quota.
+pub(crate) async fn card_import_limit(
+    quotas: &UserQuotas,
+    card_repo: &dyn CardRepository,
+    user_id: Uuid,
+    file_limit: usize,
+) -> AppResult<usize> {
+    if quotas.max_cards.is_none() {
+        return Ok(file_limit);
+    }
+    let remaining = quotas
+        .remaining_cards(card_repo.count_by_user(user_id).await?)
+        .unwrap_or(file_limit as i64);
+    Ok((remaining as usize).min(file_limit))
+}
+
+/// Fronts of existing cards, used to skip duplicates during an import. Seeded
+/// with one query; fronts imported so far are added as they are seen, so
+/// repeats within the same file are skipped too.
+pub(crate) struct DuplicateFilter {
+    seen: HashSet<String>,
+}
+
+impl DuplicateFilter {
+    pub(crate) async fn for_deck(card_repo: &dyn CardRepository, deck_id: Uuid) -> AppResult<Self> {
+        Ok(Self::from_cards(&card_repo.find_by_deck(deck_id).await?))
+    }
+
+    pub(crate) fn from_cards(cards: &[Card]) -> Self {
+        Self {
+            seen: cards
+                .iter()
+                .map(|card| normalize_front(&card.question))
+                .collect(),
+        }
+    }
+
+    /// Whether `front` was already seen; records it otherwise
+    pub(crate) fn is_duplicate(&mut self, front: &str) -> bool {
+        !self.seen.insert(normalize_front(front))
+    }
+}
+
+/// `(question, answer)` pairs of existing cards. Rows matching one are never
+/// imported and are reported as `cards_duplicate`, whatever the options; pairs
+/// imported so far are added as they are seen.
+pub(crate) struct DuplicatePairs {
+    seen: HashSet<(String, String)>,
+}
+
+impl DuplicatePairs {
+    pub(crate) fn from_cards(cards: &[Card]) -> Self {
+        Self {
+            seen: cards
+                .iter()
+                .map(|card| normalize_pair(&card.question, &card.answer))
+                .collect(),
+        }
+    }
+
+    /// Whether the pair was already seen; records it otherwise
+    pub(crate) fn is_duplicate(&mut self, question: &str, answer: &str) -> bool {
+        !self.seen.insert(normalize_pair(question, answer))
+    }
+}
+
+fn normalize_pair(question: &str, answer: &str) -> (String, String) {
+    (question.trim().to_lowercase(), answer.trim().to_lowercase())
+}
+
+/// Why an import skipped a row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SkipReason {
+    /// The front or back column/element is absent
+    MissingField,
+    /// The front or back is present but blank
+    Empty,
+    /// The front matches an existing card (with `skip_duplicates`)
+    Duplicate,
+    /// Past the per-file or per-user card limit
+    OverLimit,
+    /// Refused by the content policy (e.g. identical sides)
+    ContentPolicy,
+    /// Malformed review history (JSON import)
+    InvalidReview,
+}
+
+impl SkipReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::MissingField => "missing_field",
+            Self::Empty => "empty",
+            Self::Duplicate => "duplicate",
+            Self::OverLimit => "over_limit",
+            Self::ContentPolicy => "content_policy",
+            Self::InvalidReview => "invalid_review",
+        }
+    }
+}
+
+/// Rows skipped by an import, counted per reason
+#[derive(Debug, Default)]
+pub(crate) struct SkippedRows {
+    reasons: HashMap<String, u32>,
+}
+
+impl SkippedRows {
+    pub(crate) fn record(&mut self, reason: SkipReason) {
+        *self.reasons.entry(reason.as_str().to_string()).or_default() += 1;
+    }
+
+    pub(crate) fn total(&self) -> u32 {
+        self.reasons.values().sum()
+    }
+
+    pub(crate) fn into_reasons(self) -> HashMap<String, u32> {
+        self.reasons
+    }
+}
+
+/// Lowercase with whitespace runs collapsed to single spaces
+fn normalize_front(front: &str) -> String {
+    front
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// `card` marked for the embedding worker, or left unembedded when its owner
+/// turned AI validation off (`embed` is false)
+pub(crate) fn pending_embedding(card: Card, embed: bool) -> Card {
+    match embed {
+        true => card.with_embedding_pending(),
+        false => card,
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
     use async_trait::async_trait;
 
     use crate::{
+        application::use_cases::test_support::SingleUserRepository,
         domain::{
-            entities::{Card, DeckStats},
-            repositories::{CardRepository, DeckStatsRepository},
+            entities::{Card, Deck, DeckStats},
+            repositories::{CardRepository, DeckRepository, DeckStatsRepository},
         },
         AppError,
     };
+    use std::sync::Mutex;
 
     // ── Mocks ──────────────────────────────────────────────────────────────────
 
+    #[derive(Default)]
     struct MockCardRepo {
         fail: bool,
+        created: Mutex<Vec<Card>>,
+        embedding_statuses: Mutex<HashMap<Uuid, EmbeddingStatus>>,
     }
 
     #[async_trait]
@@ -148,6 +560,7 @@ mod tests {
             if self.fail {
                 return Err(AppError::InternalError("db error".to_string()));
             }
+            self.created.lock().unwrap().extend_from_slice(cards);
             Ok(cards.iter().map(|c| c.id).collect())
         }
         async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Card>> {
@@ -156,13 +569,71 @@ mod tests {
         async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Card>> {
             Ok(vec![])
         }
-        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
-            Ok(vec![])
+        async fn find_by_deck(&self, deck_id: Uuid) -> AppResult<Vec<Card>> {
+            let created = self.created.lock().unwrap();
+            Ok(created
+                .iter()
+                .filter(|c| c.deck_id == Some(deck_id))
+                .cloned()
+                .collect())
         }
         async fn update(&self, _card: &Card) -> AppResult<()> {
             Ok(())
         }
-        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+        async fn update_embedding(&self, id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            self.set_embedding_status(id, EmbeddingStatus::Ready).await
+        }
+        async fn set_embedding_status(&self, id: Uuid, status: EmbeddingStatus) -> AppResult<()> {
+            self.embedding_statuses.lock().unwrap().insert(id, status);
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    const USER_ID: Uuid = Uuid::from_u128(1);
+    const DECK_ID: Uuid = Uuid::from_u128(2);
+
+    /// Deck `DECK_ID`, owned by `USER_ID`
+    fn owned_deck() -> Deck {
+        Deck {
+            id: DECK_ID,
+            ..Deck::new(USER_ID, "Imported".to_string(), None)
+        }
+    }
+
+    struct MockDeckRepo {
+        decks: Mutex<Vec<Deck>>,
+    }
+
+    impl MockDeckRepo {
+        fn with_decks(decks: impl IntoIterator<Item = Deck>) -> Self {
+            Self {
+                decks: Mutex::new(decks.into_iter().collect()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl DeckRepository for MockDeckRepo {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            self.decks.lock().unwrap().push(deck.clone());
+            Ok(deck.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            let decks = self.decks.lock().unwrap();
+            Ok(decks.iter().find(|d| d.id == id).cloned())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Deck>> {
+            let decks = self.decks.lock().unwrap();
+            Ok(decks
+                .iter()
+                .filter(|d| d.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
             Ok(())
         }
         async fn delete(&self, _id: Uuid) -> AppResult<()> {
@@ -177,14 +648,6 @@ mod tests {
         async fn get_or_create(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<DeckStats> {
             Ok(DeckStats::new(deck_id, user_id))
         }
-        async fn update_after_review(
-            &self,
-            _deck_id: Uuid,
-            _is_correct: bool,
-            _review_date: chrono::NaiveDate,
-        ) -> AppResult<()> {
-            Ok(())
-        }
         async fn increment_card_count(&self, _deck_id: Uuid) -> AppResult<()> {
             Ok(())
         }
@@ -205,9 +668,40 @@ mod tests {
         }
     }
 
+    /// Fails for answers containing "fail"
+    struct FlakyEmbeddingService;
+
+    #[async_trait]
+    impl crate::domain::ports::EmbeddingService for FlakyEmbeddingService {
+        async fn generate_embedding(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+            if text.contains("fail") {
+                anyhow::bail!("embedding service unavailable");
+            }
+            Ok(vec![0.1, 0.2, 0.3])
+        }
+    }
+
+    /// Records every text it is asked to embed
+    #[derive(Default)]
+    struct CountingEmbeddingService {
+        texts: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl crate::domain::ports::EmbeddingService for CountingEmbeddingService {
+        async fn generate_embedding(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+            self.texts.lock().unwrap().push(text.to_string());
+            Ok(vec![text.len() as f32])
+        }
+    }
+
     fn make_use_case(fail_repo: bool) -> ImportTsvUseCase {
         ImportTsvUseCase::new(
-            Arc::new(MockCardRepo { fail: fail_repo }),
+            Arc::new(MockCardRepo {
+                fail: fail_repo,
+                ..Default::default()
+            }),
+            Arc::new(MockDeckRepo::with_decks([owned_deck()])),
             Arc::new(MockDeckStatsRepo),
             Arc::new(MockEmbeddingService),
         )
@@ -219,7 +713,12 @@ mod tests {
     async fn test_import_tsv_happy_path() {
         let tsv = "Hello\tHola\nWorld\tMundo\n";
         let result = make_use_case(false)
-            .execute(Uuid::new_v4(), Uuid::new_v4(), Bytes::from(tsv))
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from(tsv),
+                ImportOptions::default(),
+            )
             .await;
         assert!(result.is_ok());
         let r = result.unwrap();
@@ -227,12 +726,72 @@ mod tests {
         assert_eq!(r.cards_skipped, 0);
     }
 
+    #[tokio::test]
+    async fn test_import_tsv_into_foreign_or_missing_deck_is_rejected() {
+        let card_repo = Arc::new(MockCardRepo::default());
+        let use_case = ImportTsvUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo::with_decks([owned_deck()])),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        );
+        let options = ImportOptions {
+            skip_duplicates: true,
+            ..Default::default()
+        };
+
+        let foreign = use_case
+            .execute(
+                Uuid::new_v4(),
+                Some(DECK_ID),
+                Bytes::from("Cat\tGato\n"),
+                options,
+            )
+            .await;
+        let missing = use_case
+            .execute(
+                USER_ID,
+                Some(Uuid::new_v4()),
+                Bytes::from("Cat\tGato\n"),
+                options,
+            )
+            .await;
+        let foreign_column = use_case
+            .execute(
+                Uuid::new_v4(),
+                None,
+                Bytes::from(format!("Cat\tGato\t{}\n", owned_deck().name)),
+                ImportOptions {
+                    deck_column: true,
+                    ..options
+                },
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(foreign, Err(AppError::AuthorizationError(_))));
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+        // A deck name is looked up among the caller's own decks only
+        assert_ne!(foreign_column.deck_id, DECK_ID);
+        assert!(card_repo
+            .created
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|c| c.deck_id != Some(DECK_ID)));
+    }
+
     #[tokio::test]
     async fn test_import_tsv_skips_malformed_lines() {
         // Line 1: valid, Line 2: no tab (malformed), Line 3: empty, Line 4: valid
         let tsv = "Cat\tGato\nno_tab_here\n\nDog\tPerro\n";
         let result = make_use_case(false)
-            .execute(Uuid::new_v4(), Uuid::new_v4(), Bytes::from(tsv))
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from(tsv),
+                ImportOptions::default(),
+            )
             .await;
         assert!(result.is_ok());
         let r = result.unwrap();
@@ -240,10 +799,88 @@ mod tests {
         assert_eq!(r.cards_skipped, 1); // "no_tab_here" — empty lines don't increment skipped
     }
 
+    async fn import_default(tsv: &str) -> ImportResult {
+        make_use_case(false)
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from(tsv.to_string()),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_blank_lines_are_not_counted() {
+        for tsv in [
+            "Cat\tGato",
+            "Cat\tGato\n",
+            "Cat\tGato\r\n\r\n",
+            "\n\nCat\tGato\n  \n\t\n",
+        ] {
+            let result = import_default(tsv).await;
+            assert_eq!(result.cards_imported, 1, "{:?}", tsv);
+            assert_eq!(result.cards_skipped, 0, "{:?}", tsv);
+            assert!(result.skip_reasons.is_empty(), "{:?}", tsv);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_line_without_tab_is_missing_field() {
+        let result = import_default("Cat\tGato\nno_tab_here\n").await;
+
+        assert_eq!(result.cards_imported, 1);
+        assert_eq!(result.cards_skipped, 1);
+        assert_eq!(
+            result.skip_reasons,
+            HashMap::from([("missing_field".into(), 1)])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_blank_column_is_empty_not_missing() {
+        let result = import_default("Cat\t\n\tGato\n  \t  \tx\nDog\tPerro\n").await;
+
+        assert_eq!(result.cards_imported, 1);
+        assert_eq!(result.cards_skipped, 3);
+        assert_eq!(result.skip_reasons, HashMap::from([("empty".into(), 3)]));
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_rows_past_limit_are_over_limit() {
+        let use_case = make_use_case(false).with_quotas(UserQuotas {
+            max_decks: None,
+            max_cards: Some(1),
+        });
+
+        let result = use_case
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from("Cat\tGato\nDog\tPerro\nBird\tPajaro\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.cards_imported, 1);
+        assert_eq!(result.cards_skipped, 2);
+        assert_eq!(
+            result.skip_reasons,
+            HashMap::from([("over_limit".into(), 2)])
+        );
+    }
+
     #[tokio::test]
     async fn test_import_tsv_empty_file() {
         let result = make_use_case(false)
-            .execute(Uuid::new_v4(), Uuid::new_v4(), Bytes::from(""))
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from(""),
+                ImportOptions::default(),
+            )
             .await;
         assert!(result.is_ok());
         let r = result.unwrap();
@@ -255,7 +892,12 @@ mod tests {
     async fn test_import_tsv_file_too_large() {
         let big = vec![b'a'; MAX_FILE_BYTES + 1];
         let result = make_use_case(false)
-            .execute(Uuid::new_v4(), Uuid::new_v4(), Bytes::from(big))
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from(big),
+                ImportOptions::default(),
+            )
             .await;
         assert!(matches!(result, Err(AppError::ValidationError(_))));
     }
@@ -264,7 +906,7 @@ mod tests {
     async fn test_import_tsv_invalid_utf8() {
         let bad = Bytes::from(vec![0xFF, 0xFE, 0x00]);
         let result = make_use_case(false)
-            .execute(Uuid::new_v4(), Uuid::new_v4(), bad)
+            .execute(USER_ID, Some(DECK_ID), bad, ImportOptions::default())
             .await;
         assert!(matches!(result, Err(AppError::ValidationError(_))));
     }
@@ -273,7 +915,12 @@ mod tests {
     async fn test_import_tsv_repo_failure_propagates() {
         let tsv = "A\tB\n";
         let result = make_use_case(true)
-            .execute(Uuid::new_v4(), Uuid::new_v4(), Bytes::from(tsv))
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from(tsv),
+                ImportOptions::default(),
+            )
             .await;
         assert!(result.is_err());
     }
@@ -283,11 +930,521 @@ mod tests {
         // Leading/trailing whitespace around front/back should be trimmed
         let tsv = "  Apple  \t  Manzana  \n";
         let result = make_use_case(false)
-            .execute(Uuid::new_v4(), Uuid::new_v4(), Bytes::from(tsv))
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from(tsv),
+                ImportOptions::default(),
+            )
             .await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().cards_imported, 1);
     }
+
+    #[tokio::test]
+    async fn test_import_tsv_applies_deck_default_tags() {
+        let deck = Deck::new(Uuid::new_v4(), "Spanish".to_string(), None)
+            .with_default_tags(vec!["imported".to_string()]);
+        let card_repo = Arc::new(MockCardRepo::default());
+        let use_case = ImportTsvUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo::with_decks([deck.clone()])),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        );
+
+        let result = use_case
+            .execute(
+                deck.user_id,
+                Some(deck.id),
+                Bytes::from("Cat\tGato\nDog\tPerro\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.cards_imported, 2);
+        let created = card_repo.created.lock().unwrap();
+        assert!(created.iter().all(|c| c.tags == vec!["imported"]));
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_numbers_cards_in_file_order() {
+        let card_repo = Arc::new(MockCardRepo::default());
+        let use_case = ImportTsvUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo::with_decks([owned_deck()])),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        );
+
+        use_case
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from("Zebra\tCebra\nno_tab_here\nApple\tManzana\nMoon\tLuna\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        let created = card_repo.created.lock().unwrap();
+        let order: Vec<(&str, i32)> = created
+            .iter()
+            .map(|c| (c.question.as_str(), c.position))
+            .collect();
+        assert_eq!(order, [("Zebra", 0), ("Apple", 1), ("Moon", 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_embed_cards_embeds_duplicate_answers_once() {
+        let embedding = Arc::new(CountingEmbeddingService::default());
+        let tasks = vec![
+            (Uuid::new_v4(), "gato".to_string()),
+            (Uuid::new_v4(), "perro".to_string()),
+            (Uuid::new_v4(), "gato".to_string()),
+            (Uuid::new_v4(), "gato".to_string()),
+        ];
+
+        embed_cards(tasks, Arc::new(MockCardRepo::default()), embedding.clone()).await;
+
+        let texts = embedding.texts.lock().unwrap();
+        assert_eq!(*texts, vec!["gato", "perro"]);
+    }
+
+    #[tokio::test]
+    async fn test_embed_cards_moves_pending_cards_to_ready_or_failed() {
+        let repo = Arc::new(MockCardRepo::default());
+        let (ok, failing) = (Uuid::new_v4(), Uuid::new_v4());
+        for id in [ok, failing] {
+            repo.set_embedding_status(id, EmbeddingStatus::Pending)
+                .await
+                .unwrap();
+        }
+
+        embed_cards(
+            vec![(ok, "gato".to_string()), (failing, "fail".to_string())],
+            repo.clone(),
+            Arc::new(FlakyEmbeddingService),
+        )
+        .await;
+
+        let statuses = repo.embedding_statuses.lock().unwrap();
+        assert_eq!(statuses[&ok], EmbeddingStatus::Ready);
+        assert_eq!(statuses[&failing], EmbeddingStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_imported_cards_start_pending() {
+        let card_repo = Arc::new(MockCardRepo::default());
+        let deck = Deck::new(Uuid::new_v4(), "Spanish".to_string(), None);
+        let use_case = ImportTsvUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo::with_decks([deck.clone()])),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        );
+
+        use_case
+            .execute(
+                deck.user_id,
+                Some(deck.id),
+                Bytes::from("Hello\tHola\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        let created = card_repo.created.lock().unwrap();
+        assert_eq!(created[0].embedding_status, EmbeddingStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_cards_of_ai_opted_out_user_are_not_queued_for_embedding() {
+        let users = Arc::new(SingleUserRepository::ai_opted_out());
+        let card_repo = Arc::new(MockCardRepo::default());
+        let deck = Deck::new(users.user_id(), "Spanish".to_string(), None);
+        let use_case = ImportTsvUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo::with_decks([deck.clone()])),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        )
+        .with_user_repository(users);
+
+        use_case
+            .execute(
+                deck.user_id,
+                Some(deck.id),
+                Bytes::from("Hello\tHola\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        let created = card_repo.created.lock().unwrap();
+        assert_eq!(created[0].embedding_status, EmbeddingStatus::None);
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_skips_identical_sides_when_enabled() {
+        let tsv = "word\tword\nHello\tHola\n";
+        let strict = make_use_case(false).with_content_policy(ContentPolicy {
+            reject_identical_sides: true,
+        });
+
+        let result = strict
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from(tsv),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.cards_imported, 1);
+        assert_eq!(result.cards_skipped, 1);
+
+        let lenient = make_use_case(false)
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from(tsv),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(lenient.cards_imported, 2);
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_stops_at_card_quota() {
+        let card_repo = Arc::new(MockCardRepo::default());
+        let use_case = ImportTsvUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo::with_decks([owned_deck()])),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        )
+        .with_quotas(UserQuotas {
+            max_decks: None,
+            max_cards: Some(2),
+        });
+
+        let result = use_case
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from("Cat\tGato\nDog\tPerro\nBird\tPajaro\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.cards_imported, 2);
+        assert_eq!(result.cards_skipped, 1);
+        assert_eq!(card_repo.created.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_twice_with_skip_duplicates() {
+        let card_repo = Arc::new(MockCardRepo::default());
+        let use_case = ImportTsvUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo::with_decks([owned_deck()])),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        );
+        let (user_id, deck_id) = (USER_ID, Some(DECK_ID));
+        let options = ImportOptions {
+            skip_duplicates: true,
+            ..Default::default()
+        };
+        let tsv = "Cat\tGato\nDog\tPerro\n  the   CAT \tEl gato\n";
+
+        let first = use_case
+            .execute(user_id, deck_id, Bytes::from(tsv), options)
+            .await
+            .unwrap();
+        let second = use_case
+            .execute(user_id, deck_id, Bytes::from(tsv), options)
+            .await
+            .unwrap();
+
+        // "the CAT" differs from "Cat"; on the second run every row matches an
+        // existing card on both sides, so none reaches the front check
+        assert_eq!(first.cards_imported, 3);
+        assert_eq!(second.cards_imported, 0);
+        assert_eq!(second.cards_duplicate, 3);
+        assert_eq!(second.cards_skipped, 0);
+        assert_eq!(card_repo.created.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_twice_reports_duplicates() {
+        let card_repo = Arc::new(MockCardRepo::default());
+        let use_case = ImportTsvUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo::with_decks([owned_deck()])),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        );
+        let (user_id, deck_id) = (USER_ID, Some(DECK_ID));
+        let tsv = "Cat\tGato\nDog\tPerro\nBird\tPájaro\n";
+
+        let first = use_case
+            .execute(user_id, deck_id, Bytes::from(tsv), ImportOptions::default())
+            .await
+            .unwrap();
+        let second = use_case
+            .execute(
+                user_id,
+                deck_id,
+                Bytes::from(" CAT \tgato\nDog\tPerro\nBird\tPájaro\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(first.cards_imported, 3);
+        assert_eq!(first.cards_duplicate, 0);
+        assert_eq!(second.cards_imported, 0);
+        assert_eq!(second.cards_duplicate, 3);
+        assert_eq!(second.cards_skipped, 0);
+        assert!(second.skip_reasons.is_empty());
+        assert_eq!(card_repo.created.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_same_front_new_back_is_not_duplicate() {
+        let card_repo = Arc::new(MockCardRepo::default());
+        let use_case = ImportTsvUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo::with_decks([owned_deck()])),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        );
+        let (user_id, deck_id) = (USER_ID, Some(DECK_ID));
+
+        use_case
+            .execute(
+                user_id,
+                deck_id,
+                Bytes::from("Cat\tGato\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+        let second = use_case
+            .execute(
+                user_id,
+                deck_id,
+                Bytes::from("Cat\tEl gato\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(second.cards_imported, 1);
+        assert_eq!(second.cards_duplicate, 0);
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_reports_skip_reasons() {
+        let use_case = make_use_case(false)
+            .with_quotas(UserQuotas {
+                max_decks: None,
+                max_cards: Some(2),
+            })
+            .with_content_policy(ContentPolicy {
+                reject_identical_sides: true,
+            });
+        let tsv = "Cat\tGato\nno_tab_here\ncat\tEl gato\nSame\tsame\nDog\tPerro\nBird\tPajaro\nx\n";
+
+        let result = use_case
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from(tsv),
+                ImportOptions {
+                    skip_duplicates: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.cards_imported, 2);
+        assert_eq!(result.skip_reasons.get("missing_field"), Some(&2));
+        assert_eq!(result.skip_reasons.get("duplicate"), Some(&1));
+        assert_eq!(result.skip_reasons.get("content_policy"), Some(&1));
+        assert_eq!(result.skip_reasons.get("over_limit"), Some(&1));
+        assert_eq!(
+            result.cards_skipped,
+            result.skip_reasons.values().sum::<u32>()
+        );
+        assert_eq!(result.cards_skipped, 5);
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_ignores_comment_lines() {
+        let tsv = "#separator:tab\n#html:false\nCat\tGato\n# a note\nDog\tPerro\n";
+
+        let result = make_use_case(false)
+            .execute(
+                USER_ID,
+                Some(DECK_ID),
+                Bytes::from(tsv),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.cards_imported, 2);
+        assert_eq!(result.cards_skipped, 0);
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_deck_directive_selects_deck() {
+        let deck = Deck::new(Uuid::new_v4(), "Spanish".to_string(), None);
+        let explicit = Deck::new(deck.user_id, "German".to_string(), None);
+        let card_repo = Arc::new(MockCardRepo::default());
+        let use_case = ImportTsvUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo::with_decks([deck.clone(), explicit.clone()])),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        );
+
+        let existing = use_case
+            .execute(
+                deck.user_id,
+                None,
+                Bytes::from("#deck:Spanish\nCat\tGato\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(existing.deck_id, deck.id);
+
+        let created = use_case
+            .execute(
+                deck.user_id,
+                None,
+                Bytes::from("#deck: French\nCat\tChat\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_ne!(created.deck_id, deck.id);
+        assert!(card_repo
+            .created
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|c| c.deck_id == Some(created.deck_id) && c.answer == "Chat"));
+
+        // An explicit deck wins over the directive
+        let result = use_case
+            .execute(
+                deck.user_id,
+                Some(explicit.id),
+                Bytes::from("#deck:Spanish\nDog\tPerro\n"),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.deck_id, explicit.id);
+
+        let missing = use_case
+            .execute(
+                deck.user_id,
+                None,
+                Bytes::from("Cat\tGato\n"),
+                ImportOptions::default(),
+            )
+            .await;
+        assert!(matches!(missing, Err(AppError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_deck_column_splits_rows_across_decks() {
+        let deck = Deck::new(Uuid::new_v4(), "Spanish".to_string(), None);
+        let card_repo = Arc::new(MockCardRepo::default());
+        let use_case = ImportTsvUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockDeckRepo::with_decks([deck.clone()])),
+            Arc::new(MockDeckStatsRepo),
+            Arc::new(MockEmbeddingService),
+        );
+        let tsv = "front\tback\tdeck\n\
+                   Cat\tGato\tSpanish\n\
+                   Cat\tChat\tFrench\n\
+                   Dog\tPerro\tSpanish\n\
+                   Bird\tOiseau\tFrench\n";
+
+        let result = use_case
+            .execute(
+                deck.user_id,
+                None,
+                Bytes::from(tsv),
+                ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.cards_imported, 4);
+        assert_eq!(result.deck_id, deck.id);
+        assert_eq!(result.decks.len(), 2);
+        assert_eq!(result.decks[0].deck_id, deck.id);
+        assert_eq!(result.decks[0].cards_imported, 2);
+        let french = result.decks[1].deck_id;
+        assert_ne!(french, deck.id);
+        assert_eq!(result.decks[1].cards_imported, 2);
+
+        let created = card_repo.created.lock().unwrap();
+        let answers_in = |deck_id: Uuid| -> Vec<&str> {
+            created
+                .iter()
+                .filter(|c| c.deck_id == Some(deck_id))
+                .map(|c| c.answer.as_str())
+                .collect()
+        };
+        assert_eq!(answers_in(deck.id), vec!["Gato", "Perro"]);
+        assert_eq!(answers_in(french), vec!["Chat", "Oiseau"]);
+    }
+
+    #[tokio::test]
+    async fn test_import_tsv_deck_column_flag_without_header() {
+        let deck_id = DECK_ID;
+        let use_case = make_use_case(false);
+
+        let result = use_case
+            .execute(
+                USER_ID,
+                Some(deck_id),
+                Bytes::from("Cat\tGato\tAnimals\nHouse\tCasa\n"),
+                ImportOptions {
+                    deck_column: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // The row without a deck falls back to the given deck
+        assert_eq!(result.cards_imported, 2);
+        assert_eq!(result.deck_id, deck_id);
+        assert_eq!(result.decks.len(), 2);
+        assert!(result.decks.iter().all(|d| d.cards_imported == 1));
+    }
+
+    #[test]
+    fn test_duplicate_filter_matches_within_file() {
+        let mut filter = DuplicateFilter::from_cards(&[]);
+        assert!(!filter.is_duplicate("Hello  world"));
+        assert!(filter.is_duplicate(" hello WORLD "));
+    }
 }
 
 /// Spawns a detached Tokio task that generates embeddings for newly imported cards.
@@ -296,18 +1453,49 @@ pub fn spawn_embedding_worker(
     card_repo: Arc<dyn CardRepository>,
     embedding_service: Arc<dyn EmbeddingService>,
 ) {
-    tokio::spawn(async move {
-        for (card_id, answer_text) in tasks {
-            match embedding_service.generate_embedding(&answer_text).await {
+    tokio::spawn(embed_cards(tasks, card_repo, embedding_service));
+}
+
+/// Generates and stores embeddings for `tasks`, calling the embedding service
+/// once per distinct answer text. Cards whose embedding can't be generated or
+/// stored are marked `Failed`.
+pub(crate) async fn embed_cards(
+    tasks: Vec<(Uuid, String)>,
+    card_repo: Arc<dyn CardRepository>,
+    embedding_service: Arc<dyn EmbeddingService>,
+) {
+    let mut memo: HashMap<String, Vec<f32>> = HashMap::new();
+    for (card_id, answer_text) in tasks {
+        let embedding = match memo.get(&answer_text) {
+            Some(embedding) => embedding.clone(),
+            None => match embedding_service.generate_embedding(&answer_text).await {
                 Ok(embedding) => {
-                    if let Err(e) = card_repo.update_embedding(card_id, embedding).await {
-                        tracing::warn!("Failed to store embedding for card {}: {}", card_id, e);
-                    }
+                    memo.insert(answer_text, embedding.clone());
+                    embedding
                 }
                 Err(e) => {
                     tracing::warn!("Failed to generate embedding for card {}: {}", card_id, e);
+                    mark_failed(card_repo.as_ref(), card_id).await;
+                    continue;
                 }
-            }
+            },
+        };
+        if let Err(e) = card_repo.update_embedding(card_id, embedding).await {
+            tracing::warn!("Failed to store embedding for card {}: {}", card_id, e);
+            mark_failed(card_repo.as_ref(), card_id).await;
         }
-    });
+    }
+}
+
+async fn mark_failed(card_repo: &dyn CardRepository, card_id: Uuid) {
+    if let Err(e) = card_repo
+        .set_embedding_status(card_id, EmbeddingStatus::Failed)
+        .await
+    {
+        tracing::warn!(
+            "Failed to record embedding failure for card {}: {}",
+            card_id,
+            e
+        );
+    }
 }
@@ -0,0 +1,148 @@
+//! InspectImport use case — preview how a delimited file maps to card fields
+//! without persisting anything.
+
+use bytes::Bytes;
+
+use crate::{
+    application::dtos::ImportInspection,
+    shared::error::{AppError, AppResult},
+};
+
+use super::import_tsv::MAX_FILE_BYTES;
+
+/// Number of rows returned in the preview
+const SAMPLE_ROWS: usize = 5;
+/// Number of rows examined when guessing the front/back columns
+const GUESS_ROWS: usize = 50;
+
+#[derive(Default)]
+pub struct InspectImportUseCase;
+
+impl InspectImportUseCase {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// `delimiter` accepts a single character or one of the names `tab`,
+    /// `comma`, `semicolon`, `pipe`. Defaults to tab.
+    pub fn execute(
+        &self,
+        file_bytes: Bytes,
+        delimiter: Option<&str>,
+    ) -> AppResult<ImportInspection> {
+        if file_bytes.len() > MAX_FILE_BYTES {
+            return Err(AppError::ValidationError(
+                "File exceeds the 10 MB size limit".to_string(),
+            ));
+        }
+
+        let delimiter = parse_delimiter(delimiter)?;
+        let text = std::str::from_utf8(&file_bytes)
+            .map_err(|_| AppError::ValidationError("File is not valid UTF-8".to_string()))?;
+
+        let rows: Vec<Vec<&str>> = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.split(delimiter).map(str::trim).collect())
+            .collect();
+
+        let column_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let (front_column, back_column) = guess_front_back(&rows, column_count);
+
+        Ok(ImportInspection {
+            delimiter: delimiter.to_string(),
+            column_count,
+            total_rows: rows.len(),
+            sample_rows: rows
+                .iter()
+                .take(SAMPLE_ROWS)
+                .map(|row| row.iter().map(|f| f.to_string()).collect())
+                .collect(),
+            front_column,
+            back_column,
+        })
+    }
+}
+
+fn parse_delimiter(raw: Option<&str>) -> AppResult<char> {
+    let Some(raw) = raw.filter(|r| !r.is_empty()) else {
+        return Ok('\t');
+    };
+
+    match raw.to_lowercase().as_str() {
+        "tab" | "\\t" => return Ok('\t'),
+        "comma" => return Ok(','),
+        "semicolon" => return Ok(';'),
+        "pipe" => return Ok('|'),
+        _ => {}
+    }
+
+    let mut chars = raw.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(AppError::ValidationError(format!(
+            "Unsupported delimiter: {:?}",
+            raw
+        ))),
+    }
+}
+
+/// Pick the two columns most likely to hold card text: the ones most often
+/// filled with non-numeric content. Ties go to the leftmost column.
+fn guess_front_back(rows: &[Vec<&str>], column_count: usize) -> (Option<usize>, Option<usize>) {
+    let sample = &rows[..rows.len().min(GUESS_ROWS)];
+
+    let mut scores: Vec<(usize, usize)> = (0..column_count)
+        .map(|col| {
+            let score = sample
+                .iter()
+                .filter_map(|row| row.get(col))
+                .filter(|cell| !cell.is_empty() && cell.parse::<f64>().is_err())
+                .count();
+            (col, score)
+        })
+        .filter(|(_, score)| *score > 0)
+        .collect();
+    scores.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut best: Vec<usize> = scores.into_iter().take(2).map(|(col, _)| col).collect();
+    best.sort_unstable();
+    (best.first().copied(), best.get(1).copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect_three_column_file_skips_id_column() {
+        let file = Bytes::from("1\thola\thello\n2\tadiós\tgoodbye\n3\tgracias\tthank you\n");
+        let result = InspectImportUseCase::new().execute(file, None).unwrap();
+
+        assert_eq!(result.column_count, 3);
+        assert_eq!(result.total_rows, 3);
+        assert_eq!(result.sample_rows[0], vec!["1", "hola", "hello"]);
+        assert_eq!(result.front_column, Some(1));
+        assert_eq!(result.back_column, Some(2));
+    }
+
+    #[test]
+    fn test_inspect_with_named_delimiter() {
+        let file = Bytes::from("front,back,tags\ncat,gato,animals\ndog,perro,\n");
+        let result = InspectImportUseCase::new()
+            .execute(file, Some("comma"))
+            .unwrap();
+
+        assert_eq!(result.delimiter, ",");
+        assert_eq!(result.column_count, 3);
+        assert_eq!(result.front_column, Some(0));
+        assert_eq!(result.back_column, Some(1));
+    }
+
+    #[test]
+    fn test_inspect_rejects_multi_char_delimiter() {
+        let result = InspectImportUseCase::new().execute(Bytes::from("a\tb"), Some("::"));
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+}
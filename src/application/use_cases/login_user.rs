@@ -2,11 +2,9 @@
 
 use std::sync::Arc;
 
-use argon2::{Argon2, PasswordHash, PasswordVerifier};
-
 use crate::{
     application::dtos::{AuthResponse, LoginRequest, UserDto},
-    domain::repositories::UserRepository,
+    domain::{repositories::UserRepository, value_objects::Password},
     shared::{
         error::{AppError, AppResult},
         jwt::encode_jwt,
@@ -35,12 +33,11 @@ impl LoginUserUseCase {
             AppError::AuthenticationError("Invalid email or password".to_string())
         })?;
 
-        let parsed = PasswordHash::new(hash)
-            .map_err(|_| AppError::InternalError("Password hash corrupted".to_string()))?;
-
-        Argon2::default()
-            .verify_password(req.password.as_bytes(), &parsed)
-            .map_err(|_| AppError::AuthenticationError("Invalid email or password".to_string()))?;
+        if !Password::candidate(req.password).verify(hash)? {
+            return Err(AppError::AuthenticationError(
+                "Invalid email or password".to_string(),
+            ));
+        }
 
         let token = encode_jwt(user.id)?;
         Ok(AuthResponse {
@@ -58,19 +55,11 @@ impl LoginUserUseCase {
 mod tests {
     use super::*;
     use crate::domain::entities::User;
-    use argon2::{
-        password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
-        Argon2,
-    };
     use async_trait::async_trait;
     use uuid::Uuid;
 
     fn hash_password(password: &str) -> String {
-        let salt = SaltString::generate(&mut OsRng);
-        Argon2::default()
-            .hash_password(password.as_bytes(), &salt)
-            .unwrap()
-            .to_string()
+        Password::candidate(password.to_string()).hash().unwrap()
     }
 
     struct MockUserRepo {
@@ -3,34 +3,84 @@
 //! Each use case represents a single user action or interaction.
 //! One file per use case following the Single Responsibility Principle.
 
+pub mod backfill_embeddings;
+pub mod change_email;
+pub mod change_password;
 pub mod create_card;
 pub mod create_deck;
 pub mod create_user;
+pub mod dead_letters;
+pub mod deck_trash;
 pub mod delete_card;
 pub mod delete_deck;
+pub mod export_user_data;
+pub mod get_activity_heatmap;
+pub mod get_card_history;
 pub mod get_deck_stats;
 pub mod get_decks;
+pub mod get_detailed_health;
+pub mod get_due_count;
+pub mod get_goal_progress;
+pub mod get_scheduler_config;
+pub mod get_score_histogram;
 pub mod get_user;
 pub mod get_user_cards;
 pub mod get_user_stats;
 pub mod import_anki;
+pub mod import_csv;
+pub mod import_json;
 pub mod import_tsv;
+pub mod import_xml;
+pub mod inspect_import;
 pub mod login_user;
+pub mod optimize_fsrs;
+pub mod prune;
+pub mod recommend_related_decks;
 pub mod register_user;
 pub mod review_card;
+pub mod search_cards;
+pub mod study_session;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod update_card;
+pub mod validate_answers;
 
+pub use backfill_embeddings::BackfillEmbeddingsUseCase;
+pub use change_email::ChangeEmailUseCase;
+pub use change_password::ChangePasswordUseCase;
 pub use create_card::CreateCardUseCase;
 pub use create_deck::CreateDeckUseCase;
 pub use create_user::CreateUserUseCase;
+pub use dead_letters::DeadLettersUseCase;
+pub use deck_trash::DeckTrashUseCase;
 pub use delete_card::DeleteCardUseCase;
 pub use delete_deck::DeleteDeckUseCase;
+pub use export_user_data::ExportUserDataUseCase;
+pub use get_activity_heatmap::GetActivityHeatmapUseCase;
+pub use get_card_history::GetCardHistoryUseCase;
 pub use get_deck_stats::GetDeckStatsUseCase;
 pub use get_decks::GetDecksUseCase;
+pub use get_detailed_health::GetDetailedHealthUseCase;
+pub use get_due_count::GetDueCountUseCase;
+pub use get_goal_progress::GetGoalProgressUseCase;
+pub use get_scheduler_config::GetSchedulerConfigUseCase;
+pub use get_score_histogram::{GetScoreHistogramUseCase, ScoreHistogramQuery};
 pub use get_user::GetUserUseCase;
 pub use get_user_cards::GetUserCardsUseCase;
 pub use get_user_stats::GetUserStatsUseCase;
-pub use import_anki::ImportAnkiUseCase;
+pub use import_anki::{AnkiFieldMapping, ImportAnkiUseCase};
+pub use import_csv::ImportCsvUseCase;
+pub use import_json::ImportJsonUseCase;
 pub use import_tsv::ImportTsvUseCase;
+pub use import_xml::ImportXmlUseCase;
+pub use inspect_import::InspectImportUseCase;
 pub use login_user::LoginUserUseCase;
+pub use optimize_fsrs::OptimizeFsrsUseCase;
+pub use prune::PruneUseCase;
+pub use recommend_related_decks::RecommendRelatedDecksUseCase;
 pub use register_user::RegisterUserUseCase;
 pub use review_card::{ReviewCardUseCase, ReviewResult};
+pub use search_cards::SearchCardsUseCase;
+pub use study_session::StudySessionUseCase;
+pub use update_card::UpdateCardUseCase;
+pub use validate_answers::ValidateAnswersUseCase;
@@ -0,0 +1,163 @@
+//! OptimizeFsrs use case - suggest FSRS weights fitted to a user's reviews
+
+use std::{collections::HashMap, sync::Arc};
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::FsrsOptimizationDto,
+    domain::{
+        fsrs::DEFAULT_WEIGHTS,
+        fsrs_optimizer::{self, ReviewHistory},
+        repositories::ReviewLogRepository,
+    },
+    shared::error::{AppError, AppResult},
+};
+
+/// Fewer reviews than this say too little about the user's memory
+pub const DEFAULT_MIN_REVIEWS: usize = 100;
+
+/// Use case for fitting FSRS weights to the user's review logs
+pub struct OptimizeFsrsUseCase {
+    review_log_repository: Arc<dyn ReviewLogRepository>,
+    min_reviews: usize,
+}
+
+impl OptimizeFsrsUseCase {
+    pub fn new(review_log_repository: Arc<dyn ReviewLogRepository>) -> Self {
+        Self {
+            review_log_repository,
+            min_reviews: DEFAULT_MIN_REVIEWS,
+        }
+    }
+
+    /// Reviews a user needs before weights are fitted
+    pub fn with_min_reviews(mut self, min_reviews: usize) -> Self {
+        self.min_reviews = min_reviews;
+        self
+    }
+
+    /// Fit weights to the user's reviews, starting from the defaults. The
+    /// result is only a suggestion; no deck is changed.
+    pub async fn execute(&self, user_id: Uuid) -> AppResult<FsrsOptimizationDto> {
+        let logs = self.review_log_repository.find_by_user(user_id).await?;
+
+        let mut by_card: HashMap<Uuid, ReviewHistory> = HashMap::new();
+        let mut review_count = 0;
+        for log in logs
+            .into_iter()
+            .filter(|log| (1..=4).contains(&log.fsrs_rating))
+        {
+            by_card
+                .entry(log.card_id)
+                .or_default()
+                .push((log.fsrs_rating, log.created_at));
+            review_count += 1;
+        }
+        if review_count < self.min_reviews {
+            return Err(AppError::ValidationError(format!(
+                "At least {} reviews are needed to optimize FSRS weights, found {}",
+                self.min_reviews, review_count
+            )));
+        }
+        let mut histories: Vec<ReviewHistory> = by_card.into_values().collect();
+        for history in &mut histories {
+            history.sort_by_key(|(_, reviewed_at)| *reviewed_at);
+        }
+
+        // Replaying every review per candidate is CPU-bound
+        tokio::task::spawn_blocking(move || {
+            let weights = fsrs_optimizer::optimize(&DEFAULT_WEIGHTS, &histories);
+            FsrsOptimizationDto {
+                weights: weights.to_vec(),
+                review_count,
+                default_loss: fsrs_optimizer::log_loss(&DEFAULT_WEIGHTS, &histories),
+                loss: fsrs_optimizer::log_loss(&weights, &histories),
+            }
+        })
+        .await
+        .map_err(|e| AppError::InternalError(format!("FSRS optimization task panicked: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::ReviewLog;
+    use async_trait::async_trait;
+    use chrono::{Duration, Utc};
+
+    struct StubReviewLogRepo(Vec<ReviewLog>);
+
+    #[async_trait]
+    impl ReviewLogRepository for StubReviewLogRepo {
+        async fn create(&self, review_log: &ReviewLog) -> AppResult<Uuid> {
+            Ok(review_log.id)
+        }
+        async fn find_by_card(&self, card_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|l| l.card_id == card_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<ReviewLog>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|l| l.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn log(user_id: Uuid, card_id: Uuid, rating: i32, days_ago: i64) -> ReviewLog {
+        let mut log = ReviewLog::new(
+            card_id,
+            user_id,
+            "a".to_string(),
+            "a".to_string(),
+            1.0,
+            "exact".to_string(),
+            rating,
+        );
+        log.created_at = Utc::now() - Duration::days(days_ago);
+        log
+    }
+
+    #[tokio::test]
+    async fn test_too_few_reviews_is_rejected() {
+        let user_id = Uuid::new_v4();
+        let card_id = Uuid::new_v4();
+        let logs = (0..5)
+            .map(|n| log(user_id, card_id, 3, 50 - n * 10))
+            .collect();
+        let use_case = OptimizeFsrsUseCase::new(Arc::new(StubReviewLogRepo(logs)));
+
+        let result = use_case.execute(user_id).await;
+
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_suggests_weights_without_raising_loss() {
+        let user_id = Uuid::new_v4();
+        // Cards remembered after long gaps, logged newest first
+        let logs = (0..10)
+            .flat_map(|_| {
+                let card_id = Uuid::new_v4();
+                [40, 60, 80, 95, 100]
+                    .into_iter()
+                    .map(move |days_ago| log(user_id, card_id, 3, days_ago))
+            })
+            .collect();
+        let use_case =
+            OptimizeFsrsUseCase::new(Arc::new(StubReviewLogRepo(logs))).with_min_reviews(50);
+
+        let result = use_case.execute(user_id).await.unwrap();
+
+        assert_eq!(result.review_count, 50);
+        assert_eq!(result.weights.len(), 17);
+        assert!(result.loss <= result.default_loss);
+    }
+}
@@ -0,0 +1,240 @@
+//! Prune use case - periodic cleanup of expired study sessions and decks
+//! left in the trash past the retention window
+
+use chrono::{DateTime, Utc};
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+
+use crate::{
+    application::{dtos::PruneResultDto, use_cases::StudySessionUseCase},
+    domain::repositories::DeckRepository,
+    AppResult,
+};
+
+const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
+const DEFAULT_PRUNE_INTERVAL_SECS: u64 = 3600;
+
+/// Use case for removing rows nobody can reach anymore
+pub struct PruneUseCase {
+    deck_repository: Arc<dyn DeckRepository>,
+    study_session_use_case: Arc<StudySessionUseCase>,
+    trash_retention: chrono::Duration,
+}
+
+impl PruneUseCase {
+    pub fn new(
+        deck_repository: Arc<dyn DeckRepository>,
+        study_session_use_case: Arc<StudySessionUseCase>,
+    ) -> Self {
+        Self {
+            deck_repository,
+            study_session_use_case,
+            trash_retention: chrono::Duration::days(DEFAULT_TRASH_RETENTION_DAYS),
+        }
+    }
+
+    /// How long a deck stays restorable in the trash
+    pub fn with_trash_retention(mut self, trash_retention: chrono::Duration) -> Self {
+        self.trash_retention = trash_retention;
+        self
+    }
+
+    /// Retention from `DECK_TRASH_RETENTION_DAYS` (default 30 days)
+    pub fn trash_retention_from_env() -> chrono::Duration {
+        let days = std::env::var("DECK_TRASH_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v >= 0)
+            .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS);
+        chrono::Duration::days(days)
+    }
+
+    /// Interval of the background task from `PRUNE_INTERVAL_SECS` (default
+    /// one hour); `0` turns the task off
+    pub fn interval_from_env() -> Option<Duration> {
+        let secs = std::env::var("PRUNE_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_PRUNE_INTERVAL_SECS);
+        (secs > 0).then(|| Duration::from_secs(secs))
+    }
+
+    pub async fn execute(&self) -> AppResult<PruneResultDto> {
+        self.execute_at(Utc::now()).await
+    }
+
+    /// Prune everything that had expired at `now`
+    pub async fn execute_at(&self, now: DateTime<Utc>) -> AppResult<PruneResultDto> {
+        let study_sessions = self.study_session_use_case.prune_expired(now);
+        let trashed_decks = self
+            .deck_repository
+            .purge_deleted_before(now - self.trash_retention)
+            .await?;
+        Ok(PruneResultDto {
+            study_sessions,
+            trashed_decks,
+        })
+    }
+
+    /// Prune every `interval` until the process exits. A failed run is logged
+    /// and retried on the next tick.
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.execute().await {
+                    Ok(result) => tracing::debug!(
+                        "Pruned {} study sessions and {} trashed decks",
+                        result.study_sessions,
+                        result.trashed_decks
+                    ),
+                    Err(err) => tracing::warn!("Prune run failed: {}", err),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        entities::{Card, Deck},
+        repositories::CardRepository,
+    };
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    #[derive(Default)]
+    struct InMemoryDeckRepo {
+        decks: Mutex<Vec<Deck>>,
+    }
+
+    #[async_trait]
+    impl DeckRepository for InMemoryDeckRepo {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            self.decks.lock().unwrap().push(deck.clone());
+            Ok(deck.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            Ok(self
+                .decks
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|d| d.id == id)
+                .cloned())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(self
+                .decks
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|d| d.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, id: Uuid) -> AppResult<()> {
+            self.decks.lock().unwrap().retain(|d| d.id != id);
+            Ok(())
+        }
+        async fn purge_deleted_before(&self, cutoff: DateTime<Utc>) -> AppResult<u64> {
+            let mut decks = self.decks.lock().unwrap();
+            let before = decks.len();
+            decks.retain(|d| d.deleted_at.is_none_or(|deleted_at| deleted_at >= cutoff));
+            Ok((before - decks.len()) as u64)
+        }
+    }
+
+    struct NoCards;
+
+    #[async_trait]
+    impl CardRepository for NoCards {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Card>> {
+            Ok(None)
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prune_removes_only_expired_rows() {
+        let user_id = Uuid::new_v4();
+        let now = Utc::now();
+        let deck_repo = Arc::new(InMemoryDeckRepo::default());
+
+        let live = Deck::new(user_id, "Live".to_string(), None);
+        let mut recently_trashed = Deck::new(user_id, "Recent".to_string(), None);
+        recently_trashed.deleted_at = Some(now - chrono::Duration::days(29));
+        let mut long_trashed = Deck::new(user_id, "Old".to_string(), None);
+        long_trashed.deleted_at = Some(now - chrono::Duration::days(31));
+        for deck in [&live, &recently_trashed, &long_trashed] {
+            deck_repo.create(deck).await.unwrap();
+        }
+
+        let study = Arc::new(
+            StudySessionUseCase::new(Arc::new(NoCards), deck_repo.clone())
+                .with_ttl(chrono::Duration::minutes(10)),
+        );
+        study.start(user_id, live.id).await.unwrap();
+
+        let use_case = PruneUseCase::new(deck_repo.clone(), study);
+
+        // The session started just now and is still live
+        let result = use_case.execute_at(now).await.unwrap();
+        assert_eq!(
+            result,
+            PruneResultDto {
+                study_sessions: 0,
+                trashed_decks: 1,
+            }
+        );
+        let remaining: Vec<_> = deck_repo
+            .find_by_user(user_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|d| d.name)
+            .collect();
+        assert_eq!(remaining, vec!["Live", "Recent"]);
+
+        // Eleven minutes later the session has expired; the recent deck has
+        // not been in the trash for 30 days yet
+        let result = use_case
+            .execute_at(now + chrono::Duration::minutes(11))
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            PruneResultDto {
+                study_sessions: 1,
+                trashed_decks: 0,
+            }
+        );
+    }
+}
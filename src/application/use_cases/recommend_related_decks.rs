@@ -0,0 +1,199 @@
+//! RecommendRelatedDecks use case - rank the user's other decks by how much
+//! their vocabulary overlaps with a given deck
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::dtos::RelatedDeckDto,
+    domain::{
+        embedding::{centroid, cosine_similarity},
+        repositories::{CardRepository, DeckRepository},
+    },
+    shared::access::{Access, OwnershipPolicy},
+    AppResult,
+};
+
+/// Maximum number of decks recommended
+const MAX_RELATED_DECKS: usize = 10;
+
+/// Use case for recommending decks that overlap semantically with a deck.
+///
+/// Each deck is represented by the centroid of its cards' answer embeddings;
+/// decks are ranked by cosine similarity of their centroids. Decks without
+/// any embedded card are left out.
+pub struct RecommendRelatedDecksUseCase {
+    deck_repository: Arc<dyn DeckRepository>,
+    card_repository: Arc<dyn CardRepository>,
+    ownership_policy: OwnershipPolicy,
+}
+
+impl RecommendRelatedDecksUseCase {
+    pub fn new(
+        deck_repository: Arc<dyn DeckRepository>,
+        card_repository: Arc<dyn CardRepository>,
+    ) -> Self {
+        Self {
+            deck_repository,
+            card_repository,
+            ownership_policy: OwnershipPolicy::default(),
+        }
+    }
+
+    /// How decks owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    pub async fn execute(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<Vec<RelatedDeckDto>> {
+        self.ownership_policy.authorize(
+            self.deck_repository.find_by_id(deck_id).await?,
+            |deck| deck.user_id,
+            user_id,
+            Access::Read,
+            "Deck",
+            deck_id,
+        )?;
+
+        let Some(target) = self.deck_centroid(deck_id).await? else {
+            return Ok(vec![]);
+        };
+
+        let mut related = Vec::new();
+        for deck in self.deck_repository.find_by_user(user_id).await? {
+            if deck.id == deck_id {
+                continue;
+            }
+            if let Some(other) = self.deck_centroid(deck.id).await? {
+                related.push(RelatedDeckDto {
+                    deck_id: deck.id,
+                    name: deck.name,
+                    similarity: cosine_similarity(&target, &other),
+                });
+            }
+        }
+
+        related.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+        related.truncate(MAX_RELATED_DECKS);
+        Ok(related)
+    }
+
+    async fn deck_centroid(&self, deck_id: Uuid) -> AppResult<Option<Vec<f32>>> {
+        let cards = self.card_repository.find_by_deck(deck_id).await?;
+        Ok(centroid(
+            cards
+                .iter()
+                .filter_map(|card| card.answer_embedding.as_deref()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{Card, Deck};
+    use async_trait::async_trait;
+
+    struct MockDeckRepository {
+        decks: Vec<Deck>,
+    }
+
+    #[async_trait]
+    impl DeckRepository for MockDeckRepository {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            Ok(deck.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            Ok(self.decks.iter().find(|d| d.id == id).cloned())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(self
+                .decks
+                .iter()
+                .filter(|d| d.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct MockCardRepository {
+        cards: Vec<Card>,
+    }
+
+    #[async_trait]
+    impl CardRepository for MockCardRepository {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Card>> {
+            Ok(None)
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn find_by_deck(&self, deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(self
+                .cards
+                .iter()
+                .filter(|c| c.deck_id == Some(deck_id))
+                .cloned()
+                .collect())
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    fn card(deck: &Deck, embedding: Option<Vec<f32>>) -> Card {
+        let mut card = Card::new(deck.user_id, "Q".to_string(), "A".to_string()).with_deck(deck.id);
+        // Short vectors keep the test readable; they bypass the stored-length check
+        card.answer_embedding = embedding;
+        card
+    }
+
+    #[tokio::test]
+    async fn test_related_decks_ranked_by_centroid_similarity() {
+        let user_id = Uuid::new_v4();
+        let deck = |name: &str| Deck::new(user_id, name.to_string(), None);
+        let (animals, pets, verbs, empty) =
+            (deck("Animals"), deck("Pets"), deck("Verbs"), deck("Empty"));
+
+        let cards = vec![
+            card(&animals, Some(vec![1.0, 0.1, 0.0])),
+            card(&animals, Some(vec![0.9, 0.0, 0.1])),
+            card(&pets, Some(vec![0.8, 0.2, 0.0])),
+            card(&pets, None),
+            card(&verbs, Some(vec![0.0, 0.1, 1.0])),
+            card(&empty, None),
+        ];
+        let use_case = RecommendRelatedDecksUseCase::new(
+            Arc::new(MockDeckRepository {
+                decks: vec![animals.clone(), pets.clone(), verbs.clone(), empty],
+            }),
+            Arc::new(MockCardRepository { cards }),
+        );
+
+        let related = use_case.execute(animals.id, user_id).await.unwrap();
+
+        let names: Vec<&str> = related.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["Pets", "Verbs"]);
+        assert!(related[0].similarity > 0.9);
+        assert!(related[1].similarity < 0.2);
+    }
+}
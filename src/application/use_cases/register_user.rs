@@ -2,36 +2,31 @@
 
 use std::sync::Arc;
 
-use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
-    Argon2,
-};
-
 use crate::{
     application::dtos::{AuthResponse, RegisterRequest, UserDto},
-    domain::{entities::User, repositories::UserRepository},
+    domain::{entities::User, repositories::UserRepository, value_objects::Password},
     shared::{
         error::{AppError, AppResult},
+        event_bus::{DomainEvent, EventBus},
         jwt::encode_jwt,
     },
 };
 
 pub struct RegisterUserUseCase {
     user_repo: Arc<dyn UserRepository>,
+    event_bus: Arc<EventBus>,
 }
 
 impl RegisterUserUseCase {
-    pub fn new(user_repo: Arc<dyn UserRepository>) -> Self {
-        Self { user_repo }
+    pub fn new(user_repo: Arc<dyn UserRepository>, event_bus: Arc<EventBus>) -> Self {
+        Self {
+            user_repo,
+            event_bus,
+        }
     }
 
     pub async fn execute(&self, req: RegisterRequest) -> AppResult<AuthResponse> {
-        // Validate password length
-        if req.password.len() < 8 {
-            return Err(AppError::ValidationError(
-                "Password must be at least 8 characters".to_string(),
-            ));
-        }
+        let password = Password::new(req.password)?;
 
         // Check email uniqueness
         if self.user_repo.find_by_email(&req.email).await?.is_some() {
@@ -41,16 +36,21 @@ impl RegisterUserUseCase {
         }
 
         // Hash password
-        let salt = SaltString::generate(&mut OsRng);
-        let password_hash = Argon2::default()
-            .hash_password(req.password.as_bytes(), &salt)
-            .map_err(|e| AppError::InternalError(format!("Password hashing failed: {e}")))?
-            .to_string();
+        let password_hash = password.hash()?;
 
         // Persist user
         let user = User::new_with_password(req.email, req.name, password_hash);
         self.user_repo.create(&user).await?;
 
+        // Onboarding side effects (default deck, ...) run as event handlers,
+        // off the request path
+        let event_bus = self.event_bus.clone();
+        let event = DomainEvent::UserRegistered {
+            user_id: user.id,
+            email: user.email.clone(),
+        };
+        tokio::spawn(async move { event_bus.publish(event).await });
+
         let token = encode_jwt(user.id)?;
         Ok(AuthResponse {
             token,
@@ -67,7 +67,9 @@ impl RegisterUserUseCase {
 mod tests {
     use super::*;
     use crate::domain::entities::User;
+    use crate::shared::event_bus::EventHandler;
     use async_trait::async_trait;
+    use std::sync::Mutex;
     use uuid::Uuid;
 
     struct MockUserRepo {
@@ -97,6 +99,23 @@ mod tests {
         }
     }
 
+    #[derive(Default)]
+    struct RecordingHandler {
+        events: Mutex<Vec<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventHandler for RecordingHandler {
+        async fn handle(&self, event: DomainEvent) -> AppResult<()> {
+            self.events.lock().unwrap().push(event);
+            Ok(())
+        }
+    }
+
+    fn event_bus() -> Arc<EventBus> {
+        Arc::new(EventBus::new())
+    }
+
     fn repo(existing_email: Option<&str>) -> Arc<MockUserRepo> {
         Arc::new(MockUserRepo {
             existing_email: existing_email.map(str::to_string),
@@ -105,7 +124,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_register_success() {
-        let uc = RegisterUserUseCase::new(repo(None));
+        let uc = RegisterUserUseCase::new(repo(None), event_bus());
         let result = uc
             .execute(RegisterRequest {
                 email: "new@example.com".to_string(),
@@ -121,7 +140,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_register_duplicate_email_returns_conflict() {
-        let uc = RegisterUserUseCase::new(repo(Some("taken@example.com")));
+        let uc = RegisterUserUseCase::new(repo(Some("taken@example.com")), event_bus());
         let result = uc
             .execute(RegisterRequest {
                 email: "taken@example.com".to_string(),
@@ -134,7 +153,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_register_short_password_returns_validation_error() {
-        let uc = RegisterUserUseCase::new(repo(None));
+        let uc = RegisterUserUseCase::new(repo(None), event_bus());
         let result = uc
             .execute(RegisterRequest {
                 email: "user@example.com".to_string(),
@@ -144,4 +163,36 @@ mod tests {
             .await;
         assert!(matches!(result, Err(AppError::ValidationError(_))));
     }
+
+    #[tokio::test]
+    async fn test_register_emits_user_registered() {
+        let handler = Arc::new(RecordingHandler::default());
+        let mut bus = EventBus::new();
+        bus.register_handler(handler.clone());
+        let uc = RegisterUserUseCase::new(repo(None), Arc::new(bus));
+
+        let res = uc
+            .execute(RegisterRequest {
+                email: "dana@example.com".to_string(),
+                name: "Dana".to_string(),
+                password: "securepassword".to_string(),
+            })
+            .await
+            .unwrap();
+
+        // The event is published from a spawned task
+        for _ in 0..100 {
+            if !handler.events.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        let events = handler.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            DomainEvent::UserRegistered { user_id, email }
+                if *user_id == res.user.id && email == "dana@example.com"
+        ));
+    }
 }
@@ -1,26 +1,79 @@
 //! ReviewCard use case - AI-powered flashcard review with FSRS scheduling
 
-use anyhow::{Context, Result};
-use chrono::Utc;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::domain::{
-    entities::{CardState, FsrsState, ReviewLog},
-    ports::{AIValidator, ValidationMethod},
-    repositories::{CardRepository, ReviewLogRepository},
+    answer_list::score_list_answer,
+    answer_normalization::AnswerNormalization,
+    entities::{AnswerType, FsrsState, ReviewLog, ReviewMode},
+    fsrs::Scheduler,
+    ports::{AIValidator, ValidationMethod, ValidationResult},
+    repositories::{CardRepository, DeckRepository, ReviewLogRepository, UserRepository},
+    scheduler_config::SchedulerConfig,
 };
-use crate::shared::event_bus::{DomainEvent, EventBus};
+use crate::shared::{
+    access::{Access, OwnershipPolicy},
+    error::{AppError, AppResult},
+    event_bus::{DomainEvent, EventBus},
+    keyed_lock::KeyedLocks,
+};
+
+/// Why AI features are refused for a user who turned them off
+pub(crate) const AI_DISABLED: &str = "AI validation is turned off in your preferences";
+
+/// Whether `user_id` lets their text reach the AI validator and embedding
+/// service (`UserPreferences::ai_validation_enabled`). True when there is no
+/// repository to ask or the user is unknown.
+pub(crate) async fn ai_validation_enabled(
+    user_repository: Option<&dyn UserRepository>,
+    user_id: Uuid,
+) -> AppResult<bool> {
+    let Some(user_repository) = user_repository else {
+        return Ok(true);
+    };
+    Ok(user_repository
+        .find_by_id(user_id)
+        .await?
+        .is_none_or(|user| user.preferences.ai_validation_enabled))
+}
+
+/// Why `user_id`'s reviews in a deck with `review_mode` are self-graded, or
+/// `None` when the AI validator grades them
+pub(crate) async fn self_graded_reason(
+    review_mode: ReviewMode,
+    user_repository: Option<&dyn UserRepository>,
+    user_id: Uuid,
+) -> AppResult<Option<&'static str>> {
+    if review_mode == ReviewMode::SelfGrade {
+        Ok(Some("This deck is self-graded"))
+    } else if !ai_validation_enabled(user_repository, user_id).await? {
+        Ok(Some(AI_DISABLED))
+    } else {
+        Ok(None)
+    }
+}
 
 /// Use case for reviewing a card with AI-powered validation
-pub struct ReviewCardUseCase<R: CardRepository, L: ReviewLogRepository, V: AIValidator> {
+pub struct ReviewCardUseCase<R: CardRepository, L: ReviewLogRepository, V: AIValidator + ?Sized> {
     card_repository: Arc<R>,
     review_log_repository: Arc<L>,
     ai_validator: Arc<V>,
     event_bus: Arc<EventBus>,
+    deck_repository: Option<Arc<dyn DeckRepository>>,
+    user_repository: Option<Arc<dyn UserRepository>>,
+    scheduler_config: SchedulerConfig,
+    ownership_policy: OwnershipPolicy,
+    /// Held per (user, card) for a whole review so a double submit cannot
+    /// lose an FSRS update
+    review_locks: KeyedLocks<(Uuid, Uuid)>,
 }
 
-impl<R: CardRepository, L: ReviewLogRepository, V: AIValidator> ReviewCardUseCase<R, L, V> {
+impl<R: CardRepository, L: ReviewLogRepository, V: AIValidator + ?Sized>
+    ReviewCardUseCase<R, L, V>
+{
     pub fn new(
         card_repository: Arc<R>,
         review_log_repository: Arc<L>,
@@ -32,38 +85,207 @@ impl<R: CardRepository, L: ReviewLogRepository, V: AIValidator> ReviewCardUseCas
             review_log_repository,
             ai_validator,
             event_bus,
+            deck_repository: None,
+            user_repository: None,
+            scheduler_config: SchedulerConfig::default(),
+            ownership_policy: OwnershipPolicy::default(),
+            review_locks: KeyedLocks::default(),
         }
     }
 
-    /// Execute the review card use case
+    /// Resolve per-deck scheduler overrides through the given repository
+    pub fn with_deck_repository(mut self, deck_repository: Arc<dyn DeckRepository>) -> Self {
+        self.deck_repository = Some(deck_repository);
+        self
+    }
+
+    /// Honour the reviewer's `ai_validation_enabled` preference, read through
+    /// the given repository
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    /// Replace the global scheduler defaults
+    pub fn with_scheduler_config(mut self, scheduler_config: SchedulerConfig) -> Self {
+        self.scheduler_config = scheduler_config;
+        self
+    }
+
+    /// How cards owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    /// Scheduler configuration, answer normalization and review mode for a
+    /// card, with its deck's settings applied. A card whose deck is in the
+    /// trash is reported as not found.
+    async fn resolve_deck_settings(
+        &self,
+        card_id: Uuid,
+        deck_id: Option<Uuid>,
+    ) -> Result<(SchedulerConfig, AnswerNormalization, ReviewMode)> {
+        let (Some(deck_id), Some(deck_repository)) = (deck_id, &self.deck_repository) else {
+            return Ok((
+                self.scheduler_config.clone(),
+                AnswerNormalization::default(),
+                ReviewMode::default(),
+            ));
+        };
+        let deck = deck_repository
+            .find_by_id(deck_id)
+            .await?
+            .filter(|deck| deck.deleted_at.is_none())
+            .ok_or_else(|| AppError::NotFound(format!("Card with id {} not found", card_id)))?;
+        Ok((
+            deck.scheduler_config(&self.scheduler_config),
+            deck.answer_normalization(),
+            deck.review_mode,
+        ))
+    }
+
+    /// Execute the review card use case for a typed answer
     pub async fn execute(
         &self,
         card_id: Uuid,
         user_id: Uuid,
         user_answer: String,
     ) -> Result<ReviewResult> {
-        // 1. Get the card
-        let mut card = self
-            .card_repository
-            .find_by_id(card_id)
-            .await?
-            .context("Card not found")?;
+        self.execute_with_rating(card_id, user_id, user_answer, None)
+            .await
+    }
 
-        // 2. Validate the answer using AI
-        let validation = self
-            .ai_validator
-            .validate(&card.answer, &user_answer, &card.question)
-            .await?;
+    /// Review a card the way its deck's review mode asks for: typed decks
+    /// grade `user_answer` and refuse a `rating`, self-graded decks require a
+    /// `rating` (1-4) and keep `user_answer` only for the log. A mismatch is
+    /// an `AppError::ValidationError`. Users who turned AI validation off
+    /// self-grade every card, whatever the deck's mode.
+    ///
+    /// Only the card's owner can review it (reported per the ownership
+    /// policy); suspended cards are refused with `AppError::ValidationError`
+    /// and cards in a trashed deck with `AppError::NotFound`. A review of a
+    /// card that another review saved while this one waited is a double
+    /// submit and fails with `AppError::Conflict`.
+    pub async fn execute_with_rating(
+        &self,
+        card_id: Uuid,
+        user_id: Uuid,
+        user_answer: String,
+        rating: Option<i32>,
+    ) -> Result<ReviewResult> {
+        // Concurrent reviews of the same card run one after the other. One
+        // that finds the card reviewed after it arrived is a double submit
+        // and is refused below rather than counted twice.
+        let requested_at = Utc::now();
+        let _review_lock = self.review_locks.lock((user_id, card_id)).await;
+
+        // 1. Get the card, which must be the reviewer's and not suspended
+        let mut card = self.ownership_policy.authorize(
+            self.card_repository.find_by_id(card_id).await?,
+            |card| card.user_id,
+            user_id,
+            Access::Write,
+            "Card",
+            card_id,
+        )?;
+        if card.suspended {
+            return Err(AppError::ValidationError(format!(
+                "Card with id {} is suspended",
+                card_id
+            ))
+            .into());
+        }
+        if card
+            .fsrs_state
+            .last_review
+            .is_some_and(|last_review| last_review > requested_at)
+        {
+            return Err(AppError::Conflict(format!(
+                "Card with id {} was already reviewed by a concurrent request",
+                card_id
+            ))
+            .into());
+        }
+        let loaded_at = card.updated_at;
+
+        // 2. Validate the answer using AI, after stripping the deck's filler
+        //    prefixes. Blank answers are always wrong, so skip the
+        //    (potentially paid) validator for them. Self-graded decks take
+        //    the learner's rating instead.
+        let (config, normalization, review_mode) =
+            self.resolve_deck_settings(card.id, card.deck_id).await?;
+        let normalized_answer = normalization.apply(&user_answer);
+        let self_graded_by =
+            self_graded_reason(review_mode, self.user_repository.as_deref(), user_id).await?;
+        let mut validation = if let Some(reason) = self_graded_by {
+            let rating = rating.ok_or_else(|| {
+                AppError::ValidationError(format!("{}; submit a rating from 1 to 4", reason))
+            })?;
+            if !(1..=4).contains(&rating) {
+                return Err(AppError::ValidationError(format!(
+                    "rating must be between 1 and 4, got {}",
+                    rating
+                ))
+                .into());
+            }
+            ValidationResult {
+                score: (rating - 1) as f32 / 3.0,
+                method: ValidationMethod::SelfGrade,
+                explanation: None,
+                threshold: None,
+            }
+        } else if rating.is_some() {
+            return Err(AppError::ValidationError(
+                "This deck grades typed answers; submit user_answer without a rating".to_string(),
+            )
+            .into());
+        } else if normalized_answer.is_empty() {
+            ValidationResult {
+                score: 0.0,
+                method: ValidationMethod::Blank,
+                explanation: None,
+                threshold: None,
+            }
+        } else if card.answer_type == AnswerType::List {
+            ValidationResult {
+                score: score_list_answer(&card.answer, &normalized_answer),
+                method: ValidationMethod::List,
+                explanation: None,
+                threshold: None,
+            }
+        } else {
+            self.ai_validator
+                .validate(&card.answer, &normalized_answer, &card.question)
+                .await?
+        };
 
-        // 3. Convert AI score to FSRS rating (1-4)
-        let fsrs_rating = score_to_fsrs_rating(validation.score);
+        // 3. Convert AI score to FSRS rating (1-4). Answers that leave out a
+        //    required keyword are capped, whatever the validator thought.
+        if !matches!(
+            validation.method,
+            ValidationMethod::Blank | ValidationMethod::SelfGrade
+        ) && !card.missing_keywords(&normalized_answer).is_empty()
+        {
+            validation.score = validation.score.min(config.missing_keyword_max_score);
+        }
+        let fsrs_rating = match (&validation.method, rating) {
+            (ValidationMethod::Blank, _) => 1,
+            (ValidationMethod::SelfGrade, Some(rating)) => rating,
+            _ => score_to_fsrs_rating(validation.score, &config),
+        };
 
         // 4. Update FSRS state
-        card.fsrs_state = update_fsrs_state(&card.fsrs_state, fsrs_rating);
-        card.updated_at = Utc::now();
+        let now = Utc::now();
+        card.fsrs_state =
+            update_fsrs_state(&card.fsrs_state, fsrs_rating, &config, &card.answer, now);
+        card.updated_at = now;
 
-        // 5. Save updated card
-        self.card_repository.update(&card).await?;
+        // 5. Save updated card, unless another process (e.g. a second server)
+        //    saved it since it was loaded
+        self.card_repository
+            .update_if_unchanged(&card, loaded_at)
+            .await?;
 
         // 6. Create review log
         let review_log = ReviewLog::new(
@@ -74,18 +296,23 @@ impl<R: CardRepository, L: ReviewLogRepository, V: AIValidator> ReviewCardUseCas
             validation.score,
             validation.method.as_str().to_string(),
             fsrs_rating,
-        );
+        )
+        .with_explanation(validation.explanation.clone());
         self.review_log_repository.create(&review_log).await?;
 
-        // 7. Emit domain event
-        self.event_bus
-            .publish(DomainEvent::CardReviewed {
-                card_id,
-                user_id,
-                score: validation.score,
-                rating: fsrs_rating,
-            })
-            .await;
+        // 7. Emit domain event in the background, so handler retries and
+        //    their backoff don't hold up the response. Statistics are
+        //    best-effort: a handler that keeps failing is dead-lettered and
+        //    the stats stay behind until an admin replays it, which counts
+        //    the review on the day of the replay.
+        let event_bus = self.event_bus.clone();
+        let event = DomainEvent::CardReviewed {
+            card_id,
+            user_id,
+            score: validation.score,
+            rating: fsrs_rating,
+        };
+        tokio::spawn(async move { event_bus.publish(event).await });
 
         Ok(ReviewResult {
             card_id,
@@ -93,6 +320,8 @@ impl<R: CardRepository, L: ReviewLogRepository, V: AIValidator> ReviewCardUseCas
             fsrs_rating,
             validation_method: validation.method,
             next_review_in_days: card.fsrs_state.scheduled_days,
+            threshold: validation.threshold,
+            expected_answer: card.answer,
         })
     }
 }
@@ -105,83 +334,48 @@ pub struct ReviewResult {
     pub fsrs_rating: i32,
     pub validation_method: ValidationMethod,
     pub next_review_in_days: i32,
+    /// Score the validation method needed to accept the answer, if it uses one
+    pub threshold: Option<f32>,
+    /// The card's answer, for showing the learner what was expected
+    pub expected_answer: String,
 }
 
 /// Convert AI score (0.0-1.0) to FSRS rating (1-4)
-fn score_to_fsrs_rating(score: f32) -> i32 {
+fn score_to_fsrs_rating(score: f32, config: &SchedulerConfig) -> i32 {
     match score {
-        s if s >= 0.9 => 4, // Easy
-        s if s >= 0.7 => 3, // Good
-        s if s >= 0.5 => 2, // Hard
-        _ => 1,             // Again
+        s if s >= config.easy_threshold => 4, // Easy
+        s if s >= config.good_threshold => 3, // Good
+        s if s >= config.hard_threshold => 2, // Hard
+        _ => 1,                               // Again
     }
 }
 
-/// Update FSRS state based on rating
-fn update_fsrs_state(current: &FsrsState, rating: i32) -> FsrsState {
-    let mut next = FsrsState {
-        stability: current.stability,
-        difficulty: current.difficulty,
-        elapsed_days: 0,
-        scheduled_days: current.scheduled_days,
-        reps: current.reps + 1,
-        lapses: current.lapses,
-        state: current.state.clone(),
-        last_review: Some(Utc::now()),
-    };
+/// Update FSRS state based on rating, reviewed at `now`. `answer` is the
+/// card's expected answer, used to seed the difficulty on the first review.
+///
+/// Cards are scheduled with the FSRS-4.5 memory model, using the deck's
+/// custom weights when it has valid ones and the default weights otherwise.
+fn update_fsrs_state(
+    current: &FsrsState,
+    rating: i32,
+    config: &SchedulerConfig,
+    answer: &str,
+    now: DateTime<Utc>,
+) -> FsrsState {
+    let scheduler = config
+        .fsrs_weights
+        .as_deref()
+        .and_then(|w| Scheduler::new(w).ok())
+        .unwrap_or_default()
+        .with_desired_retention(config.desired_retention);
 
-    // Initialize for first review
+    let mut next = scheduler.next_state(current, rating.clamp(1, 4), now);
+    next.scheduled_days = next.scheduled_days.min(config.maximum_interval_days);
     if current.reps == 0 {
-        next.stability = 1.0;
-        next.difficulty = 5.0;
-    }
-
-    match rating {
-        1 => {
-            // Again - reset card to learning
-            next.lapses += 1;
-            next.stability = (next.stability * 0.5).max(0.1);
-            next.difficulty = (next.difficulty + 1.0).min(10.0);
-            next.scheduled_days = 1;
-            next.state = CardState::Relearning;
-        }
-        2 => {
-            // Hard - slightly increase interval
-            next.stability *= 1.2;
-            next.difficulty = (next.difficulty + 0.15).min(10.0);
-            next.scheduled_days = ((next.stability * 1.2) as i32).max(1);
-            next.state = if next.reps <= 1 {
-                CardState::Learning
-            } else {
-                CardState::Review
-            };
-        }
-        3 => {
-            // Good - normal progression
-            next.stability *= 2.5;
-            // difficulty unchanged
-            next.scheduled_days = ((next.stability * 2.5) as i32).max(1);
-            next.state = if next.reps <= 1 {
-                CardState::Learning
-            } else {
-                CardState::Review
-            };
-        }
-        4 => {
-            // Easy - large increase
-            next.stability *= 4.0;
-            next.difficulty = (next.difficulty - 0.15).max(1.0);
-            next.scheduled_days = ((next.stability * 4.0) as i32).max(1);
-            next.state = CardState::Review;
-        }
-        _ => {
-            // Default to Good
-            next.stability *= 2.5;
-            next.scheduled_days = ((next.stability * 2.5) as i32).max(1);
-            next.state = CardState::Review;
-        }
+        // Shift the rating-based initial difficulty by the answer's offset
+        let offset = config.initial_difficulty_for(answer) - config.initial_difficulty;
+        next.difficulty = (next.difficulty + offset).clamp(1.0, 10.0);
     }
-
     next
 }
 
@@ -189,10 +383,8 @@ fn update_fsrs_state(current: &FsrsState, rating: i32) -> FsrsState {
 mod tests {
     use super::*;
     use crate::{
-        domain::{
-            entities::Card,
-            ports::{ValidationMethod, ValidationResult},
-        },
+        application::use_cases::test_support::SingleUserRepository,
+        domain::entities::{Card, CardState, Deck, EmbeddingStatus, User},
         shared::error::AppResult,
     };
     use async_trait::async_trait;
@@ -200,18 +392,20 @@ mod tests {
 
     #[test]
     fn test_score_to_fsrs_rating() {
-        assert_eq!(score_to_fsrs_rating(0.95), 4); // Easy
-        assert_eq!(score_to_fsrs_rating(0.75), 3); // Good
-        assert_eq!(score_to_fsrs_rating(0.55), 2); // Hard
-        assert_eq!(score_to_fsrs_rating(0.30), 1); // Again
+        let config = SchedulerConfig::default();
+        assert_eq!(score_to_fsrs_rating(0.95, &config), 4); // Easy
+        assert_eq!(score_to_fsrs_rating(0.75, &config), 3); // Good
+        assert_eq!(score_to_fsrs_rating(0.55, &config), 2); // Hard
+        assert_eq!(score_to_fsrs_rating(0.30, &config), 1); // Again
     }
 
     #[test]
     fn test_update_fsrs_state_new_card() {
+        let config = SchedulerConfig::default();
         let mut state = FsrsState::default();
 
         // First review with Good rating
-        state = update_fsrs_state(&state, 3);
+        state = update_fsrs_state(&state, 3, &config, "A", Utc::now());
 
         assert_eq!(state.state, CardState::Learning);
         assert_eq!(state.reps, 1);
@@ -221,42 +415,143 @@ mod tests {
 
     #[test]
     fn test_update_fsrs_state_progression() {
+        let config = SchedulerConfig::default();
         let mut state = FsrsState::default();
+        let start = Utc::now();
 
         // First review - Good
-        state = update_fsrs_state(&state, 3);
+        state = update_fsrs_state(&state, 3, &config, "A", start);
         assert_eq!(state.state, CardState::Learning);
         assert_eq!(state.reps, 1);
 
-        // Second review - Good
-        state = update_fsrs_state(&state, 3);
+        // Second review - Good, once the card is due
+        let due = start + chrono::Duration::days(state.scheduled_days.into());
+        state = update_fsrs_state(&state, 3, &config, "A", due);
         assert_eq!(state.state, CardState::Review);
         assert_eq!(state.reps, 2);
 
         // Third review - Easy
         let prev_stability = state.stability;
-        state = update_fsrs_state(&state, 4);
+        let due = due + chrono::Duration::days(state.scheduled_days.into());
+        state = update_fsrs_state(&state, 4, &config, "A", due);
         assert!(state.stability > prev_stability);
     }
 
     #[test]
     fn test_update_fsrs_state_lapses() {
+        let config = SchedulerConfig::default();
         let mut state = FsrsState::default();
 
         // Build up some progress
-        state = update_fsrs_state(&state, 3);
-        state = update_fsrs_state(&state, 3);
+        let start = Utc::now();
+        state = update_fsrs_state(&state, 3, &config, "A", start);
+        state = update_fsrs_state(&state, 3, &config, "A", start + chrono::Duration::days(4));
         assert_eq!(state.state, CardState::Review);
 
         // Fail the card
-        state = update_fsrs_state(&state, 1);
+        let prev_stability = state.stability;
+        state = update_fsrs_state(&state, 1, &config, "A", start + chrono::Duration::days(30));
         assert_eq!(state.state, CardState::Relearning);
         assert_eq!(state.lapses, 1);
+        assert_eq!(state.scheduled_days, 1);
+        assert!(state.stability < prev_stability);
+    }
+
+    #[test]
+    fn test_update_fsrs_state_uses_custom_weights() {
+        let default_config = SchedulerConfig::default();
+        let mut weights = crate::domain::fsrs::DEFAULT_WEIGHTS.to_vec();
+        weights[2] = 12.0; // initial stability for Good
+        let custom_config = SchedulerConfig {
+            fsrs_weights: Some(weights),
+            ..Default::default()
+        };
+
+        let state = FsrsState::default();
+        let now = Utc::now();
+        let default_next = update_fsrs_state(&state, 3, &default_config, "A", now);
+        let custom_next = update_fsrs_state(&state, 3, &custom_config, "A", now);
+
+        assert_eq!(custom_next.scheduled_days, 12);
+        assert_ne!(custom_next.scheduled_days, default_next.scheduled_days);
+    }
+
+    #[test]
+    fn test_update_fsrs_state_defaults_to_fsrs_weights() {
+        let state = FsrsState::default();
+        let now = Utc::now();
+
+        // A new card rated Good starts at w2 and is due after about that many days
+        let next = update_fsrs_state(&state, 3, &SchedulerConfig::default(), "A", now);
+        assert_eq!(next.stability, crate::domain::fsrs::DEFAULT_WEIGHTS[2]);
+        assert_eq!(next.scheduled_days, 4);
+
+        let capped = SchedulerConfig {
+            maximum_interval_days: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            update_fsrs_state(&state, 3, &capped, "A", now).scheduled_days,
+            2
+        );
+    }
+
+    #[test]
+    fn test_update_fsrs_state_seeds_difficulty_from_answer() {
+        let config = SchedulerConfig {
+            answer_complexity_difficulty: true,
+            ..Default::default()
+        };
+        let state = FsrsState::default();
+        let now = Utc::now();
+
+        let short = update_fsrs_state(&state, 3, &config, "gato", now);
+        let long = update_fsrs_state(
+            &state,
+            3,
+            &config,
+            "photosynthesis converts light energy into chemical energy stored in glucose",
+            now,
+        );
+        assert!(long.difficulty > short.difficulty);
+
+        // Only the first review is seeded
+        let again = update_fsrs_state(&long, 3, &config, "gato", now);
+        let again_long = update_fsrs_state(&long, 3, &config, "photosynthesis converts", now);
+        assert_eq!(again.difficulty, again_long.difficulty);
+
+        let mut weights = crate::domain::fsrs::DEFAULT_WEIGHTS.to_vec();
+        weights[2] = 12.0;
+        let weighted = SchedulerConfig {
+            fsrs_weights: Some(weights),
+            ..config
+        };
+        assert!(
+            update_fsrs_state(
+                &state,
+                3,
+                &weighted,
+                "photosynthesis converts light energy into chemical energy",
+                now
+            )
+            .difficulty
+                > update_fsrs_state(&state, 3, &weighted, "gato", now).difficulty
+        );
     }
 
     // Mock implementations for testing
     struct MockCardRepository {
         card: Option<Card>,
+        updated: std::sync::Mutex<Option<Card>>,
+    }
+
+    impl MockCardRepository {
+        fn new(card: Option<Card>) -> Self {
+            Self {
+                card,
+                updated: std::sync::Mutex::new(None),
+            }
+        }
     }
 
     #[async_trait]
@@ -281,7 +576,8 @@ mod tests {
             Ok(cards.iter().map(|_| Uuid::new_v4()).collect())
         }
 
-        async fn update(&self, _card: &Card) -> AppResult<()> {
+        async fn update(&self, card: &Card) -> AppResult<()> {
+            *self.updated.lock().unwrap() = Some(card.clone());
             Ok(())
         }
 
@@ -327,10 +623,314 @@ mod tests {
             Ok(ValidationResult {
                 score: self.score,
                 method: self.method.clone(),
+                explanation: None,
+                threshold: None,
+            })
+        }
+    }
+
+    /// Scores 1.0 only when the answer matches the expected one exactly
+    struct ExactValidator;
+
+    #[async_trait]
+    impl AIValidator for ExactValidator {
+        async fn validate(
+            &self,
+            expected: &str,
+            actual: &str,
+            _question: &str,
+        ) -> anyhow::Result<ValidationResult> {
+            Ok(ValidationResult {
+                score: if expected == actual { 1.0 } else { 0.0 },
+                method: ValidationMethod::Exact,
+                explanation: None,
+                threshold: None,
+            })
+        }
+    }
+
+    struct SingleDeckRepository(Deck);
+
+    #[async_trait]
+    impl DeckRepository for SingleDeckRepository {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            Ok(deck.id)
+        }
+
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            Ok((id == self.0.id).then(|| self.0.clone()))
+        }
+
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(vec![self.0.clone()])
+        }
+
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Accepts by embedding similarity against a fixed threshold
+    struct EmbeddingValidator;
+
+    #[async_trait]
+    impl AIValidator for EmbeddingValidator {
+        async fn validate(
+            &self,
+            _expected: &str,
+            _actual: &str,
+            _question: &str,
+        ) -> anyhow::Result<ValidationResult> {
+            Ok(ValidationResult {
+                score: 0.9,
+                method: ValidationMethod::Embedding,
+                explanation: None,
+                threshold: Some(0.85),
             })
         }
     }
 
+    #[tokio::test]
+    async fn test_deck_filler_prefix_is_stripped_before_validation() {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Spanish".to_string(), None)
+            .with_ignored_answer_prefixes(vec!["it's".to_string()]);
+        let card = Card::new(user_id, "hello".to_string(), "hola".to_string()).with_deck(deck.id);
+        let card_id = card.id;
+
+        let use_case = ReviewCardUseCase::new(
+            Arc::new(MockCardRepository::new(Some(card))),
+            Arc::new(MockReviewLogRepository),
+            Arc::new(ExactValidator),
+            Arc::new(crate::shared::event_bus::EventBus::new()),
+        )
+        .with_deck_repository(Arc::new(SingleDeckRepository(deck)));
+
+        let result = use_case
+            .execute(card_id, user_id, "it's hola".to_string())
+            .await
+            .unwrap();
+        assert_eq!(result.ai_score, 1.0);
+        assert_eq!(result.fsrs_rating, 4);
+
+        let result = use_case
+            .execute(card_id, user_id, "that's hola".to_string())
+            .await
+            .unwrap();
+        assert_eq!(result.ai_score, 0.0);
+    }
+
+    /// Holds one card and, like `PgCardRepository`, refuses to save over a
+    /// newer version. Loading is slow so concurrent reviews interleave.
+    struct SharedCardRepository {
+        card: std::sync::Mutex<Card>,
+    }
+
+    #[async_trait]
+    impl CardRepository for SharedCardRepository {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Card>> {
+            let card = self.card.lock().unwrap().clone();
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            Ok(Some(card))
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn update(&self, card: &Card) -> AppResult<()> {
+            *self.card.lock().unwrap() = card.clone();
+            Ok(())
+        }
+        async fn update_if_unchanged(
+            &self,
+            card: &Card,
+            loaded_at: chrono::DateTime<Utc>,
+        ) -> AppResult<()> {
+            let mut stored = self.card.lock().unwrap();
+            if stored.updated_at != loaded_at {
+                return Err(AppError::Conflict("stale card".to_string()));
+            }
+            *stored = card.clone();
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_double_submit_is_counted_once() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "hello".to_string(), "hola".to_string());
+        let card_id = card.id;
+        let card_repo = Arc::new(SharedCardRepository {
+            card: std::sync::Mutex::new(card),
+        });
+        let use_case = Arc::new(ReviewCardUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockReviewLogRepository),
+            Arc::new(ExactValidator),
+            Arc::new(crate::shared::event_bus::EventBus::new()),
+        ));
+
+        let review = || {
+            let use_case = use_case.clone();
+            tokio::spawn(
+                async move { use_case.execute(card_id, user_id, "hola".to_string()).await },
+            )
+        };
+        let (first, second) = tokio::join!(review(), review());
+
+        let results = [first.unwrap(), second.unwrap()];
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+        assert!(results.iter().any(|result| matches!(
+            result
+                .as_ref()
+                .err()
+                .and_then(|err| err.downcast_ref::<AppError>()),
+            Some(AppError::Conflict(_))
+        )));
+        assert_eq!(card_repo.card.lock().unwrap().fsrs_state.reps, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reviews_one_after_another_are_each_counted() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "hello".to_string(), "hola".to_string());
+        let card_id = card.id;
+        let card_repo = Arc::new(SharedCardRepository {
+            card: std::sync::Mutex::new(card),
+        });
+        let use_case = ReviewCardUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockReviewLogRepository),
+            Arc::new(ExactValidator),
+            Arc::new(crate::shared::event_bus::EventBus::new()),
+        );
+
+        for _ in 0..2 {
+            use_case
+                .execute(card_id, user_id, "hola".to_string())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(card_repo.card.lock().unwrap().fsrs_state.reps, 2);
+    }
+
+    fn review_mode_use_case(
+        review_mode: ReviewMode,
+    ) -> (
+        ReviewCardUseCase<MockCardRepository, MockReviewLogRepository, ExactValidator>,
+        Uuid,
+        Uuid,
+    ) {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Spanish".to_string(), None).with_review_mode(review_mode);
+        let card = Card::new(user_id, "hello".to_string(), "hola".to_string()).with_deck(deck.id);
+        let card_id = card.id;
+        let use_case = ReviewCardUseCase::new(
+            Arc::new(MockCardRepository::new(Some(card))),
+            Arc::new(MockReviewLogRepository),
+            Arc::new(ExactValidator),
+            Arc::new(crate::shared::event_bus::EventBus::new()),
+        )
+        .with_deck_repository(Arc::new(SingleDeckRepository(deck)));
+        (use_case, card_id, user_id)
+    }
+
+    fn is_validation_error(err: &anyhow::Error) -> bool {
+        matches!(
+            err.downcast_ref::<AppError>(),
+            Some(AppError::ValidationError(_))
+        )
+    }
+
+    #[tokio::test]
+    async fn test_self_grade_deck_rejects_typed_answer_without_rating() {
+        let (use_case, card_id, user_id) = review_mode_use_case(ReviewMode::SelfGrade);
+
+        let err = use_case
+            .execute_with_rating(card_id, user_id, "hola".to_string(), None)
+            .await
+            .unwrap_err();
+
+        assert!(is_validation_error(&err));
+    }
+
+    #[tokio::test]
+    async fn test_self_grade_deck_uses_submitted_rating() {
+        let (use_case, card_id, user_id) = review_mode_use_case(ReviewMode::SelfGrade);
+
+        let result = use_case
+            .execute_with_rating(card_id, user_id, String::new(), Some(2))
+            .await
+            .unwrap();
+
+        assert_eq!(result.fsrs_rating, 2);
+        assert!(matches!(
+            result.validation_method,
+            ValidationMethod::SelfGrade
+        ));
+
+        let err = use_case
+            .execute_with_rating(card_id, user_id, String::new(), Some(5))
+            .await
+            .unwrap_err();
+        assert!(is_validation_error(&err));
+    }
+
+    #[tokio::test]
+    async fn test_typed_deck_rejects_rating_only_submission() {
+        let (use_case, card_id, user_id) = review_mode_use_case(ReviewMode::Typed);
+
+        let err = use_case
+            .execute_with_rating(card_id, user_id, String::new(), Some(3))
+            .await
+            .unwrap_err();
+
+        assert!(is_validation_error(&err));
+    }
+
+    #[tokio::test]
+    async fn test_embedding_result_includes_threshold() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "house".to_string(), "casa".to_string());
+        let card_id = card.id;
+
+        let use_case = ReviewCardUseCase::new(
+            Arc::new(MockCardRepository::new(Some(card))),
+            Arc::new(MockReviewLogRepository),
+            Arc::new(EmbeddingValidator),
+            Arc::new(crate::shared::event_bus::EventBus::new()),
+        );
+
+        let result = use_case
+            .execute(card_id, user_id, "home".to_string())
+            .await
+            .unwrap();
+        assert!(matches!(
+            result.validation_method,
+            ValidationMethod::Embedding
+        ));
+        assert_eq!(result.threshold, Some(0.85));
+    }
+
     #[tokio::test]
     async fn test_review_card_use_case_success() {
         let card_id = Uuid::new_v4();
@@ -343,12 +943,19 @@ mod tests {
             question: "What is 2+2?".to_string(),
             answer: "4".to_string(),
             answer_embedding: None,
+            embedding_status: EmbeddingStatus::None,
             fsrs_state: FsrsState::default(),
+            tags: vec![],
+            required_keywords: vec![],
+            answer_type: Default::default(),
+            suspended: false,
+            not_before: None,
+            position: 0,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
 
-        let card_repo = Arc::new(MockCardRepository { card: Some(card) });
+        let card_repo = Arc::new(MockCardRepository::new(Some(card)));
         let log_repo = Arc::new(MockReviewLogRepository);
         let validator = Arc::new(MockAIValidator {
             score: 0.95,
@@ -370,12 +977,174 @@ mod tests {
         assert_eq!(review_result.fsrs_rating, 4); // Easy
     }
 
+    struct FailingStatsHandler;
+
+    #[async_trait]
+    impl crate::shared::event_bus::EventHandler for FailingStatsHandler {
+        async fn handle(&self, _event: crate::shared::event_bus::DomainEvent) -> AppResult<()> {
+            Err(crate::shared::error::AppError::InternalError(
+                "stats table locked".to_string(),
+            ))
+        }
+
+        fn name(&self) -> &str {
+            "statistics"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_review_succeeds_when_stats_handler_fails() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "Hola".to_string(), "Hello".to_string());
+        let card_id = card.id;
+        let card_repo = Arc::new(MockCardRepository::new(Some(card)));
+        let validator = Arc::new(MockAIValidator {
+            score: 0.95,
+            method: ValidationMethod::Exact,
+        });
+        let mut event_bus = crate::shared::event_bus::EventBus::new().with_retry_policy(
+            crate::shared::event_bus::RetryPolicy {
+                max_attempts: 2,
+                initial_backoff: std::time::Duration::ZERO,
+            },
+        );
+        event_bus.register_handler(Arc::new(FailingStatsHandler));
+
+        let use_case = ReviewCardUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockReviewLogRepository),
+            validator,
+            Arc::new(event_bus),
+        );
+
+        let result = use_case
+            .execute(card_id, user_id, "Hello".to_string())
+            .await
+            .unwrap();
+        assert_eq!(result.fsrs_rating, 4);
+        assert_eq!(
+            card_repo
+                .updated
+                .lock()
+                .unwrap()
+                .as_ref()
+                .unwrap()
+                .fsrs_state
+                .reps,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_review_does_not_wait_for_handler_retries() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "Hola".to_string(), "Hello".to_string());
+        let card_id = card.id;
+        let mut event_bus = crate::shared::event_bus::EventBus::new().with_retry_policy(
+            crate::shared::event_bus::RetryPolicy {
+                max_attempts: 3,
+                initial_backoff: std::time::Duration::from_secs(60),
+            },
+        );
+        event_bus.register_handler(Arc::new(FailingStatsHandler));
+        let use_case = ReviewCardUseCase::new(
+            Arc::new(MockCardRepository::new(Some(card))),
+            Arc::new(MockReviewLogRepository),
+            Arc::new(MockAIValidator {
+                score: 0.95,
+                method: ValidationMethod::Exact,
+            }),
+            Arc::new(event_bus),
+        );
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            use_case.execute(card_id, user_id, "Hello".to_string()),
+        )
+        .await;
+
+        assert!(matches!(result, Ok(Ok(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_answer_is_matched_in_any_order() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(
+            user_id,
+            "Name three colors".to_string(),
+            "red, blue, green".to_string(),
+        )
+        .with_answer_type(AnswerType::List);
+        let card_id = card.id;
+        // The validator would fail the answer; list cards don't ask it
+        let use_case = ReviewCardUseCase::new(
+            Arc::new(MockCardRepository::new(Some(card))),
+            Arc::new(MockReviewLogRepository),
+            Arc::new(MockAIValidator {
+                score: 0.0,
+                method: ValidationMethod::Llm,
+            }),
+            Arc::new(crate::shared::event_bus::EventBus::new()),
+        );
+
+        let full = use_case
+            .execute(card_id, user_id, "green blue red".to_string())
+            .await
+            .unwrap();
+        assert!(matches!(full.validation_method, ValidationMethod::List));
+        assert_eq!(full.ai_score, 1.0);
+
+        let partial = use_case
+            .execute(card_id, user_id, "red blue".to_string())
+            .await
+            .unwrap();
+        assert!((partial.ai_score - 0.67).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_missing_required_keyword_caps_score() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(
+            user_id,
+            "What powers the cell?".to_string(),
+            "The mitochondria produce ATP".to_string(),
+        )
+        .with_required_keywords(["ATP"]);
+
+        let review = |answer: &'static str| {
+            let card = card.clone();
+            async move {
+                let use_case = ReviewCardUseCase::new(
+                    Arc::new(MockCardRepository::new(Some(card.clone()))),
+                    Arc::new(MockReviewLogRepository),
+                    Arc::new(MockAIValidator {
+                        score: 1.0,
+                        method: ValidationMethod::Llm,
+                    }),
+                    Arc::new(crate::shared::event_bus::EventBus::new()),
+                );
+                use_case
+                    .execute(card.id, user_id, answer.to_string())
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let complete = review("mitochondria make atp").await;
+        assert_eq!(complete.ai_score, 1.0);
+        assert_eq!(complete.fsrs_rating, 4);
+
+        let incomplete = review("the mitochondria").await;
+        assert_eq!(incomplete.ai_score, 0.4);
+        assert_eq!(incomplete.fsrs_rating, 1);
+    }
+
     #[tokio::test]
     async fn test_review_card_use_case_card_not_found() {
         let card_id = Uuid::new_v4();
         let user_id = Uuid::new_v4();
 
-        let card_repo = Arc::new(MockCardRepository { card: None });
+        let card_repo = Arc::new(MockCardRepository::new(None));
         let log_repo = Arc::new(MockReviewLogRepository);
         let validator = Arc::new(MockAIValidator {
             score: 0.95,
@@ -392,6 +1161,78 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn exact_use_case(
+        card: Card,
+    ) -> ReviewCardUseCase<MockCardRepository, MockReviewLogRepository, ExactValidator> {
+        ReviewCardUseCase::new(
+            Arc::new(MockCardRepository::new(Some(card))),
+            Arc::new(MockReviewLogRepository),
+            Arc::new(ExactValidator),
+            Arc::new(crate::shared::event_bus::EventBus::new()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_review_of_another_users_card_is_refused() {
+        let card = Card::new(Uuid::new_v4(), "hello".to_string(), "hola".to_string());
+        let card_id = card.id;
+        let intruder = Uuid::new_v4();
+
+        let err = exact_use_case(card.clone())
+            .execute(card_id, intruder, "hola".to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AppError>(),
+            Some(AppError::AuthorizationError(_))
+        ));
+
+        let err = exact_use_case(card)
+            .with_ownership_policy(OwnershipPolicy::HideAll)
+            .execute(card_id, intruder, "hola".to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AppError>(),
+            Some(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_review_of_suspended_card_is_refused() {
+        let user_id = Uuid::new_v4();
+        let mut card = Card::new(user_id, "hello".to_string(), "hola".to_string());
+        card.suspended = true;
+        let card_id = card.id;
+        let use_case = exact_use_case(card);
+
+        let err = use_case
+            .execute(card_id, user_id, "hola".to_string())
+            .await
+            .unwrap_err();
+        assert!(is_validation_error(&err));
+        assert!(use_case.card_repository.updated.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_review_of_card_in_trashed_deck_is_not_found() {
+        let user_id = Uuid::new_v4();
+        let mut deck = Deck::new(user_id, "Spanish".to_string(), None);
+        deck.deleted_at = Some(Utc::now());
+        let card = Card::new(user_id, "hello".to_string(), "hola".to_string()).with_deck(deck.id);
+        let card_id = card.id;
+
+        let err = exact_use_case(card)
+            .with_deck_repository(Arc::new(SingleDeckRepository(deck)))
+            .execute(card_id, user_id, "hola".to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AppError>(),
+            Some(AppError::NotFound(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_review_card_different_scores() {
         let test_cases = vec![
@@ -412,12 +1253,19 @@ mod tests {
                 question: "Test".to_string(),
                 answer: "Answer".to_string(),
                 answer_embedding: None,
+                embedding_status: EmbeddingStatus::None,
                 fsrs_state: FsrsState::default(),
+                tags: vec![],
+                required_keywords: vec![],
+                answer_type: Default::default(),
+                suspended: false,
+                not_before: None,
+                position: 0,
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             };
 
-            let card_repo = Arc::new(MockCardRepository { card: Some(card) });
+            let card_repo = Arc::new(MockCardRepository::new(Some(card)));
             let log_repo = Arc::new(MockReviewLogRepository);
             let validator = Arc::new(MockAIValidator {
                 score,
@@ -436,4 +1284,105 @@ mod tests {
             assert_eq!(review_result.fsrs_rating, expected_rating);
         }
     }
+
+    struct PanickingAIValidator;
+
+    #[async_trait]
+    impl AIValidator for PanickingAIValidator {
+        async fn validate(
+            &self,
+            _expected: &str,
+            _actual: &str,
+            _question: &str,
+        ) -> anyhow::Result<ValidationResult> {
+            panic!("validator must not be called for blank answers");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blank_answer_skips_validator() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(
+            user_id,
+            "Capital of France?".to_string(),
+            "Paris".to_string(),
+        );
+        let card_id = card.id;
+
+        let card_repo = Arc::new(MockCardRepository::new(Some(card)));
+        let use_case = ReviewCardUseCase::new(
+            card_repo.clone(),
+            Arc::new(MockReviewLogRepository),
+            Arc::new(PanickingAIValidator),
+            Arc::new(crate::shared::event_bus::EventBus::new()),
+        );
+
+        let result = use_case
+            .execute(card_id, user_id, "   ".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(result.ai_score, 0.0);
+        assert_eq!(result.fsrs_rating, 1);
+        assert_eq!(result.validation_method.as_str(), "blank");
+        assert_eq!(result.next_review_in_days, 1);
+
+        let updated = card_repo.updated.lock().unwrap().clone().unwrap();
+        assert_eq!(updated.fsrs_state.state, CardState::Relearning);
+    }
+
+    struct CountingAIValidator {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl AIValidator for CountingAIValidator {
+        async fn validate(
+            &self,
+            _expected: &str,
+            _actual: &str,
+            _question: &str,
+        ) -> anyhow::Result<ValidationResult> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(ValidationResult {
+                score: 1.0,
+                method: ValidationMethod::Exact,
+                explanation: None,
+                threshold: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ai_disabled_user_must_self_grade() {
+        let mut user = User::new("a@example.com".to_string(), "A".to_string());
+        user.preferences.ai_validation_enabled = false;
+        let user_id = user.id;
+        let card = Card::new(user_id, "hello".to_string(), "hola".to_string());
+        let card_id = card.id;
+        let validator = Arc::new(CountingAIValidator {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let use_case = ReviewCardUseCase::new(
+            Arc::new(MockCardRepository::new(Some(card))),
+            Arc::new(MockReviewLogRepository),
+            validator.clone(),
+            Arc::new(crate::shared::event_bus::EventBus::new()),
+        )
+        .with_user_repository(Arc::new(SingleUserRepository(user)));
+
+        let err = use_case
+            .execute(card_id, user_id, "hola".to_string())
+            .await
+            .unwrap_err();
+        assert!(is_validation_error(&err));
+
+        let result = use_case
+            .execute_with_rating(card_id, user_id, "hola".to_string(), Some(3))
+            .await
+            .unwrap();
+        assert_eq!(result.fsrs_rating, 3);
+        assert_eq!(result.validation_method.as_str(), "self_grade");
+        assert_eq!(validator.calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
 }
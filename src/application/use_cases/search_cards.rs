@@ -0,0 +1,323 @@
+//! SearchCards use case - find a user's cards by the meaning of their answers
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::{
+        dtos::{CardDto, CardSearchHitDto, SearchCardsRequest},
+        use_cases::{
+            backfill_embeddings::BACKFILL_STATUSES,
+            import_tsv::embed_cards,
+            review_card::{ai_validation_enabled, AI_DISABLED},
+        },
+    },
+    domain::{
+        ports::EmbeddingService,
+        repositories::{CardRepository, UserRepository},
+    },
+    shared::{
+        deadline,
+        error::{AppError, AppResult},
+    },
+};
+
+/// Cards returned when the request gives no limit
+const DEFAULT_LIMIT: i64 = 10;
+/// Most cards one search may return
+const MAX_LIMIT: i64 = 50;
+/// Most cards without an embedding one search re-embeds before retrying
+const MAX_REEMBED: usize = 20;
+
+/// Use case for semantic card search: the query is embedded like an answer
+/// and compared with the stored answer embeddings
+pub struct SearchCardsUseCase {
+    card_repository: Arc<dyn CardRepository>,
+    embedding_service: Arc<dyn EmbeddingService>,
+    user_repository: Option<Arc<dyn UserRepository>>,
+}
+
+impl SearchCardsUseCase {
+    pub fn new(
+        card_repository: Arc<dyn CardRepository>,
+        embedding_service: Arc<dyn EmbeddingService>,
+    ) -> Self {
+        Self {
+            card_repository,
+            embedding_service,
+            user_repository: None,
+        }
+    }
+
+    /// Refuse users who turned AI validation off in their preferences, so
+    /// their queries never reach the embedding service
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    /// The user's cards nearest to `request.query`, nearest first. Fails
+    /// with `AppError::ServiceUnavailable` when no embedding service is
+    /// configured (or it is down).
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        request: SearchCardsRequest,
+    ) -> AppResult<Vec<CardSearchHitDto>> {
+        let query = request.query.trim();
+        if query.is_empty() {
+            return Err(AppError::ValidationError(
+                "query must not be empty".to_string(),
+            ));
+        }
+        let limit = request.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(AppError::ValidationError(format!(
+                "limit must be between 1 and {}",
+                MAX_LIMIT
+            )));
+        }
+
+        if !ai_validation_enabled(self.user_repository.as_deref(), user_id).await? {
+            return Err(AppError::ValidationError(AI_DISABLED.to_string()));
+        }
+
+        let query_embedding = match self.embedding_service.generate_embedding(query).await {
+            Ok(embedding) => embedding,
+            Err(e) if deadline::is_exceeded(&e) => {
+                return Err(AppError::Timeout(
+                    "Embedding the search query did not finish before the request deadline"
+                        .to_string(),
+                ))
+            }
+            Err(e) => {
+                tracing::warn!("Search query embedding failed: {:#}", e);
+                return Err(AppError::ServiceUnavailable(
+                    "Semantic search needs an embedding service (set OPENAI_API_KEY)".to_string(),
+                ));
+            }
+        };
+
+        let mut hits = self
+            .card_repository
+            .search_by_embedding(user_id, &query_embedding, limit)
+            .await?;
+        if self.reembed_missing(user_id).await? {
+            hits = self
+                .card_repository
+                .search_by_embedding(user_id, &query_embedding, limit)
+                .await?;
+        }
+        Ok(hits
+            .into_iter()
+            .map(|(card, distance)| CardSearchHitDto {
+                card: CardDto::from(card),
+                distance,
+            })
+            .collect())
+    }
+
+    /// Embeds up to `MAX_REEMBED` of the user's cards that have no stored
+    /// embedding, so they can match. Returns whether there were any.
+    async fn reembed_missing(&self, user_id: Uuid) -> AppResult<bool> {
+        let tasks: Vec<(Uuid, String)> = self
+            .card_repository
+            .find_by_embedding_status(user_id, &BACKFILL_STATUSES)
+            .await?
+            .into_iter()
+            .filter(|card| card.answer_embedding.is_none())
+            .take(MAX_REEMBED)
+            .map(|card| (card.id, card.answer))
+            .collect();
+        if tasks.is_empty() {
+            return Ok(false);
+        }
+        embed_cards(
+            tasks,
+            self.card_repository.clone(),
+            self.embedding_service.clone(),
+        )
+        .await;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::use_cases::test_support::SingleUserRepository;
+    use crate::domain::entities::{Card, EmbeddingStatus};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    struct StubCardRepo(Mutex<Vec<Card>>);
+
+    impl StubCardRepo {
+        fn new(cards: Vec<Card>) -> Self {
+            Self(Mutex::new(cards))
+        }
+    }
+
+    #[async_trait]
+    impl CardRepository for StubCardRepo {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Card>> {
+            Ok(self.0.lock().unwrap().iter().find(|c| c.id == id).cloned())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|c| c.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, id: Uuid, embedding: Vec<f32>) -> AppResult<()> {
+            if let Some(card) = self.0.lock().unwrap().iter_mut().find(|c| c.id == id) {
+                card.answer_embedding = Some(embedding);
+                card.embedding_status = EmbeddingStatus::Ready;
+            }
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Embeds every text as the same unit vector along the x axis
+    struct FixedEmbedding;
+
+    #[async_trait]
+    impl EmbeddingService for FixedEmbedding {
+        async fn generate_embedding(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+            Ok(vec![1.0, 0.0])
+        }
+    }
+
+    /// Like the fallback validator, which has no embeddings
+    struct NoEmbeddings;
+
+    #[async_trait]
+    impl EmbeddingService for NoEmbeddings {
+        async fn generate_embedding(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+            anyhow::bail!("Embedding generation not available without OPENAI_API_KEY")
+        }
+    }
+
+    fn card(user_id: Uuid, answer: &str, embedding: Option<Vec<f32>>) -> Card {
+        let mut card = Card::new(user_id, "Q".to_string(), answer.to_string());
+        card.answer_embedding = embedding;
+        card
+    }
+
+    fn request(query: &str) -> SearchCardsRequest {
+        SearchCardsRequest {
+            query: query.to_string(),
+            limit: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nearest_cards_come_first() {
+        let user_id = Uuid::new_v4();
+        let cards = vec![
+            card(user_id, "far", Some(vec![0.0, 1.0])),
+            card(user_id, "near", Some(vec![1.0, 0.1])),
+            card(user_id, "being embedded", None).with_embedding_pending(),
+            card(Uuid::new_v4(), "someone else's", Some(vec![1.0, 0.0])),
+        ];
+        let use_case =
+            SearchCardsUseCase::new(Arc::new(StubCardRepo::new(cards)), Arc::new(FixedEmbedding));
+
+        let hits = use_case.execute(user_id, request("hello")).await.unwrap();
+
+        let answers: Vec<&str> = hits.iter().map(|hit| hit.card.answer.as_str()).collect();
+        assert_eq!(answers, ["near", "far"]);
+        assert!(hits[0].distance < hits[1].distance);
+    }
+
+    #[tokio::test]
+    async fn test_card_without_embedding_is_reembedded_and_found() {
+        let user_id = Uuid::new_v4();
+        let cards = vec![
+            card(user_id, "far", Some(vec![0.0, 1.0])),
+            card(user_id, "never embedded", None),
+        ];
+        let repo = Arc::new(StubCardRepo::new(cards));
+        let use_case = SearchCardsUseCase::new(repo.clone(), Arc::new(FixedEmbedding));
+
+        let hits = use_case.execute(user_id, request("hello")).await.unwrap();
+
+        let answers: Vec<&str> = hits.iter().map(|hit| hit.card.answer.as_str()).collect();
+        assert_eq!(answers, ["never embedded", "far"]);
+        let stored = repo.find_by_user(user_id).await.unwrap();
+        assert!(stored.iter().all(|c| c.answer_embedding.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_without_embedding_service_is_unavailable() {
+        let use_case =
+            SearchCardsUseCase::new(Arc::new(StubCardRepo::new(vec![])), Arc::new(NoEmbeddings));
+
+        let err = use_case
+            .execute(Uuid::new_v4(), request("greetings"))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, AppError::ServiceUnavailable(_)));
+        assert_eq!(
+            err.status_code(),
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blank_query_and_bad_limit_are_rejected() {
+        let use_case = SearchCardsUseCase::new(
+            Arc::new(StubCardRepo::new(vec![])),
+            Arc::new(FixedEmbedding),
+        );
+        let user_id = Uuid::new_v4();
+
+        assert!(matches!(
+            use_case.execute(user_id, request("  ")).await,
+            Err(AppError::ValidationError(_))
+        ));
+        let too_many = SearchCardsRequest {
+            limit: Some(MAX_LIMIT + 1),
+            ..request("hello")
+        };
+        assert!(matches!(
+            use_case.execute(user_id, too_many).await,
+            Err(AppError::ValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_refuses_users_who_turned_ai_off() {
+        let users = Arc::new(SingleUserRepository::ai_opted_out());
+        let user_id = users.user_id();
+        let use_case = SearchCardsUseCase::new(
+            Arc::new(StubCardRepo::new(vec![])),
+            Arc::new(FixedEmbedding),
+        )
+        .with_user_repository(users);
+
+        let result = use_case.execute(user_id, request("hello")).await;
+
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+}
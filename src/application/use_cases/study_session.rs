@@ -0,0 +1,448 @@
+//! StudySession use case - resumable study sessions over a deck's due cards
+
+use chrono::{DateTime, Duration, Utc};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+};
+use uuid::Uuid;
+
+use crate::{
+    application::{
+        dtos::{CardDto, StudySessionDto, StudySessionNextDto},
+        services::card_service::new_card_limit,
+    },
+    domain::repositories::{CardListOptions, CardRepository, DeckRepository, UserRepository},
+    shared::{
+        access::{Access, OwnershipPolicy},
+        error::{AppError, AppResult},
+    },
+};
+
+/// Idle time after which a session is dropped
+pub const DEFAULT_SESSION_TTL_MINUTES: i64 = 120;
+
+struct StudySession {
+    user_id: Uuid,
+    deck_id: Uuid,
+    queue: VecDeque<Uuid>,
+    /// Cards handed out by `next`; only these count as session reviews
+    served: HashSet<Uuid>,
+    reviewed: HashSet<Uuid>,
+    total: usize,
+    expires_at: DateTime<Utc>,
+}
+
+/// In-memory registry of study sessions. Sessions expire after `ttl` without
+/// activity; expired sessions are purged whenever a new one is created.
+#[derive(Default)]
+struct StudySessionStore {
+    sessions: Mutex<HashMap<Uuid, StudySession>>,
+}
+
+impl StudySessionStore {
+    /// Run `f` on a live session owned by `user_id`, pushing its expiry back
+    fn with_session<T>(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        ttl: Duration,
+        policy: OwnershipPolicy,
+        f: impl FnOnce(Uuid, &mut StudySession) -> T,
+    ) -> AppResult<T> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let now = Utc::now();
+        if sessions
+            .get(&session_id)
+            .is_some_and(|session| session.expires_at <= now)
+        {
+            sessions.remove(&session_id);
+            return Err(AppError::NotFound(format!(
+                "Study session {} has expired",
+                session_id
+            )));
+        }
+        let session = policy.authorize(
+            sessions.get_mut(&session_id),
+            |session| session.user_id,
+            user_id,
+            Access::Write,
+            "Study session",
+            session_id,
+        )?;
+        session.expires_at = now + ttl;
+        Ok(f(session_id, session))
+    }
+}
+
+/// Use case for building a study queue once and walking through it across
+/// requests, so a long session can be resumed where it stopped
+pub struct StudySessionUseCase {
+    card_repository: Arc<dyn CardRepository>,
+    deck_repository: Arc<dyn DeckRepository>,
+    user_repository: Option<Arc<dyn UserRepository>>,
+    sessions: StudySessionStore,
+    ttl: Duration,
+    ownership_policy: OwnershipPolicy,
+}
+
+impl StudySessionUseCase {
+    pub fn new(
+        card_repository: Arc<dyn CardRepository>,
+        deck_repository: Arc<dyn DeckRepository>,
+    ) -> Self {
+        Self {
+            card_repository,
+            deck_repository,
+            user_repository: None,
+            sessions: StudySessionStore::default(),
+            ttl: Duration::minutes(DEFAULT_SESSION_TTL_MINUTES),
+            ownership_policy: OwnershipPolicy::default(),
+        }
+    }
+
+    /// Idle time after which a session expires
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Daily new-card limits follow the deck owner's study day instead of
+    /// the UTC calendar day
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    /// How decks and sessions owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    /// Start a session over the deck's cards that are due now, most overdue
+    /// first. Suspended cards are left out, and new cards are capped by the
+    /// deck's `total_new_limit` and `new_cards_per_day` like in the deck card
+    /// listing.
+    pub async fn start(&self, user_id: Uuid, deck_id: Uuid) -> AppResult<StudySessionDto> {
+        let deck = self.ownership_policy.authorize(
+            self.deck_repository.find_by_id(deck_id).await?,
+            |deck| deck.user_id,
+            user_id,
+            Access::Read,
+            "Deck",
+            deck_id,
+        )?;
+
+        let now = Utc::now();
+        let options = CardListOptions {
+            new_card_limit: new_card_limit(
+                self.card_repository.as_ref(),
+                self.user_repository.as_deref(),
+                &deck,
+                now,
+            )
+            .await?,
+            ..Default::default()
+        };
+        let mut due: Vec<_> = self
+            .card_repository
+            .find_by_deck_paginated(deck_id, options)
+            .await?
+            .into_iter()
+            .filter(|card| !card.suspended && card.due_at() <= now)
+            .collect();
+        due.sort_by_key(|card| card.due_at());
+
+        let session_id = Uuid::new_v4();
+        let session = StudySession {
+            user_id,
+            deck_id,
+            queue: due.iter().map(|card| card.id).collect(),
+            served: HashSet::new(),
+            reviewed: HashSet::new(),
+            total: due.len(),
+            expires_at: now + self.ttl,
+        };
+        let dto = StudySessionDto {
+            session_id,
+            deck_id,
+            total: session.total,
+            expires_at: session.expires_at,
+            review_mode: deck.review_mode,
+        };
+
+        let mut sessions = self.sessions.sessions.lock().unwrap();
+        sessions.retain(|_, session| session.expires_at > now);
+        sessions.insert(session_id, session);
+        Ok(dto)
+    }
+
+    /// Pop the next card of the session. Cards deleted since the session
+    /// started are skipped.
+    pub async fn next(
+        &self,
+        user_id: Uuid,
+        deck_id: Uuid,
+        session_id: Uuid,
+    ) -> AppResult<StudySessionNextDto> {
+        loop {
+            let (card_id, progress) = self.sessions.with_session(
+                session_id,
+                user_id,
+                self.ttl,
+                self.ownership_policy,
+                |session_id, session| {
+                    if session.deck_id != deck_id {
+                        return Err(AppError::NotFound(format!(
+                            "Study session {} not found",
+                            session_id
+                        )));
+                    }
+                    let card_id = session.queue.pop_front();
+                    if let Some(card_id) = card_id {
+                        session.served.insert(card_id);
+                    }
+                    Ok((
+                        card_id,
+                        StudySessionNextDto {
+                            session_id,
+                            card: None,
+                            position: session.total - session.queue.len(),
+                            remaining: session.queue.len(),
+                            reviewed: session.reviewed.len(),
+                            total: session.total,
+                        },
+                    ))
+                },
+            )??;
+
+            let Some(card_id) = card_id else {
+                return Ok(progress);
+            };
+            if let Some(card) = self.card_repository.find_by_id(card_id).await? {
+                return Ok(StudySessionNextDto {
+                    card: Some(CardDto::from(card)),
+                    ..progress
+                });
+            }
+        }
+    }
+
+    /// Drop sessions that expired before `now`; returns how many were dropped
+    pub fn prune_expired(&self, now: DateTime<Utc>) -> usize {
+        let mut sessions = self.sessions.sessions.lock().unwrap();
+        let before = sessions.len();
+        sessions.retain(|_, session| session.expires_at > now);
+        before - sessions.len()
+    }
+
+    /// Count a review of `card_id` towards the session's progress. Cards the
+    /// session never handed out are ignored.
+    pub fn record_review(&self, user_id: Uuid, session_id: Uuid, card_id: Uuid) -> AppResult<()> {
+        self.sessions.with_session(
+            session_id,
+            user_id,
+            self.ttl,
+            self.ownership_policy,
+            |_, session| {
+                if session.served.contains(&card_id) {
+                    session.reviewed.insert(card_id);
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{Card, CardState, Deck};
+    use async_trait::async_trait;
+
+    struct StubCardRepo(Vec<Card>);
+
+    #[async_trait]
+    impl CardRepository for StubCardRepo {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Card>> {
+            Ok(self.0.iter().find(|c| c.id == id).cloned())
+        }
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|c| c.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn find_by_deck(&self, deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|c| c.deck_id == Some(deck_id))
+                .cloned()
+                .collect())
+        }
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct StubDeckRepo(Deck);
+
+    #[async_trait]
+    impl DeckRepository for StubDeckRepo {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            Ok(deck.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            Ok(Some(self.0.clone()).filter(|deck| deck.id == id))
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(vec![self.0.clone()])
+        }
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    fn card(user_id: Uuid, deck_id: Uuid, question: &str, age_minutes: i64) -> Card {
+        let mut card = Card::new(user_id, question.to_string(), "A".to_string()).with_deck(deck_id);
+        card.created_at = Utc::now() - Duration::minutes(age_minutes);
+        card
+    }
+
+    fn setup(cards: Vec<Card>, deck: Deck) -> StudySessionUseCase {
+        StudySessionUseCase::new(Arc::new(StubCardRepo(cards)), Arc::new(StubDeckRepo(deck)))
+    }
+
+    #[tokio::test]
+    async fn test_session_serves_due_cards_in_order_until_exhausted() {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Spanish".to_string(), None);
+        let deck_id = deck.id;
+        let mut suspended = card(user_id, deck_id, "suspended", 50);
+        suspended.suspended = true;
+        let mut later = card(user_id, deck_id, "not yet", 40);
+        later.not_before = Some(Utc::now() + Duration::days(1));
+        let use_case = setup(
+            vec![
+                card(user_id, deck_id, "second", 10),
+                card(user_id, deck_id, "first", 30),
+                suspended,
+                later,
+            ],
+            deck,
+        );
+
+        let session = use_case.start(user_id, deck_id).await.unwrap();
+        assert_eq!(session.total, 2);
+
+        let first = use_case
+            .next(user_id, deck_id, session.session_id)
+            .await
+            .unwrap();
+        assert_eq!(first.card.as_ref().unwrap().question, "first");
+        assert_eq!((first.position, first.remaining), (1, 1));
+
+        use_case
+            .record_review(user_id, session.session_id, first.card.unwrap().id)
+            .unwrap();
+
+        let second = use_case
+            .next(user_id, deck_id, session.session_id)
+            .await
+            .unwrap();
+        assert_eq!(second.card.unwrap().question, "second");
+        assert_eq!((second.position, second.remaining), (2, 0));
+        assert_eq!(second.reviewed, 1);
+
+        let done = use_case
+            .next(user_id, deck_id, session.session_id)
+            .await
+            .unwrap();
+        assert!(done.card.is_none());
+        assert_eq!(done.remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_caps_new_cards_like_the_deck_listing() {
+        let user_id = Uuid::new_v4();
+        let mut deck =
+            Deck::new(user_id, "Spanish".to_string(), None).with_total_new_limit(Some(3));
+        deck.new_cards_per_day = Some(2);
+        let deck_id = deck.id;
+        let mut reviewed = card(user_id, deck_id, "reviewed", 60);
+        reviewed.fsrs_state.state = CardState::Review;
+        reviewed.fsrs_state.last_review = Some(Utc::now() - Duration::days(3));
+        reviewed.fsrs_state.scheduled_days = 1;
+        let use_case = setup(
+            vec![
+                reviewed,
+                card(user_id, deck_id, "new 1", 50),
+                card(user_id, deck_id, "new 2", 40),
+                card(user_id, deck_id, "new 3", 30),
+            ],
+            deck,
+        );
+
+        let session = use_case.start(user_id, deck_id).await.unwrap();
+
+        // One card already left `New`, so two of the three new cards fit
+        // under both limits
+        assert_eq!(session.total, 3);
+        let mut questions = Vec::new();
+        for _ in 0..3 {
+            let next = use_case
+                .next(user_id, deck_id, session.session_id)
+                .await
+                .unwrap();
+            questions.push(next.card.unwrap().question);
+        }
+        assert_eq!(questions, vec!["reviewed", "new 1", "new 2"]);
+    }
+
+    #[tokio::test]
+    async fn test_expired_session_is_not_found() {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Spanish".to_string(), None);
+        let deck_id = deck.id;
+        let use_case =
+            setup(vec![card(user_id, deck_id, "q", 10)], deck).with_ttl(Duration::zero());
+
+        let session = use_case.start(user_id, deck_id).await.unwrap();
+        let result = use_case.next(user_id, deck_id, session.session_id).await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_session_of_another_user_is_rejected() {
+        let user_id = Uuid::new_v4();
+        let deck = Deck::new(user_id, "Spanish".to_string(), None);
+        let deck_id = deck.id;
+        let use_case = setup(vec![card(user_id, deck_id, "q", 10)], deck);
+
+        let session = use_case.start(user_id, deck_id).await.unwrap();
+        let result = use_case
+            .next(Uuid::new_v4(), deck_id, session.session_id)
+            .await;
+
+        assert!(matches!(result, Err(AppError::AuthorizationError(_))));
+    }
+}
@@ -0,0 +1,48 @@
+//! Stubs shared by the use case tests
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::User, repositories::UserRepository},
+    shared::error::AppResult,
+};
+
+/// User repository holding exactly one user
+pub(crate) struct SingleUserRepository(pub User);
+
+impl SingleUserRepository {
+    /// A repository whose only user turned AI validation off
+    pub(crate) fn ai_opted_out() -> Self {
+        let mut user = User::new("opted-out@example.com".to_string(), "Opted Out".to_string());
+        user.preferences.ai_validation_enabled = false;
+        Self(user)
+    }
+
+    pub(crate) fn user_id(&self) -> Uuid {
+        self.0.id
+    }
+}
+
+#[async_trait]
+impl UserRepository for SingleUserRepository {
+    async fn create(&self, user: &User) -> AppResult<Uuid> {
+        Ok(user.id)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> AppResult<Option<User>> {
+        Ok((self.0.id == id).then(|| self.0.clone()))
+    }
+
+    async fn find_by_email(&self, _email: &str) -> AppResult<Option<User>> {
+        Ok(None)
+    }
+
+    async fn update(&self, _user: &User) -> AppResult<()> {
+        Ok(())
+    }
+
+    async fn delete(&self, _id: Uuid) -> AppResult<()> {
+        Ok(())
+    }
+}
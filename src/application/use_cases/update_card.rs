@@ -0,0 +1,429 @@
+//! UpdateCard use case - edit the text of a card without touching its schedule
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::{
+        dtos::{CardDto, EditCardRequest},
+        use_cases::review_card::ai_validation_enabled,
+    },
+    domain::{
+        entities::{Card, EmbeddingStatus},
+        ports::EmbeddingService,
+        repositories::{CardRepository, UserRepository},
+    },
+    shared::{
+        access::{Access, OwnershipPolicy},
+        error::{AppError, AppResult},
+    },
+};
+
+/// Use case for editing a card's question and answer. The FSRS state is kept,
+/// and the answer embedding is regenerated only when the answer changed.
+pub struct UpdateCardUseCase {
+    card_repository: Arc<dyn CardRepository>,
+    embedding_service: Arc<dyn EmbeddingService>,
+    user_repository: Option<Arc<dyn UserRepository>>,
+    ownership_policy: OwnershipPolicy,
+}
+
+impl UpdateCardUseCase {
+    pub fn new(
+        card_repository: Arc<dyn CardRepository>,
+        embedding_service: Arc<dyn EmbeddingService>,
+    ) -> Self {
+        Self {
+            card_repository,
+            embedding_service,
+            user_repository: None,
+            ownership_policy: OwnershipPolicy::default(),
+        }
+    }
+
+    /// How cards owned by another user are reported
+    pub fn with_ownership_policy(mut self, ownership_policy: OwnershipPolicy) -> Self {
+        self.ownership_policy = ownership_policy;
+        self
+    }
+
+    /// Answers of users who turned AI validation off are not re-embedded
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    /// Edit the card. Fails with `Conflict` when the card was saved again
+    /// (e.g. reviewed) after it was loaded here.
+    pub async fn execute(
+        &self,
+        card_id: Uuid,
+        user_id: Uuid,
+        request: EditCardRequest,
+    ) -> AppResult<CardDto> {
+        let question = request.question.trim();
+        let answer = request.answer.trim();
+        if question.is_empty() || answer.is_empty() {
+            return Err(AppError::ValidationError(
+                "question and answer must not be empty".to_string(),
+            ));
+        }
+
+        let mut card = self.ownership_policy.authorize(
+            self.card_repository.find_by_id(card_id).await?,
+            |card| card.user_id,
+            user_id,
+            Access::Write,
+            "Card",
+            card_id,
+        )?;
+        let loaded_at = card.updated_at;
+
+        let answer_changed = card.answer != answer;
+        card.question = question.to_string();
+        card.answer = answer.to_string();
+        if let Some(answer_type) = request.answer_type {
+            card.answer_type = answer_type;
+        }
+        card.updated_at = chrono::Utc::now();
+        // A review or another edit saved since the card was loaded would be
+        // overwritten; `Conflict` instead
+        self.card_repository
+            .update_if_unchanged(&card, loaded_at)
+            .await?;
+
+        if answer_changed {
+            if ai_validation_enabled(self.user_repository.as_deref(), user_id).await? {
+                self.refresh_embedding(&mut card).await?;
+            } else {
+                // The old embedding no longer matches the answer
+                self.card_repository
+                    .set_embedding_status(card.id, EmbeddingStatus::None)
+                    .await?;
+                card.embedding_status = EmbeddingStatus::None;
+            }
+        }
+
+        Ok(CardDto::from(card))
+    }
+
+    /// Embed the new answer. On failure the card is marked `Failed`, so the
+    /// embedding backfill picks it up later, and the edit still succeeds.
+    async fn refresh_embedding(&self, card: &mut Card) -> AppResult<()> {
+        match self
+            .embedding_service
+            .generate_embedding(&card.answer)
+            .await
+        {
+            Ok(embedding) => {
+                self.card_repository
+                    .update_embedding(card.id, embedding.clone())
+                    .await?;
+                card.answer_embedding = Some(embedding);
+                card.embedding_status = EmbeddingStatus::Ready;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to regenerate embedding for card {}: {}", card.id, e);
+                self.card_repository
+                    .set_embedding_status(card.id, EmbeddingStatus::Failed)
+                    .await?;
+                card.embedding_status = EmbeddingStatus::Failed;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::use_cases::test_support::SingleUserRepository;
+    use crate::domain::entities::AnswerType;
+    use async_trait::async_trait;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    struct InMemoryCardRepo {
+        cards: Mutex<Vec<Card>>,
+        /// Reads return the card as it was before a concurrent save
+        stale_reads: bool,
+    }
+
+    #[async_trait]
+    impl CardRepository for InMemoryCardRepo {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            self.cards.lock().unwrap().push(card.clone());
+            Ok(card.id)
+        }
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Card>> {
+            let cards = self.cards.lock().unwrap();
+            Ok(cards.iter().find(|c| c.id == id).cloned().map(|mut c| {
+                if self.stale_reads {
+                    c.updated_at -= chrono::Duration::seconds(1);
+                }
+                c
+            }))
+        }
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+        async fn update(&self, card: &Card) -> AppResult<()> {
+            let mut cards = self.cards.lock().unwrap();
+            if let Some(c) = cards.iter_mut().find(|c| c.id == card.id) {
+                *c = card.clone();
+            }
+            Ok(())
+        }
+        async fn update_if_unchanged(
+            &self,
+            card: &Card,
+            loaded_at: chrono::DateTime<chrono::Utc>,
+        ) -> AppResult<()> {
+            let mut cards = self.cards.lock().unwrap();
+            match cards.iter_mut().find(|c| c.id == card.id) {
+                Some(c) if c.updated_at == loaded_at => {
+                    *c = card.clone();
+                    Ok(())
+                }
+                _ => Err(AppError::Conflict("changed concurrently".to_string())),
+            }
+        }
+        async fn update_embedding(&self, id: Uuid, embedding: Vec<f32>) -> AppResult<()> {
+            let mut cards = self.cards.lock().unwrap();
+            if let Some(c) = cards.iter_mut().find(|c| c.id == id) {
+                c.answer_embedding = Some(embedding);
+                c.embedding_status = EmbeddingStatus::Ready;
+            }
+            Ok(())
+        }
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Counts the texts it is asked to embed
+    #[derive(Default)]
+    struct CountingEmbedding {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingService for CountingEmbedding {
+        async fn generate_embedding(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![0.5, 0.5])
+        }
+    }
+
+    fn setup(
+        card: Card,
+    ) -> (
+        UpdateCardUseCase,
+        Arc<InMemoryCardRepo>,
+        Arc<CountingEmbedding>,
+    ) {
+        let repo = Arc::new(InMemoryCardRepo {
+            cards: Mutex::new(vec![card]),
+            stale_reads: false,
+        });
+        let embedding = Arc::new(CountingEmbedding::default());
+        (
+            UpdateCardUseCase::new(repo.clone(), embedding.clone()),
+            repo,
+            embedding,
+        )
+    }
+
+    fn edit(question: &str, answer: &str) -> EditCardRequest {
+        EditCardRequest {
+            question: question.to_string(),
+            answer: answer.to_string(),
+            answer_type: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_edit_card_of_another_user_is_rejected() {
+        let card = Card::new(Uuid::new_v4(), "Q".to_string(), "A".to_string());
+        let card_id = card.id;
+        let (use_case, repo, embedding) = setup(card);
+
+        let result = use_case
+            .execute(card_id, Uuid::new_v4(), edit("New Q", "New A"))
+            .await;
+
+        assert!(matches!(result, Err(AppError::AuthorizationError(_))));
+        assert_eq!(repo.find_by_id(card_id).await.unwrap().unwrap().answer, "A");
+        assert_eq!(embedding.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_edit_of_another_user_card_is_hidden_by_policy() {
+        let card = Card::new(Uuid::new_v4(), "Q".to_string(), "A".to_string());
+        let card_id = card.id;
+        let (use_case, _, _) = setup(card);
+        let use_case = use_case.with_ownership_policy(OwnershipPolicy::HideAll);
+
+        let result = use_case
+            .execute(card_id, Uuid::new_v4(), edit("New Q", "New A"))
+            .await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_edit_racing_another_save_is_a_conflict() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "Q".to_string(), "A".to_string());
+        let card_id = card.id;
+        let repo = Arc::new(InMemoryCardRepo {
+            cards: Mutex::new(vec![card]),
+            stale_reads: true,
+        });
+        let embedding = Arc::new(CountingEmbedding::default());
+        let use_case = UpdateCardUseCase::new(repo.clone(), embedding.clone());
+
+        let result = use_case
+            .execute(card_id, user_id, edit("Q", "Lost answer"))
+            .await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert_eq!(repo.cards.lock().unwrap()[0].answer, "A");
+        assert_eq!(embedding.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_edit_missing_card_is_not_found() {
+        let card = Card::new(Uuid::new_v4(), "Q".to_string(), "A".to_string());
+        let (use_case, _, _) = setup(card);
+
+        let result = use_case
+            .execute(Uuid::new_v4(), Uuid::new_v4(), edit("Q", "A"))
+            .await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_new_answer_regenerates_embedding() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "Q".to_string(), "A".to_string());
+        let card_id = card.id;
+        let (use_case, repo, embedding) = setup(card);
+
+        let dto = use_case
+            .execute(card_id, user_id, edit("Q", "Another answer"))
+            .await
+            .unwrap();
+
+        assert_eq!(dto.answer, "Another answer");
+        assert_eq!(embedding.calls.load(Ordering::SeqCst), 1);
+        let stored = repo.find_by_id(card_id).await.unwrap().unwrap();
+        assert_eq!(stored.answer_embedding, Some(vec![0.5, 0.5]));
+    }
+
+    #[tokio::test]
+    async fn test_new_answer_of_ai_opted_out_user_is_not_embedded() {
+        let users = Arc::new(SingleUserRepository::ai_opted_out());
+        let user_id = users.user_id();
+        let card = Card::new(user_id, "Q".to_string(), "A".to_string());
+        let card_id = card.id;
+        let (use_case, _, embedding) = setup(card);
+        let use_case = use_case.with_user_repository(users);
+
+        let dto = use_case
+            .execute(card_id, user_id, edit("Q", "Another answer"))
+            .await
+            .unwrap();
+
+        assert_eq!(dto.answer, "Another answer");
+        assert_eq!(embedding.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_question_only_edit_keeps_embedding() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "Q".to_string(), "A".to_string());
+        let (card_id, created_at) = (card.id, card.updated_at);
+        let (use_case, repo, embedding) = setup(card);
+
+        let dto = use_case
+            .execute(card_id, user_id, edit("Better question", " A "))
+            .await
+            .unwrap();
+
+        assert_eq!(dto.question, "Better question");
+        assert_eq!(embedding.calls.load(Ordering::SeqCst), 0);
+        let stored = repo.find_by_id(card_id).await.unwrap().unwrap();
+        assert!(stored.updated_at >= created_at);
+        assert!(stored.answer_embedding.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_edit_keeps_scheduling_state() {
+        let user_id = Uuid::new_v4();
+        let mut card = Card::new(user_id, "Q".to_string(), "A".to_string());
+        card.fsrs_state.stability = 12.5;
+        card.fsrs_state.reps = 4;
+        let card_id = card.id;
+        let (use_case, repo, _) = setup(card);
+
+        use_case
+            .execute(card_id, user_id, edit("Q2", "A2"))
+            .await
+            .unwrap();
+
+        let stored = repo.find_by_id(card_id).await.unwrap().unwrap();
+        assert_eq!(stored.fsrs_state.stability, 12.5);
+        assert_eq!(stored.fsrs_state.reps, 4);
+    }
+
+    #[tokio::test]
+    async fn test_edit_changes_answer_type_only_when_given() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "Colors".to_string(), "red, blue".to_string());
+        let card_id = card.id;
+        let (use_case, repo, _) = setup(card);
+
+        use_case
+            .execute(
+                card_id,
+                user_id,
+                EditCardRequest {
+                    answer_type: Some(AnswerType::List),
+                    ..edit("Colors", "red, blue")
+                },
+            )
+            .await
+            .unwrap();
+        let listed = repo.find_by_id(card_id).await.unwrap().unwrap();
+        use_case
+            .execute(card_id, user_id, edit("Two colors", "red, blue"))
+            .await
+            .unwrap();
+        let kept = repo.find_by_id(card_id).await.unwrap().unwrap();
+
+        assert_eq!(listed.answer_type, AnswerType::List);
+        assert_eq!(kept.answer_type, AnswerType::List);
+    }
+
+    #[tokio::test]
+    async fn test_blank_answer_is_rejected() {
+        let user_id = Uuid::new_v4();
+        let card = Card::new(user_id, "Q".to_string(), "A".to_string());
+        let card_id = card.id;
+        let (use_case, _, _) = setup(card);
+
+        let result = use_case.execute(card_id, user_id, edit("Q", "  ")).await;
+
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+}
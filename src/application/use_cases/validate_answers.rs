@@ -0,0 +1,208 @@
+//! ValidateAnswers use case - grade answers with the AI validator without
+//! touching any card, schedule or review log (e.g. teachers grading students)
+
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::{
+    application::{
+        dtos::{ValidateAnswerItem, ValidationPreviewDto},
+        use_cases::review_card::{ai_validation_enabled, AI_DISABLED},
+    },
+    domain::{ports::AIValidator, repositories::UserRepository},
+    shared::{
+        deadline,
+        error::{AppError, AppResult},
+        rate_limit::AiRateLimiter,
+    },
+};
+
+/// Maximum number of answers graded in one request
+pub const MAX_BATCH: usize = 50;
+
+/// Use case for previewing AI scores of arbitrary answers
+pub struct ValidateAnswersUseCase {
+    validator: Arc<dyn AIValidator>,
+    rate_limiter: Option<Arc<AiRateLimiter>>,
+    user_repository: Option<Arc<dyn UserRepository>>,
+}
+
+impl ValidateAnswersUseCase {
+    pub fn new(validator: Arc<dyn AIValidator>) -> Self {
+        Self {
+            validator,
+            rate_limiter: None,
+            user_repository: None,
+        }
+    }
+
+    /// Charge each graded answer against the user's AI request limit
+    pub fn with_rate_limiter(mut self, rate_limiter: Option<Arc<AiRateLimiter>>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Refuse users who turned AI validation off in their preferences
+    pub fn with_user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    /// Grade every item, in request order. A failure of the validator on one
+    /// item is reported on that item only.
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        items: Vec<ValidateAnswerItem>,
+    ) -> AppResult<Vec<ValidationPreviewDto>> {
+        if items.is_empty() {
+            return Ok(vec![]);
+        }
+        if items.len() > MAX_BATCH {
+            return Err(AppError::ValidationError(format!(
+                "At most {} answers can be validated per request",
+                MAX_BATCH
+            )));
+        }
+        if !ai_validation_enabled(self.user_repository.as_deref(), user_id).await? {
+            return Err(AppError::ValidationError(AI_DISABLED.to_string()));
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(user_id, items.len() as u32)?;
+        }
+
+        let mut tasks = JoinSet::new();
+        for (index, item) in items.into_iter().enumerate() {
+            let validator = self.validator.clone();
+            // Spawned tasks don't inherit the request deadline
+            let request_deadline = deadline::current();
+            tasks.spawn(deadline::scope(request_deadline, async move {
+                let result = validator
+                    .validate(&item.expected, &item.answer, &item.question)
+                    .await;
+                (index, result)
+            }));
+        }
+
+        let mut previews = vec![None; tasks.len()];
+        while let Some(joined) = tasks.join_next().await {
+            let (index, result) = joined
+                .map_err(|e| AppError::InternalError(format!("Validation task failed: {}", e)))?;
+            previews[index] = Some(match result {
+                Ok(validation) => ValidationPreviewDto {
+                    score: Some(validation.score),
+                    validation_method: Some(validation.method.as_str().to_string()),
+                    threshold: validation.threshold,
+                    explanation: validation.explanation,
+                    error: None,
+                },
+                Err(err) => ValidationPreviewDto {
+                    score: None,
+                    validation_method: None,
+                    threshold: None,
+                    explanation: None,
+                    error: Some(err.to_string()),
+                },
+            });
+        }
+
+        Ok(previews.into_iter().flatten().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::use_cases::test_support::SingleUserRepository;
+    use crate::domain::ports::{ValidationMethod, ValidationResult};
+    use async_trait::async_trait;
+    use std::time::Duration;
+
+    /// Exact matches score 1.0, "fail" errors, anything else scores 0.0
+    struct StubValidator;
+
+    #[async_trait]
+    impl AIValidator for StubValidator {
+        async fn validate(
+            &self,
+            expected: &str,
+            actual: &str,
+            _question: &str,
+        ) -> anyhow::Result<ValidationResult> {
+            if actual == "fail" {
+                anyhow::bail!("validator unavailable");
+            }
+            Ok(ValidationResult {
+                score: if expected == actual { 1.0 } else { 0.0 },
+                method: ValidationMethod::Exact,
+                explanation: None,
+                threshold: None,
+            })
+        }
+    }
+
+    fn item(expected: &str, answer: &str) -> ValidateAnswerItem {
+        ValidateAnswerItem {
+            question: "Translate".to_string(),
+            expected: expected.to_string(),
+            answer: answer.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_returns_per_item_results_in_order() {
+        let use_case = ValidateAnswersUseCase::new(Arc::new(StubValidator));
+
+        let results = use_case
+            .execute(
+                Uuid::new_v4(),
+                vec![
+                    item("hola", "hola"),
+                    item("casa", "perro"),
+                    item("gato", "fail"),
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].score, Some(1.0));
+        assert_eq!(results[0].validation_method.as_deref(), Some("exact"));
+        assert_eq!(results[1].score, Some(0.0));
+        assert!(results[2].score.is_none());
+        assert!(results[2].error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_batch_cap_and_rate_limit() {
+        let user_id = Uuid::new_v4();
+        let use_case = ValidateAnswersUseCase::new(Arc::new(StubValidator)).with_rate_limiter(
+            Some(Arc::new(AiRateLimiter::new(3, Duration::from_secs(60)))),
+        );
+
+        let too_many = use_case
+            .execute(user_id, vec![item("a", "a"); MAX_BATCH + 1])
+            .await;
+        assert!(matches!(too_many, Err(AppError::ValidationError(_))));
+
+        assert!(use_case
+            .execute(user_id, vec![item("a", "a"); 2])
+            .await
+            .is_ok());
+        let limited = use_case.execute(user_id, vec![item("a", "a"); 2]).await;
+        assert!(matches!(limited, Err(AppError::RateLimited(_))));
+    }
+
+    #[tokio::test]
+    async fn test_refuses_users_who_turned_ai_off() {
+        let users = Arc::new(SingleUserRepository::ai_opted_out());
+        let user_id = users.user_id();
+        let use_case =
+            ValidateAnswersUseCase::new(Arc::new(StubValidator)).with_user_repository(users);
+
+        let result = use_case.execute(user_id, vec![item("a", "a")]).await;
+
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+/// How a diff segment relates the learner's answer to the expected one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    /// In both answers
+    Equal,
+    /// Missing from the learner's answer; must be inserted to get the expected one
+    Insert,
+    /// Only in the learner's answer; must be deleted to get the expected one
+    Delete,
+}
+
+/// Longest answer, in words, that is diffed. The diff table grows with the
+/// product of both lengths, so longer answers get no diff.
+pub const MAX_DIFF_WORDS: usize = 500;
+
+/// A run of consecutive words with the same [`DiffOp`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffSegment {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// Word-level diff turning `actual` (the learner's answer) into `expected`.
+///
+/// Words are compared case-insensitively; `Equal` segments keep the
+/// learner's spelling. Segments are joined with single spaces. `None` when
+/// either answer has more than [`MAX_DIFF_WORDS`] words.
+pub fn diff_words(expected: &str, actual: &str) -> Option<Vec<DiffSegment>> {
+    let expected: Vec<&str> = expected.split_whitespace().collect();
+    let actual: Vec<&str> = actual.split_whitespace().collect();
+    if expected.len() > MAX_DIFF_WORDS || actual.len() > MAX_DIFF_WORDS {
+        return None;
+    }
+    let same = |a: &str, e: &str| a.to_lowercase() == e.to_lowercase();
+
+    // lcs[i][j]: longest common subsequence of actual[i..] and expected[j..]
+    let mut lcs = vec![vec![0usize; expected.len() + 1]; actual.len() + 1];
+    for i in (0..actual.len()).rev() {
+        for j in (0..expected.len()).rev() {
+            lcs[i][j] = if same(actual[i], expected[j]) {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut segments: Vec<DiffSegment> = Vec::new();
+    let mut push = |op: DiffOp, word: &str| match segments.last_mut() {
+        Some(last) if last.op == op => {
+            last.text.push(' ');
+            last.text.push_str(word);
+        }
+        _ => segments.push(DiffSegment {
+            op,
+            text: word.to_string(),
+        }),
+    };
+
+    let (mut i, mut j) = (0, 0);
+    while i < actual.len() && j < expected.len() {
+        if same(actual[i], expected[j]) {
+            push(DiffOp::Equal, actual[i]);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            push(DiffOp::Delete, actual[i]);
+            i += 1;
+        } else {
+            push(DiffOp::Insert, expected[j]);
+            j += 1;
+        }
+    }
+    for word in &actual[i..] {
+        push(DiffOp::Delete, word);
+    }
+    for word in &expected[j..] {
+        push(DiffOp::Insert, word);
+    }
+
+    Some(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(op: DiffOp, text: &str) -> DiffSegment {
+        DiffSegment {
+            op,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_single_changed_word() {
+        let diff = diff_words("the red house", "the blue house").unwrap();
+
+        assert_eq!(
+            diff,
+            vec![
+                segment(DiffOp::Equal, "the"),
+                segment(DiffOp::Delete, "blue"),
+                segment(DiffOp::Insert, "red"),
+                segment(DiffOp::Equal, "house"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matching_answers_are_one_equal_segment() {
+        assert_eq!(
+            diff_words("La Casa", "la  casa"),
+            Some(vec![segment(DiffOp::Equal, "la casa")])
+        );
+    }
+
+    #[test]
+    fn test_missing_and_extra_words() {
+        assert_eq!(
+            diff_words("casa", ""),
+            Some(vec![segment(DiffOp::Insert, "casa")])
+        );
+        assert_eq!(
+            diff_words("casa", "la casa grande"),
+            Some(vec![
+                segment(DiffOp::Delete, "la"),
+                segment(DiffOp::Equal, "casa"),
+                segment(DiffOp::Delete, "grande"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_overlong_answers_are_not_diffed() {
+        let long = "word ".repeat(MAX_DIFF_WORDS + 1);
+        assert_eq!(diff_words("casa", &long), None);
+        assert_eq!(diff_words(&long, "casa"), None);
+        assert!(diff_words(&long[5..], "casa").is_some());
+    }
+}
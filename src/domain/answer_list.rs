@@ -0,0 +1,158 @@
+//! Order-independent grading of list answers ("name three colors")
+
+/// Separators between the items of a list answer
+const ITEM_SEPARATORS: [char; 4] = [',', ';', '/', '\n'];
+
+/// Similarity an answered item needs to count as one of the expected items
+const ITEM_MATCH_THRESHOLD: f32 = 0.8;
+
+/// Longest answer, in characters, graded with fuzzy matching. Each pair of
+/// items is compared by edit distance, so longer answers (or card answers)
+/// only count items that match exactly.
+pub const MAX_LIST_ANSWER_CHARS: usize = 2_000;
+
+/// Longest run of words tried as one item of an answer without separators
+const MAX_RUN_WORDS: usize = 8;
+
+/// Items of a delimited list, trimmed and lowercased, blanks dropped
+pub fn list_items(text: &str) -> Vec<String> {
+    text.split(ITEM_SEPARATORS)
+        .map(|item| item.trim().to_lowercase())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Fraction of the expected items found in `answer`, in any order.
+///
+/// Each expected item is matched at most once, to the most similar unused
+/// item of the answer, and counts when the two are close enough to forgive a
+/// typo. An answer without separators ("green blue red") is split into runs
+/// of words instead, so multi-word items still match. Extra items in the
+/// answer are not penalised.
+pub fn score_list_answer(expected: &str, answer: &str) -> f32 {
+    let too_long = expected.len().max(answer.len()) > MAX_LIST_ANSWER_CHARS;
+    let expected = list_items(expected);
+    if expected.is_empty() {
+        return 0.0;
+    }
+    if too_long {
+        return score_exact_items(&expected, answer);
+    }
+
+    let mut candidates = if answer.contains(ITEM_SEPARATORS) {
+        list_items(answer)
+    } else {
+        let max_words = expected
+            .iter()
+            .map(|item| item.split_whitespace().count())
+            .max()
+            .unwrap_or(1)
+            .min(MAX_RUN_WORDS);
+        word_runs(&answer.to_lowercase(), max_words)
+    };
+
+    let mut matched = 0;
+    for item in &expected {
+        let best = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, candidate)| (i, similarity(item, candidate)))
+            .filter(|(_, score)| *score >= ITEM_MATCH_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, _)) = best {
+            candidates.swap_remove(i);
+            matched += 1;
+        }
+    }
+    matched as f32 / expected.len() as f32
+}
+
+/// Fraction of the expected items that are also items of `answer`, compared
+/// exactly after trimming and lowercasing
+fn score_exact_items(expected: &[String], answer: &str) -> f32 {
+    let answered: std::collections::HashSet<String> = list_items(answer).into_iter().collect();
+    let matched = expected
+        .iter()
+        .filter(|item| answered.contains(*item))
+        .count();
+    matched as f32 / expected.len() as f32
+}
+
+/// Every run of 1 to `max_words` consecutive words
+fn word_runs(text: &str, max_words: usize) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    (1..=max_words.max(1))
+        .flat_map(|n| words.windows(n).map(|run| run.join(" ")))
+        .collect()
+}
+
+/// 1.0 for equal strings, falling with the edit distance relative to the
+/// longer string
+fn similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(&a, &b) as f32 / longest as f32
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            current[j + 1] = (previous[j] + usize::from(ca != cb))
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_any_order_matches_fully() {
+        assert_eq!(score_list_answer("red, blue, green", "green blue red"), 1.0);
+        assert_eq!(
+            score_list_answer("red, blue, green", "Blue; Green; Red"),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_partial_answer_scores_fraction_of_items() {
+        let score = score_list_answer("red, blue, green", "red blue");
+        assert!((score - 2.0 / 3.0).abs() < 0.01, "{}", score);
+    }
+
+    #[test]
+    fn test_items_match_fuzzily_but_only_once() {
+        assert_eq!(
+            score_list_answer("red, blue, green", "gren, blue, red"),
+            1.0
+        );
+        let score = score_list_answer("red, blue, green", "red, red, red");
+        assert!((score - 1.0 / 3.0).abs() < 0.01, "{}", score);
+    }
+
+    #[test]
+    fn test_multi_word_items_without_separators() {
+        assert_eq!(score_list_answer("New York, Paris", "paris new york"), 1.0);
+    }
+
+    #[test]
+    fn test_overlong_answers_only_match_exact_items() {
+        let padding = ", filler".repeat(MAX_LIST_ANSWER_CHARS / 8);
+        let answer = format!("Red, gren{}", padding);
+
+        let score = score_list_answer("red, green", &answer);
+
+        assert!((score - 0.5).abs() < 0.01, "{}", score);
+    }
+}
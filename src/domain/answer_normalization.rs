@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// One step of the answer normalization pipeline. Steps run in the order
+/// they are configured, each on the output of the previous one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum NormalizationStep {
+    /// Remove leading and trailing whitespace
+    Trim,
+    /// Trim and turn every run of whitespace into a single space
+    CollapseWhitespace,
+    Lowercase,
+    /// Remove accents and other combining marks ("canción" -> "cancion")
+    StripDiacritics,
+    /// Remove quotation marks wrapping the answer (`"hola"` -> `hola`)
+    StripQuotes,
+    /// Remove punctuation anywhere in the answer
+    StripPunctuation,
+    /// Drop a leading article, compared case-insensitively as a whole word
+    StripArticles {
+        #[serde(default = "default_articles")]
+        articles: Vec<String>,
+    },
+    /// Drop the deck's ignored prefixes, e.g. "the answer is"
+    StripIgnoredPrefixes,
+}
+
+fn default_articles() -> Vec<String> {
+    ["the", "a", "an"].map(String::from).to_vec()
+}
+
+impl NormalizationStep {
+    /// Pipeline used when a deck doesn't configure one
+    pub fn default_pipeline() -> Vec<Self> {
+        vec![Self::Trim, Self::StripIgnoredPrefixes]
+    }
+}
+
+/// How a user's answer is cleaned up before it is compared with the card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnswerNormalization {
+    pub steps: Vec<NormalizationStep>,
+    /// Filler phrases such as "the answer is" dropped from the start of an
+    /// answer by `StripIgnoredPrefixes`, compared case-insensitively
+    pub ignored_prefixes: Vec<String>,
+}
+
+impl Default for AnswerNormalization {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl AnswerNormalization {
+    /// The default pipeline with the given ignored prefixes
+    pub fn new(ignored_prefixes: Vec<String>) -> Self {
+        Self {
+            steps: NormalizationStep::default_pipeline(),
+            ignored_prefixes,
+        }
+    }
+
+    /// Replace the pipeline
+    pub fn with_steps(mut self, steps: Vec<NormalizationStep>) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Run every step in order. A step that would strip a non-empty answer
+    /// down to nothing is skipped, so an answer that consists of nothing but
+    /// a prefix or an article is kept as is.
+    pub fn apply(&self, answer: &str) -> String {
+        self.steps.iter().fold(answer.to_string(), |text, step| {
+            let next = self.apply_step(step, &text);
+            if next.trim().is_empty() && !text.trim().is_empty() {
+                text
+            } else {
+                next
+            }
+        })
+    }
+
+    fn apply_step(&self, step: &NormalizationStep, text: &str) -> String {
+        match step {
+            NormalizationStep::Trim => text.trim().to_string(),
+            NormalizationStep::CollapseWhitespace => {
+                text.split_whitespace().collect::<Vec<_>>().join(" ")
+            }
+            NormalizationStep::Lowercase => text.to_lowercase(),
+            NormalizationStep::StripDiacritics => text
+                .nfd()
+                .filter(|c| !is_combining_mark(*c))
+                .nfc()
+                .collect(),
+            NormalizationStep::StripQuotes => {
+                let mut rest = text.trim();
+                while let Some(inner) = strip_wrapping_quotes(rest) {
+                    rest = inner.trim();
+                }
+                rest.to_string()
+            }
+            NormalizationStep::StripPunctuation => {
+                text.chars().filter(|c| !is_punctuation(*c)).collect()
+            }
+            NormalizationStep::StripArticles { articles } => strip_prefixes(text, articles),
+            NormalizationStep::StripIgnoredPrefixes => strip_prefixes(text, &self.ignored_prefixes),
+        }
+    }
+}
+
+const QUOTE_PAIRS: [(char, char); 5] =
+    [('"', '"'), ('\'', '\''), ('“', '”'), ('‘', '’'), ('«', '»')];
+
+/// `text` without one pair of quotes around it
+fn strip_wrapping_quotes(text: &str) -> Option<&str> {
+    QUOTE_PAIRS
+        .iter()
+        .find_map(|(open, close)| text.strip_prefix(*open)?.strip_suffix(*close))
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation()
+        || matches!(
+            c,
+            '¿' | '¡' | '«' | '»' | '“' | '”' | '‘' | '’' | '…' | '–' | '—' | '·'
+        )
+}
+
+/// Strip `prefixes` from the start of `text`, repeatedly, so
+/// "the answer is: it's hola" becomes "hola"
+fn strip_prefixes(text: &str, prefixes: &[String]) -> String {
+    let mut rest = text.trim_start();
+    while let Some(stripped) = prefixes
+        .iter()
+        .find_map(|prefix| strip_prefix_words(rest, prefix))
+    {
+        rest = stripped;
+    }
+    rest.to_string()
+}
+
+/// `text` without a leading `prefix` and the punctuation/whitespace that
+/// follows it, or `None` when `text` doesn't start with `prefix` as whole words
+fn strip_prefix_words<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let prefix = prefix.trim();
+    if prefix.is_empty() {
+        return None;
+    }
+    let head = text.get(..prefix.len())?;
+    if head.to_lowercase() != prefix.to_lowercase() {
+        return None;
+    }
+    let tail = &text[prefix.len()..];
+    if tail.chars().next().is_some_and(char::is_alphanumeric) {
+        return None;
+    }
+    Some(tail.trim_start_matches(|c: char| c.is_whitespace() || matches!(c, ':' | ',' | '-')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalization(prefixes: &[&str]) -> AnswerNormalization {
+        AnswerNormalization::new(prefixes.iter().map(|p| p.to_string()).collect())
+    }
+
+    fn pipeline(steps: Vec<NormalizationStep>) -> AnswerNormalization {
+        AnswerNormalization::default().with_steps(steps)
+    }
+
+    fn articles() -> NormalizationStep {
+        NormalizationStep::StripArticles {
+            articles: default_articles(),
+        }
+    }
+
+    #[test]
+    fn test_strips_configured_prefix() {
+        let normalization = normalization(&["it's", "the answer is"]);
+        assert_eq!(normalization.apply("it's hola"), "hola");
+        assert_eq!(normalization.apply("The answer is: hola"), "hola");
+        assert_eq!(normalization.apply("the answer is it's hola"), "hola");
+    }
+
+    #[test]
+    fn test_only_strips_whole_words_at_start() {
+        let normalization = normalization(&["it"]);
+        assert_eq!(normalization.apply("item"), "item");
+        assert_eq!(normalization.apply("hola it"), "hola it");
+        assert_eq!(normalization.apply("it"), "it");
+        assert_eq!(AnswerNormalization::default().apply(" hola "), "hola");
+    }
+
+    #[test]
+    fn test_pipeline_order_is_respected() {
+        use NormalizationStep::*;
+
+        // Quotes first, so the article is at the start when it is stripped
+        let quotes_first = pipeline(vec![StripQuotes, articles()]);
+        assert_eq!(quotes_first.apply("\"the house\""), "house");
+
+        let articles_first = pipeline(vec![articles(), StripQuotes]);
+        assert_eq!(articles_first.apply("\"the house\""), "the house");
+    }
+
+    #[test]
+    fn test_individual_steps() {
+        use NormalizationStep::*;
+
+        let full = pipeline(vec![
+            CollapseWhitespace,
+            Lowercase,
+            StripDiacritics,
+            StripPunctuation,
+            articles(),
+        ]);
+        assert_eq!(full.apply("  ¡La  Canción!  "), "la cancion");
+        assert_eq!(full.apply("The   Café."), "cafe");
+        // An answer that is nothing but an article is kept
+        assert_eq!(full.apply("The"), "the");
+        assert_eq!(pipeline(vec![]).apply(" as is "), " as is ");
+    }
+
+    #[test]
+    fn test_steps_deserialize_from_tagged_json() {
+        let steps: Vec<NormalizationStep> = serde_json::from_str(
+            r#"[{"step":"strip_quotes"},{"step":"strip_articles"},{"step":"strip_articles","articles":["el","la"]}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                NormalizationStep::StripQuotes,
+                articles(),
+                NormalizationStep::StripArticles {
+                    articles: vec!["el".to_string(), "la".to_string()]
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Vector helpers for answer embeddings.
+
+use crate::shared::error::{AppError, AppResult};
+
+/// Length of stored answer embeddings; matches the `vector(1536)` column and
+/// OpenAI's text-embedding-3-small
+pub const EMBEDDING_DIMENSIONS: usize = 1536;
+
+/// Reject embeddings pgvector can't store or compare: a length other than
+/// [`EMBEDDING_DIMENSIONS`], or NaN/infinite components
+pub fn validate_embedding(vec: &[f32]) -> AppResult<()> {
+    if vec.len() != EMBEDDING_DIMENSIONS {
+        return Err(AppError::ValidationError(format!(
+            "Embedding has {} dimensions, expected {}",
+            vec.len(),
+            EMBEDDING_DIMENSIONS
+        )));
+    }
+    if let Some(index) = vec.iter().position(|value| !value.is_finite()) {
+        return Err(AppError::ValidationError(format!(
+            "Embedding component {} is not a finite number",
+            index
+        )));
+    }
+    Ok(())
+}
+
+/// Calculate cosine similarity between two vectors
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let magnitude_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let magnitude_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if magnitude_a == 0.0 || magnitude_b == 0.0 {
+        return 0.0;
+    }
+
+    dot_product / (magnitude_a * magnitude_b)
+}
+
+/// Component-wise mean of `embeddings`. Vectors whose length differs from the
+/// first one are ignored; returns `None` when there is nothing to average.
+pub fn centroid<'a, I>(embeddings: I) -> Option<Vec<f32>>
+where
+    I: IntoIterator<Item = &'a [f32]>,
+{
+    let mut embeddings = embeddings.into_iter();
+    let mut sum = embeddings.next()?.to_vec();
+    let mut count = 1usize;
+    for embedding in embeddings {
+        if embedding.len() != sum.len() {
+            continue;
+        }
+        for (total, value) in sum.iter_mut().zip(embedding) {
+            *total += value;
+        }
+        count += 1;
+    }
+    for total in &mut sum {
+        *total /= count as f32;
+    }
+    Some(sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        let a = vec![1.0, 0.0, 0.0];
+        let b = vec![1.0, 0.0, 0.0];
+        assert!((cosine_similarity(&a, &b) - 1.0).abs() < 0.001);
+
+        let c = vec![1.0, 0.0];
+        let d = vec![0.0, 1.0];
+        assert!((cosine_similarity(&c, &d) - 0.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_validate_embedding_rejects_wrong_length() {
+        assert!(validate_embedding(&vec![0.1; EMBEDDING_DIMENSIONS]).is_ok());
+        assert!(matches!(
+            validate_embedding(&[0.1, 0.2, 0.3]),
+            Err(AppError::ValidationError(_))
+        ));
+        assert!(validate_embedding(&[]).is_err());
+    }
+
+    #[test]
+    fn test_validate_embedding_rejects_non_finite_values() {
+        let mut embedding = vec![0.1; EMBEDDING_DIMENSIONS];
+        embedding[7] = f32::NAN;
+        assert!(matches!(
+            validate_embedding(&embedding),
+            Err(AppError::ValidationError(_))
+        ));
+        embedding[7] = f32::INFINITY;
+        assert!(validate_embedding(&embedding).is_err());
+    }
+
+    #[test]
+    fn test_centroid_averages_components() {
+        let vectors = [vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0]];
+        let mean = centroid(vectors.iter().map(Vec::as_slice)).unwrap();
+        assert_eq!(mean, vec![0.5, 0.5]);
+        assert!(centroid(std::iter::empty()).is_none());
+    }
+}
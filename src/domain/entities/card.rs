@@ -2,6 +2,11 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::{
+    domain::{embedding::validate_embedding, value_objects::Interval},
+    shared::error::{AppError, AppResult},
+};
+
 /// FSRS (Free Spaced Repetition Scheduler) state for a card
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FsrsState {
@@ -38,6 +43,18 @@ impl Default for FsrsState {
     }
 }
 
+impl FsrsState {
+    /// Scheduled interval; negative stored values count as zero
+    pub fn scheduled_interval(&self) -> Interval {
+        Interval::from_days(self.scheduled_days.into()).unwrap_or(Interval::ZERO)
+    }
+
+    /// Store `interval` as whole days
+    pub fn set_scheduled_interval(&mut self, interval: Interval) {
+        self.scheduled_days = interval.as_days().try_into().unwrap_or(i32::MAX);
+    }
+}
+
 /// Card State according to FSRS algorithm
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "card_state", rename_all = "lowercase")]
@@ -49,6 +66,128 @@ pub enum CardState {
     Relearning,
 }
 
+impl CardState {
+    /// Lowercase name, as used in JSON and query parameters
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::Learning => "learning",
+            Self::Review => "review",
+            Self::Relearning => "relearning",
+        }
+    }
+}
+
+impl std::str::FromStr for CardState {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "new" => Ok(Self::New),
+            "learning" => Ok(Self::Learning),
+            "review" => Ok(Self::Review),
+            "relearning" => Ok(Self::Relearning),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown card state: {} (expected new, learning, review or relearning)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Whether a card is up for review at a given time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DueStatus {
+    /// Never reviewed
+    New,
+    /// Reviewed before and due now or overdue
+    Due,
+    /// Reviewed before and scheduled for later
+    NotDue,
+    /// Left out of study until unsuspended
+    Suspended,
+}
+
+/// Progress of a card's answer embedding, which is generated in the
+/// background after imports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingStatus {
+    /// No embedding and none requested
+    #[default]
+    None,
+    /// Queued for the embedding worker
+    Pending,
+    /// Embedding stored; the card is searchable
+    Ready,
+    /// The last attempt to generate or store the embedding failed
+    Failed,
+}
+
+impl EmbeddingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Pending => "pending",
+            Self::Ready => "ready",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl std::str::FromStr for EmbeddingStatus {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "none" => Ok(Self::None),
+            "pending" => Ok(Self::Pending),
+            "ready" => Ok(Self::Ready),
+            "failed" => Ok(Self::Failed),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown embedding status: {} (expected none, pending, ready or failed)",
+                other
+            ))),
+        }
+    }
+}
+
+/// How a typed answer is compared with the card's answer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerType {
+    /// Graded as a whole by the answer validator
+    #[default]
+    Text,
+    /// The answer is a delimited set of items, matched in any order
+    List,
+}
+
+impl AnswerType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::List => "list",
+        }
+    }
+}
+
+impl std::str::FromStr for AnswerType {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "text" => Ok(Self::Text),
+            "list" => Ok(Self::List),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown answer type: {} (expected text or list)",
+                other
+            ))),
+        }
+    }
+}
+
 /// Card entity - represents a flashcard for learning
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Card {
@@ -58,7 +197,28 @@ pub struct Card {
     pub question: String,
     pub answer: String,
     pub answer_embedding: Option<Vec<f32>>,
+    #[serde(default)]
+    pub embedding_status: EmbeddingStatus,
     pub fsrs_state: FsrsState,
+    /// Free-form labels, trimmed and unique
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Terms the user's answer must mention; missing any of them caps the
+    /// review score
+    #[serde(default)]
+    pub required_keywords: Vec<String>,
+    #[serde(default)]
+    pub answer_type: AnswerType,
+    /// Suspended cards are kept but not scheduled for study
+    #[serde(default)]
+    pub suspended: bool,
+    /// The card is not due before this time, whatever its schedule says
+    #[serde(default)]
+    pub not_before: Option<DateTime<Utc>>,
+    /// Place of the card in the file it was imported from (0 for cards
+    /// created one by one); orders cards created in the same instant
+    #[serde(default)]
+    pub position: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -73,7 +233,14 @@ impl Card {
             question,
             answer,
             answer_embedding: None,
+            embedding_status: EmbeddingStatus::None,
             fsrs_state: FsrsState::default(),
+            tags: Vec::new(),
+            required_keywords: Vec::new(),
+            answer_type: AnswerType::Text,
+            suspended: false,
+            not_before: None,
+            position: 0,
             created_at: now,
             updated_at: now,
         }
@@ -84,8 +251,167 @@ impl Card {
         self
     }
 
-    pub fn with_embedding(mut self, embedding: Vec<f32>) -> Self {
+    /// Attach an answer embedding, rejecting one that can't be stored
+    pub fn with_embedding(mut self, embedding: Vec<f32>) -> AppResult<Self> {
+        validate_embedding(&embedding)?;
         self.answer_embedding = Some(embedding);
+        self.embedding_status = EmbeddingStatus::Ready;
+        Ok(self)
+    }
+
+    /// Mark the card as waiting for the embedding worker
+    pub fn with_embedding_pending(mut self) -> Self {
+        self.embedding_status = EmbeddingStatus::Pending;
+        self
+    }
+
+    /// Add `tags` to the card, skipping blank values and tags already present
+    pub fn with_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for tag in tags {
+            let tag = tag.as_ref().trim();
+            if !tag.is_empty() && !self.tags.iter().any(|t| t == tag) {
+                self.tags.push(tag.to_string());
+            }
+        }
         self
     }
+
+    /// Require `keywords` in answers to this card, skipping blank values and
+    /// case-insensitive repeats
+    pub fn with_required_keywords<I, S>(mut self, keywords: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for keyword in keywords {
+            let keyword = keyword.as_ref().trim();
+            if !keyword.is_empty()
+                && !self
+                    .required_keywords
+                    .iter()
+                    .any(|k| k.eq_ignore_ascii_case(keyword))
+            {
+                self.required_keywords.push(keyword.to_string());
+            }
+        }
+        self
+    }
+
+    pub fn with_answer_type(mut self, answer_type: AnswerType) -> Self {
+        self.answer_type = answer_type;
+        self
+    }
+
+    pub fn with_position(mut self, position: i32) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Required keywords that `answer` does not contain, compared
+    /// case-insensitively
+    pub fn missing_keywords(&self, answer: &str) -> Vec<&str> {
+        let answer = answer.to_lowercase();
+        self.required_keywords
+            .iter()
+            .filter(|k| !answer.contains(&k.to_lowercase()))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Forget all review progress, returning the card to the `New` state
+    pub fn reset(&mut self) {
+        self.fsrs_state = FsrsState::default();
+        self.updated_at = Utc::now();
+    }
+
+    /// Make the card due `days` days from now without otherwise changing its
+    /// memory state
+    pub fn reschedule(&mut self, days: i32) {
+        let now = Utc::now();
+        self.fsrs_state.last_review = Some(now);
+        self.fsrs_state
+            .set_scheduled_interval(Interval::from_days(days.into()).unwrap_or(Interval::ZERO));
+        self.updated_at = now;
+    }
+
+    /// When the card is next due: last review plus the scheduled interval,
+    /// or creation time for cards that were never reviewed, but never before
+    /// `not_before`
+    pub fn due_at(&self) -> DateTime<Utc> {
+        let scheduled = match self.fsrs_state.last_review {
+            Some(last) => last + self.fsrs_state.scheduled_interval().as_duration(),
+            None => self.created_at,
+        };
+        self.not_before
+            .map_or(scheduled, |not_before| scheduled.max(not_before))
+    }
+
+    /// Due status at `now`. Suspension wins over everything else; a card
+    /// that was never reviewed is `New` even when it is held back by
+    /// `not_before`.
+    pub fn due_status(&self, now: DateTime<Utc>) -> DueStatus {
+        if self.suspended {
+            DueStatus::Suspended
+        } else if self.fsrs_state.last_review.is_none() {
+            DueStatus::New
+        } else if self.due_at() <= now {
+            DueStatus::Due
+        } else {
+            DueStatus::NotDue
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, response::IntoResponse};
+
+    #[test]
+    fn test_card_state_parses_lowercase_names() {
+        for state in [
+            CardState::New,
+            CardState::Learning,
+            CardState::Review,
+            CardState::Relearning,
+        ] {
+            assert_eq!(state.as_str().parse::<CardState>().unwrap(), state);
+            let json = serde_json::to_string(&state).unwrap();
+            assert_eq!(json, format!("\"{}\"", state.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_unknown_card_state_is_bad_request() {
+        let err = "done".parse::<CardState>().unwrap_err();
+
+        assert!(matches!(err, AppError::ValidationError(_)));
+        assert_eq!(err.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_due_status() {
+        let now = Utc::now();
+        let mut card = Card::new(Uuid::new_v4(), "Q".to_string(), "A".to_string());
+        assert_eq!(card.due_status(now), DueStatus::New);
+
+        card.fsrs_state.last_review = Some(now - chrono::Duration::days(10));
+        card.fsrs_state
+            .set_scheduled_interval(Interval::from_days(3).unwrap());
+        assert_eq!(card.due_status(now), DueStatus::Due);
+
+        card.reschedule(4);
+        assert_eq!(card.due_status(now), DueStatus::NotDue);
+
+        card.suspended = true;
+        assert_eq!(card.due_status(now), DueStatus::Suspended);
+        assert_eq!(
+            serde_json::to_string(&DueStatus::NotDue).unwrap(),
+            "\"not_due\""
+        );
+    }
 }
@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::shared::event_bus::DomainEvent;
+
+/// Event whose handler still failed after every retry, kept for replay
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DeadLetterEvent {
+    pub id: Uuid,
+    /// Name of the handler that failed (`EventHandler::name`)
+    pub handler: String,
+    #[sqlx(json)]
+    pub event: DomainEvent,
+    /// Error of the last attempt
+    pub error: String,
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+    /// Set once a replay succeeded
+    pub replayed_at: Option<DateTime<Utc>>,
+}
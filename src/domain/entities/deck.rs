@@ -2,6 +2,53 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::domain::answer_normalization::{AnswerNormalization, NormalizationStep};
+use crate::domain::scheduler_config::{SchedulerConfig, SchedulerOverrides};
+use crate::shared::error::AppError;
+
+/// How the learner answers cards of a deck
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewMode {
+    /// The learner types an answer, which the AI validator grades
+    #[default]
+    Typed,
+    /// The learner grades themselves with an again/hard/good/easy rating
+    SelfGrade,
+}
+
+impl ReviewMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Typed => "typed",
+            Self::SelfGrade => "self_grade",
+        }
+    }
+}
+
+impl std::str::FromStr for ReviewMode {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "typed" => Ok(Self::Typed),
+            "self_grade" => Ok(Self::SelfGrade),
+            other => Err(AppError::ValidationError(format!(
+                "Unknown review mode: {} (expected typed or self_grade)",
+                other
+            ))),
+        }
+    }
+}
+
+impl TryFrom<String> for ReviewMode {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
 /// Deck entity - represents a collection of cards
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Deck {
@@ -9,6 +56,43 @@ pub struct Deck {
     pub user_id: Uuid,
     pub name: String,
     pub description: Option<String>,
+    #[sqlx(json)]
+    pub scheduler_overrides: SchedulerOverrides,
+    /// Optimized FSRS weights (17 or 19 values) used instead of the defaults
+    pub fsrs_weights: Option<Vec<f32>>,
+    /// Tags applied to every card created in or imported into this deck
+    #[serde(default)]
+    pub default_tags: Vec<String>,
+    /// Maximum number of cards ever introduced from this deck; once this many
+    /// have left the `New` state no further new cards are served
+    #[serde(default)]
+    pub total_new_limit: Option<i32>,
+    /// Maximum number of cards introduced from this deck per study day of its
+    /// owner (see `UserPreferences::review_day_start`)
+    #[serde(default)]
+    pub new_cards_per_day: Option<i32>,
+    /// Filler phrases stripped from the start of answers before validation
+    #[serde(default)]
+    pub ignored_answer_prefixes: Vec<String>,
+    /// Normalization steps applied to answers, in order; `None` uses the
+    /// default pipeline
+    #[sqlx(json(nullable))]
+    #[serde(default)]
+    pub answer_normalization: Option<Vec<NormalizationStep>>,
+    /// Language tag of the deck's content, e.g. `ar` or `pt-BR`
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Whether the deck's text is written right to left
+    #[serde(default)]
+    pub rtl: bool,
+    /// Typed answers graded by AI, or self-graded ratings
+    #[sqlx(try_from = "String")]
+    #[serde(default)]
+    pub review_mode: ReviewMode,
+    /// When the deck was moved to the trash; trashed decks and their cards are
+    /// hidden until restored
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -21,8 +105,81 @@ impl Deck {
             user_id,
             name,
             description,
+            scheduler_overrides: SchedulerOverrides::default(),
+            fsrs_weights: None,
+            default_tags: Vec::new(),
+            total_new_limit: None,
+            new_cards_per_day: None,
+            ignored_answer_prefixes: Vec::new(),
+            answer_normalization: None,
+            language: None,
+            rtl: false,
+            review_mode: ReviewMode::Typed,
+            deleted_at: None,
             created_at: now,
             updated_at: now,
         }
     }
+
+    pub fn with_scheduler_overrides(mut self, overrides: SchedulerOverrides) -> Self {
+        self.scheduler_overrides = overrides;
+        self
+    }
+
+    pub fn with_fsrs_weights(mut self, weights: Vec<f32>) -> Self {
+        self.fsrs_weights = Some(weights);
+        self
+    }
+
+    pub fn with_default_tags(mut self, tags: Vec<String>) -> Self {
+        self.default_tags = tags;
+        self
+    }
+
+    pub fn with_total_new_limit(mut self, limit: Option<i32>) -> Self {
+        self.total_new_limit = limit;
+        self
+    }
+
+    pub fn with_ignored_answer_prefixes(mut self, prefixes: Vec<String>) -> Self {
+        self.ignored_answer_prefixes = prefixes;
+        self
+    }
+
+    pub fn with_answer_normalization(mut self, steps: Option<Vec<NormalizationStep>>) -> Self {
+        self.answer_normalization = steps;
+        self
+    }
+
+    /// Set the deck language and text direction; see
+    /// [`language::resolve_rtl`](crate::domain::language::resolve_rtl)
+    pub fn with_language(mut self, language: Option<String>, rtl: bool) -> Self {
+        self.language = language;
+        self.rtl = rtl;
+        self
+    }
+
+    pub fn with_review_mode(mut self, review_mode: ReviewMode) -> Self {
+        self.review_mode = review_mode;
+        self
+    }
+
+    /// How answers to this deck's cards are normalized before validation
+    pub fn answer_normalization(&self) -> AnswerNormalization {
+        let normalization = AnswerNormalization::new(self.ignored_answer_prefixes.clone());
+        match &self.answer_normalization {
+            Some(steps) => normalization.with_steps(steps.clone()),
+            None => normalization,
+        }
+    }
+
+    /// Scheduler configuration for this deck: `defaults` with the deck's
+    /// overrides and custom FSRS weights applied
+    pub fn scheduler_config(&self, defaults: &SchedulerConfig) -> SchedulerConfig {
+        let mut config = defaults.resolve(&self.scheduler_overrides);
+        if let Some(weights) = &self.fsrs_weights {
+            config.fsrs_weights = Some(weights.clone());
+        }
+        config
+    }
 }
@@ -1,4 +1,5 @@
 pub mod card;
+pub mod dead_letter;
 pub mod deck;
 pub mod review;
 pub mod review_log;
@@ -6,6 +7,7 @@ pub mod stats;
 pub mod user;
 
 pub use card::*;
+pub use dead_letter::*;
 pub use deck::*;
 pub use review::*;
 pub use review_log::*;
@@ -9,10 +9,15 @@ pub struct ReviewLog {
     pub card_id: Uuid,
     pub user_id: Uuid,
     pub user_answer: String,
+    /// Not persisted; empty on logs loaded from storage
+    #[sqlx(default)]
     pub expected_answer: String,
     pub ai_score: f32,
     pub validation_method: String,
     pub fsrs_rating: i32,
+    /// Feedback from the validator on this answer
+    #[serde(default)]
+    pub explanation: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -35,7 +40,13 @@ impl ReviewLog {
             ai_score,
             validation_method,
             fsrs_rating,
+            explanation: None,
             created_at: Utc::now(),
         }
     }
+
+    pub fn with_explanation(mut self, explanation: Option<String>) -> Self {
+        self.explanation = explanation;
+        self
+    }
 }
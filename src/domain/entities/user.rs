@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -9,6 +9,9 @@ pub struct User {
     pub email: String,
     pub name: String,
     pub password_hash: Option<String>,
+    #[sqlx(flatten)]
+    #[serde(default)]
+    pub preferences: UserPreferences,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -21,6 +24,7 @@ impl User {
             email,
             name,
             password_hash: None,
+            preferences: UserPreferences::default(),
             created_at: now,
             updated_at: now,
         }
@@ -33,8 +37,135 @@ impl User {
             email,
             name,
             password_hash: Some(password_hash),
+            preferences: UserPreferences::default(),
             created_at: now,
             updated_at: now,
         }
     }
 }
+
+/// When a user's study day starts, for "days studied" and other per-day stats,
+/// how many reviews they aim for each day, and whether answers are AI-graded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(default)]
+pub struct UserPreferences {
+    /// Offset of the user's local time from UTC, in minutes
+    pub utc_offset_minutes: i32,
+    /// Local hour (0-23) at which the study day rolls over, so reviews done
+    /// after midnight but before it count toward the previous day
+    pub day_cutoff_hour: i32,
+    /// Reviews per study day the user aims for; `None` means no goal
+    pub daily_review_goal: Option<i32>,
+    /// When false every review must be self-graded and the user's answers
+    /// are never sent to the AI validator or embedding service
+    pub ai_validation_enabled: bool,
+}
+
+impl Default for UserPreferences {
+    fn default() -> Self {
+        Self {
+            utc_offset_minutes: 0,
+            day_cutoff_hour: 4,
+            daily_review_goal: None,
+            ai_validation_enabled: true,
+        }
+    }
+}
+
+impl UserPreferences {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if !(-14 * 60..=14 * 60).contains(&self.utc_offset_minutes) {
+            return Err("utc_offset_minutes must be between -840 and 840");
+        }
+        if !(0..=23).contains(&self.day_cutoff_hour) {
+            return Err("day_cutoff_hour must be between 0 and 23");
+        }
+        if self.daily_review_goal.is_some_and(|goal| goal < 1) {
+            return Err("daily_review_goal must be at least 1");
+        }
+        Ok(())
+    }
+
+    /// Wall-clock time in the user's timezone at `at`
+    pub fn local_time(&self, at: DateTime<Utc>) -> NaiveDateTime {
+        (at + Duration::minutes(self.utc_offset_minutes as i64)).naive_utc()
+    }
+
+    /// Study day that a review at `at` counts toward
+    pub fn review_date(&self, at: DateTime<Utc>) -> NaiveDate {
+        (self.local_time(at) - Duration::hours(self.day_cutoff_hour as i64)).date()
+    }
+
+    /// Moment the study day containing `at` started
+    pub fn review_day_start(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let local_start = self.review_date(at).and_time(NaiveTime::MIN)
+            + Duration::hours(self.day_cutoff_hour as i64);
+        (local_start - Duration::minutes(self.utc_offset_minutes as i64)).and_utc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_review_before_cutoff_counts_toward_previous_day() {
+        // UTC+2, day starts at 4am local
+        let preferences = UserPreferences {
+            utc_offset_minutes: 120,
+            day_cutoff_hour: 4,
+            ..Default::default()
+        };
+        // 02:00 local on March 10th
+        let early = Utc.with_ymd_and_hms(2026, 3, 10, 0, 0, 0).unwrap();
+        // 05:00 local on March 10th
+        let after = Utc.with_ymd_and_hms(2026, 3, 10, 3, 0, 0).unwrap();
+
+        assert_eq!(
+            preferences.review_date(early),
+            NaiveDate::from_ymd_opt(2026, 3, 9).unwrap()
+        );
+        assert_eq!(
+            preferences.review_date(after),
+            NaiveDate::from_ymd_opt(2026, 3, 10).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_review_day_start_is_the_local_cutoff() {
+        // UTC+2, day starts at 4am local
+        let preferences = UserPreferences {
+            utc_offset_minutes: 120,
+            day_cutoff_hour: 4,
+            ..Default::default()
+        };
+        // 02:00 local on March 10th, still March 9th's study day
+        let early = Utc.with_ymd_and_hms(2026, 3, 10, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            preferences.review_day_start(early),
+            Utc.with_ymd_and_hms(2026, 3, 9, 2, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_preferences_validation() {
+        assert!(UserPreferences::default().validate().is_ok());
+        let bad_hour = UserPreferences {
+            day_cutoff_hour: 24,
+            ..Default::default()
+        };
+        assert!(bad_hour.validate().is_err());
+        let bad_offset = UserPreferences {
+            utc_offset_minutes: 15 * 60,
+            ..Default::default()
+        };
+        assert!(bad_offset.validate().is_err());
+        let bad_goal = UserPreferences {
+            daily_review_goal: Some(0),
+            ..Default::default()
+        };
+        assert!(bad_goal.validate().is_err());
+    }
+}
@@ -0,0 +1,286 @@
+//! FSRS-4.5 memory model driven by an explicit weight vector.
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    entities::{CardState, FsrsState},
+    value_objects::Interval,
+};
+
+/// Default FSRS-4.5 weights (w0..w16)
+pub const DEFAULT_WEIGHTS: [f32; 17] = [
+    0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367, 1.0461, 2.1072,
+    0.0793, 0.3246, 1.587, 0.2272, 2.8755,
+];
+
+const DECAY: f32 = -0.5;
+/// Chosen so that retrievability is 90% when elapsed time equals stability
+const FACTOR: f32 = 19.0 / 81.0;
+
+/// Check a user-supplied weight vector. FSRS-4.5 uses 17 weights; FSRS-5
+/// exports 19, of which the two short-term weights are ignored here.
+pub fn validate_weights(weights: &[f32]) -> Result<(), &'static str> {
+    if weights.len() != 17 && weights.len() != 19 {
+        return Err("FSRS weights must contain exactly 17 or 19 values");
+    }
+    if weights.iter().any(|w| !w.is_finite()) {
+        return Err("FSRS weights must be finite numbers");
+    }
+    if weights[..4].iter().any(|w| *w <= 0.0) {
+        return Err("FSRS initial stability weights (w0-w3) must be positive");
+    }
+    Ok(())
+}
+
+/// FSRS scheduler parameterised by a weight vector and target retention
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    weights: [f32; 17],
+    desired_retention: f32,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self {
+            weights: DEFAULT_WEIGHTS,
+            desired_retention: 0.9,
+        }
+    }
+}
+
+impl Scheduler {
+    /// Build a scheduler from validated weights (see [`validate_weights`])
+    pub fn new(weights: &[f32]) -> Result<Self, &'static str> {
+        validate_weights(weights)?;
+        let mut w = [0.0; 17];
+        w.copy_from_slice(&weights[..17]);
+        Ok(Self {
+            weights: w,
+            ..Default::default()
+        })
+    }
+
+    /// Aim for `desired_retention` (between 0 and 1) when scheduling intervals
+    pub fn with_desired_retention(mut self, desired_retention: f32) -> Self {
+        self.desired_retention = desired_retention;
+        self
+    }
+
+    /// Probability of recall after `elapsed_days` for a memory of the given stability
+    pub fn retrievability(&self, elapsed_days: f32, stability: f32) -> f32 {
+        (1.0 + FACTOR * elapsed_days / stability).powf(DECAY)
+    }
+
+    /// Interval in days at which retrievability drops to the desired retention
+    pub fn interval(&self, stability: f32) -> f32 {
+        stability / FACTOR * (self.desired_retention.powf(1.0 / DECAY) - 1.0)
+    }
+
+    fn init_stability(&self, rating: i32) -> f32 {
+        self.weights[(rating.clamp(1, 4) - 1) as usize].max(0.1)
+    }
+
+    fn init_difficulty(&self, rating: i32) -> f32 {
+        let w = &self.weights;
+        (w[4] - (rating as f32 - 3.0) * w[5]).clamp(1.0, 10.0)
+    }
+
+    fn next_difficulty(&self, difficulty: f32, rating: i32) -> f32 {
+        let w = &self.weights;
+        let next = difficulty - w[6] * (rating as f32 - 3.0);
+        // Mean reversion towards the initial difficulty of a Good rating
+        (w[7] * self.init_difficulty(3) + (1.0 - w[7]) * next).clamp(1.0, 10.0)
+    }
+
+    fn recall_stability(&self, d: f32, s: f32, r: f32, rating: i32) -> f32 {
+        let w = &self.weights;
+        let hard_penalty = if rating == 2 { w[15] } else { 1.0 };
+        let easy_bonus = if rating == 4 { w[16] } else { 1.0 };
+        s * (1.0
+            + w[8].exp()
+                * (11.0 - d)
+                * s.powf(-w[9])
+                * ((w[10] * (1.0 - r)).exp() - 1.0)
+                * hard_penalty
+                * easy_bonus)
+    }
+
+    fn forget_stability(&self, d: f32, s: f32, r: f32) -> f32 {
+        let w = &self.weights;
+        (w[11] * d.powf(-w[12]) * ((s + 1.0).powf(w[13]) - 1.0) * (w[14] * (1.0 - r)).exp()).min(s)
+    }
+
+    /// Compute the memory state after reviewing with `rating` (1-4) at `now`
+    pub fn next_state(&self, current: &FsrsState, rating: i32, now: DateTime<Utc>) -> FsrsState {
+        let elapsed = current
+            .last_review
+            .map(|last| Interval::between(last, now))
+            .unwrap_or(Interval::ZERO);
+        let elapsed_days = elapsed.as_days() as f32;
+
+        let mut next = FsrsState {
+            stability: current.stability,
+            difficulty: current.difficulty,
+            elapsed_days: elapsed.as_days().try_into().unwrap_or(i32::MAX),
+            scheduled_days: current.scheduled_days,
+            reps: current.reps + 1,
+            lapses: current.lapses,
+            state: current.state.clone(),
+            last_review: Some(now),
+        };
+
+        if current.reps == 0 || current.stability <= 0.0 {
+            next.stability = self.init_stability(rating);
+            next.difficulty = self.init_difficulty(rating);
+        } else {
+            let r = self.retrievability(elapsed_days, current.stability);
+            next.difficulty = self.next_difficulty(current.difficulty, rating);
+            next.stability = if rating == 1 {
+                self.forget_stability(current.difficulty, current.stability, r)
+            } else {
+                self.recall_stability(current.difficulty, current.stability, r, rating)
+            }
+            .max(0.1);
+        }
+
+        next.state = match rating {
+            1 => {
+                next.lapses += 1;
+                CardState::Relearning
+            }
+            4 => CardState::Review,
+            _ if next.reps <= 1 => CardState::Learning,
+            _ => CardState::Review,
+        };
+        let interval_days = if rating == 1 {
+            1
+        } else {
+            (self.interval(next.stability).round() as i64).clamp(1, i32::MAX.into())
+        };
+        next.set_scheduled_interval(Interval::from_days(interval_days).unwrap_or(Interval::ZERO));
+
+        next
+    }
+
+    /// Replay a review history, oldest first, starting from a new card
+    pub fn replay<I>(&self, reviews: I) -> FsrsState
+    where
+        I: IntoIterator<Item = (i32, DateTime<Utc>)>,
+    {
+        reviews
+            .into_iter()
+            .fold(FsrsState::default(), |state, (rating, reviewed_at)| {
+                self.next_state(&state, rating, reviewed_at)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_weights_length() {
+        assert!(validate_weights(&DEFAULT_WEIGHTS).is_ok());
+        assert!(validate_weights(&[1.0; 19]).is_ok());
+        assert!(validate_weights(&[1.0; 5]).is_err());
+        assert!(validate_weights(&[]).is_err());
+    }
+
+    #[test]
+    fn test_interval_equals_stability_at_ninety_percent() {
+        let scheduler = Scheduler::default();
+        assert!((scheduler.interval(10.0) - 10.0).abs() < 0.01);
+        assert!((scheduler.retrievability(10.0, 10.0) - 0.9).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_higher_retention_shortens_intervals() {
+        let strict = Scheduler::default().with_desired_retention(0.95);
+        let relaxed = Scheduler::default().with_desired_retention(0.8);
+        assert!(strict.interval(10.0) < 10.0);
+        assert!(relaxed.interval(10.0) > 10.0);
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    // Expected values below come from the FSRS-4.5 reference equations with
+    // the default weights and 90% desired retention.
+
+    #[test]
+    fn test_first_review_matches_reference() {
+        let scheduler = Scheduler::default();
+        let now = Utc::now();
+        let cases = [
+            (1, 0.4872, 7.6214, 1),
+            (2, 1.4003, 6.3916, 1),
+            (3, 3.7145, 5.1618, 4),
+            (4, 13.8206, 3.932, 14),
+        ];
+        for (rating, stability, difficulty, days) in cases {
+            let next = scheduler.next_state(&FsrsState::default(), rating, now);
+            assert_close(next.stability, stability);
+            assert_close(next.difficulty, difficulty);
+            assert_eq!(next.scheduled_days, days, "rating {}", rating);
+        }
+    }
+
+    #[test]
+    fn test_review_after_four_days_matches_reference() {
+        let scheduler = Scheduler::default();
+        let start = Utc::now();
+        let first = scheduler.next_state(&FsrsState::default(), 3, start);
+        let later = start + chrono::Duration::days(4);
+        assert_close(scheduler.retrievability(4.0, first.stability), 0.8935);
+
+        let cases = [
+            (1, 1.4332, 6.9012, 1),
+            (2, 6.2350, 6.0315, 6),
+            (3, 14.8081, 5.1618, 15),
+            (4, 35.6141, 4.2921, 36),
+        ];
+        for (rating, stability, difficulty, days) in cases {
+            let next = scheduler.next_state(&first, rating, later);
+            assert_close(next.stability, stability);
+            assert_close(next.difficulty, difficulty);
+            assert_eq!(next.scheduled_days, days, "rating {}", rating);
+            assert_eq!(next.elapsed_days, 4);
+        }
+    }
+
+    #[test]
+    fn test_good_reviews_on_due_date_match_reference_intervals() {
+        let scheduler = Scheduler::default();
+        let mut at = Utc::now();
+        let mut state = FsrsState::default();
+        let mut intervals = Vec::new();
+        for _ in 0..4 {
+            state = scheduler.next_state(&state, 3, at);
+            intervals.push(state.scheduled_days);
+            at += chrono::Duration::days(state.scheduled_days.into());
+        }
+        assert_eq!(intervals, vec![4, 15, 49, 146]);
+    }
+
+    #[test]
+    fn test_custom_weights_change_intervals() {
+        let now = Utc::now();
+        let state = FsrsState::default();
+
+        let default_next = Scheduler::default().next_state(&state, 3, now);
+
+        let mut custom = DEFAULT_WEIGHTS;
+        custom[2] = 10.0; // initial stability for Good
+        let custom_next = Scheduler::new(&custom).unwrap().next_state(&state, 3, now);
+
+        assert_eq!(default_next.scheduled_days, 4);
+        assert_eq!(custom_next.scheduled_days, 10);
+    }
+}
@@ -0,0 +1,185 @@
+//! Fit FSRS weights to a user's review history.
+//!
+//! Each card's reviews are replayed through the [`Scheduler`]; before every
+//! review after the first, the predicted retrievability is scored against
+//! whether the card was recalled (any rating but Again). The weights are
+//! tuned by a bounded coordinate search that minimises the mean log-loss.
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::{entities::FsrsState, fsrs::Scheduler};
+
+/// Reviews of one card as `(rating, reviewed_at)`, oldest first
+pub type ReviewHistory = Vec<(i32, DateTime<Utc>)>;
+
+/// Range each weight is kept in while searching
+const BOUNDS: [(f32, f32); 17] = [
+    (0.01, 100.0),
+    (0.01, 100.0),
+    (0.01, 100.0),
+    (0.01, 100.0),
+    (1.0, 10.0),
+    (0.001, 5.0),
+    (0.001, 5.0),
+    (0.001, 0.75),
+    (0.001, 4.5),
+    (0.001, 0.8),
+    (0.001, 3.5),
+    (0.001, 5.0),
+    (0.001, 0.25),
+    (0.001, 0.9),
+    (0.001, 4.0),
+    (0.001, 1.0),
+    (1.0, 6.0),
+];
+
+const MAX_PASSES: usize = 40;
+const INITIAL_STEP: f32 = 0.5;
+const MIN_STEP: f32 = 0.01;
+
+/// Mean log-loss of the recall predictions `weights` make over `histories`.
+/// Zero when no history has a second review.
+pub fn log_loss(weights: &[f32; 17], histories: &[ReviewHistory]) -> f32 {
+    let Ok(scheduler) = Scheduler::new(weights) else {
+        return f32::INFINITY;
+    };
+
+    let mut total = 0.0f64;
+    let mut count = 0usize;
+    for history in histories {
+        let mut state = FsrsState::default();
+        for &(rating, reviewed_at) in history {
+            if let Some(last) = state.last_review.filter(|_| state.reps > 0) {
+                let elapsed_days = (reviewed_at - last).num_seconds().max(0) as f32 / 86_400.0;
+                let p = scheduler
+                    .retrievability(elapsed_days, state.stability)
+                    .clamp(1e-4, 1.0 - 1e-4) as f64;
+                total -= if rating > 1 { p.ln() } else { (1.0 - p).ln() };
+                count += 1;
+            }
+            state = scheduler.next_state(&state, rating, reviewed_at);
+        }
+    }
+
+    if count == 0 {
+        0.0
+    } else {
+        (total / count as f64) as f32
+    }
+}
+
+/// Weights that fit `histories` better than `start`, or `start` itself when
+/// no change lowers the loss
+pub fn optimize(start: &[f32; 17], histories: &[ReviewHistory]) -> [f32; 17] {
+    let mut weights = *start;
+    for (w, (low, high)) in weights.iter_mut().zip(BOUNDS) {
+        *w = w.clamp(low, high);
+    }
+    let mut best = log_loss(&weights, histories);
+
+    let mut step = INITIAL_STEP;
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+        for (i, (low, high)) in BOUNDS.iter().enumerate() {
+            for factor in [1.0 + step, 1.0 / (1.0 + step)] {
+                let mut candidate = weights;
+                candidate[i] = (weights[i] * factor).clamp(*low, *high);
+                if candidate[i] == weights[i] {
+                    continue;
+                }
+                let loss = log_loss(&candidate, histories);
+                if loss < best {
+                    weights = candidate;
+                    best = loss;
+                    improved = true;
+                    break;
+                }
+            }
+        }
+        if !improved {
+            step /= 2.0;
+            if step < MIN_STEP {
+                break;
+            }
+        }
+    }
+    weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::fsrs::DEFAULT_WEIGHTS;
+    use chrono::Duration;
+
+    /// Deterministic uniform numbers in 0..1
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> f32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32
+        }
+    }
+
+    /// Histories of a learner whose memory follows `weights`
+    fn simulate(weights: &[f32; 17], cards: usize, reviews: usize) -> Vec<ReviewHistory> {
+        let scheduler = Scheduler::new(weights).unwrap();
+        let mut rng = Lcg(7);
+        let start = Utc::now() - Duration::days(365);
+        (0..cards)
+            .map(|_| {
+                let mut state = FsrsState::default();
+                let mut at = start;
+                let mut history = Vec::new();
+                for n in 0..reviews {
+                    let rating = if n == 0 {
+                        3
+                    } else {
+                        at += Duration::days(1 + (rng.next() * 20.0) as i64);
+                        let elapsed = (at - state.last_review.unwrap()).num_days() as f32;
+                        if rng.next() < scheduler.retrievability(elapsed, state.stability) {
+                            3
+                        } else {
+                            1
+                        }
+                    };
+                    state = scheduler.next_state(&state, rating, at);
+                    history.push((rating, at));
+                }
+                history
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_no_repeat_reviews_has_zero_loss() {
+        let histories = vec![vec![(3, Utc::now())]];
+        assert_eq!(log_loss(&DEFAULT_WEIGHTS, &histories), 0.0);
+    }
+
+    #[test]
+    fn test_optimization_reduces_loss_versus_default_weights() {
+        // A learner who remembers far longer than the defaults assume
+        let mut learner = DEFAULT_WEIGHTS;
+        for w in &mut learner[..4] {
+            *w *= 8.0;
+        }
+        let histories = simulate(&learner, 60, 8);
+
+        let default_loss = log_loss(&DEFAULT_WEIGHTS, &histories);
+        let fitted = optimize(&DEFAULT_WEIGHTS, &histories);
+        let fitted_loss = log_loss(&fitted, &histories);
+
+        assert!(
+            fitted_loss < default_loss * 0.95,
+            "{} vs {}",
+            fitted_loss,
+            default_loss
+        );
+        assert!(fitted[2] > DEFAULT_WEIGHTS[2]);
+    }
+}
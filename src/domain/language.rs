@@ -0,0 +1,86 @@
+use thiserror::Error;
+
+/// Primary language subtags of scripts written right to left
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ku", "ps", "sd", "syr", "ug", "ur", "yi",
+];
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LanguageError {
+    #[error("language must be a language tag such as \"ar\" or \"pt-BR\", got {0:?}")]
+    InvalidTag(String),
+    #[error("rtl={rtl} does not match the text direction of language {language:?}")]
+    DirectionMismatch { language: String, rtl: bool },
+}
+
+/// Trim a BCP 47 style language tag (`ar`, `pt-BR`, `zh-Hant`); blank means
+/// no language
+pub fn parse_language_tag(tag: &str) -> Result<Option<String>, LanguageError> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Ok(None);
+    }
+    let mut subtags = tag.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    let valid = (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags
+            .all(|s| (1..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()));
+    match valid {
+        true => Ok(Some(tag.to_string())),
+        false => Err(LanguageError::InvalidTag(tag.to_string())),
+    }
+}
+
+/// Whether `tag` names a language written right to left
+pub fn is_rtl_language(tag: &str) -> bool {
+    let primary = tag
+        .split('-')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    RTL_LANGUAGES.contains(&primary.as_str())
+}
+
+/// Text direction of a deck: inferred from `language` when `rtl` is not
+/// given, and checked against it when both are
+pub fn resolve_rtl(language: Option<&str>, rtl: Option<bool>) -> Result<bool, LanguageError> {
+    match (language, rtl) {
+        (None, rtl) => Ok(rtl.unwrap_or(false)),
+        (Some(language), None) => Ok(is_rtl_language(language)),
+        (Some(language), Some(rtl)) if rtl == is_rtl_language(language) => Ok(rtl),
+        (Some(language), Some(rtl)) => Err(LanguageError::DirectionMismatch {
+            language: language.to_string(),
+            rtl,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rtl_is_inferred_from_language() {
+        assert_eq!(resolve_rtl(Some("ar"), None), Ok(true));
+        assert_eq!(resolve_rtl(Some("he-IL"), None), Ok(true));
+        assert_eq!(resolve_rtl(Some("es"), None), Ok(false));
+        assert_eq!(resolve_rtl(None, None), Ok(false));
+        assert_eq!(resolve_rtl(None, Some(true)), Ok(true));
+    }
+
+    #[test]
+    fn test_rtl_must_match_language() {
+        assert_eq!(resolve_rtl(Some("fa"), Some(true)), Ok(true));
+        assert!(resolve_rtl(Some("ar"), Some(false)).is_err());
+        assert!(resolve_rtl(Some("fr"), Some(true)).is_err());
+    }
+
+    #[test]
+    fn test_parse_language_tag() {
+        assert_eq!(parse_language_tag(" pt-BR "), Ok(Some("pt-BR".to_string())));
+        assert_eq!(parse_language_tag(""), Ok(None));
+        assert!(parse_language_tag("arabic").is_err());
+        assert!(parse_language_tag("ar_EG").is_err());
+    }
+}
@@ -14,12 +14,24 @@
 //! - I: Interface Segregation via focused traits
 //! - D: Dependency Inversion via repository interfaces
 
+pub mod answer_diff;
+pub mod answer_list;
+pub mod answer_normalization;
+pub mod embedding;
 pub mod entities;
+pub mod fsrs;
+pub mod fsrs_optimizer;
+pub mod language;
 pub mod ports;
 pub mod repositories;
+pub mod scheduler_config;
 pub mod value_objects;
 
+pub use answer_diff::*;
+pub use answer_list::*;
+pub use answer_normalization::*;
 pub use entities::*;
 pub use ports::*;
 pub use repositories::*;
+pub use scheduler_config::*;
 pub use value_objects::*;
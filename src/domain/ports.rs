@@ -1,5 +1,6 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 
 /// AI Validator trait - defines the interface for AI-based answer validation
 #[async_trait]
@@ -27,6 +28,11 @@ pub trait EmbeddingService: Send + Sync {
 pub struct ValidationResult {
     pub score: f32,
     pub method: ValidationMethod,
+    /// Feedback on the answer, when the validator provides one
+    pub explanation: Option<String>,
+    /// Score `method` required to accept the answer (e.g. the embedding
+    /// similarity threshold), for methods that apply one
+    pub threshold: Option<f32>,
 }
 
 /// Method used for validation
@@ -35,6 +41,14 @@ pub enum ValidationMethod {
     Exact,
     Embedding,
     Llm,
+    /// Weighted mean of the embedding similarity and the LLM score
+    Blended,
+    /// Items of a list answer matched in any order; no validator is called
+    List,
+    /// Empty answer, graded as Again without calling a validator
+    Blank,
+    /// Rated by the learner on a self-graded deck; no validator is called
+    SelfGrade,
 }
 
 impl ValidationMethod {
@@ -43,6 +57,70 @@ impl ValidationMethod {
             ValidationMethod::Exact => "exact",
             ValidationMethod::Embedding => "embedding",
             ValidationMethod::Llm => "llm",
+            ValidationMethod::Blended => "blended",
+            ValidationMethod::List => "list",
+            ValidationMethod::Blank => "blank",
+            ValidationMethod::SelfGrade => "self_grade",
         }
     }
 }
+
+/// Status of one dependency in the detailed health report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentStatus {
+    Ok,
+    Degraded,
+    Down,
+}
+
+/// Result of a single health check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentHealth {
+    pub status: ComponentStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ComponentHealth {
+    pub fn ok() -> Self {
+        Self {
+            status: ComponentStatus::Ok,
+            detail: None,
+        }
+    }
+
+    pub fn degraded(detail: impl Into<String>) -> Self {
+        Self {
+            status: ComponentStatus::Degraded,
+            detail: Some(detail.into()),
+        }
+    }
+
+    pub fn down(detail: impl Into<String>) -> Self {
+        Self {
+            status: ComponentStatus::Down,
+            detail: Some(detail.into()),
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// Health Check trait - probes one dependency of the service
+#[async_trait]
+pub trait HealthCheck: Send + Sync {
+    /// Component name used as the key in the health report
+    fn name(&self) -> &str;
+
+    /// Whether the service cannot work without this component; a critical
+    /// component being down makes the whole service down
+    fn critical(&self) -> bool {
+        false
+    }
+
+    async fn check(&self) -> ComponentHealth;
+}
@@ -1,6 +1,76 @@
-use crate::{domain::entities::Card, AppResult};
+use crate::{
+    domain::{
+        embedding::cosine_similarity,
+        entities::{Card, CardState, EmbeddingStatus},
+    },
+    AppResult,
+};
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+/// Ordering applied to paginated card lists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CardOrder {
+    /// Overdue cards first, then by due date
+    #[default]
+    Due,
+    /// Due cards first, hardest (highest FSRS difficulty) first
+    Difficulty,
+}
+
+impl std::str::FromStr for CardOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "due" => Ok(Self::Due),
+            "difficulty" => Ok(Self::Difficulty),
+            other => Err(format!("Unknown card order: {}", other)),
+        }
+    }
+}
+
+/// Pagination, exclusion and ordering options for card list queries
+#[derive(Debug, Clone, Default)]
+pub struct CardListOptions {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub exclude_card_ids: Option<Vec<Uuid>>,
+    pub order: CardOrder,
+    /// Only cards currently in this learning state
+    pub state: Option<CardState>,
+    /// Serve at most this many `New` cards, oldest first
+    pub new_card_limit: Option<i64>,
+}
+
+/// Which of a user's cards a bulk change applies to; every field set must match
+#[derive(Debug, Clone, Default)]
+pub struct CardSelection {
+    /// Cards carrying this tag
+    pub tag: Option<String>,
+    pub deck_id: Option<Uuid>,
+    /// Cards currently in this learning state
+    pub state: Option<CardState>,
+}
+
+impl CardSelection {
+    /// True when no field is set, i.e. the selection would match every card
+    pub fn is_empty(&self) -> bool {
+        self.tag.is_none() && self.deck_id.is_none() && self.state.is_none()
+    }
+
+    pub fn matches(&self, card: &Card) -> bool {
+        self.tag
+            .as_deref()
+            .is_none_or(|tag| card.tags.iter().any(|t| t == tag))
+            && self.deck_id.is_none_or(|id| card.deck_id == Some(id))
+            && self
+                .state
+                .as_ref()
+                .is_none_or(|state| card.fsrs_state.state == *state)
+    }
+}
+
 /// Repository interface for Card domain
 #[async_trait::async_trait]
 pub trait CardRepository: Send + Sync {
@@ -9,55 +79,353 @@ pub trait CardRepository: Send + Sync {
     async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Card>>;
     async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Card>>;
     async fn find_by_deck(&self, deck_id: Uuid) -> AppResult<Vec<Card>>;
+    /// Number of the user's cards, leaving out those in trashed decks like
+    /// `DeckRepository::count_by_user` leaves out the decks
+    async fn count_by_user(&self, user_id: Uuid) -> AppResult<i64> {
+        Ok(self.find_by_user(user_id).await?.len() as i64)
+    }
+    /// Number of cards in the deck, counted even while it is in the trash
+    async fn count_by_deck(&self, deck_id: Uuid) -> AppResult<i64> {
+        Ok(self.find_by_deck(deck_id).await?.len() as i64)
+    }
     async fn find_by_user_paginated(
         &self,
         user_id: Uuid,
-        limit: Option<i64>,
-        offset: Option<i64>,
-        exclude_card_ids: Option<Vec<Uuid>>,
+        options: CardListOptions,
     ) -> AppResult<Vec<Card>> {
         let cards = self.find_by_user(user_id).await?;
-        Ok(paginate_cards(cards, limit, offset, exclude_card_ids))
+        Ok(paginate_cards(cards, options))
+    }
+    /// Number of the user's cards matching the filters of `options`; its
+    /// `limit` and `offset` are ignored
+    async fn count_by_user_matching(
+        &self,
+        user_id: Uuid,
+        options: CardListOptions,
+    ) -> AppResult<i64> {
+        let cards = self.find_by_user(user_id).await?;
+        let options = CardListOptions {
+            limit: None,
+            offset: None,
+            ..options
+        };
+        Ok(paginate_cards(cards, options).len() as i64)
     }
     async fn find_by_deck_paginated(
         &self,
         deck_id: Uuid,
-        limit: Option<i64>,
-        offset: Option<i64>,
-        exclude_card_ids: Option<Vec<Uuid>>,
+        options: CardListOptions,
     ) -> AppResult<Vec<Card>> {
         let cards = self.find_by_deck(deck_id).await?;
-        Ok(paginate_cards(cards, limit, offset, exclude_card_ids))
+        Ok(paginate_cards(cards, options))
+    }
+    /// Number of the deck's cards matching the filters of `options`; its
+    /// `limit` and `offset` are ignored
+    async fn count_by_deck_matching(
+        &self,
+        deck_id: Uuid,
+        options: CardListOptions,
+    ) -> AppResult<i64> {
+        let cards = self.find_by_deck(deck_id).await?;
+        let options = CardListOptions {
+            limit: None,
+            offset: None,
+            ..options
+        };
+        Ok(paginate_cards(cards, options).len() as i64)
+    }
+    /// Distinct tags on the user's cards with the number of cards carrying
+    /// each, most used first (ties ordered by tag)
+    async fn count_tags_by_user(&self, user_id: Uuid) -> AppResult<Vec<(String, i64)>> {
+        let cards = self.find_by_user(user_id).await?;
+        Ok(count_tags(&cards))
+    }
+    /// Number of cards in the deck that have left the `New` state
+    async fn count_introduced_by_deck(&self, deck_id: Uuid) -> AppResult<i64> {
+        let cards = self.find_by_deck(deck_id).await?;
+        Ok(cards
+            .iter()
+            .filter(|card| card.fsrs_state.state != CardState::New)
+            .count() as i64)
+    }
+    /// Number of cards in the deck first reviewed at or after `since`. The
+    /// default only sees cards reviewed exactly once since then; stores with
+    /// a review history count every first review.
+    async fn count_introduced_by_deck_since(
+        &self,
+        deck_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> AppResult<i64> {
+        let cards = self.find_by_deck(deck_id).await?;
+        Ok(cards
+            .iter()
+            .filter(|card| {
+                card.fsrs_state.reps == 1
+                    && card
+                        .fsrs_state
+                        .last_review
+                        .is_some_and(|reviewed| reviewed >= since)
+            })
+            .count() as i64)
+    }
+    /// Number of the user's cards that are due at `now`, excluding suspended
+    /// cards
+    async fn count_due_by_user(&self, user_id: Uuid, now: DateTime<Utc>) -> AppResult<i64> {
+        let cards = self.find_by_user(user_id).await?;
+        Ok(cards
+            .iter()
+            .filter(|card| !card.suspended && card.due_at() <= now)
+            .count() as i64)
+    }
+    /// One page of the user's unsuspended cards that are due at `now`, most
+    /// overdue first. Cards that were never reviewed are due from their
+    /// creation.
+    async fn find_due_by_user(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Vec<Card>> {
+        let cards = self.find_by_user(user_id).await?;
+        Ok(page(due_cards(cards, now), limit, offset))
+    }
+    /// One page of the deck's unsuspended cards that are due at `now`, most
+    /// overdue first
+    async fn find_due_by_deck(
+        &self,
+        deck_id: Uuid,
+        now: DateTime<Utc>,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Vec<Card>> {
+        let cards = self.find_by_deck(deck_id).await?;
+        Ok(page(due_cards(cards, now), limit, offset))
+    }
+    /// The user's `limit` cards whose answer embedding is closest to
+    /// `query_embedding`, nearest first, with their cosine distance (0 for
+    /// the same direction, up to 2). Cards without an embedding are left out.
+    async fn search_by_embedding(
+        &self,
+        user_id: Uuid,
+        query_embedding: &[f32],
+        limit: i64,
+    ) -> AppResult<Vec<(Card, f32)>> {
+        let mut hits: Vec<(Card, f32)> = self
+            .find_by_user(user_id)
+            .await?
+            .into_iter()
+            .filter_map(|card| {
+                let distance =
+                    1.0 - cosine_similarity(card.answer_embedding.as_deref()?, query_embedding);
+                Some((card, distance))
+            })
+            .collect();
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+        hits.truncate(limit.max(0) as usize);
+        Ok(hits)
+    }
+    /// A random unsuspended card of the user, optionally limited to one deck
+    async fn find_random(&self, user_id: Uuid, deck_id: Option<Uuid>) -> AppResult<Option<Card>> {
+        let mut cards: Vec<Card> = self
+            .find_by_user(user_id)
+            .await?
+            .into_iter()
+            .filter(|card| !card.suspended && deck_id.is_none_or(|id| card.deck_id == Some(id)))
+            .collect();
+        if cards.is_empty() {
+            return Ok(None);
+        }
+        let index = (Uuid::new_v4().as_u128() % cards.len() as u128) as usize;
+        Ok(Some(cards.swap_remove(index)))
     }
     async fn update(&self, card: &Card) -> AppResult<()>;
+    /// Update `card` unless it was saved again after it was loaded, i.e. its
+    /// stored `updated_at` is no longer `loaded_at`; `Conflict` then
+    async fn update_if_unchanged(&self, card: &Card, _loaded_at: DateTime<Utc>) -> AppResult<()> {
+        self.update(card).await
+    }
+    /// The user's cards matching `selection`
+    async fn find_matching(
+        &self,
+        user_id: Uuid,
+        selection: &CardSelection,
+    ) -> AppResult<Vec<Card>> {
+        Ok(self
+            .find_by_user(user_id)
+            .await?
+            .into_iter()
+            .filter(|card| selection.matches(card))
+            .collect())
+    }
+    /// Update several cards at once, each paired with the `updated_at` it was
+    /// loaded with, like `update_if_unchanged`; implementations should apply
+    /// all or none, so one conflict leaves every card as it was
+    async fn update_many_if_unchanged(&self, updates: &[(Card, DateTime<Utc>)]) -> AppResult<()> {
+        for (card, loaded_at) in updates {
+            self.update_if_unchanged(card, *loaded_at).await?;
+        }
+        Ok(())
+    }
+    /// Store the card's answer embedding and mark it `Ready`
     async fn update_embedding(&self, id: Uuid, embedding: Vec<f32>) -> AppResult<()>;
+    /// Record embedding progress without touching the embedding itself.
+    /// Repositories that don't track it ignore the call.
+    async fn set_embedding_status(&self, _id: Uuid, _status: EmbeddingStatus) -> AppResult<()> {
+        Ok(())
+    }
+    /// The user's cards whose embedding status is one of `statuses`
+    async fn find_by_embedding_status(
+        &self,
+        user_id: Uuid,
+        statuses: &[EmbeddingStatus],
+    ) -> AppResult<Vec<Card>> {
+        let cards = self.find_by_user(user_id).await?;
+        Ok(cards
+            .into_iter()
+            .filter(|card| statuses.contains(&card.embedding_status))
+            .collect())
+    }
     async fn delete(&self, id: Uuid) -> AppResult<()>;
 }
 
-fn paginate_cards(
-    cards: Vec<Card>,
-    limit: Option<i64>,
-    offset: Option<i64>,
-    exclude_card_ids: Option<Vec<Uuid>>,
-) -> Vec<Card> {
-    let start = offset.unwrap_or(0).max(0) as usize;
-    let max_items = limit.unwrap_or(i64::MAX).max(0) as usize;
-    let exclude_card_ids = exclude_card_ids.unwrap_or_default();
+fn paginate_cards(mut cards: Vec<Card>, options: CardListOptions) -> Vec<Card> {
+    let start = options.offset.unwrap_or(0).max(0) as usize;
+    let max_items = options.limit.unwrap_or(i64::MAX).max(0) as usize;
+    let exclude_card_ids = options.exclude_card_ids.unwrap_or_default();
+
+    if options.order == CardOrder::Difficulty {
+        let now = Utc::now();
+        cards.sort_by(|a, b| {
+            (a.due_at() > now)
+                .cmp(&(b.due_at() > now))
+                .then(b.fsrs_state.difficulty.total_cmp(&a.fsrs_state.difficulty))
+        });
+    }
+
+    if let Some(limit) = options.new_card_limit {
+        let mut new_cards: Vec<_> = cards
+            .iter()
+            .filter(|card| card.fsrs_state.state == CardState::New)
+            .map(|card| (card.created_at, card.position, card.id))
+            .collect();
+        new_cards.sort();
+        let served: Vec<Uuid> = new_cards
+            .into_iter()
+            .take(limit.max(0) as usize)
+            .map(|(_, _, id)| id)
+            .collect();
+        cards.retain(|card| card.fsrs_state.state != CardState::New || served.contains(&card.id));
+    }
 
     cards
         .into_iter()
         .filter(|card| !exclude_card_ids.contains(&card.id))
+        .filter(|card| {
+            options
+                .state
+                .as_ref()
+                .is_none_or(|state| card.fsrs_state.state == *state)
+        })
         .skip(start)
         .take(max_items)
         .collect()
 }
 
+fn page(cards: Vec<Card>, limit: i64, offset: i64) -> Vec<Card> {
+    cards
+        .into_iter()
+        .skip(offset.max(0) as usize)
+        .take(limit.max(0) as usize)
+        .collect()
+}
+
+fn due_cards(mut cards: Vec<Card>, now: DateTime<Utc>) -> Vec<Card> {
+    cards.retain(|card| !card.suspended && card.due_at() <= now);
+    cards.sort_by_key(|card| (card.due_at(), card.position, card.id));
+    cards
+}
+
+fn count_tags(cards: &[Card]) -> Vec<(String, i64)> {
+    let mut counts: std::collections::HashMap<&str, i64> = std::collections::HashMap::new();
+    for tag in cards.iter().flat_map(|card| &card.tags) {
+        *counts.entry(tag).or_default() += 1;
+    }
+
+    let mut counts: Vec<(String, i64)> = counts
+        .into_iter()
+        .map(|(tag, count)| (tag.to_string(), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
 #[cfg(test)]
 mod tests {
-    use super::paginate_cards;
+    use super::{count_tags, due_cards, page, paginate_cards, CardListOptions, CardOrder};
     use crate::domain::entities::Card;
+    use chrono::{Duration, Utc};
     use uuid::Uuid;
 
+    #[test]
+    fn due_cards_keeps_new_and_overdue_cards() {
+        let user_id = Uuid::new_v4();
+        let now = Utc::now();
+        let mut new = Card::new(user_id, "Q1".to_string(), "A1".to_string());
+        new.created_at = now - Duration::hours(1);
+        let mut overdue = Card::new(user_id, "Q2".to_string(), "A2".to_string());
+        overdue.fsrs_state.last_review = Some(now - Duration::days(10));
+        overdue.fsrs_state.scheduled_days = 3;
+        let mut due_today = Card::new(user_id, "Q3".to_string(), "A3".to_string());
+        due_today.fsrs_state.last_review = Some(now - Duration::days(2));
+        due_today.fsrs_state.scheduled_days = 2;
+        let mut not_due = Card::new(user_id, "Q4".to_string(), "A4".to_string());
+        not_due.fsrs_state.last_review = Some(now - Duration::days(1));
+        not_due.fsrs_state.scheduled_days = 5;
+        let mut suspended = Card::new(user_id, "Q5".to_string(), "A5".to_string());
+        suspended.suspended = true;
+        let mut buried = Card::new(user_id, "Q6".to_string(), "A6".to_string());
+        buried.not_before = Some(now + Duration::days(1));
+
+        let due: Vec<Uuid> = due_cards(
+            vec![
+                not_due,
+                suspended,
+                buried,
+                new.clone(),
+                due_today.clone(),
+                overdue.clone(),
+            ],
+            now,
+        )
+        .into_iter()
+        .map(|card| card.id)
+        .collect();
+
+        // Most overdue first; the new card is due since its creation
+        assert_eq!(due, vec![overdue.id, new.id, due_today.id]);
+    }
+
+    #[test]
+    fn due_cards_page_keeps_due_order() {
+        let user_id = Uuid::new_v4();
+        let now = Utc::now();
+        let cards: Vec<Card> = (0..5)
+            .map(|i| {
+                let mut card = Card::new(user_id, format!("Q{}", i), "A".to_string());
+                card.created_at = now - Duration::hours(5 - i);
+                card
+            })
+            .collect();
+
+        let due: Vec<Uuid> = page(due_cards(cards.clone(), now), 2, 1)
+            .iter()
+            .map(|c| c.id)
+            .collect();
+
+        assert_eq!(due, vec![cards[1].id, cards[2].id]);
+    }
+
     #[test]
     fn paginate_cards_excludes_loaded_cards() {
         let user_id = Uuid::new_v4();
@@ -67,12 +435,67 @@ mod tests {
 
         let paginated = paginate_cards(
             vec![card1.clone(), card2.clone(), card3.clone()],
-            Some(10),
-            Some(0),
-            Some(vec![card1.id, card3.id]),
+            CardListOptions {
+                limit: Some(10),
+                offset: Some(0),
+                exclude_card_ids: Some(vec![card1.id, card3.id]),
+                ..Default::default()
+            },
         );
 
         assert_eq!(paginated.len(), 1);
         assert_eq!(paginated[0].id, card2.id);
     }
+
+    #[test]
+    fn paginate_cards_orders_by_difficulty_desc() {
+        let user_id = Uuid::new_v4();
+        let mut easy = Card::new(user_id, "Q1".to_string(), "A1".to_string());
+        easy.fsrs_state.difficulty = 2.0;
+        let mut hard = Card::new(user_id, "Q2".to_string(), "A2".to_string());
+        hard.fsrs_state.difficulty = 8.5;
+        let mut medium = Card::new(user_id, "Q3".to_string(), "A3".to_string());
+        medium.fsrs_state.difficulty = 5.0;
+
+        let paginated = paginate_cards(
+            vec![easy.clone(), hard.clone(), medium.clone()],
+            CardListOptions {
+                order: CardOrder::Difficulty,
+                ..Default::default()
+            },
+        );
+
+        let ids: Vec<Uuid> = paginated.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![hard.id, medium.id, easy.id]);
+    }
+
+    #[test]
+    fn card_order_parses_query_values() {
+        assert_eq!("difficulty".parse::<CardOrder>(), Ok(CardOrder::Difficulty));
+        assert_eq!("due".parse::<CardOrder>(), Ok(CardOrder::Due));
+        assert!("random".parse::<CardOrder>().is_err());
+    }
+
+    #[test]
+    fn count_tags_orders_by_count_desc() {
+        let user_id = Uuid::new_v4();
+        let cards = vec![
+            Card::new(user_id, "Q1".to_string(), "A1".to_string()).with_tags(["verbs", "spanish"]),
+            Card::new(user_id, "Q2".to_string(), "A2".to_string()).with_tags(["spanish"]),
+            Card::new(user_id, "Q3".to_string(), "A3".to_string())
+                .with_tags(["spanish", "nouns", "verbs"]),
+            Card::new(user_id, "Q4".to_string(), "A4".to_string()),
+        ];
+
+        let counts = count_tags(&cards);
+
+        assert_eq!(
+            counts,
+            vec![
+                ("spanish".to_string(), 3),
+                ("verbs".to_string(), 2),
+                ("nouns".to_string(), 1),
+            ]
+        );
+    }
 }
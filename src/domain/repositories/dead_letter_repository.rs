@@ -0,0 +1,13 @@
+use crate::{domain::entities::DeadLetterEvent, AppResult};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Repository interface for dead-lettered domain events
+#[async_trait::async_trait]
+pub trait DeadLetterRepository: Send + Sync {
+    async fn create(&self, dead_letter: &DeadLetterEvent) -> AppResult<Uuid>;
+    async fn find_by_id(&self, id: Uuid) -> AppResult<Option<DeadLetterEvent>>;
+    /// Events not replayed yet, newest first
+    async fn list_pending(&self, limit: i64, offset: i64) -> AppResult<Vec<DeadLetterEvent>>;
+    async fn mark_replayed(&self, id: Uuid, replayed_at: DateTime<Utc>) -> AppResult<()>;
+}
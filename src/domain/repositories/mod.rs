@@ -1,4 +1,5 @@
 pub mod card_repository;
+pub mod dead_letter_repository;
 pub mod deck_repository;
 pub mod review_log_repository;
 pub mod review_repository;
@@ -6,6 +7,7 @@ pub mod stats_repository;
 pub mod user_repository;
 
 pub use card_repository::*;
+pub use dead_letter_repository::*;
 pub use deck_repository::*;
 pub use review_log_repository::*;
 pub use review_repository::*;
@@ -1,10 +1,50 @@
 use crate::{domain::entities::ReviewLog, AppResult};
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+/// Which of a user's review logs a score histogram covers
+#[derive(Debug, Clone, Default)]
+pub struct ScoreHistogramFilter {
+    /// Only reviews of these cards
+    pub card_ids: Option<Vec<Uuid>>,
+    /// Reviews at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Reviews before this time
+    pub to: Option<DateTime<Utc>>,
+}
+
 /// Repository interface for ReviewLog domain
 #[async_trait::async_trait]
 pub trait ReviewLogRepository: Send + Sync {
     async fn create(&self, review_log: &ReviewLog) -> AppResult<Uuid>;
     async fn find_by_card(&self, card_id: Uuid) -> AppResult<Vec<ReviewLog>>;
     async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<ReviewLog>>;
+    /// Number of matching reviews whose AI score falls in each of `buckets`
+    /// equal-width ranges over 0.0..=1.0; a score of exactly 1.0 counts in
+    /// the last bucket
+    async fn score_histogram(
+        &self,
+        user_id: Uuid,
+        filter: ScoreHistogramFilter,
+        buckets: usize,
+    ) -> AppResult<Vec<i64>> {
+        let mut counts = vec![0; buckets];
+        for log in self.find_by_user(user_id).await? {
+            let in_range = filter
+                .card_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&log.card_id))
+                && filter.from.is_none_or(|from| log.created_at >= from)
+                && filter.to.is_none_or(|to| log.created_at < to);
+            if in_range && buckets > 0 {
+                counts[score_bucket(log.ai_score, buckets)] += 1;
+            }
+        }
+        Ok(counts)
+    }
+}
+
+/// Zero-based bucket of `score` among `buckets` equal-width ranges
+fn score_bucket(score: f32, buckets: usize) -> usize {
+    ((score.clamp(0.0, 1.0) * buckets as f32) as usize).min(buckets - 1)
 }
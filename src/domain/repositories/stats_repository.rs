@@ -8,24 +8,23 @@ use uuid::Uuid;
 #[async_trait::async_trait]
 pub trait UserStatsRepository: Send + Sync {
     async fn get_or_create(&self, user_id: Uuid) -> AppResult<UserStats>;
+    /// Count one review for the user and, when given, for the reviewed card's
+    /// deck. Both are written or neither is, so a retried review event can't
+    /// count twice for one of them.
     async fn update_after_review(
         &self,
         user_id: Uuid,
+        deck_id: Option<Uuid>,
         is_correct: bool,
         review_date: chrono::NaiveDate,
     ) -> AppResult<()>;
 }
 
-/// Repository interface for DeckStats domain
+/// Repository interface for DeckStats domain. Reviews are counted through
+/// [`UserStatsRepository::update_after_review`], together with the user's.
 #[async_trait::async_trait]
 pub trait DeckStatsRepository: Send + Sync {
     async fn get_or_create(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<DeckStats>;
-    async fn update_after_review(
-        &self,
-        deck_id: Uuid,
-        is_correct: bool,
-        review_date: chrono::NaiveDate,
-    ) -> AppResult<()>;
     async fn increment_card_count(&self, deck_id: Uuid) -> AppResult<()>;
     async fn decrement_card_count(&self, deck_id: Uuid) -> AppResult<()>;
     async fn add_to_card_count(&self, deck_id: Uuid, count: i32) -> AppResult<()>;
@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+
+/// Effective configuration used by the review scheduler
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerConfig {
+    /// Minimum AI score for an Easy (4) rating
+    pub easy_threshold: f32,
+    /// Minimum AI score for a Good (3) rating
+    pub good_threshold: f32,
+    /// Minimum AI score for a Hard (2) rating; anything below is Again (1)
+    pub hard_threshold: f32,
+    /// Unused: FSRS derives the first-review stability from the rating
+    pub initial_stability: f32,
+    /// Baseline the answer-complexity difficulty is measured from; its offset
+    /// shifts FSRS's initial difficulty
+    pub initial_difficulty: f32,
+    /// Upper bound for any scheduled interval, in days
+    pub maximum_interval_days: i32,
+    /// Probability of recall the FSRS scheduler aims for when a card comes
+    /// due; higher values mean shorter intervals
+    #[serde(default = "default_desired_retention")]
+    pub desired_retention: f32,
+    /// Highest score an answer can get when it leaves out one of the card's
+    /// required keywords
+    #[serde(default = "default_missing_keyword_max_score")]
+    pub missing_keyword_max_score: f32,
+    /// Seed a card's first-review difficulty from the length of its answer
+    /// instead of starting every card at `initial_difficulty`
+    #[serde(default)]
+    pub answer_complexity_difficulty: bool,
+    /// Custom FSRS weights; when absent the FSRS-4.5 defaults are used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fsrs_weights: Option<Vec<f32>>,
+}
+
+fn default_missing_keyword_max_score() -> f32 {
+    0.4
+}
+
+fn default_desired_retention() -> f32 {
+    0.9
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            easy_threshold: 0.9,
+            good_threshold: 0.7,
+            hard_threshold: 0.5,
+            initial_stability: 1.0,
+            initial_difficulty: 5.0,
+            maximum_interval_days: 36_500,
+            desired_retention: default_desired_retention(),
+            missing_keyword_max_score: default_missing_keyword_max_score(),
+            answer_complexity_difficulty: false,
+            fsrs_weights: None,
+        }
+    }
+}
+
+impl SchedulerConfig {
+    /// Defaults, with the answer-complexity heuristic turned on by
+    /// `ANSWER_COMPLEXITY_DIFFICULTY` (`true`/`1`)
+    pub fn from_env() -> Self {
+        Self {
+            answer_complexity_difficulty: matches!(
+                std::env::var("ANSWER_COMPLEXITY_DIFFICULTY")
+                    .ok()
+                    .as_deref(),
+                Some("true") | Some("1")
+            ),
+            ..Default::default()
+        }
+    }
+
+    /// Difficulty a card with this `answer` starts from on its first review.
+    /// With the heuristic on, a one-word answer starts at 3.0 and each
+    /// doubling of the word count adds 1.25 (about 5.0 at three words), plus
+    /// 0.25 per word longer than eight characters, clamped to 1.0-10.0.
+    pub fn initial_difficulty_for(&self, answer: &str) -> f32 {
+        if !self.answer_complexity_difficulty {
+            return self.initial_difficulty;
+        }
+        let words: Vec<&str> = answer.split_whitespace().collect();
+        let long_words = words.iter().filter(|w| w.chars().count() > 8).count();
+        let word_count = words.len().max(1) as f32;
+        (3.0 + 1.25 * word_count.log2() + 0.25 * long_words as f32).clamp(1.0, 10.0)
+    }
+
+    /// Check that the score thresholds rise from Hard to Easy, so every
+    /// rating stays reachable
+    pub fn validate_thresholds(&self) -> Result<(), &'static str> {
+        if self.hard_threshold > self.good_threshold || self.good_threshold > self.easy_threshold {
+            return Err("Score thresholds must satisfy hard <= good <= easy");
+        }
+        Ok(())
+    }
+
+    /// Merge overrides on top of this configuration. Fields set in `overrides`
+    /// shadow the corresponding values in `self`.
+    pub fn resolve(&self, overrides: &SchedulerOverrides) -> Self {
+        Self {
+            easy_threshold: overrides.easy_threshold.unwrap_or(self.easy_threshold),
+            good_threshold: overrides.good_threshold.unwrap_or(self.good_threshold),
+            hard_threshold: overrides.hard_threshold.unwrap_or(self.hard_threshold),
+            initial_stability: overrides
+                .initial_stability
+                .unwrap_or(self.initial_stability),
+            initial_difficulty: overrides
+                .initial_difficulty
+                .unwrap_or(self.initial_difficulty),
+            maximum_interval_days: overrides
+                .maximum_interval_days
+                .unwrap_or(self.maximum_interval_days),
+            desired_retention: overrides
+                .desired_retention
+                .unwrap_or(self.desired_retention),
+            missing_keyword_max_score: overrides
+                .missing_keyword_max_score
+                .unwrap_or(self.missing_keyword_max_score),
+            answer_complexity_difficulty: overrides
+                .answer_complexity_difficulty
+                .unwrap_or(self.answer_complexity_difficulty),
+            fsrs_weights: self.fsrs_weights.clone(),
+        }
+    }
+}
+
+/// Optional per-deck scheduler settings. Unset fields fall back to the global defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub easy_threshold: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub good_threshold: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hard_threshold: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_stability: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_difficulty: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum_interval_days: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desired_retention: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub missing_keyword_max_score: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer_complexity_difficulty: Option<bool>,
+}
+
+impl SchedulerOverrides {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        let thresholds = [
+            self.easy_threshold,
+            self.good_threshold,
+            self.hard_threshold,
+        ];
+        if thresholds
+            .iter()
+            .flatten()
+            .any(|t| !(0.0..=1.0).contains(t))
+        {
+            return Err("Score thresholds must be between 0.0 and 1.0");
+        }
+        // Thresholds left unset fall back to the defaults, so the ordering
+        // is checked on the resolved configuration
+        SchedulerConfig::default()
+            .resolve(self)
+            .validate_thresholds()?;
+        if self.initial_stability.is_some_and(|s| s <= 0.0) {
+            return Err("Initial stability must be positive");
+        }
+        if self
+            .initial_difficulty
+            .is_some_and(|d| !(1.0..=10.0).contains(&d))
+        {
+            return Err("Initial difficulty must be between 1.0 and 10.0");
+        }
+        if self.maximum_interval_days.is_some_and(|d| d < 1) {
+            return Err("Maximum interval must be at least 1 day");
+        }
+        if self
+            .desired_retention
+            .is_some_and(|r| !(0.7..=0.99).contains(&r))
+        {
+            return Err("Desired retention must be between 0.7 and 0.99");
+        }
+        if self
+            .missing_keyword_max_score
+            .is_some_and(|s| !(0.0..=1.0).contains(&s))
+        {
+            return Err("Missing keyword score cap must be between 0.0 and 1.0");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_without_overrides_keeps_defaults() {
+        let defaults = SchedulerConfig::default();
+        assert_eq!(defaults.resolve(&SchedulerOverrides::default()), defaults);
+    }
+
+    #[test]
+    fn test_resolve_applies_only_set_fields() {
+        let overrides = SchedulerOverrides {
+            good_threshold: Some(0.8),
+            maximum_interval_days: Some(180),
+            ..Default::default()
+        };
+        let resolved = SchedulerConfig::default().resolve(&overrides);
+
+        assert_eq!(resolved.good_threshold, 0.8);
+        assert_eq!(resolved.maximum_interval_days, 180);
+        assert_eq!(resolved.easy_threshold, 0.9);
+        assert_eq!(resolved.initial_difficulty, 5.0);
+    }
+
+    #[test]
+    fn test_answer_complexity_seeds_initial_difficulty() {
+        let enabled = SchedulerConfig {
+            answer_complexity_difficulty: true,
+            ..Default::default()
+        };
+
+        let one_word = enabled.initial_difficulty_for("hola");
+        let long = enabled.initial_difficulty_for(
+            "the mitochondrion is the organelle that produces most of the cell's chemical energy",
+        );
+        assert!(long > one_word);
+        assert!((1.0..=10.0).contains(&long));
+        assert_eq!(
+            SchedulerConfig::default().initial_difficulty_for("a much longer answer"),
+            5.0
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_threshold() {
+        let overrides = SchedulerOverrides {
+            easy_threshold: Some(1.5),
+            ..Default::default()
+        };
+        assert!(overrides.validate().is_err());
+        assert!(SchedulerOverrides::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_misordered_thresholds() {
+        let inverted = SchedulerOverrides {
+            hard_threshold: Some(0.8),
+            good_threshold: Some(0.6),
+            ..Default::default()
+        };
+        assert!(inverted.validate().is_err());
+
+        // 0.95 is above the default Easy threshold of 0.9
+        let above_default_easy = SchedulerOverrides {
+            good_threshold: Some(0.95),
+            ..Default::default()
+        };
+        assert!(above_default_easy.validate().is_err());
+
+        let ordered = SchedulerOverrides {
+            hard_threshold: Some(0.4),
+            good_threshold: Some(0.6),
+            easy_threshold: Some(0.8),
+            ..Default::default()
+        };
+        assert!(ordered.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_retention() {
+        for (retention, valid) in [(0.5, false), (0.85, true), (1.0, false)] {
+            let overrides = SchedulerOverrides {
+                desired_retention: Some(retention),
+                ..Default::default()
+            };
+            assert_eq!(overrides.validate().is_ok(), valid, "{}", retention);
+        }
+    }
+}
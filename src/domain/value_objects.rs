@@ -1,4 +1,12 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
+    Algorithm, Argon2, Params, PasswordHash, PasswordVerifier, Version,
+};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::shared::error::{AppError, AppResult};
 
 /// Email value object - ensures email validity
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -18,6 +26,91 @@ impl Email {
     }
 }
 
+/// Minimum accepted password length, in characters
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+/// Password value object - a plaintext password that meets the password policy
+#[derive(Clone, PartialEq, Eq)]
+pub struct Password(String);
+
+impl Password {
+    /// Accept a new password (registration, password change), enforcing the policy
+    pub fn new(password: String) -> AppResult<Self> {
+        if password.chars().count() < MIN_PASSWORD_LEN {
+            return Err(AppError::ValidationError(format!(
+                "Password must be at least {} characters",
+                MIN_PASSWORD_LEN
+            )));
+        }
+        Ok(Self(password))
+    }
+
+    /// Wrap a password the user typed to prove who they are. The policy is
+    /// not applied: a wrong password must fail verification, not validation.
+    pub fn candidate(password: String) -> Self {
+        Self(password)
+    }
+
+    /// Argon2 hash with a random salt (PHC string format), using the cost
+    /// parameters from [`argon2_params_from_env`]
+    pub fn hash(&self) -> AppResult<String> {
+        self.hash_with(argon2_params_from_env()?)
+    }
+
+    /// Argon2id hash with explicit cost parameters
+    pub fn hash_with(&self, params: Params) -> AppResult<String> {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password(self.0.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| AppError::InternalError(format!("Password hashing failed: {e}")))
+    }
+
+    /// Check this password against a stored PHC hash. The cost parameters
+    /// are read from the hash, so hashes made with older settings still verify.
+    pub fn verify(&self, hash: &str) -> AppResult<bool> {
+        let parsed = PasswordHash::new(hash)
+            .map_err(|_| AppError::InternalError("Password hash corrupted".to_string()))?;
+        Ok(Argon2::default()
+            .verify_password(self.0.as_bytes(), &parsed)
+            .is_ok())
+    }
+}
+
+/// Argon2 cost parameters from ARGON2_MEMORY_KIB, ARGON2_ITERATIONS and
+/// ARGON2_PARALLELISM. Unset variables keep the argon2 crate defaults
+/// (19 MiB, 2 iterations, 1 lane, as recommended by OWASP).
+pub fn argon2_params_from_env() -> AppResult<Params> {
+    argon2_params_from(|name| std::env::var(name).ok())
+}
+
+/// Argon2 cost parameters read through `lookup`, which returns the value of
+/// the named setting; see [`argon2_params_from_env`]
+pub fn argon2_params_from(lookup: impl Fn(&str) -> Option<String>) -> AppResult<Params> {
+    let var = |name: &str, default: u32| -> AppResult<u32> {
+        match lookup(name) {
+            Some(value) => value.trim().parse().map_err(|_| {
+                AppError::InternalError(format!("{} must be a positive integer", name))
+            }),
+            None => Ok(default),
+        }
+    };
+
+    Params::new(
+        var("ARGON2_MEMORY_KIB", Params::DEFAULT_M_COST)?,
+        var("ARGON2_ITERATIONS", Params::DEFAULT_T_COST)?,
+        var("ARGON2_PARALLELISM", Params::DEFAULT_P_COST)?,
+        None,
+    )
+    .map_err(|e| AppError::InternalError(format!("Invalid Argon2 parameters: {e}")))
+}
+
+impl fmt::Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Password(***)")
+    }
+}
+
 /// Grade value object - represents FSRS grading (0-5)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Grade(u8);
@@ -36,6 +129,47 @@ impl Grade {
     }
 }
 
+/// Interval value object - a non-negative scheduling interval.
+///
+/// Stored as a duration so sub-day learning steps and day-based review
+/// intervals share one unit; persisted state keeps whole days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Interval(Duration);
+
+impl Interval {
+    pub const ZERO: Self = Self(Duration::zero());
+
+    pub fn new(duration: Duration) -> Result<Self, &'static str> {
+        if duration < Duration::zero() {
+            Err("Interval must not be negative")
+        } else {
+            Ok(Self(duration))
+        }
+    }
+
+    pub fn from_days(days: i64) -> Result<Self, &'static str> {
+        Self::new(Duration::try_days(days).ok_or("Interval is out of range")?)
+    }
+
+    pub fn from_minutes(minutes: i64) -> Result<Self, &'static str> {
+        Self::new(Duration::try_minutes(minutes).ok_or("Interval is out of range")?)
+    }
+
+    /// Time from `start` to `end`, or zero if `end` is before `start`
+    pub fn between(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self((end - start).max(Duration::zero()))
+    }
+
+    /// Whole days, rounded down
+    pub fn as_days(&self) -> i64 {
+        self.0.num_days()
+    }
+
+    pub fn as_duration(&self) -> Duration {
+        self.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,6 +184,72 @@ mod tests {
         assert!(Email::new("invalid".to_string()).is_err());
     }
 
+    #[test]
+    fn test_password_policy() {
+        assert!(matches!(
+            Password::new("short".to_string()),
+            Err(AppError::ValidationError(_))
+        ));
+        assert!(Password::new("long enough".to_string()).is_ok());
+        // Counted in characters, not bytes
+        assert!(Password::new("ñññññññ".to_string()).is_err());
+        assert_eq!(
+            format!("{:?}", Password::candidate("secret".to_string())),
+            "Password(***)"
+        );
+    }
+
+    #[test]
+    fn test_password_hash_verify_round_trip() {
+        let password = Password::new("correct horse".to_string()).unwrap();
+        let hash = password.hash().unwrap();
+
+        assert!(hash.starts_with("$argon2"));
+        assert!(password.verify(&hash).unwrap());
+        assert!(!Password::candidate("wrong".to_string())
+            .verify(&hash)
+            .unwrap());
+        assert!(password.verify("not a hash").is_err());
+    }
+
+    #[test]
+    fn test_argon2_params_from_lookup() {
+        let params = argon2_params_from(|name| match name {
+            "ARGON2_MEMORY_KIB" => Some("8192".to_string()),
+            "ARGON2_ITERATIONS" => Some("1".to_string()),
+            "ARGON2_PARALLELISM" => Some(" 2 ".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(
+            (params.m_cost(), params.t_cost(), params.p_cost()),
+            (8192, 1, 2)
+        );
+
+        let password = Password::new("correct horse".to_string()).unwrap();
+        let hash = password.hash_with(params).unwrap();
+        assert!(hash.contains("m=8192,t=1,p=2"));
+        assert!(password.verify(&hash).unwrap());
+    }
+
+    #[test]
+    fn test_argon2_params_defaults_and_rejects_garbage() {
+        let defaults = argon2_params_from(|_| None).unwrap();
+        assert_eq!(
+            (defaults.m_cost(), defaults.t_cost(), defaults.p_cost()),
+            (
+                Params::DEFAULT_M_COST,
+                Params::DEFAULT_T_COST,
+                Params::DEFAULT_P_COST
+            )
+        );
+
+        let garbage = |name: &str| (name == "ARGON2_ITERATIONS").then(|| "many".to_string());
+        assert!(argon2_params_from(garbage).is_err());
+        let zero_lanes = |name: &str| (name == "ARGON2_PARALLELISM").then(|| "0".to_string());
+        assert!(argon2_params_from(zero_lanes).is_err());
+    }
+
     #[test]
     fn test_valid_grade() {
         assert!(Grade::new(5).is_ok());
@@ -60,4 +260,31 @@ mod tests {
     fn test_invalid_grade() {
         assert!(Grade::new(6).is_err());
     }
+
+    #[test]
+    fn test_negative_interval_rejected() {
+        assert!(Interval::from_days(-1).is_err());
+        assert!(Interval::from_minutes(-10).is_err());
+        assert!(Interval::new(Duration::seconds(-1)).is_err());
+        assert_eq!(Interval::from_days(0), Ok(Interval::ZERO));
+    }
+
+    #[test]
+    fn test_interval_day_minute_conversions() {
+        assert_eq!(Interval::from_days(2), Interval::from_minutes(2 * 24 * 60));
+        assert_eq!(Interval::from_days(3).unwrap().as_days(), 3);
+        // Sub-day steps round down to zero whole days
+        assert_eq!(Interval::from_minutes(10).unwrap().as_days(), 0);
+        assert_eq!(Interval::from_minutes(36 * 60).unwrap().as_days(), 1);
+    }
+
+    #[test]
+    fn test_interval_between_clamps_to_zero() {
+        let now = Utc::now();
+        assert_eq!(
+            Interval::between(now, now - Duration::days(1)),
+            Interval::ZERO
+        );
+        assert_eq!(Interval::between(now - Duration::days(2), now).as_days(), 2);
+    }
 }
@@ -11,29 +11,109 @@ use async_openai::{
     Client,
 };
 use async_trait::async_trait;
+use std::{borrow::Cow, sync::Arc};
 
-use crate::domain::ports::{AIValidator, EmbeddingService, ValidationMethod, ValidationResult};
+use crate::{
+    domain::{
+        embedding::cosine_similarity,
+        ports::{AIValidator, EmbeddingService, ValidationMethod, ValidationResult},
+    },
+    shared::deadline,
+};
 
-/// OpenAI-based AI validator with cascading validation strategy
+/// OpenAI-based AI validator with cascading validation strategy.
+///
+/// Every OpenAI call is cancelled once the current request deadline (see
+/// [`deadline`]) passes, failing with [`deadline::DeadlineExceeded`].
 pub struct OpenAIValidator {
     client: Client<OpenAIConfig>,
     embedding_model: String,
     chat_model: String,
     _exact_match_threshold: f32,
     embedding_threshold: f32,
+    blend_weights: Option<BlendWeights>,
+    max_answer_chars: Option<usize>,
+}
+
+/// Characters of each answer sent to the LLM or embedded by default
+pub const DEFAULT_MAX_ANSWER_CHARS: usize = 500;
+
+/// Relative weights of the embedding similarity and the LLM score when a
+/// borderline answer is graded by blending both
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlendWeights {
+    pub embedding: f32,
+    pub llm: f32,
+}
+
+impl BlendWeights {
+    /// Weighted mean of the two scores
+    pub fn blend(&self, embedding_score: f32, llm_score: f32) -> f32 {
+        let total = self.embedding + self.llm;
+        if total <= 0.0 {
+            return llm_score;
+        }
+        (self.embedding * embedding_score + self.llm * llm_score) / total
+    }
 }
 
 impl OpenAIValidator {
     pub fn new(api_key: String) -> Self {
-        let config = OpenAIConfig::new().with_api_key(api_key);
-        let client = Client::with_config(config);
+        Self::with_config(OpenAIConfig::new().with_api_key(api_key))
+    }
 
+    /// Validator talking to a self-hosted OpenAI-compatible server (e.g.
+    /// Ollama or vLLM) instead of api.openai.com
+    pub fn local(api_base: String, api_key: Option<String>) -> Self {
+        let mut config = OpenAIConfig::new().with_api_base(api_base);
+        if let Some(api_key) = api_key {
+            config = config.with_api_key(api_key);
+        }
+        Self::with_config(config)
+    }
+
+    /// Override the model used for LLM grading
+    pub fn with_chat_model(mut self, chat_model: String) -> Self {
+        self.chat_model = chat_model;
+        self
+    }
+
+    /// Override the similarity at which an embedding match is accepted
+    /// without asking the LLM (default 0.85)
+    pub fn with_embedding_threshold(mut self, threshold: f32) -> Self {
+        self.embedding_threshold = threshold;
+        self
+    }
+
+    /// Grade borderline answers with a weighted mean of the embedding
+    /// similarity and the LLM score instead of the LLM score alone
+    pub fn with_blended_scoring(mut self, weights: BlendWeights) -> Self {
+        self.blend_weights = Some(weights);
+        self
+    }
+
+    /// Characters of the expected and given answers sent to the embedding
+    /// model and the LLM (default 500); `None` sends them whole
+    pub fn with_max_answer_chars(mut self, max_answer_chars: Option<usize>) -> Self {
+        self.max_answer_chars = max_answer_chars;
+        self
+    }
+
+    /// Override the model used for answer embeddings
+    pub fn with_embedding_model(mut self, embedding_model: String) -> Self {
+        self.embedding_model = embedding_model;
+        self
+    }
+
+    fn with_config(config: OpenAIConfig) -> Self {
         Self {
-            client,
+            client: Client::with_config(config),
             embedding_model: "text-embedding-3-small".to_string(),
             chat_model: "gpt-4o-mini".to_string(),
             _exact_match_threshold: 0.95,
             embedding_threshold: 0.85,
+            blend_weights: None,
+            max_answer_chars: Some(DEFAULT_MAX_ANSWER_CHARS),
         }
     }
 
@@ -53,10 +133,18 @@ impl OpenAIValidator {
     async fn check_embedding_similarity(&self, expected: &str, user_answer: &str) -> Result<f32> {
         let request = CreateEmbeddingRequestArgs::default()
             .model(&self.embedding_model)
-            .input(vec![expected.to_string(), user_answer.to_string()])
+            .input(vec![
+                truncate_chars(expected, self.max_answer_chars)
+                    .0
+                    .into_owned(),
+                truncate_chars(user_answer, self.max_answer_chars)
+                    .0
+                    .into_owned(),
+            ])
             .build()?;
 
-        let response = self.client.embeddings().create(request).await?;
+        let response =
+            deadline::run(async { Ok(self.client.embeddings().create(request).await?) }).await?;
 
         if response.data.len() < 2 {
             return Ok(0.0);
@@ -70,37 +158,30 @@ impl OpenAIValidator {
         Ok(similarity)
     }
 
-    /// Validate using LLM
+    /// Validate using LLM. `fallback_score` is used when the reply contains
+    /// no parseable score.
     async fn check_llm_validation(
         &self,
         expected: &str,
         user_answer: &str,
         question: &str,
+        fallback_score: f32,
     ) -> Result<f32> {
-        let system_prompt = r#"You are an expert language tutor evaluating student answers.
-Compare the student's answer with the expected answer in the context of the question.
-Rate the answer from 0.0 to 1.0 based on semantic correctness and completeness.
-Consider:
-- Meaning and intent (more important than exact wording)
-- Grammatical correctness
-- Completeness of the response
-
-Respond with ONLY a number between 0.0 and 1.0, nothing else."#;
-
-        let user_prompt = format!(
-            "Question: {}\n\nExpected Answer: {}\n\nStudent Answer: {}\n\nScore:",
-            question, expected, user_answer
-        );
-
         let request = CreateChatCompletionRequest {
             model: self.chat_model.clone(),
             messages: vec![
                 ChatCompletionRequestMessage::System(ChatCompletionRequestSystemMessage {
-                    content: system_prompt.into(),
+                    content: LLM_GRADING_PROMPT.into(),
                     name: None,
                 }),
                 ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
-                    content: user_prompt.into(),
+                    content: llm_grading_request(
+                        expected,
+                        user_answer,
+                        question,
+                        self.max_answer_chars,
+                    )
+                    .into(),
                     name: None,
                 }),
             ],
@@ -109,7 +190,8 @@ Respond with ONLY a number between 0.0 and 1.0, nothing else."#;
             ..Default::default()
         };
 
-        let response = self.client.chat().create(request).await?;
+        let response =
+            deadline::run(async { Ok(self.client.chat().create(request).await?) }).await?;
 
         let score_text = response
             .choices
@@ -117,8 +199,7 @@ Respond with ONLY a number between 0.0 and 1.0, nothing else."#;
             .and_then(|choice| choice.message.content.as_ref())
             .context("No response from LLM")?;
 
-        let score: f32 = score_text.trim().parse().unwrap_or(0.0);
-        Ok(score.clamp(0.0, 1.0))
+        Ok(score_from_llm_reply(score_text, fallback_score))
     }
 }
 
@@ -135,10 +216,14 @@ impl AIValidator for OpenAIValidator {
             return Ok(ValidationResult {
                 score,
                 method: ValidationMethod::Exact,
+                explanation: None,
+                threshold: None,
             });
         }
 
         // Strategy 2: Embedding similarity
+        let mut embedding_score = None;
+        let mut borderline = false;
         match self
             .check_embedding_similarity(expected_answer, user_answer)
             .await
@@ -147,6 +232,8 @@ impl AIValidator for OpenAIValidator {
                 return Ok(ValidationResult {
                     score,
                     method: ValidationMethod::Embedding,
+                    explanation: None,
+                    threshold: Some(self.embedding_threshold),
                 });
             }
             Ok(score) if score >= 0.6 => {
@@ -155,40 +242,253 @@ impl AIValidator for OpenAIValidator {
                     "Embedding score borderline ({}), falling back to LLM",
                     score
                 );
+                embedding_score = Some(score);
+                borderline = true;
             }
+            Ok(score) => embedding_score = Some(score),
+            // No time left for the LLM either
+            Err(e) if deadline::is_exceeded(&e) => return Err(e),
             Err(e) => {
                 tracing::warn!("Embedding check failed: {}, falling back to LLM", e);
             }
-            _ => {}
         }
 
         // Strategy 3: LLM validation (most expensive)
         let score = self
-            .check_llm_validation(expected_answer, user_answer, question_context)
+            .check_llm_validation(
+                expected_answer,
+                user_answer,
+                question_context,
+                embedding_score.unwrap_or(0.0),
+            )
             .await?;
 
+        if let (Some(weights), Some(embedding_score), true) =
+            (self.blend_weights, embedding_score, borderline)
+        {
+            return Ok(ValidationResult {
+                score: weights.blend(embedding_score, score),
+                method: ValidationMethod::Blended,
+                explanation: None,
+                threshold: None,
+            });
+        }
+
         Ok(ValidationResult {
             score,
             method: ValidationMethod::Llm,
+            explanation: None,
+            threshold: None,
+        })
+    }
+}
+
+/// System prompt for LLM grading, shared by every chat-based validator
+const LLM_GRADING_PROMPT: &str = r#"You are an expert language tutor evaluating student answers.
+Compare the student's answer with the expected answer in the context of the question.
+Rate the answer from 0.0 to 1.0 based on semantic correctness and completeness.
+Consider:
+- Meaning and intent (more important than exact wording)
+- Grammatical correctness
+- Completeness of the response
+
+Respond with ONLY a number between 0.0 and 1.0, nothing else."#;
+
+/// User message asking the LLM to grade one answer. Answers longer than
+/// `max_answer_chars` are cut, and the LLM is told so.
+fn llm_grading_request(
+    expected: &str,
+    user_answer: &str,
+    question: &str,
+    max_answer_chars: Option<usize>,
+) -> String {
+    let (expected, expected_cut) = truncate_chars(expected, max_answer_chars);
+    let (user_answer, answer_cut) = truncate_chars(user_answer, max_answer_chars);
+    let mut request = format!(
+        "Question: {}\n\nExpected Answer: {}\n\nStudent Answer: {}\n\n",
+        question, expected, user_answer
+    );
+    if expected_cut || answer_cut {
+        request.push_str(&format!(
+            "(Answers longer than {} characters were truncated; grade the part shown.)\n\n",
+            max_answer_chars.unwrap_or_default()
+        ));
+    }
+    request.push_str("Score:");
+    request
+}
+
+/// The first `max_chars` characters of `text`, and whether any were cut
+pub(crate) fn truncate_chars(text: &str, max_chars: Option<usize>) -> (Cow<'_, str>, bool) {
+    match max_chars.and_then(|max| text.char_indices().nth(max)) {
+        Some((end, _)) => (Cow::Borrowed(&text[..end]), true),
+        None => (Cow::Borrowed(text), false),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Claude validator (Anthropic messages API)
+// ---------------------------------------------------------------------------
+
+/// Grades answers with an Anthropic Claude model: exact matches are accepted
+/// locally, everything else is scored by the LLM with the same prompt as
+/// [`OpenAIValidator`]. Claude has no embeddings, so there is no embedding
+/// stage.
+///
+/// Calls are cancelled once the current request deadline passes, like the
+/// OpenAI ones.
+pub struct ClaudeValidator {
+    client: reqwest::Client,
+    api_base: String,
+    api_key: String,
+    model: String,
+    max_answer_chars: Option<usize>,
+}
+
+impl ClaudeValidator {
+    pub const DEFAULT_MODEL: &'static str = "claude-3-5-haiku-latest";
+
+    /// `model` falls back to [`Self::DEFAULT_MODEL`] when `None`
+    pub fn new(api_key: String, model: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_base: "https://api.anthropic.com/v1".to_string(),
+            api_key,
+            model: model.unwrap_or_else(|| Self::DEFAULT_MODEL.to_string()),
+            max_answer_chars: Some(DEFAULT_MAX_ANSWER_CHARS),
+        }
+    }
+
+    /// Characters of the expected and given answers sent to the LLM
+    /// (default 500); `None` sends them whole
+    pub fn with_max_answer_chars(mut self, max_answer_chars: Option<usize>) -> Self {
+        self.max_answer_chars = max_answer_chars;
+        self
+    }
+
+    /// Send requests to another Anthropic-compatible endpoint
+    pub fn with_api_base(mut self, api_base: String) -> Self {
+        self.api_base = api_base;
+        self
+    }
+
+    async fn check_llm_validation(
+        &self,
+        expected: &str,
+        user_answer: &str,
+        question: &str,
+    ) -> Result<f32> {
+        let request = serde_json::json!({
+            "model": self.model,
+            "max_tokens": 10,
+            "temperature": 0.0,
+            "system": LLM_GRADING_PROMPT,
+            "messages": [{
+                "role": "user",
+                "content": llm_grading_request(expected, user_answer, question, self.max_answer_chars),
+            }],
+        });
+
+        let body: serde_json::Value = deadline::run(async {
+            let response = self
+                .client
+                .post(format!("{}/messages", self.api_base.trim_end_matches('/')))
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", "2023-06-01")
+                .json(&request)
+                .send()
+                .await
+                .context("Anthropic request failed")?
+                .error_for_status()
+                .context("Anthropic request rejected")?;
+            Ok(response.json().await?)
         })
+        .await?;
+
+        score_from_claude_response(&body)
     }
 }
 
-/// Calculate cosine similarity between two vectors
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
+/// Score in the text of a messages API response; an unparseable reply
+/// counts as wrong
+fn score_from_claude_response(body: &serde_json::Value) -> Result<f32> {
+    let text = body["content"]
+        .as_array()
+        .and_then(|blocks| blocks.iter().find_map(|block| block["text"].as_str()))
+        .context("No response from LLM")?;
+    Ok(score_from_llm_reply(text, 0.0))
+}
+
+#[async_trait]
+impl AIValidator for ClaudeValidator {
+    async fn validate(
+        &self,
+        expected_answer: &str,
+        user_answer: &str,
+        question_context: &str,
+    ) -> Result<ValidationResult> {
+        if expected_answer.trim().to_lowercase() == user_answer.trim().to_lowercase() {
+            return Ok(ValidationResult {
+                score: 1.0,
+                method: ValidationMethod::Exact,
+                explanation: None,
+                threshold: None,
+            });
+        }
+
+        let score = self
+            .check_llm_validation(expected_answer, user_answer, question_context)
+            .await?;
+        Ok(ValidationResult {
+            score,
+            method: ValidationMethod::Llm,
+            explanation: None,
+            threshold: None,
+        })
     }
+}
 
-    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let magnitude_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let magnitude_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+/// Turn an LLM reply into a score in `[0.0, 1.0]`.
+///
+/// Takes the first number in the reply so that answers like `Score: 0.8` or
+/// `0.8/1.0` still parse. Falls back to `fallback_score` when no number is
+/// found.
+fn score_from_llm_reply(reply: &str, fallback_score: f32) -> f32 {
+    match first_float(reply) {
+        Some(score) => score.clamp(0.0, 1.0),
+        None => {
+            tracing::warn!(
+                "Could not parse LLM score from {:?}, using fallback {}",
+                reply,
+                fallback_score
+            );
+            fallback_score.clamp(0.0, 1.0)
+        }
+    }
+}
 
-    if magnitude_a == 0.0 || magnitude_b == 0.0 {
-        return 0.0;
+/// Extract the first decimal number (e.g. `0.85`, `1`, `.5`) from `text`
+fn first_float(text: &str) -> Option<f32> {
+    let chars: Vec<char> = text.chars().collect();
+    let start = chars.iter().enumerate().position(|(i, c)| {
+        c.is_ascii_digit()
+            || (*c == '.' && chars.get(i + 1).is_some_and(|next| next.is_ascii_digit()))
+    })?;
+
+    let mut number = String::new();
+    let mut seen_dot = false;
+    for &c in &chars[start..] {
+        if c.is_ascii_digit() {
+            number.push(c);
+        } else if c == '.' && !seen_dot {
+            seen_dot = true;
+            number.push(c);
+        } else {
+            break;
+        }
     }
 
-    dot_product / (magnitude_a * magnitude_b)
+    number.trim_end_matches('.').parse().ok()
 }
 
 // ---------------------------------------------------------------------------
@@ -216,15 +516,17 @@ impl AIValidator for FallbackValidator {
             return Ok(ValidationResult {
                 score: 1.0,
                 method: ValidationMethod::Exact,
+                explanation: None,
+                threshold: None,
             });
         }
 
-        // Word-overlap (Jaccard)
-        let expected_words: std::collections::HashSet<&str> = expected.split_whitespace().collect();
-        let actual_words: std::collections::HashSet<&str> = actual.split_whitespace().collect();
+        // Token-overlap (Jaccard)
+        let expected_tokens = tokenize(&expected);
+        let actual_tokens = tokenize(&actual);
 
-        let intersection = expected_words.intersection(&actual_words).count() as f32;
-        let union = expected_words.union(&actual_words).count() as f32;
+        let intersection = expected_tokens.intersection(&actual_tokens).count() as f32;
+        let union = expected_tokens.union(&actual_tokens).count() as f32;
 
         let jaccard = if union > 0.0 {
             intersection / union
@@ -236,24 +538,126 @@ impl AIValidator for FallbackValidator {
         Ok(ValidationResult {
             score: jaccard,
             method: ValidationMethod::Exact, // closest approximation
+            explanation: None,
+            threshold: None,
         })
     }
 }
 
+/// Grades with `primary` and falls back to `fallback` when it errors, so an
+/// OpenAI outage degrades reviews to word-overlap scoring instead of failing
+pub struct ChainValidator {
+    primary: Arc<dyn AIValidator>,
+    fallback: Arc<dyn AIValidator>,
+}
+
+impl ChainValidator {
+    pub fn new(primary: Arc<dyn AIValidator>, fallback: Arc<dyn AIValidator>) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+#[async_trait]
+impl AIValidator for ChainValidator {
+    async fn validate(
+        &self,
+        expected_answer: &str,
+        user_answer: &str,
+        question_context: &str,
+    ) -> Result<ValidationResult> {
+        match self
+            .primary
+            .validate(expected_answer, user_answer, question_context)
+            .await
+        {
+            Ok(result) => Ok(result),
+            Err(e) => {
+                tracing::warn!("Primary validator failed, using fallback: {:#}", e);
+                self.fallback
+                    .validate(expected_answer, user_answer, question_context)
+                    .await
+            }
+        }
+    }
+}
+
+/// Returns true for characters of scripts written without spaces between words
+/// (Han ideographs, Hiragana, Katakana and CJK punctuation).
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{303F}'   // CJK symbols and punctuation
+        | '\u{3040}'..='\u{30FF}' // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{FF66}'..='\u{FF9F}' // Half-width Katakana
+    )
+}
+
+/// Split text into comparison tokens.
+///
+/// Space-delimited text is split on whitespace. Runs of CJK characters have no
+/// word boundaries, so they are split into overlapping character bigrams
+/// (a single-character run becomes one unigram).
+fn tokenize(text: &str) -> std::collections::HashSet<String> {
+    let mut tokens = std::collections::HashSet::new();
+
+    for word in text.split_whitespace() {
+        let mut run: Vec<char> = Vec::new();
+        let mut latin = String::new();
+
+        for c in word.chars() {
+            if is_cjk(c) {
+                if !latin.is_empty() {
+                    tokens.insert(std::mem::take(&mut latin));
+                }
+                run.push(c);
+            } else {
+                push_cjk_bigrams(&mut tokens, &mut run);
+                latin.push(c);
+            }
+        }
+
+        push_cjk_bigrams(&mut tokens, &mut run);
+        if !latin.is_empty() {
+            tokens.insert(latin);
+        }
+    }
+
+    tokens
+}
+
+fn push_cjk_bigrams(tokens: &mut std::collections::HashSet<String>, run: &mut Vec<char>) {
+    match run.len() {
+        0 => {}
+        1 => {
+            tokens.insert(run[0].to_string());
+        }
+        _ => {
+            for pair in run.windows(2) {
+                tokens.insert(pair.iter().collect());
+            }
+        }
+    }
+    run.clear();
+}
+
 #[async_trait]
 impl EmbeddingService for OpenAIValidator {
     async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
         let request = CreateEmbeddingRequestArgs::default()
             .model(&self.embedding_model)
-            .input(text)
+            .input(truncate_chars(text, self.max_answer_chars).0.into_owned())
             .build()?;
 
-        let response = self
-            .client
-            .embeddings()
-            .create(request)
-            .await
-            .context("Failed to generate embedding")?;
+        let response = deadline::run(async {
+            self.client
+                .embeddings()
+                .create(request)
+                .await
+                .context("Failed to generate embedding")
+        })
+        .await?;
 
         if response.data.is_empty() {
             anyhow::bail!("No embedding returned from API");
@@ -274,14 +678,340 @@ impl EmbeddingService for FallbackValidator {
 mod tests {
     use super::*;
 
+    struct FailingValidator;
+
+    #[async_trait]
+    impl AIValidator for FailingValidator {
+        async fn validate(&self, _: &str, _: &str, _: &str) -> Result<ValidationResult> {
+            anyhow::bail!("API unavailable")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chain_falls_back_when_primary_fails() {
+        let chain = ChainValidator::new(Arc::new(FailingValidator), Arc::new(FallbackValidator));
+
+        let result = chain.validate("hola", "hola", "Translate").await.unwrap();
+
+        assert_eq!(result.score, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_openai_call_is_cancelled_at_request_deadline() {
+        // Accepts connections but never answers, like a stalled upstream
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        let validator = OpenAIValidator::local(format!("http://{}/v1", addr), None);
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(100);
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            deadline::scope(
+                Some(deadline),
+                validator.validate("casa", "la casa", "Translate: house"),
+            ),
+        )
+        .await
+        .expect("validator ignored the request deadline");
+
+        assert!(deadline::is_exceeded(&result.unwrap_err()));
+    }
+
+    /// OpenAI-compatible server answering every request with `body`
+    async fn serve_json(body: &'static str) -> String {
+        serve_routes(vec![("/", body)]).await
+    }
+
+    /// OpenAI-compatible server answering with the body of the first route
+    /// whose pattern appears in the request path
+    async fn serve_routes(routes: Vec<(&'static str, &'static str)>) -> String {
+        serve_recording(routes).await.0
+    }
+
+    /// Like [`serve_routes`], also returning every request received
+    async fn serve_recording(
+        routes: Vec<(&'static str, &'static str)>,
+    ) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = received.clone();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let routes = routes.clone();
+                let log = log.clone();
+                tokio::spawn(async move {
+                    // Read the headers and the body they announce
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    loop {
+                        let n = socket.read(&mut buf).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                        let text = String::from_utf8_lossy(&request);
+                        if let Some(end) = text.find("\r\n\r\n") {
+                            let length = text[..end]
+                                .lines()
+                                .find_map(|l| {
+                                    l.to_ascii_lowercase()
+                                        .strip_prefix("content-length:")
+                                        .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                                })
+                                .unwrap_or(0);
+                            if request.len() >= end + 4 + length {
+                                break;
+                            }
+                        }
+                    }
+                    let text = String::from_utf8_lossy(&request);
+                    log.lock().unwrap().push(text.to_string());
+                    let path = text.split_whitespace().nth(1).unwrap_or("/");
+                    let body = routes
+                        .iter()
+                        .find(|(pattern, _)| path.contains(pattern))
+                        .map_or("{}", |(_, body)| body);
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (format!("http://{}/v1", addr), received)
+    }
+
+    #[tokio::test]
+    async fn test_embedding_result_reports_threshold() {
+        let base_url = serve_json(
+            r#"{"object":"list","model":"test","data":[
+                {"object":"embedding","index":0,"embedding":[1.0,0.0]},
+                {"object":"embedding","index":1,"embedding":[0.9,0.1]}
+            ],"usage":{"prompt_tokens":2,"total_tokens":2}}"#,
+        )
+        .await;
+        let validator = OpenAIValidator::local(base_url, None).with_embedding_threshold(0.8);
+
+        let result = validator
+            .validate("house", "home", "Translate: casa")
+            .await
+            .unwrap();
+
+        assert!(matches!(result.method, ValidationMethod::Embedding));
+        assert!(result.score >= 0.8);
+        assert_eq!(result.threshold, Some(0.8));
+    }
+
+    #[tokio::test]
+    async fn test_blended_score_lies_between_embedding_and_llm_scores() {
+        // Borderline similarity of about 0.71 and an LLM score of 0.95
+        let base_url = serve_routes(vec![
+            (
+                "/embeddings",
+                r#"{"object":"list","model":"test","data":[
+                    {"object":"embedding","index":0,"embedding":[1.0,0.0]},
+                    {"object":"embedding","index":1,"embedding":[0.7,0.7]}
+                ],"usage":{"prompt_tokens":2,"total_tokens":2}}"#,
+            ),
+            (
+                "/chat/completions",
+                r#"{"id":"chat","object":"chat.completion","created":0,"model":"test",
+                    "choices":[{"index":0,"finish_reason":"stop",
+                        "message":{"role":"assistant","content":"0.95"}}]}"#,
+            ),
+        ])
+        .await;
+        let embedding_score = cosine_similarity(&[1.0, 0.0], &[0.7, 0.7]);
+
+        let llm_only = OpenAIValidator::local(base_url.clone(), None)
+            .validate("house", "home", "Translate: casa")
+            .await
+            .unwrap();
+        assert!(matches!(llm_only.method, ValidationMethod::Llm));
+        assert_eq!(llm_only.score, 0.95);
+
+        let blended = OpenAIValidator::local(base_url, None)
+            .with_blended_scoring(BlendWeights {
+                embedding: 0.5,
+                llm: 0.5,
+            })
+            .validate("house", "home", "Translate: casa")
+            .await
+            .unwrap();
+        assert!(matches!(blended.method, ValidationMethod::Blended));
+        assert!(blended.score > embedding_score && blended.score < 0.95);
+        assert!((blended.score - (embedding_score + 0.95) / 2.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_long_answer_is_truncated_before_llm_sees_it() {
+        let (base_url, received) = serve_recording(vec![
+            (
+                "/embeddings",
+                r#"{"object":"list","model":"test","data":[
+                    {"object":"embedding","index":0,"embedding":[1.0,0.0]},
+                    {"object":"embedding","index":1,"embedding":[0.0,1.0]}
+                ],"usage":{"prompt_tokens":2,"total_tokens":2}}"#,
+            ),
+            (
+                "/chat/completions",
+                r#"{"id":"chat","object":"chat.completion","created":0,"model":"test",
+                    "choices":[{"index":0,"finish_reason":"stop",
+                        "message":{"role":"assistant","content":"0.4"}}]}"#,
+            ),
+        ])
+        .await;
+        let essay = format!("{} END_OF_ESSAY", "la casa es grande ".repeat(100));
+
+        OpenAIValidator::local(base_url, None)
+            .with_max_answer_chars(Some(50))
+            .validate("house", &essay, "Translate: casa")
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        let chat = received
+            .iter()
+            .find(|request| request.contains("/chat/completions"))
+            .expect("LLM was not called");
+        assert!(chat.contains(&essay[..50]));
+        assert!(!chat.contains(&essay[..51]));
+        assert!(chat.contains("truncated"));
+        assert!(received
+            .iter()
+            .all(|request| !request.contains("END_OF_ESSAY")));
+    }
+
     #[test]
-    fn test_cosine_similarity() {
-        let a = vec![1.0, 0.0, 0.0];
-        let b = vec![1.0, 0.0, 0.0];
-        assert!((cosine_similarity(&a, &b) - 1.0).abs() < 0.001);
-
-        let c = vec![1.0, 0.0];
-        let d = vec![0.0, 1.0];
-        assert!((cosine_similarity(&c, &d) - 0.0).abs() < 0.001);
+    fn test_truncate_chars_respects_char_boundaries() {
+        assert_eq!(truncate_chars("año nuevo", Some(2)), ("añ".into(), true));
+        assert_eq!(truncate_chars("año", Some(3)), ("año".into(), false));
+        assert_eq!(truncate_chars("año", None), ("año".into(), false));
+    }
+
+    #[test]
+    fn test_blend_weights() {
+        let weights = BlendWeights {
+            embedding: 1.0,
+            llm: 3.0,
+        };
+        for (embedding, llm) in [(0.6, 0.9), (0.8, 0.2), (0.7, 0.7)] {
+            let score = weights.blend(embedding, llm);
+            assert!(score >= f32::min(embedding, llm) && score <= f32::max(embedding, llm));
+        }
+        assert!((weights.blend(0.6, 1.0) - 0.9).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_claude_response_parses_to_clamped_score() {
+        let body: serde_json::Value = serde_json::from_str(
+            r#"{"id":"msg_1","type":"message","role":"assistant","model":"claude",
+                "content":[{"type":"text","text":"0.85"}],
+                "stop_reason":"end_turn"}"#,
+        )
+        .unwrap();
+        assert_eq!(score_from_claude_response(&body).unwrap(), 0.85);
+
+        let too_high = serde_json::json!({"content": [{"type": "text", "text": "1.5"}]});
+        assert_eq!(score_from_claude_response(&too_high).unwrap(), 1.0);
+        assert!(score_from_claude_response(&serde_json::json!({"content": []})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_claude_validator_grades_with_messages_api() {
+        let base_url = serve_routes(vec![(
+            "/messages",
+            r#"{"id":"msg_1","type":"message","role":"assistant","model":"claude",
+                "content":[{"type":"text","text":"Score: 0.7"}]}"#,
+        )])
+        .await;
+        let validator =
+            ClaudeValidator::new("sk-ant-test".to_string(), None).with_api_base(base_url);
+
+        let result = validator
+            .validate("house", "home", "Translate: casa")
+            .await
+            .unwrap();
+
+        assert!(matches!(result.method, ValidationMethod::Llm));
+        assert_eq!(result.score, 0.7);
+    }
+
+    #[test]
+    fn test_llm_score_plain_number() {
+        assert_eq!(score_from_llm_reply("0.85", 0.0), 0.85);
+    }
+
+    #[test]
+    fn test_llm_score_with_prefix_and_scale() {
+        assert_eq!(score_from_llm_reply("Score: 0.7", 0.0), 0.7);
+        assert_eq!(score_from_llm_reply("0.8/1.0", 0.0), 0.8);
+    }
+
+    #[test]
+    fn test_llm_score_garbage_falls_back_to_embedding_score() {
+        assert_eq!(score_from_llm_reply("I cannot grade this.", 0.65), 0.65);
+        assert_eq!(score_from_llm_reply("", 0.4), 0.4);
+    }
+
+    #[test]
+    fn test_tokenize_keeps_whitespace_words() {
+        let tokens = tokenize("the cat sat");
+        assert_eq!(tokens.len(), 3);
+        assert!(tokens.contains("cat"));
+    }
+
+    #[test]
+    fn test_tokenize_splits_cjk_into_bigrams() {
+        let tokens = tokenize("東京都");
+        assert_eq!(tokens.len(), 2);
+        assert!(tokens.contains("東京"));
+        assert!(tokens.contains("京都"));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_scores_similar_japanese_above_dissimilar() {
+        let validator = FallbackValidator;
+
+        let similar = validator
+            .validate("私は毎朝コーヒーを飲みます", "私は毎朝お茶を飲みます", "")
+            .await
+            .unwrap();
+        let dissimilar = validator
+            .validate("私は毎朝コーヒーを飲みます", "昨日は雨が降った", "")
+            .await
+            .unwrap();
+
+        assert!(similar.score > 0.3);
+        assert!(dissimilar.score < 0.1);
+        assert!(similar.score > dissimilar.score + 0.2);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_scores_similar_chinese_above_dissimilar() {
+        let validator = FallbackValidator;
+
+        let similar = validator
+            .validate("我喜欢学习中文", "我很喜欢学习中文", "")
+            .await
+            .unwrap();
+        let dissimilar = validator
+            .validate("我喜欢学习中文", "今天天气很好", "")
+            .await
+            .unwrap();
+
+        assert!(similar.score > 0.5);
+        assert!(similar.score > dissimilar.score + 0.3);
     }
 }
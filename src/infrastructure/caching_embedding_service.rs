@@ -0,0 +1,133 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use moka::future::Cache;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use crate::domain::ports::EmbeddingService;
+
+/// Default number of embeddings kept in memory
+pub const DEFAULT_EMBEDDING_CACHE_SIZE: u64 = 10_000;
+
+/// Hits and misses of a [`CachingEmbeddingService`] since startup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EmbeddingCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Bounded in-memory cache in front of an `EmbeddingService`.
+///
+/// Keys are the text lowercased with whitespace collapsed, so repeated
+/// answers ("yes", "Sí ") are embedded once. Past the capacity, the least
+/// recently (and least often) used entries are evicted. Failed calls are not
+/// cached.
+pub struct CachingEmbeddingService {
+    inner: Arc<dyn EmbeddingService>,
+    cache: Cache<String, Arc<Vec<f32>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CachingEmbeddingService {
+    pub fn new(inner: Arc<dyn EmbeddingService>, capacity: u64) -> Self {
+        Self {
+            inner,
+            cache: Cache::new(capacity),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Capacity from `EMBEDDING_CACHE_SIZE` (default 10 000).
+    /// Returns `None` when set to 0, meaning caching is disabled.
+    pub fn capacity_from_env() -> Option<u64> {
+        let capacity = std::env::var("EMBEDDING_CACHE_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_EMBEDDING_CACHE_SIZE);
+        (capacity > 0).then_some(capacity)
+    }
+
+    pub fn stats(&self) -> EmbeddingCacheStats {
+        EmbeddingCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn cache_key(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[async_trait]
+impl EmbeddingService for CachingEmbeddingService {
+    async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        let key = cache_key(text);
+        if let Some(embedding) = self.cache.get(&key).await {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(embedding.as_ref().clone());
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let embedding = self.inner.generate_embedding(text).await?;
+        self.cache.insert(key, Arc::new(embedding.clone())).await;
+        Ok(embedding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[derive(Default)]
+    struct CountingEmbeddingService {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingService for CountingEmbeddingService {
+        async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if text.contains("fail") {
+                anyhow::bail!("embedding service unavailable");
+            }
+            Ok(vec![text.len() as f32])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_text_is_served_from_cache() {
+        let inner = Arc::new(CountingEmbeddingService::default());
+        let service = CachingEmbeddingService::new(inner.clone(), 100);
+
+        let first = service.generate_embedding("sí").await.unwrap();
+        let second = service.generate_embedding("  Sí ").await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(service.stats(), EmbeddingCacheStats { hits: 1, misses: 1 });
+
+        service.generate_embedding("yes").await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_not_cached() {
+        let inner = Arc::new(CountingEmbeddingService::default());
+        let service = CachingEmbeddingService::new(inner.clone(), 100);
+
+        assert!(service.generate_embedding("fail").await.is_err());
+        assert!(service.generate_embedding("fail").await.is_err());
+
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(service.stats().misses, 2);
+    }
+}
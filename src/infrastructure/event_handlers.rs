@@ -2,7 +2,13 @@ use chrono::Utc;
 use std::sync::Arc;
 
 use crate::{
-    domain::repositories::{CardRepository, DeckStatsRepository, UserStatsRepository},
+    domain::{
+        entities::{Deck, UserPreferences},
+        repositories::{
+            CardRepository, DeckRepository, DeckStatsRepository, UserRepository,
+            UserStatsRepository,
+        },
+    },
     shared::event_bus::{DomainEvent, EventHandler},
     AppResult,
 };
@@ -12,6 +18,7 @@ pub struct StatisticsEventHandler {
     user_stats_repo: Arc<dyn UserStatsRepository>,
     deck_stats_repo: Arc<dyn DeckStatsRepository>,
     card_repo: Arc<dyn CardRepository>,
+    user_repo: Option<Arc<dyn UserRepository>>,
 }
 
 impl StatisticsEventHandler {
@@ -24,8 +31,57 @@ impl StatisticsEventHandler {
             user_stats_repo,
             deck_stats_repo,
             card_repo,
+            user_repo: None,
         }
     }
+
+    /// Date reviews by the user's study day (UTC offset and day cutoff hour)
+    /// instead of the UTC calendar day
+    pub fn with_user_repository(mut self, user_repo: Arc<dyn UserRepository>) -> Self {
+        self.user_repo = Some(user_repo);
+        self
+    }
+
+    async fn review_date(&self, user_id: uuid::Uuid) -> AppResult<chrono::NaiveDate> {
+        let preferences = match &self.user_repo {
+            Some(repo) => repo
+                .find_by_id(user_id)
+                .await?
+                .map(|user| user.preferences)
+                .unwrap_or_default(),
+            None => UserPreferences::default(),
+        };
+        Ok(preferences.review_date(Utc::now()))
+    }
+
+    /// Count one review in the user's stats and in its deck's
+    async fn record_review(
+        &self,
+        card_id: uuid::Uuid,
+        user_id: uuid::Uuid,
+        rating: i32,
+    ) -> AppResult<()> {
+        // A review is correct when it was rated Good or Easy. The rating
+        // already reflects the deck's thresholds for typed answers, and is
+        // the user's own grade for self-graded decks, whose scores are on a
+        // different scale
+        let is_correct = rating >= 3;
+
+        // The user's study day, for tracking "days studied"
+        let review_date = self.review_date(user_id).await?;
+
+        // Get the card to find its deck (if any)
+        let deck_id = self
+            .card_repo
+            .find_by_id(card_id)
+            .await?
+            .and_then(|card| card.deck_id);
+
+        // Update user- and deck-level statistics together
+        self.user_stats_repo
+            .update_after_review(user_id, deck_id, is_correct, review_date)
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -35,28 +91,19 @@ impl EventHandler for StatisticsEventHandler {
             DomainEvent::CardReviewed {
                 card_id,
                 user_id,
-                score,
-                rating: _,
+                score: _,
+                rating,
             } => {
-                // Determine if the review was correct (score >= 70%)
-                let is_correct = score >= 0.7;
-
-                // Get current UTC date for tracking "days studied"
-                let review_date = Utc::now().date_naive();
-
-                // Update user-level statistics
-                self.user_stats_repo
-                    .update_after_review(user_id, is_correct, review_date)
-                    .await?;
-
-                // Get the card to find its deck (if any)
-                if let Some(card) = self.card_repo.find_by_id(card_id).await? {
-                    if let Some(deck_id) = card.deck_id {
-                        // Update deck-level statistics
-                        self.deck_stats_repo
-                            .update_after_review(deck_id, is_correct, review_date)
-                            .await?;
-                    }
+                // The review itself is already saved; a failure here only
+                // leaves the stats behind until the event is replayed
+                if let Err(e) = self.record_review(card_id, user_id, rating).await {
+                    tracing::warn!(
+                        "Statistics update failed for user {} after reviewing card {}; the review was kept: {}",
+                        user_id,
+                        card_id,
+                        e
+                    );
+                    return Err(e);
                 }
 
                 tracing::info!(
@@ -76,7 +123,258 @@ impl EventHandler for StatisticsEventHandler {
                     tracing::info!("Deck {} card count incremented", deck_id);
                 }
             }
+            DomainEvent::UserRegistered { .. } => {}
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "statistics"
+    }
+}
+
+/// Name of the default deck created for every new user
+pub const INBOX_DECK_NAME: &str = "Inbox";
+
+/// Event handler that runs onboarding side effects for newly registered users
+pub struct OnboardingEventHandler {
+    deck_repo: Arc<dyn DeckRepository>,
+}
+
+impl OnboardingEventHandler {
+    pub fn new(deck_repo: Arc<dyn DeckRepository>) -> Self {
+        Self { deck_repo }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventHandler for OnboardingEventHandler {
+    async fn handle(&self, event: DomainEvent) -> AppResult<()> {
+        if let DomainEvent::UserRegistered { user_id, email: _ } = event {
+            let inbox = Deck::new(
+                user_id,
+                INBOX_DECK_NAME.to_string(),
+                Some("Default deck for new cards".to_string()),
+            );
+            self.deck_repo.create(&inbox).await?;
+            tracing::info!("Inbox deck {} created for user {}", inbox.id, user_id);
         }
         Ok(())
     }
+
+    fn name(&self) -> &str {
+        "onboarding"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::{Card, DeckStats, UserStats};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    struct InMemoryUserStatsRepository {
+        stats: Mutex<UserStats>,
+    }
+
+    #[async_trait]
+    impl UserStatsRepository for InMemoryUserStatsRepository {
+        async fn get_or_create(&self, _user_id: Uuid) -> AppResult<UserStats> {
+            Ok(self.stats.lock().unwrap().clone())
+        }
+
+        async fn update_after_review(
+            &self,
+            _user_id: Uuid,
+            _deck_id: Option<Uuid>,
+            is_correct: bool,
+            _review_date: chrono::NaiveDate,
+        ) -> AppResult<()> {
+            let mut stats = self.stats.lock().unwrap();
+            stats.total_reviews += 1;
+            if is_correct {
+                stats.correct_reviews += 1;
+            }
+            Ok(())
+        }
+    }
+
+    struct NoopDeckStatsRepository;
+
+    #[async_trait]
+    impl DeckStatsRepository for NoopDeckStatsRepository {
+        async fn get_or_create(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<DeckStats> {
+            Ok(DeckStats::new(deck_id, user_id))
+        }
+
+        async fn increment_card_count(&self, _deck_id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn decrement_card_count(&self, _deck_id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn add_to_card_count(&self, _deck_id: Uuid, _count: i32) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct EmptyCardRepository;
+
+    #[async_trait]
+    impl CardRepository for EmptyCardRepository {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Card>> {
+            Ok(None)
+        }
+
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockDeckRepository {
+        decks: Mutex<Vec<Deck>>,
+    }
+
+    #[async_trait]
+    impl DeckRepository for MockDeckRepository {
+        async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
+            self.decks.lock().unwrap().push(deck.clone());
+            Ok(deck.id)
+        }
+
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
+            Ok(self
+                .decks
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|d| d.id == id)
+                .cloned())
+        }
+
+        async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Deck>> {
+            Ok(self
+                .decks
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|d| d.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn update(&self, _deck: &Deck) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    async fn review(rating: i32, score: f32) -> UserStats {
+        let user_id = Uuid::new_v4();
+        let stats_repo = Arc::new(InMemoryUserStatsRepository {
+            stats: Mutex::new(UserStats::new(user_id)),
+        });
+        let handler = StatisticsEventHandler::new(
+            stats_repo.clone(),
+            Arc::new(NoopDeckStatsRepository),
+            Arc::new(EmptyCardRepository),
+        );
+
+        handler
+            .handle(DomainEvent::CardReviewed {
+                card_id: Uuid::new_v4(),
+                user_id,
+                score,
+                rating,
+            })
+            .await
+            .unwrap();
+
+        stats_repo.get_or_create(user_id).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_self_graded_good_review_counts_as_correct() {
+        // Self-grade scores are (rating - 1) / 3, so Good is 0.667
+        let stats = review(3, 2.0 / 3.0).await;
+
+        assert_eq!(stats.total_reviews, 1);
+        assert_eq!(stats.correct_reviews, 1);
+    }
+
+    #[tokio::test]
+    async fn test_hard_review_counts_as_incorrect() {
+        let stats = review(2, 0.6).await;
+
+        assert_eq!(stats.total_reviews, 1);
+        assert_eq!(stats.correct_reviews, 0);
+    }
+
+    #[tokio::test]
+    async fn test_onboarding_creates_inbox_deck() {
+        let repo = Arc::new(MockDeckRepository::default());
+        let handler = OnboardingEventHandler::new(repo.clone());
+        let user_id = Uuid::new_v4();
+
+        handler
+            .handle(DomainEvent::UserRegistered {
+                user_id,
+                email: "new@example.com".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let decks = repo.find_by_user(user_id).await.unwrap();
+        assert_eq!(decks.len(), 1);
+        assert_eq!(decks[0].name, INBOX_DECK_NAME);
+    }
+
+    #[tokio::test]
+    async fn test_onboarding_ignores_other_events() {
+        let repo = Arc::new(MockDeckRepository::default());
+        let handler = OnboardingEventHandler::new(repo.clone());
+
+        handler
+            .handle(DomainEvent::CardCreated {
+                card_id: Uuid::new_v4(),
+                user_id: Uuid::new_v4(),
+                deck_id: None,
+            })
+            .await
+            .unwrap();
+
+        assert!(repo.decks.lock().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,139 @@
+//! Health checks for the detailed health endpoint
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::domain::ports::{ComponentHealth, HealthCheck};
+
+/// Newest schema migration, identified by the column it adds
+/// (`scripts/migrate_add_<name>.sql`)
+const LATEST_MIGRATION: (&str, &str, &str) = ("migrate_add_card_position", "cards", "position");
+
+/// Database connectivity (`SELECT 1`)
+pub struct DatabaseHealthCheck {
+    pool: PgPool,
+}
+
+impl DatabaseHealthCheck {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for DatabaseHealthCheck {
+    fn name(&self) -> &str {
+        "database"
+    }
+
+    fn critical(&self) -> bool {
+        true
+    }
+
+    async fn check(&self) -> ComponentHealth {
+        match sqlx::query("SELECT 1").execute(&self.pool).await {
+            Ok(_) => ComponentHealth::ok(),
+            Err(e) => ComponentHealth::down(e.to_string()),
+        }
+    }
+}
+
+/// Whether the newest migration script has been applied
+pub struct MigrationHealthCheck {
+    pool: PgPool,
+}
+
+impl MigrationHealthCheck {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for MigrationHealthCheck {
+    fn name(&self) -> &str {
+        "migrations"
+    }
+
+    async fn check(&self) -> ComponentHealth {
+        let (migration, table, column) = LATEST_MIGRATION;
+        let applied = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM information_schema.columns \
+             WHERE table_name = $1 AND column_name = $2)",
+        )
+        .bind(table)
+        .bind(column)
+        .fetch_one(&self.pool)
+        .await;
+
+        match applied {
+            Ok(true) => ComponentHealth::ok().with_detail(migration),
+            Ok(false) => ComponentHealth::degraded(format!("{} not applied", migration)),
+            Err(e) => ComponentHealth::down(e.to_string()),
+        }
+    }
+}
+
+/// OpenAI reachability via `GET /v1/models`. Results are cached so frequent
+/// health polling does not hit the API on every request.
+pub struct OpenAIHealthCheck {
+    client: reqwest::Client,
+    api_key: String,
+    ttl: Duration,
+    cached: Mutex<Option<(Instant, ComponentHealth)>>,
+}
+
+impl OpenAIHealthCheck {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(3))
+                .build()
+                .unwrap_or_default(),
+            api_key,
+            ttl: Duration::from_secs(60),
+            cached: Mutex::new(None),
+        }
+    }
+
+    async fn probe(&self) -> ComponentHealth {
+        let response = self
+            .client
+            .get("https://api.openai.com/v1/models")
+            .bearer_auth(&self.api_key)
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => ComponentHealth::ok(),
+            Ok(response) if response.status() == reqwest::StatusCode::UNAUTHORIZED => {
+                ComponentHealth::down("API key rejected")
+            }
+            Ok(response) => ComponentHealth::degraded(format!("HTTP {}", response.status())),
+            Err(e) => ComponentHealth::down(e.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for OpenAIHealthCheck {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    async fn check(&self) -> ComponentHealth {
+        if let Some((checked_at, health)) = self.cached.lock().unwrap().as_ref() {
+            if checked_at.elapsed() < self.ttl {
+                return health.clone();
+            }
+        }
+
+        let health = self.probe().await;
+        *self.cached.lock().unwrap() = Some((Instant::now(), health.clone()));
+        health
+    }
+}
@@ -0,0 +1,255 @@
+//! Embeddings from any HTTP embedding API (Cohere, Voyage, a self-hosted
+//! server, ...) speaking the OpenAI `/embeddings` shape or close to it
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use crate::domain::ports::EmbeddingService;
+use crate::infrastructure::ai_validator::{truncate_chars, DEFAULT_MAX_ANSWER_CHARS};
+use crate::shared::deadline;
+
+/// Where and how to request embeddings. The defaults match the OpenAI
+/// `/embeddings` API; `input_field` and `response_path` adapt it to providers
+/// that name things differently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddingProvider {
+    /// API root; requests go to `{base_url}/embeddings`
+    pub base_url: String,
+    pub model: String,
+    /// Sent as `Authorization: Bearer <key>` when set
+    pub api_key: Option<String>,
+    /// Request field holding the text to embed
+    pub input_field: String,
+    /// JSON pointer to the vector in the response
+    pub response_path: String,
+}
+
+impl EmbeddingProvider {
+    pub const DEFAULT_INPUT_FIELD: &'static str = "input";
+    pub const DEFAULT_RESPONSE_PATH: &'static str = "/data/0/embedding";
+
+    pub fn new(base_url: String, model: String) -> Self {
+        Self {
+            base_url,
+            model,
+            api_key: None,
+            input_field: Self::DEFAULT_INPUT_FIELD.to_string(),
+            response_path: Self::DEFAULT_RESPONSE_PATH.to_string(),
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
+
+    /// Name of the request field holding the text (e.g. `texts` for Cohere)
+    pub fn with_input_field(mut self, input_field: String) -> Self {
+        self.input_field = input_field;
+        self
+    }
+
+    /// JSON pointer to the vector (e.g. `/embeddings/0` for Cohere)
+    pub fn with_response_path(mut self, response_path: String) -> Self {
+        self.response_path = response_path;
+        self
+    }
+
+    /// Reads EMBEDDING_BASE_URL, EMBEDDING_MODEL, EMBEDDING_API_KEY,
+    /// EMBEDDING_INPUT_FIELD and EMBEDDING_RESPONSE_PATH. Returns `None`
+    /// when EMBEDDING_BASE_URL is unset; a base URL without a model is an
+    /// error.
+    pub fn from_env() -> Result<Option<Self>> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let Some(base_url) = var("EMBEDDING_BASE_URL") else {
+            return Ok(None);
+        };
+        let model = var("EMBEDDING_MODEL")
+            .context("EMBEDDING_BASE_URL requires EMBEDDING_MODEL to be set")?;
+        let mut provider = Self::new(base_url, model).with_api_key(var("EMBEDDING_API_KEY"));
+        if let Some(input_field) = var("EMBEDDING_INPUT_FIELD") {
+            provider = provider.with_input_field(input_field);
+        }
+        if let Some(response_path) = var("EMBEDDING_RESPONSE_PATH") {
+            provider = provider.with_response_path(response_path);
+        }
+        Ok(Some(provider))
+    }
+}
+
+/// `EmbeddingService` backed by an [`EmbeddingProvider`].
+///
+/// Each text is sent on its own as a one-element array, which every
+/// OpenAI-compatible server accepts. Calls are cancelled once the current
+/// request deadline passes.
+pub struct HttpEmbeddingService {
+    client: reqwest::Client,
+    provider: EmbeddingProvider,
+    max_answer_chars: Option<usize>,
+}
+
+impl HttpEmbeddingService {
+    pub fn new(provider: EmbeddingProvider) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            provider,
+            max_answer_chars: Some(DEFAULT_MAX_ANSWER_CHARS),
+        }
+    }
+
+    /// Characters of each text sent to the provider (default 500); `None`
+    /// sends them whole
+    pub fn with_max_answer_chars(mut self, max_answer_chars: Option<usize>) -> Self {
+        self.max_answer_chars = max_answer_chars;
+        self
+    }
+}
+
+#[async_trait]
+impl EmbeddingService for HttpEmbeddingService {
+    async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        let provider = &self.provider;
+        let mut request = serde_json::json!({ "model": provider.model });
+        request[provider.input_field.as_str()] =
+            serde_json::json!([truncate_chars(text, self.max_answer_chars).0]);
+
+        let body: serde_json::Value = deadline::run(async {
+            let mut builder = self.client.post(format!(
+                "{}/embeddings",
+                provider.base_url.trim_end_matches('/')
+            ));
+            if let Some(api_key) = &provider.api_key {
+                builder = builder.bearer_auth(api_key);
+            }
+            let response = builder
+                .json(&request)
+                .send()
+                .await
+                .context("Embedding request failed")?
+                .error_for_status()
+                .context("Embedding request rejected")?;
+            Ok(response.json().await?)
+        })
+        .await?;
+
+        embedding_from_response(&body, &provider.response_path)
+    }
+}
+
+/// The vector at `pointer` in `body`
+fn embedding_from_response(body: &serde_json::Value, pointer: &str) -> Result<Vec<f32>> {
+    let values = body
+        .pointer(pointer)
+        .and_then(|v| v.as_array())
+        .with_context(|| format!("No embedding at {} in the response", pointer))?;
+    values
+        .iter()
+        .map(|v| v.as_f64().map(|x| x as f32))
+        .collect::<Option<Vec<f32>>>()
+        .context("Embedding contains a non-numeric value")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Answers every request with `body`, recording the requests received
+    async fn serve(body: &'static str) -> (String, Arc<Mutex<Vec<String>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = received.clone();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                // Read until the announced body has arrived
+                loop {
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length = text[..end]
+                            .lines()
+                            .find_map(|l| {
+                                l.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                            })
+                            .unwrap_or(0);
+                        if request.len() >= end + 4 + length {
+                            break;
+                        }
+                    }
+                }
+                log.lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&request).to_string());
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (format!("http://{}/v1", addr), received)
+    }
+
+    #[tokio::test]
+    async fn test_openai_compatible_response_is_parsed() {
+        let (base_url, received) = serve(
+            r#"{"object":"list","model":"nomic","data":[
+                {"object":"embedding","index":0,"embedding":[0.25,-0.5,1.0]}]}"#,
+        )
+        .await;
+        let service = HttpEmbeddingService::new(
+            EmbeddingProvider::new(base_url, "nomic".to_string())
+                .with_api_key(Some("secret".to_string())),
+        );
+
+        let embedding = service.generate_embedding("hola").await.unwrap();
+
+        assert_eq!(embedding, vec![0.25, -0.5, 1.0]);
+        let request = received.lock().unwrap()[0].clone();
+        assert!(request.starts_with("POST /v1/embeddings "));
+        assert!(request
+            .to_lowercase()
+            .contains("authorization: bearer secret"));
+        assert!(request.contains(r#""input":["hola"]"#));
+        assert!(request.contains(r#""model":"nomic""#));
+    }
+
+    #[tokio::test]
+    async fn test_custom_field_names() {
+        let (base_url, received) = serve(r#"{"id":"x","embeddings":[[1.0,2.0]]}"#).await;
+        let service = HttpEmbeddingService::new(
+            EmbeddingProvider::new(base_url, "embed-multilingual-v3.0".to_string())
+                .with_input_field("texts".to_string())
+                .with_response_path("/embeddings/0".to_string()),
+        );
+
+        let embedding = service.generate_embedding("hola").await.unwrap();
+
+        assert_eq!(embedding, vec![1.0, 2.0]);
+        let request = received.lock().unwrap()[0].clone();
+        assert!(request.contains(r#""texts":["hola"]"#));
+        assert!(!request.to_lowercase().contains("authorization:"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_vector_is_an_error() {
+        let (base_url, _) = serve(r#"{"data":[]}"#).await;
+        let service =
+            HttpEmbeddingService::new(EmbeddingProvider::new(base_url, "model".to_string()));
+
+        let err = service.generate_embedding("hola").await.unwrap_err();
+
+        assert!(err.to_string().contains("/data/0/embedding"));
+    }
+}
@@ -0,0 +1,279 @@
+//! Proxy for images and audio linked from cards
+//!
+//! Only URLs signed by `MediaSigner` are fetched, so the endpoint can't be
+//! used to relay arbitrary traffic. Redirects are not followed and host names
+//! that resolve to internal addresses are refused. Responses are size- and
+//! type-limited and cached for a short while.
+
+use bytes::Bytes;
+use moka::future::Cache;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use crate::shared::{
+    error::{AppError, AppResult},
+    media_signature::MediaSigner,
+};
+
+/// Largest media file served when `MEDIA_PROXY_MAX_BYTES` is unset
+pub const DEFAULT_MAX_BYTES: usize = 5 * 1024 * 1024;
+const CACHE_TTL: Duration = Duration::from_secs(300);
+/// Upper bound on the memory held by cached media
+const CACHE_CAPACITY_BYTES: u64 = 64 * 1024 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A fetched media file
+#[derive(Debug, Clone)]
+pub struct ProxiedMedia {
+    pub content_type: String,
+    pub body: Bytes,
+}
+
+/// Fetches signed media URLs on behalf of clients
+pub struct MediaProxy {
+    signer: MediaSigner,
+    client: reqwest::Client,
+    max_bytes: usize,
+    cache: Cache<String, ProxiedMedia>,
+}
+
+impl MediaProxy {
+    pub fn new(signer: MediaSigner) -> Self {
+        Self {
+            signer,
+            // A redirect or an HTTP proxy would reach a host `check_target`
+            // never saw, and the resolver re-checks every address connected to
+            client: reqwest::Client::builder()
+                .timeout(FETCH_TIMEOUT)
+                .redirect(reqwest::redirect::Policy::none())
+                .no_proxy()
+                .dns_resolver(PublicResolver)
+                .build()
+                .expect("Failed to create HTTP client"),
+            max_bytes: DEFAULT_MAX_BYTES,
+            cache: Cache::builder()
+                .time_to_live(CACHE_TTL)
+                .weigher(|_, media: &ProxiedMedia| media.body.len().try_into().unwrap_or(u32::MAX))
+                .max_capacity(CACHE_CAPACITY_BYTES)
+                .build(),
+        }
+    }
+
+    /// Proxy keyed with `MEDIA_PROXY_SECRET` and limited to
+    /// `MEDIA_PROXY_MAX_BYTES`; `None` when no secret is configured
+    pub fn from_env() -> Option<Self> {
+        let max_bytes = std::env::var("MEDIA_PROXY_MAX_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_BYTES);
+        MediaSigner::from_env().map(|signer| Self::new(signer).with_max_bytes(max_bytes))
+    }
+
+    /// Largest response body that is passed through
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Signer used to mint the URLs this proxy accepts
+    pub fn signer(&self) -> &MediaSigner {
+        &self.signer
+    }
+
+    /// Fetch `url` after checking its signature. A missing or invalid
+    /// signature is an `AuthorizationError`; upstream failures and media that
+    /// is too large or not an image/audio file are `ExternalApiError`s.
+    pub async fn fetch(&self, url: &str, signature: Option<&str>) -> AppResult<ProxiedMedia> {
+        if !signature.is_some_and(|sig| self.signer.verify(url, sig)) {
+            return Err(AppError::AuthorizationError(
+                "Invalid media signature".to_string(),
+            ));
+        }
+        check_target(url)?;
+
+        if let Some(media) = self.cache.get(url).await {
+            return Ok(media);
+        }
+        let media = self.download(url).await?;
+        self.cache.insert(url.to_string(), media.clone()).await;
+        Ok(media)
+    }
+
+    async fn download(&self, url: &str) -> AppResult<ProxiedMedia> {
+        let mut response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalApiError(format!("Media fetch failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(AppError::ExternalApiError(format!(
+                "Media fetch failed with status {}",
+                response.status()
+            )));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        if !is_allowed_content_type(&content_type) {
+            return Err(AppError::ExternalApiError(format!(
+                "Unsupported media type {:?}",
+                content_type
+            )));
+        }
+        let too_large =
+            || AppError::ExternalApiError(format!("Media exceeds {} bytes", self.max_bytes));
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_bytes as u64)
+        {
+            return Err(too_large());
+        }
+
+        // Read chunk by chunk so a missing or lying Content-Length can't
+        // make us buffer more than the limit
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| AppError::ExternalApiError(format!("Media fetch failed: {}", e)))?
+        {
+            if body.len() + chunk.len() > self.max_bytes {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(ProxiedMedia {
+            content_type,
+            body: body.into(),
+        })
+    }
+}
+
+/// Image and audio types, except SVG, which can carry scripts
+fn is_allowed_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    let mime = mime.to_ascii_lowercase();
+    (mime.starts_with("image/") && mime != "image/svg+xml") || mime.starts_with("audio/")
+}
+
+/// DNS resolver that drops internal addresses, so a public-looking host name
+/// can't point the proxy at the private network
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0)).await?;
+            let public: Vec<SocketAddr> = addrs.filter(|a| !is_internal_ip(a.ip())).collect();
+            if public.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            Ok(Box::new(public.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Reject non-http(s) URLs and hosts on the loopback/private network, which
+/// a card author could otherwise reach through a signed link
+fn check_target(url: &str) -> AppResult<()> {
+    let invalid = || AppError::ValidationError("Unsupported media URL".to_string());
+    let parsed = reqwest::Url::parse(url).map_err(|_| invalid())?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid());
+    }
+    let host = parsed.host_str().unwrap_or_default();
+    let internal = match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        Ok(ip) => is_internal_ip(ip),
+        Err(_) => {
+            host.is_empty()
+                || host.eq_ignore_ascii_case("localhost")
+                || host.ends_with(".localhost")
+        }
+    };
+    if internal {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+fn is_internal_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+        }
+        IpAddr::V6(ip) => {
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.to_ipv4_mapped().is_some_and(|v4| is_internal_ip(IpAddr::V4(v4)))
+                // unique local fc00::/7 and link-local fe80::/10
+                || (ip.segments()[0] & 0xfe00) == 0xfc00
+                || (ip.segments()[0] & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unsigned_or_tampered_urls_are_forbidden() {
+        let proxy = MediaProxy::new(MediaSigner::new("secret"));
+        let url = "https://example.com/cat.png";
+        let sig = proxy.signer().sign(url);
+
+        let unsigned = proxy.fetch(url, None).await;
+        assert!(matches!(unsigned, Err(AppError::AuthorizationError(_))));
+
+        let tampered = proxy.fetch("https://example.com/dog.png", Some(&sig)).await;
+        assert!(matches!(tampered, Err(AppError::AuthorizationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_signed_internal_targets_are_rejected() {
+        let proxy = MediaProxy::new(MediaSigner::new("secret"));
+        for url in [
+            "http://localhost/a.png",
+            "http://127.0.0.1/a.png",
+            "http://169.254.169.254/latest/a.png",
+            "http://[::1]/a.png",
+            "file:///etc/passwd",
+        ] {
+            let sig = proxy.signer().sign(url);
+            let result = proxy.fetch(url, Some(&sig)).await;
+            assert!(
+                matches!(result, Err(AppError::ValidationError(_))),
+                "{} was not rejected",
+                url
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolver_refuses_names_of_internal_hosts() {
+        let name: Name = "localhost".parse().unwrap();
+        assert!(PublicResolver.resolve(name).await.is_err());
+    }
+
+    #[test]
+    fn test_only_image_and_audio_types_are_allowed() {
+        assert!(is_allowed_content_type("image/png"));
+        assert!(is_allowed_content_type("Audio/mpeg; charset=binary"));
+        assert!(!is_allowed_content_type("text/html"));
+        assert!(!is_allowed_content_type("image/svg+xml"));
+        assert!(!is_allowed_content_type(""));
+    }
+}
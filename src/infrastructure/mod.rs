@@ -10,10 +10,19 @@
 //! - O: Extensions without modifying domain
 
 pub mod ai_validator;
+pub mod caching_embedding_service;
 pub mod database;
 pub mod event_handlers;
+pub mod health_checks;
+pub mod http_embedding_service;
+pub mod media_proxy;
 pub mod repositories;
+pub mod validator_factory;
 
 pub use ai_validator::*;
+pub use caching_embedding_service::*;
 pub use event_handlers::*;
+pub use health_checks::*;
+pub use http_embedding_service::*;
 pub use repositories::*;
+pub use validator_factory::*;
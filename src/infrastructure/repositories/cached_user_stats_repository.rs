@@ -0,0 +1,272 @@
+use moka::future::Cache;
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::UserStats, repositories::UserStatsRepository},
+    AppResult,
+};
+
+/// Default time-to-live for cached user statistics
+const DEFAULT_TTL_SECS: u64 = 60;
+
+/// Read-through cache in front of a `UserStatsRepository`.
+///
+/// Entries expire after the configured TTL and are invalidated both before
+/// and after `update_after_review` writes new statistics for the user, so
+/// neither an entry cached before the write nor one a concurrent read cached
+/// from the old row outlives it.
+pub struct CachedUserStatsRepository {
+    inner: Arc<dyn UserStatsRepository>,
+    cache: Cache<Uuid, UserStats>,
+}
+
+impl CachedUserStatsRepository {
+    pub fn new(inner: Arc<dyn UserStatsRepository>, ttl: Duration) -> Self {
+        Self {
+            inner,
+            cache: Cache::builder().time_to_live(ttl).build(),
+        }
+    }
+
+    /// Cache TTL from `USER_STATS_CACHE_TTL_SECS` (default 60s).
+    /// Returns `None` when set to 0, meaning caching is disabled.
+    pub fn ttl_from_env() -> Option<Duration> {
+        let secs = std::env::var("USER_STATS_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_TTL_SECS);
+        (secs > 0).then(|| Duration::from_secs(secs))
+    }
+}
+
+#[async_trait::async_trait]
+impl UserStatsRepository for CachedUserStatsRepository {
+    async fn get_or_create(&self, user_id: Uuid) -> AppResult<UserStats> {
+        if let Some(stats) = self.cache.get(&user_id).await {
+            return Ok(stats);
+        }
+
+        let stats = self.inner.get_or_create(user_id).await?;
+        self.cache.insert(user_id, stats.clone()).await;
+        Ok(stats)
+    }
+
+    async fn update_after_review(
+        &self,
+        user_id: Uuid,
+        deck_id: Option<Uuid>,
+        is_correct: bool,
+        review_date: chrono::NaiveDate,
+    ) -> AppResult<()> {
+        self.cache.invalidate(&user_id).await;
+        let result = self
+            .inner
+            .update_after_review(user_id, deck_id, is_correct, review_date)
+            .await;
+        // A read racing the write may have cached the old row meanwhile
+        self.cache.invalidate(&user_id).await;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{
+            entities::{Card, DeckStats},
+            repositories::{CardRepository, DeckStatsRepository},
+        },
+        infrastructure::StatisticsEventHandler,
+        shared::event_bus::{DomainEvent, EventHandler},
+    };
+    use async_trait::async_trait;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, OnceLock,
+    };
+
+    struct CountingUserStatsRepository {
+        stats: Mutex<UserStats>,
+        reads: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl UserStatsRepository for CountingUserStatsRepository {
+        async fn get_or_create(&self, _user_id: Uuid) -> AppResult<UserStats> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self.stats.lock().unwrap().clone())
+        }
+
+        async fn update_after_review(
+            &self,
+            _user_id: Uuid,
+            _deck_id: Option<Uuid>,
+            is_correct: bool,
+            _review_date: chrono::NaiveDate,
+        ) -> AppResult<()> {
+            let mut stats = self.stats.lock().unwrap();
+            stats.total_reviews += 1;
+            if is_correct {
+                stats.correct_reviews += 1;
+            }
+            Ok(())
+        }
+    }
+
+    /// Plays a reader racing each write: it checks what the cache holds
+    /// mid-write, then caches the row as it was before the write
+    #[derive(Default)]
+    struct RacingReaderRepository {
+        stats: Mutex<Option<UserStats>>,
+        cache: OnceLock<Cache<Uuid, UserStats>>,
+        cached_during_write: Mutex<Option<bool>>,
+    }
+
+    #[async_trait]
+    impl UserStatsRepository for RacingReaderRepository {
+        async fn get_or_create(&self, user_id: Uuid) -> AppResult<UserStats> {
+            Ok(self
+                .stats
+                .lock()
+                .unwrap()
+                .get_or_insert_with(|| UserStats::new(user_id))
+                .clone())
+        }
+
+        async fn update_after_review(
+            &self,
+            user_id: Uuid,
+            _deck_id: Option<Uuid>,
+            _is_correct: bool,
+            _review_date: chrono::NaiveDate,
+        ) -> AppResult<()> {
+            let cache = self.cache.get().unwrap();
+            *self.cached_during_write.lock().unwrap() = Some(cache.get(&user_id).await.is_some());
+            let old = self.get_or_create(user_id).await?;
+            cache.insert(user_id, old).await;
+            self.stats.lock().unwrap().as_mut().unwrap().total_reviews += 1;
+            Ok(())
+        }
+    }
+
+    struct NoopDeckStatsRepository;
+
+    #[async_trait]
+    impl DeckStatsRepository for NoopDeckStatsRepository {
+        async fn get_or_create(&self, deck_id: Uuid, user_id: Uuid) -> AppResult<DeckStats> {
+            Ok(DeckStats::new(deck_id, user_id))
+        }
+
+        async fn increment_card_count(&self, _deck_id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn decrement_card_count(&self, _deck_id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn add_to_card_count(&self, _deck_id: Uuid, _count: i32) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    struct EmptyCardRepository;
+
+    #[async_trait]
+    impl CardRepository for EmptyCardRepository {
+        async fn create(&self, card: &Card) -> AppResult<Uuid> {
+            Ok(card.id)
+        }
+
+        async fn find_by_id(&self, _id: Uuid) -> AppResult<Option<Card>> {
+            Ok(None)
+        }
+
+        async fn find_by_user(&self, _user_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+
+        async fn find_by_deck(&self, _deck_id: Uuid) -> AppResult<Vec<Card>> {
+            Ok(vec![])
+        }
+
+        async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
+            Ok(cards.iter().map(|c| c.id).collect())
+        }
+
+        async fn update(&self, _card: &Card) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn update_embedding(&self, _id: Uuid, _embedding: Vec<f32>) -> AppResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: Uuid) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_value_returned_within_ttl_and_invalidated_on_review() {
+        let user_id = Uuid::new_v4();
+        let inner = Arc::new(CountingUserStatsRepository {
+            stats: Mutex::new(UserStats::new(user_id)),
+            reads: AtomicUsize::new(0),
+        });
+        let cached = Arc::new(CachedUserStatsRepository::new(
+            inner.clone(),
+            Duration::from_secs(60),
+        ));
+
+        let first = cached.get_or_create(user_id).await.unwrap();
+        let second = cached.get_or_create(user_id).await.unwrap();
+        assert_eq!(first.total_reviews, 0);
+        assert_eq!(second.total_reviews, 0);
+        assert_eq!(inner.reads.load(Ordering::SeqCst), 1);
+
+        let handler = StatisticsEventHandler::new(
+            cached.clone(),
+            Arc::new(NoopDeckStatsRepository),
+            Arc::new(EmptyCardRepository),
+        );
+        handler
+            .handle(DomainEvent::CardReviewed {
+                card_id: Uuid::new_v4(),
+                user_id,
+                score: 0.9,
+                rating: 4,
+            })
+            .await
+            .unwrap();
+
+        let after_review = cached.get_or_create(user_id).await.unwrap();
+        assert_eq!(after_review.total_reviews, 1);
+        assert_eq!(inner.reads.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reads_racing_a_write_do_not_keep_stale_stats() {
+        let user_id = Uuid::new_v4();
+        let inner = Arc::new(RacingReaderRepository::default());
+        let cached = CachedUserStatsRepository::new(inner.clone(), Duration::from_secs(60));
+        inner.cache.set(cached.cache.clone()).unwrap();
+        assert_eq!(
+            cached.get_or_create(user_id).await.unwrap().total_reviews,
+            0
+        );
+
+        cached
+            .update_after_review(user_id, None, true, chrono::Utc::now().date_naive())
+            .await
+            .unwrap();
+
+        assert_eq!(*inner.cached_during_write.lock().unwrap(), Some(false));
+        assert_eq!(
+            cached.get_or_create(user_id).await.unwrap().total_reviews,
+            1
+        );
+    }
+}
@@ -1,11 +1,15 @@
+pub mod cached_user_stats_repository;
 pub mod pg_card_repository;
+pub mod pg_dead_letter_repository;
 pub mod pg_deck_repository;
 pub mod pg_review_log_repository;
 pub mod pg_review_repository;
 pub mod pg_stats_repository;
 pub mod pg_user_repository;
 
+pub use cached_user_stats_repository::*;
 pub use pg_card_repository::*;
+pub use pg_dead_letter_repository::*;
 pub use pg_deck_repository::*;
 pub use pg_review_log_repository::*;
 pub use pg_review_repository::*;
@@ -1,7 +1,8 @@
 use crate::{
     domain::{
-        entities::{Card, FsrsState},
-        repositories::CardRepository,
+        embedding::{cosine_similarity, validate_embedding},
+        entities::{Card, EmbeddingStatus, FsrsState},
+        repositories::{CardListOptions, CardOrder, CardRepository, CardSelection},
     },
     AppResult,
 };
@@ -9,16 +10,105 @@ use pgvector::Vector;
 use sqlx::{PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
+const CARD_COLUMNS: &str =
+    "id, user_id, deck_id, question, answer, answer_embedding, embedding_status, fsrs_state, tags, required_keywords, answer_type, suspended, not_before, position, created_at, updated_at";
+
+/// `CARD_COLUMNS` for databases without pgvector: the embedding is always NULL
+const CARD_COLUMNS_WITHOUT_EMBEDDING: &str =
+    "id, user_id, deck_id, question, answer, NULL AS answer_embedding, embedding_status, fsrs_state, tags, required_keywords, answer_type, suspended, not_before, position, created_at, updated_at";
+
+/// Filter for cards outside trashed decks, appended after a `WHERE` clause
+macro_rules! not_in_trash_sql {
+    () => {
+        " AND NOT EXISTS (SELECT 1 FROM decks WHERE decks.id = cards.deck_id AND decks.deleted_at IS NOT NULL)"
+    };
+}
+
+/// SQL for when a card is due; mirrors `Card::due_at`. `GREATEST` ignores a
+/// NULL `not_before`.
+macro_rules! due_at_sql {
+    () => {
+        "GREATEST(COALESCE(((fsrs_state ->> 'last_review')::timestamptz + make_interval(days => COALESCE((fsrs_state ->> 'scheduled_days')::int, 0))), created_at), not_before)"
+    };
+}
+
 /// PostgreSQL Card Repository implementation
 pub struct PgCardRepository {
     pool: PgPool,
+    embeddings: bool,
 }
 
 impl PgCardRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            embeddings: true,
+        }
     }
 
+    /// Whether `answer_embedding` is read and written. Disable it when the
+    /// database has no pgvector extension; embeddings are then dropped.
+    pub fn with_embeddings(mut self, enabled: bool) -> Self {
+        self.embeddings = enabled;
+        self
+    }
+
+    fn columns(&self) -> &'static str {
+        if self.embeddings {
+            CARD_COLUMNS
+        } else {
+            CARD_COLUMNS_WITHOUT_EMBEDDING
+        }
+    }
+
+    /// `INSERT` of a full card row, without the embedding column when
+    /// embeddings are disabled
+    fn insert_query<'a>(&self, card: &'a Card) -> AppResult<QueryBuilder<'a, Postgres>> {
+        let fsrs_json = serde_json::to_value(&card.fsrs_state)?;
+        let columns = if self.embeddings {
+            CARD_COLUMNS.to_string()
+        } else {
+            CARD_COLUMNS.replace("answer_embedding, ", "")
+        };
+
+        let mut query = QueryBuilder::new(format!("INSERT INTO cards ({columns}) VALUES ("));
+        let mut values = query.separated(", ");
+        values
+            .push_bind(card.id)
+            .push_bind(card.user_id)
+            .push_bind(card.deck_id)
+            .push_bind(&card.question)
+            .push_bind(&card.answer);
+        if self.embeddings {
+            values.push_bind(
+                card.answer_embedding
+                    .as_deref()
+                    .map(pg_vector)
+                    .transpose()?,
+            );
+        }
+        // Without pgvector no embedding will ever be stored
+        let embedding_status = if self.embeddings {
+            card.embedding_status
+        } else {
+            EmbeddingStatus::None
+        };
+        values
+            .push_bind(embedding_status.as_str())
+            .push_bind(fsrs_json)
+            .push_bind(&card.tags)
+            .push_bind(&card.required_keywords)
+            .push_bind(card.answer_type.as_str())
+            .push_bind(card.suspended)
+            .push_bind(card.not_before)
+            .push_bind(card.position)
+            .push_bind(card.created_at)
+            .push_bind(card.updated_at);
+        query.push(") RETURNING id");
+        Ok(query)
+    }
+
+    #[allow(clippy::type_complexity)]
     fn map_card_rows(
         rows: Vec<(
             Uuid,
@@ -27,7 +117,14 @@ impl PgCardRepository {
             String,
             String,
             Option<Vector>,
+            String,
             serde_json::Value,
+            Vec<String>,
+            Vec<String>,
+            String,
+            bool,
+            Option<chrono::DateTime<chrono::Utc>>,
+            i32,
             chrono::DateTime<chrono::Utc>,
             chrono::DateTime<chrono::Utc>,
         )>,
@@ -41,7 +138,14 @@ impl PgCardRepository {
             question,
             answer,
             embedding_vec,
+            embedding_status,
             fsrs_state_json,
+            tags,
+            required_keywords,
+            answer_type,
+            suspended,
+            not_before,
+            position,
             created_at,
             updated_at,
         ) in rows
@@ -55,7 +159,14 @@ impl PgCardRepository {
                 question,
                 answer,
                 answer_embedding,
+                embedding_status: embedding_status.parse()?,
                 fsrs_state,
+                tags,
+                required_keywords,
+                answer_type: answer_type.parse()?,
+                suspended,
+                not_before,
+                position,
                 created_at,
                 updated_at,
             });
@@ -68,28 +179,11 @@ impl PgCardRepository {
 #[async_trait::async_trait]
 impl CardRepository for PgCardRepository {
     async fn create(&self, card: &Card) -> AppResult<Uuid> {
-        let fsrs_json = serde_json::to_value(&card.fsrs_state)?;
-        let embedding_vec = card
-            .answer_embedding
-            .as_ref()
-            .map(|v| Vector::from(v.clone()));
-
-        sqlx::query_scalar(
-            "INSERT INTO cards (id, user_id, deck_id, question, answer, answer_embedding, fsrs_state, created_at, updated_at) 
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id",
-        )
-        .bind(card.id)
-        .bind(card.user_id)
-        .bind(card.deck_id)
-        .bind(&card.question)
-        .bind(&card.answer)
-        .bind(embedding_vec)
-        .bind(fsrs_json)
-        .bind(card.created_at)
-        .bind(card.updated_at)
-        .fetch_one(&self.pool)
-        .await
-        .map_err(Into::into)
+        self.insert_query(card)?
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Into::into)
     }
 
     async fn bulk_create(&self, cards: &[Card]) -> AppResult<Vec<Uuid>> {
@@ -97,27 +191,11 @@ impl CardRepository for PgCardRepository {
         let mut ids = Vec::with_capacity(cards.len());
 
         for card in cards {
-            let fsrs_json = serde_json::to_value(&card.fsrs_state)?;
-            let embedding_vec = card
-                .answer_embedding
-                .as_ref()
-                .map(|v| Vector::from(v.clone()));
-
-            let id: Uuid = sqlx::query_scalar(
-                "INSERT INTO cards (id, user_id, deck_id, question, answer, answer_embedding, fsrs_state, created_at, updated_at)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id",
-            )
-            .bind(card.id)
-            .bind(card.user_id)
-            .bind(card.deck_id)
-            .bind(&card.question)
-            .bind(&card.answer)
-            .bind(embedding_vec)
-            .bind(fsrs_json)
-            .bind(card.created_at)
-            .bind(card.updated_at)
-            .fetch_one(&mut *tx)
-            .await?;
+            let id: Uuid = self
+                .insert_query(card)?
+                .build_query_scalar()
+                .fetch_one(&mut *tx)
+                .await?;
 
             ids.push(id);
         }
@@ -127,15 +205,80 @@ impl CardRepository for PgCardRepository {
     }
 
     async fn update_embedding(&self, id: Uuid, embedding: Vec<f32>) -> AppResult<()> {
-        let embedding_vec = Vector::from(embedding);
-        sqlx::query("UPDATE cards SET answer_embedding = $1, updated_at = NOW() WHERE id = $2")
-            .bind(embedding_vec)
+        if !self.embeddings {
+            return Ok(());
+        }
+        let embedding_vec = pg_vector(&embedding)?;
+        // `updated_at` is left alone: it is the review lock token, and the
+        // embedding worker must not make a loaded card look stale
+        sqlx::query(
+            "UPDATE cards SET answer_embedding = $1, embedding_status = 'ready' WHERE id = $2",
+        )
+        .bind(embedding_vec)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_embedding_status(&self, id: Uuid, status: EmbeddingStatus) -> AppResult<()> {
+        if !self.embeddings {
+            return Ok(());
+        }
+        sqlx::query("UPDATE cards SET embedding_status = $1 WHERE id = $2")
+            .bind(status.as_str())
             .bind(id)
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 
+    async fn find_by_embedding_status(
+        &self,
+        user_id: Uuid,
+        statuses: &[EmbeddingStatus],
+    ) -> AppResult<Vec<Card>> {
+        if !self.embeddings || statuses.is_empty() {
+            return Ok(vec![]);
+        }
+        let rows = sqlx::query_as::<
+            _,
+            (
+                Uuid,
+                Uuid,
+                Option<Uuid>,
+                String,
+                String,
+                Option<Vector>,
+                String,
+                serde_json::Value,
+                Vec<String>,
+                Vec<String>,
+                String,
+                bool,
+                Option<chrono::DateTime<chrono::Utc>>,
+                i32,
+                chrono::DateTime<chrono::Utc>,
+                chrono::DateTime<chrono::Utc>,
+            ),
+        >(&format!(
+            "SELECT {} FROM cards WHERE user_id = $1 AND embedding_status = ANY($2) \
+             ORDER BY created_at, position, id",
+            self.columns()
+        ))
+        .bind(user_id)
+        .bind(
+            statuses
+                .iter()
+                .map(EmbeddingStatus::as_str)
+                .collect::<Vec<_>>(),
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Self::map_card_rows(rows)
+    }
+
     async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Card>> {
         let row = sqlx::query_as::<
             _,
@@ -146,55 +289,36 @@ impl CardRepository for PgCardRepository {
                 String,
                 String,
                 Option<Vector>,
+                String,
                 serde_json::Value,
+                Vec<String>,
+                Vec<String>,
+                String,
+                bool,
+                Option<chrono::DateTime<chrono::Utc>>,
+                i32,
                 chrono::DateTime<chrono::Utc>,
                 chrono::DateTime<chrono::Utc>,
             ),
-        >(
-            "SELECT id, user_id, deck_id, question, answer, answer_embedding, fsrs_state, created_at, updated_at 
-             FROM cards WHERE id = $1",
-        )
+        >(&format!(
+            "SELECT {} FROM cards WHERE id = $1",
+            self.columns()
+        ))
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
 
-        match row {
-            Some((
-                id,
-                user_id,
-                deck_id,
-                question,
-                answer,
-                embedding_vec,
-                fsrs_state_json,
-                created_at,
-                updated_at,
-            )) => {
-                let fsrs_state: FsrsState = serde_json::from_value(fsrs_state_json)?;
-                let answer_embedding = embedding_vec.map(|v| v.to_vec());
-                Ok(Some(Card {
-                    id,
-                    user_id,
-                    deck_id,
-                    question,
-                    answer,
-                    answer_embedding,
-                    fsrs_state,
-                    created_at,
-                    updated_at,
-                }))
-            }
-            None => Ok(None),
-        }
+        Ok(Self::map_card_rows(row.into_iter().collect())?.pop())
     }
 
     async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Card>> {
-        let mut query = QueryBuilder::<Postgres>::new(
-            "SELECT id, user_id, deck_id, question, answer, answer_embedding, fsrs_state, created_at, updated_at \
-             FROM cards WHERE user_id = ",
-        );
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM cards WHERE user_id = ",
+            self.columns()
+        ));
         query.push_bind(user_id);
-        query.push(" ORDER BY created_at, id");
+        query.push(not_in_trash_sql!());
+        query.push(" ORDER BY created_at, position, id");
 
         let rows = query
             .build_query_as::<(
@@ -204,7 +328,14 @@ impl CardRepository for PgCardRepository {
                 String,
                 String,
                 Option<Vector>,
+                String,
                 serde_json::Value,
+                Vec<String>,
+                Vec<String>,
+                String,
+                bool,
+                Option<chrono::DateTime<chrono::Utc>>,
+                i32,
                 chrono::DateTime<chrono::Utc>,
                 chrono::DateTime<chrono::Utc>,
             )>()
@@ -215,12 +346,13 @@ impl CardRepository for PgCardRepository {
     }
 
     async fn find_by_deck(&self, deck_id: Uuid) -> AppResult<Vec<Card>> {
-        let mut query = QueryBuilder::<Postgres>::new(
-            "SELECT id, user_id, deck_id, question, answer, answer_embedding, fsrs_state, created_at, updated_at \
-             FROM cards WHERE deck_id = ",
-        );
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM cards WHERE deck_id = ",
+            self.columns()
+        ));
         query.push_bind(deck_id);
-        query.push(" ORDER BY created_at, id");
+        query.push(not_in_trash_sql!());
+        query.push(" ORDER BY created_at, position, id");
 
         let rows = query
             .build_query_as::<(
@@ -230,7 +362,14 @@ impl CardRepository for PgCardRepository {
                 String,
                 String,
                 Option<Vector>,
+                String,
                 serde_json::Value,
+                Vec<String>,
+                Vec<String>,
+                String,
+                bool,
+                Option<chrono::DateTime<chrono::Utc>>,
+                i32,
                 chrono::DateTime<chrono::Utc>,
                 chrono::DateTime<chrono::Utc>,
             )>()
@@ -243,22 +382,25 @@ impl CardRepository for PgCardRepository {
     async fn find_by_user_paginated(
         &self,
         user_id: Uuid,
-        limit: Option<i64>,
-        offset: Option<i64>,
-        exclude_card_ids: Option<Vec<Uuid>>,
+        options: CardListOptions,
     ) -> AppResult<Vec<Card>> {
-        let mut query = QueryBuilder::<Postgres>::new(
-            "SELECT id, user_id, deck_id, question, answer, answer_embedding, fsrs_state, created_at, updated_at \
-             FROM cards WHERE user_id = ",
-        );
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM cards WHERE user_id = ",
+            self.columns()
+        ));
         query.push_bind(user_id);
-        push_excluded_card_filter(&mut query, exclude_card_ids);
-        query.push(fsrs_order_by_clause());
-        if let Some(limit) = limit {
+        query.push(not_in_trash_sql!());
+        push_excluded_card_filter(&mut query, options.exclude_card_ids);
+        if let Some(state) = options.state {
+            query.push(" AND fsrs_state->>'state' = ");
+            query.push_bind(state.as_str());
+        }
+        query.push(order_by_clause(options.order));
+        if let Some(limit) = options.limit {
             query.push(" LIMIT ");
             query.push_bind(limit);
         }
-        if let Some(offset) = offset {
+        if let Some(offset) = options.offset {
             query.push(" OFFSET ");
             query.push_bind(offset);
         }
@@ -271,7 +413,65 @@ impl CardRepository for PgCardRepository {
                 String,
                 String,
                 Option<Vector>,
+                String,
                 serde_json::Value,
+                Vec<String>,
+                Vec<String>,
+                String,
+                bool,
+                Option<chrono::DateTime<chrono::Utc>>,
+                i32,
+                chrono::DateTime<chrono::Utc>,
+                chrono::DateTime<chrono::Utc>,
+            )>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Self::map_card_rows(rows)
+    }
+
+    async fn find_matching(
+        &self,
+        user_id: Uuid,
+        selection: &CardSelection,
+    ) -> AppResult<Vec<Card>> {
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM cards WHERE user_id = ",
+            self.columns()
+        ));
+        query.push_bind(user_id);
+        query.push(not_in_trash_sql!());
+        if let Some(tag) = &selection.tag {
+            query.push(" AND tags @> ARRAY[");
+            query.push_bind(tag.clone());
+            query.push("]::text[]");
+        }
+        if let Some(deck_id) = selection.deck_id {
+            query.push(" AND deck_id = ");
+            query.push_bind(deck_id);
+        }
+        if let Some(state) = &selection.state {
+            query.push(" AND fsrs_state->>'state' = ");
+            query.push_bind(state.as_str());
+        }
+        query.push(" ORDER BY created_at, position, id");
+
+        let rows = query
+            .build_query_as::<(
+                Uuid,
+                Uuid,
+                Option<Uuid>,
+                String,
+                String,
+                Option<Vector>,
+                String,
+                serde_json::Value,
+                Vec<String>,
+                Vec<String>,
+                String,
+                bool,
+                Option<chrono::DateTime<chrono::Utc>>,
+                i32,
                 chrono::DateTime<chrono::Utc>,
                 chrono::DateTime<chrono::Utc>,
             )>()
@@ -281,25 +481,44 @@ impl CardRepository for PgCardRepository {
         Self::map_card_rows(rows)
     }
 
+    async fn count_by_user_matching(
+        &self,
+        user_id: Uuid,
+        options: CardListOptions,
+    ) -> AppResult<i64> {
+        let mut query =
+            QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM cards WHERE user_id = ");
+        query.push_bind(user_id);
+        query.push(not_in_trash_sql!());
+        push_excluded_card_filter(&mut query, options.exclude_card_ids);
+        if let Some(state) = options.state {
+            query.push(" AND fsrs_state->>'state' = ");
+            query.push_bind(state.as_str());
+        }
+
+        query
+            .build_query_scalar::<i64>()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn find_by_deck_paginated(
         &self,
         deck_id: Uuid,
-        limit: Option<i64>,
-        offset: Option<i64>,
-        exclude_card_ids: Option<Vec<Uuid>>,
+        options: CardListOptions,
     ) -> AppResult<Vec<Card>> {
-        let mut query = QueryBuilder::<Postgres>::new(
-            "SELECT id, user_id, deck_id, question, answer, answer_embedding, fsrs_state, created_at, updated_at \
-             FROM cards WHERE deck_id = ",
-        );
-        query.push_bind(deck_id);
-        push_excluded_card_filter(&mut query, exclude_card_ids);
-        query.push(fsrs_order_by_clause());
-        if let Some(limit) = limit {
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM cards WHERE deck_id = ",
+            self.columns()
+        ));
+        push_deck_card_filters(&mut query, deck_id, &options);
+        query.push(order_by_clause(options.order));
+        if let Some(limit) = options.limit {
             query.push(" LIMIT ");
             query.push_bind(limit);
         }
-        if let Some(offset) = offset {
+        if let Some(offset) = options.offset {
             query.push(" OFFSET ");
             query.push_bind(offset);
         }
@@ -312,7 +531,14 @@ impl CardRepository for PgCardRepository {
                 String,
                 String,
                 Option<Vector>,
+                String,
                 serde_json::Value,
+                Vec<String>,
+                Vec<String>,
+                String,
+                bool,
+                Option<chrono::DateTime<chrono::Utc>>,
+                i32,
                 chrono::DateTime<chrono::Utc>,
                 chrono::DateTime<chrono::Utc>,
             )>()
@@ -322,19 +548,330 @@ impl CardRepository for PgCardRepository {
         Self::map_card_rows(rows)
     }
 
-    async fn update(&self, card: &Card) -> AppResult<()> {
-        let fsrs_json = serde_json::to_value(&card.fsrs_state)?;
+    async fn count_by_deck_matching(
+        &self,
+        deck_id: Uuid,
+        options: CardListOptions,
+    ) -> AppResult<i64> {
+        let mut query =
+            QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM cards WHERE deck_id = ");
+        push_deck_card_filters(&mut query, deck_id, &options);
+
+        query
+            .build_query_scalar::<i64>()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Into::into)
+    }
 
-        sqlx::query(
-            "UPDATE cards SET question = $1, answer = $2, fsrs_state = $3, updated_at = $4 WHERE id = $5",
-        )
-        .bind(&card.question)
-        .bind(&card.answer)
-        .bind(fsrs_json)
-        .bind(card.updated_at)
-        .bind(card.id)
-        .execute(&self.pool)
+    async fn find_random(&self, user_id: Uuid, deck_id: Option<Uuid>) -> AppResult<Option<Card>> {
+        let row = sqlx::query_as::<
+            _,
+            (
+                Uuid,
+                Uuid,
+                Option<Uuid>,
+                String,
+                String,
+                Option<Vector>,
+                String,
+                serde_json::Value,
+                Vec<String>,
+                Vec<String>,
+                String,
+                bool,
+                Option<chrono::DateTime<chrono::Utc>>,
+                i32,
+                chrono::DateTime<chrono::Utc>,
+                chrono::DateTime<chrono::Utc>,
+            ),
+        >(&format!(
+            concat!(
+                "SELECT {} FROM cards \
+                 WHERE user_id = $1 AND NOT suspended AND ($2::uuid IS NULL OR deck_id = $2)",
+                not_in_trash_sql!(),
+                " ORDER BY random() LIMIT 1"
+            ),
+            self.columns()
+        ))
+        .bind(user_id)
+        .bind(deck_id)
+        .fetch_optional(&self.pool)
         .await?;
+
+        Ok(Self::map_card_rows(row.into_iter().collect())?.pop())
+    }
+
+    async fn count_tags_by_user(&self, user_id: Uuid) -> AppResult<Vec<(String, i64)>> {
+        sqlx::query_as::<_, (String, i64)>(concat!(
+            "SELECT tag, COUNT(*) AS count FROM cards, unnest(tags) AS tag WHERE user_id = $1",
+            not_in_trash_sql!(),
+            " GROUP BY tag ORDER BY count DESC, tag"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn count_by_user(&self, user_id: Uuid) -> AppResult<i64> {
+        sqlx::query_scalar::<_, i64>(concat!(
+            "SELECT COUNT(*) FROM cards WHERE user_id = $1",
+            not_in_trash_sql!()
+        ))
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn count_by_deck(&self, deck_id: Uuid) -> AppResult<i64> {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM cards WHERE deck_id = $1")
+            .bind(deck_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn count_introduced_by_deck(&self, deck_id: Uuid) -> AppResult<i64> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM cards WHERE deck_id = $1 AND fsrs_state->>'state' <> 'new'",
+        )
+        .bind(deck_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn count_introduced_by_deck_since(
+        &self,
+        deck_id: Uuid,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> AppResult<i64> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM ( \
+                 SELECT review_logs.card_id FROM review_logs \
+                 JOIN cards ON cards.id = review_logs.card_id \
+                 WHERE cards.deck_id = $1 \
+                 GROUP BY review_logs.card_id \
+                 HAVING MIN(review_logs.created_at) >= $2 \
+             ) introduced",
+        )
+        .bind(deck_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn count_due_by_user(
+        &self,
+        user_id: Uuid,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> AppResult<i64> {
+        sqlx::query_scalar::<_, i64>(concat!(
+            "SELECT COUNT(*) FROM cards \
+             WHERE user_id = $1 AND NOT suspended AND ",
+            due_at_sql!(),
+            " <= $2",
+            not_in_trash_sql!()
+        ))
+        .bind(user_id)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn find_due_by_user(
+        &self,
+        user_id: Uuid,
+        now: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Vec<Card>> {
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM cards WHERE user_id = ",
+            self.columns()
+        ));
+        query.push_bind(user_id);
+        query.push(concat!(" AND NOT suspended AND ", due_at_sql!(), " <= "));
+        query.push_bind(now);
+        query.push(not_in_trash_sql!());
+        query.push(concat!(" ORDER BY ", due_at_sql!(), ", position, id"));
+        query.push(" LIMIT ");
+        query.push_bind(limit);
+        query.push(" OFFSET ");
+        query.push_bind(offset);
+
+        let rows = query
+            .build_query_as::<(
+                Uuid,
+                Uuid,
+                Option<Uuid>,
+                String,
+                String,
+                Option<Vector>,
+                String,
+                serde_json::Value,
+                Vec<String>,
+                Vec<String>,
+                String,
+                bool,
+                Option<chrono::DateTime<chrono::Utc>>,
+                i32,
+                chrono::DateTime<chrono::Utc>,
+                chrono::DateTime<chrono::Utc>,
+            )>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Self::map_card_rows(rows)
+    }
+
+    async fn find_due_by_deck(
+        &self,
+        deck_id: Uuid,
+        now: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Vec<Card>> {
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM cards WHERE deck_id = ",
+            self.columns()
+        ));
+        query.push_bind(deck_id);
+        query.push(concat!(" AND NOT suspended AND ", due_at_sql!(), " <= "));
+        query.push_bind(now);
+        query.push(not_in_trash_sql!());
+        query.push(concat!(" ORDER BY ", due_at_sql!(), ", position, id"));
+        query.push(" LIMIT ");
+        query.push_bind(limit);
+        query.push(" OFFSET ");
+        query.push_bind(offset);
+
+        let rows = query
+            .build_query_as::<(
+                Uuid,
+                Uuid,
+                Option<Uuid>,
+                String,
+                String,
+                Option<Vector>,
+                String,
+                serde_json::Value,
+                Vec<String>,
+                Vec<String>,
+                String,
+                bool,
+                Option<chrono::DateTime<chrono::Utc>>,
+                i32,
+                chrono::DateTime<chrono::Utc>,
+                chrono::DateTime<chrono::Utc>,
+            )>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Self::map_card_rows(rows)
+    }
+
+    async fn search_by_embedding(
+        &self,
+        user_id: Uuid,
+        query_embedding: &[f32],
+        limit: i64,
+    ) -> AppResult<Vec<(Card, f32)>> {
+        if !self.embeddings {
+            return Err(crate::AppError::ServiceUnavailable(
+                "Semantic search needs the pgvector extension".to_string(),
+            ));
+        }
+        let query_vector = pg_vector(query_embedding)?;
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {} FROM cards WHERE user_id = ",
+            CARD_COLUMNS
+        ));
+        query.push_bind(user_id);
+        query.push(concat!(
+            " AND answer_embedding IS NOT NULL",
+            not_in_trash_sql!()
+        ));
+        query.push(" ORDER BY answer_embedding <=> ");
+        query.push_bind(query_vector);
+        query.push(", id LIMIT ");
+        query.push_bind(limit.max(0));
+
+        let rows = query
+            .build_query_as::<(
+                Uuid,
+                Uuid,
+                Option<Uuid>,
+                String,
+                String,
+                Option<Vector>,
+                String,
+                serde_json::Value,
+                Vec<String>,
+                Vec<String>,
+                String,
+                bool,
+                Option<chrono::DateTime<chrono::Utc>>,
+                i32,
+                chrono::DateTime<chrono::Utc>,
+                chrono::DateTime<chrono::Utc>,
+            )>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        // Same cosine distance as `<=>`, without a wider row type
+        Ok(Self::map_card_rows(rows)?
+            .into_iter()
+            .map(|card| {
+                let distance = card.answer_embedding.as_deref().map_or(1.0, |embedding| {
+                    1.0 - cosine_similarity(embedding, query_embedding)
+                });
+                (card, distance)
+            })
+            .collect())
+    }
+
+    async fn update(&self, card: &Card) -> AppResult<()> {
+        update_card_query(card)?.execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn update_if_unchanged(
+        &self,
+        card: &Card,
+        loaded_at: chrono::DateTime<chrono::Utc>,
+    ) -> AppResult<()> {
+        let updated = update_card_if_unchanged_query(card, loaded_at)?
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        if updated == 0 {
+            return Err(concurrent_change(card.id));
+        }
+        Ok(())
+    }
+
+    async fn update_many_if_unchanged(
+        &self,
+        updates: &[(Card, chrono::DateTime<chrono::Utc>)],
+    ) -> AppResult<()> {
+        // Dropping the transaction on a conflict rolls back the cards
+        // already written
+        let mut tx = self.pool.begin().await?;
+        for (card, loaded_at) in updates {
+            let updated = update_card_if_unchanged_query(card, *loaded_at)?
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+            if updated == 0 {
+                return Err(concurrent_change(card.id));
+            }
+        }
+        tx.commit().await?;
         Ok(())
     }
 
@@ -347,6 +884,62 @@ impl CardRepository for PgCardRepository {
     }
 }
 
+/// pgvector value for an embedding, checked first so a bad vector fails with
+/// a `ValidationError` rather than an opaque database error
+fn pg_vector(embedding: &[f32]) -> AppResult<Vector> {
+    validate_embedding(embedding)?;
+    Ok(Vector::from(embedding.to_vec()))
+}
+
+/// `update_card_query` that only matches while the stored `updated_at` is
+/// still `loaded_at`
+fn update_card_if_unchanged_query(
+    card: &Card,
+    loaded_at: chrono::DateTime<chrono::Utc>,
+) -> AppResult<sqlx::query::Query<'_, Postgres, sqlx::postgres::PgArguments>> {
+    let fsrs_json = serde_json::to_value(&card.fsrs_state)?;
+
+    Ok(sqlx::query(
+        "UPDATE cards SET question = $1, answer = $2, fsrs_state = $3, tags = $4, required_keywords = $5, answer_type = $6, suspended = $7, not_before = $8, updated_at = $9 \
+         WHERE id = $10 AND updated_at = $11",
+    )
+    .bind(&card.question)
+    .bind(&card.answer)
+    .bind(fsrs_json)
+    .bind(&card.tags)
+    .bind(&card.required_keywords)
+    .bind(card.answer_type.as_str())
+    .bind(card.suspended)
+    .bind(card.not_before)
+    .bind(card.updated_at)
+    .bind(card.id)
+    .bind(loaded_at))
+}
+
+fn concurrent_change(card_id: Uuid) -> crate::AppError {
+    crate::AppError::Conflict(format!("Card {} was changed concurrently; retry", card_id))
+}
+
+fn update_card_query(
+    card: &Card,
+) -> AppResult<sqlx::query::Query<'_, Postgres, sqlx::postgres::PgArguments>> {
+    let fsrs_json = serde_json::to_value(&card.fsrs_state)?;
+
+    Ok(sqlx::query(
+        "UPDATE cards SET question = $1, answer = $2, fsrs_state = $3, tags = $4, required_keywords = $5, answer_type = $6, suspended = $7, not_before = $8, updated_at = $9 WHERE id = $10",
+    )
+    .bind(&card.question)
+    .bind(&card.answer)
+    .bind(fsrs_json)
+    .bind(&card.tags)
+    .bind(&card.required_keywords)
+    .bind(card.answer_type.as_str())
+    .bind(card.suspended)
+    .bind(card.not_before)
+    .bind(card.updated_at)
+    .bind(card.id))
+}
+
 fn push_excluded_card_filter(
     query: &mut QueryBuilder<Postgres>,
     exclude_card_ids: Option<Vec<Uuid>>,
@@ -365,9 +958,57 @@ fn push_excluded_card_filter(
     }
 }
 
+/// Conditions of a deck card listing after `WHERE deck_id = `, shared by the
+/// page query and its count
+fn push_deck_card_filters(
+    query: &mut QueryBuilder<Postgres>,
+    deck_id: Uuid,
+    options: &CardListOptions,
+) {
+    query.push_bind(deck_id);
+    query.push(not_in_trash_sql!());
+    push_excluded_card_filter(query, options.exclude_card_ids.clone());
+    if let Some(limit) = options.new_card_limit {
+        query.push(
+            " AND (fsrs_state->>'state' <> 'new' OR id IN (\
+             SELECT id FROM cards WHERE deck_id = ",
+        );
+        query.push_bind(deck_id);
+        query.push(" AND fsrs_state->>'state' = 'new' ORDER BY created_at, position, id LIMIT ");
+        query.push_bind(limit.max(0));
+        query.push("))");
+    }
+    if let Some(state) = &options.state {
+        query.push(" AND fsrs_state->>'state' = ");
+        query.push_bind(state.as_str());
+    }
+}
+
+fn order_by_clause(order: CardOrder) -> &'static str {
+    match order {
+        CardOrder::Due => fsrs_order_by_clause(),
+        CardOrder::Difficulty => difficulty_order_by_clause(),
+    }
+}
+
 fn fsrs_order_by_clause() -> &'static str {
-    " ORDER BY \
-     CASE WHEN COALESCE(((fsrs_state ->> 'last_review')::timestamptz + make_interval(days => COALESCE((fsrs_state ->> 'scheduled_days')::int, 0))), created_at) <= NOW() THEN 0 ELSE 1 END, \
-     COALESCE(((fsrs_state ->> 'last_review')::timestamptz + make_interval(days => COALESCE((fsrs_state ->> 'scheduled_days')::int, 0))), created_at), \
-     created_at, id"
+    concat!(
+        " ORDER BY CASE WHEN ",
+        due_at_sql!(),
+        " <= NOW() THEN 0 ELSE 1 END, ",
+        due_at_sql!(),
+        ", created_at, position, id"
+    )
+}
+
+/// Due cards first, hardest first within each bucket. The difficulty cast
+/// matches `idx_cards_fsrs_difficulty`.
+fn difficulty_order_by_clause() -> &'static str {
+    concat!(
+        " ORDER BY CASE WHEN ",
+        due_at_sql!(),
+        " <= NOW() THEN 0 ELSE 1 END, \
+         ((fsrs_state ->> 'difficulty')::real) DESC NULLS LAST, \
+         created_at, position, id"
+    )
 }
@@ -0,0 +1,70 @@
+use crate::{
+    domain::{entities::DeadLetterEvent, repositories::DeadLetterRepository},
+    AppResult,
+};
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json, PgPool};
+use uuid::Uuid;
+
+const DEAD_LETTER_COLUMNS: &str = "id, handler, event, error, attempts, created_at, replayed_at";
+
+/// PostgreSQL dead-letter Repository implementation
+pub struct PgDeadLetterRepository {
+    pool: PgPool,
+}
+
+impl PgDeadLetterRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl DeadLetterRepository for PgDeadLetterRepository {
+    async fn create(&self, dead_letter: &DeadLetterEvent) -> AppResult<Uuid> {
+        sqlx::query_scalar(&format!(
+            "INSERT INTO dead_letter_events ({DEAD_LETTER_COLUMNS}) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id"
+        ))
+        .bind(dead_letter.id)
+        .bind(&dead_letter.handler)
+        .bind(Json(&dead_letter.event))
+        .bind(&dead_letter.error)
+        .bind(dead_letter.attempts)
+        .bind(dead_letter.created_at)
+        .bind(dead_letter.replayed_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> AppResult<Option<DeadLetterEvent>> {
+        let dead_letter = sqlx::query_as::<_, DeadLetterEvent>(&format!(
+            "SELECT {DEAD_LETTER_COLUMNS} FROM dead_letter_events WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(dead_letter)
+    }
+
+    async fn list_pending(&self, limit: i64, offset: i64) -> AppResult<Vec<DeadLetterEvent>> {
+        let dead_letters = sqlx::query_as::<_, DeadLetterEvent>(&format!(
+            "SELECT {DEAD_LETTER_COLUMNS} FROM dead_letter_events \
+             WHERE replayed_at IS NULL ORDER BY created_at DESC LIMIT $1 OFFSET $2"
+        ))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(dead_letters)
+    }
+
+    async fn mark_replayed(&self, id: Uuid, replayed_at: DateTime<Utc>) -> AppResult<()> {
+        sqlx::query("UPDATE dead_letter_events SET replayed_at = $1 WHERE id = $2")
+            .bind(replayed_at)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
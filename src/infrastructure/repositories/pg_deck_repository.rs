@@ -2,9 +2,13 @@ use crate::{
     domain::{entities::Deck, repositories::DeckRepository},
     AppResult,
 };
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json, PgPool};
 use uuid::Uuid;
 
+const DECK_COLUMNS: &str =
+    "id, user_id, name, description, scheduler_overrides, fsrs_weights, default_tags, total_new_limit, new_cards_per_day, ignored_answer_prefixes, answer_normalization, language, rtl, review_mode, deleted_at, created_at, updated_at";
+
 /// PostgreSQL Deck Repository implementation
 pub struct PgDeckRepository {
     pool: PgPool,
@@ -19,14 +23,24 @@ impl PgDeckRepository {
 #[async_trait::async_trait]
 impl DeckRepository for PgDeckRepository {
     async fn create(&self, deck: &Deck) -> AppResult<Uuid> {
-        sqlx::query_scalar(
-            "INSERT INTO decks (id, user_id, name, description, created_at, updated_at) 
-             VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
-        )
+        sqlx::query_scalar(&format!(
+            "INSERT INTO decks ({DECK_COLUMNS}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17) RETURNING id"
+        ))
         .bind(deck.id)
         .bind(deck.user_id)
         .bind(&deck.name)
         .bind(&deck.description)
+        .bind(Json(&deck.scheduler_overrides))
+        .bind(&deck.fsrs_weights)
+        .bind(&deck.default_tags)
+        .bind(deck.total_new_limit)
+        .bind(deck.new_cards_per_day)
+        .bind(&deck.ignored_answer_prefixes)
+        .bind(deck.answer_normalization.as_ref().map(Json))
+        .bind(&deck.language)
+        .bind(deck.rtl)
+        .bind(deck.review_mode.as_str())
+        .bind(deck.deleted_at)
         .bind(deck.created_at)
         .bind(deck.updated_at)
         .fetch_one(&self.pool)
@@ -35,10 +49,9 @@ impl DeckRepository for PgDeckRepository {
     }
 
     async fn find_by_id(&self, id: Uuid) -> AppResult<Option<Deck>> {
-        let deck = sqlx::query_as::<_, Deck>(
-            "SELECT id, user_id, name, description, created_at, updated_at 
-             FROM decks WHERE id = $1",
-        )
+        let deck = sqlx::query_as::<_, Deck>(&format!(
+            "SELECT {DECK_COLUMNS} FROM decks WHERE id = $1 AND deleted_at IS NULL"
+        ))
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
@@ -46,24 +59,77 @@ impl DeckRepository for PgDeckRepository {
     }
 
     async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<Deck>> {
-        let decks = sqlx::query_as::<_, Deck>(
-            "SELECT id, user_id, name, description, created_at, updated_at 
-             FROM decks WHERE user_id = $1 ORDER BY created_at DESC",
-        )
+        let decks = sqlx::query_as::<_, Deck>(&format!(
+            "SELECT {DECK_COLUMNS} FROM decks WHERE user_id = $1 AND deleted_at IS NULL \
+             ORDER BY created_at DESC"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(decks)
+    }
+
+    async fn find_by_user_paginated(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Vec<Deck>> {
+        let decks = sqlx::query_as::<_, Deck>(&format!(
+            "SELECT {DECK_COLUMNS} FROM decks WHERE user_id = $1 AND deleted_at IS NULL \
+             ORDER BY created_at DESC, id LIMIT $2 OFFSET $3"
+        ))
         .bind(user_id)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&self.pool)
         .await?;
         Ok(decks)
     }
 
+    async fn find_deleted_by_user(&self, user_id: Uuid) -> AppResult<Vec<Deck>> {
+        let decks = sqlx::query_as::<_, Deck>(&format!(
+            "SELECT {DECK_COLUMNS} FROM decks WHERE user_id = $1 AND deleted_at IS NOT NULL \
+             ORDER BY deleted_at DESC"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(decks)
+    }
+
+    async fn count_by_user(&self, user_id: Uuid) -> AppResult<i64> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM decks WHERE user_id = $1 AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
     async fn update(&self, deck: &Deck) -> AppResult<()> {
-        sqlx::query("UPDATE decks SET name = $1, description = $2, updated_at = $3 WHERE id = $4")
-            .bind(&deck.name)
-            .bind(&deck.description)
-            .bind(deck.updated_at)
-            .bind(deck.id)
-            .execute(&self.pool)
-            .await?;
+        sqlx::query(
+            "UPDATE decks SET name = $1, description = $2, scheduler_overrides = $3, fsrs_weights = $4, default_tags = $5, total_new_limit = $6, new_cards_per_day = $7, ignored_answer_prefixes = $8, 
+             answer_normalization = $9, language = $10, rtl = $11, review_mode = $12, deleted_at = $13, updated_at = $14 WHERE id = $15",
+        )
+        .bind(&deck.name)
+        .bind(&deck.description)
+        .bind(Json(&deck.scheduler_overrides))
+        .bind(&deck.fsrs_weights)
+        .bind(&deck.default_tags)
+        .bind(deck.total_new_limit)
+        .bind(deck.new_cards_per_day)
+        .bind(&deck.ignored_answer_prefixes)
+        .bind(deck.answer_normalization.as_ref().map(Json))
+        .bind(&deck.language)
+        .bind(deck.rtl)
+        .bind(deck.review_mode.as_str())
+        .bind(deck.deleted_at)
+        .bind(deck.updated_at)
+        .bind(deck.id)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
@@ -74,4 +140,24 @@ impl DeckRepository for PgDeckRepository {
             .await?;
         Ok(())
     }
+
+    async fn purge_deleted_before(&self, cutoff: DateTime<Utc>) -> AppResult<u64> {
+        let mut tx = self.pool.begin().await?;
+        // cards.deck_id is ON DELETE SET NULL; trashed cards go with their deck
+        sqlx::query(
+            "DELETE FROM cards WHERE deck_id IN \
+             (SELECT id FROM decks WHERE deleted_at IS NOT NULL AND deleted_at < $1)",
+        )
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?;
+        let purged =
+            sqlx::query("DELETE FROM decks WHERE deleted_at IS NOT NULL AND deleted_at < $1")
+                .bind(cutoff)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        tx.commit().await?;
+        Ok(purged)
+    }
 }
@@ -1,5 +1,8 @@
 use crate::{
-    domain::{entities::ReviewLog, repositories::ReviewLogRepository},
+    domain::{
+        entities::ReviewLog,
+        repositories::{ReviewLogRepository, ScoreHistogramFilter},
+    },
     AppResult,
 };
 use sqlx::PgPool;
@@ -20,8 +23,8 @@ impl PgReviewLogRepository {
 impl ReviewLogRepository for PgReviewLogRepository {
     async fn create(&self, review_log: &ReviewLog) -> AppResult<Uuid> {
         sqlx::query_scalar(
-            "INSERT INTO review_logs (id, card_id, user_id, user_answer, ai_score, fsrs_rating, validation_method, created_at) 
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id",
+            "INSERT INTO review_logs (id, card_id, user_id, user_answer, ai_score, fsrs_rating, validation_method, explanation, created_at) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id",
         )
         .bind(review_log.id)
         .bind(review_log.card_id)
@@ -30,6 +33,7 @@ impl ReviewLogRepository for PgReviewLogRepository {
         .bind(review_log.ai_score)
         .bind(review_log.fsrs_rating)
         .bind(&review_log.validation_method)
+        .bind(&review_log.explanation)
         .bind(review_log.created_at)
         .fetch_one(&self.pool)
         .await
@@ -38,7 +42,7 @@ impl ReviewLogRepository for PgReviewLogRepository {
 
     async fn find_by_card(&self, card_id: Uuid) -> AppResult<Vec<ReviewLog>> {
         let logs = sqlx::query_as::<_, ReviewLog>(
-            "SELECT id, card_id, user_id, user_answer, ai_score, fsrs_rating, validation_method, created_at 
+            "SELECT id, card_id, user_id, user_answer, ai_score, fsrs_rating, validation_method, explanation, created_at 
              FROM review_logs WHERE card_id = $1 ORDER BY created_at DESC",
         )
         .bind(card_id)
@@ -49,7 +53,7 @@ impl ReviewLogRepository for PgReviewLogRepository {
 
     async fn find_by_user(&self, user_id: Uuid) -> AppResult<Vec<ReviewLog>> {
         let logs = sqlx::query_as::<_, ReviewLog>(
-            "SELECT id, card_id, user_id, user_answer, ai_score, fsrs_rating, validation_method, created_at 
+            "SELECT id, card_id, user_id, user_answer, ai_score, fsrs_rating, validation_method, explanation, created_at 
              FROM review_logs WHERE user_id = $1 ORDER BY created_at DESC",
         )
         .bind(user_id)
@@ -57,4 +61,37 @@ impl ReviewLogRepository for PgReviewLogRepository {
         .await?;
         Ok(logs)
     }
+
+    async fn score_histogram(
+        &self,
+        user_id: Uuid,
+        filter: ScoreHistogramFilter,
+        buckets: usize,
+    ) -> AppResult<Vec<i64>> {
+        // width_bucket puts a score of exactly 1.0 in bucket n + 1; fold it into n
+        let rows = sqlx::query_as::<_, (i32, i64)>(
+            "SELECT LEAST(GREATEST(width_bucket(ai_score::float8, 0.0, 1.0, $2), 1), $2) AS bucket, COUNT(*) \
+             FROM review_logs \
+             WHERE user_id = $1 \
+             AND ($3::uuid[] IS NULL OR card_id = ANY($3)) \
+             AND ($4::timestamptz IS NULL OR created_at >= $4) \
+             AND ($5::timestamptz IS NULL OR created_at < $5) \
+             GROUP BY bucket",
+        )
+        .bind(user_id)
+        .bind(buckets as i32)
+        .bind(filter.card_ids)
+        .bind(filter.from)
+        .bind(filter.to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut counts = vec![0; buckets];
+        for (bucket, count) in rows {
+            if let Some(slot) = counts.get_mut(bucket as usize - 1) {
+                *slot = count;
+            }
+        }
+        Ok(counts)
+    }
 }
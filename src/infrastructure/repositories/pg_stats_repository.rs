@@ -8,6 +8,15 @@ use crate::{
 use sqlx::PgPool;
 use uuid::Uuid;
 
+/// Count one review ($1 = 1 when correct) on study day $2 for deck $3
+const DECK_REVIEW_UPDATE: &str = "UPDATE deck_stats
+     SET total_reviews = total_reviews + 1,
+         correct_reviews = correct_reviews + $1,
+         days_studied = days_studied + CASE WHEN last_active_date IS DISTINCT FROM $2 THEN 1 ELSE 0 END,
+         last_active_date = $2,
+         updated_at = NOW()
+     WHERE deck_id = $3";
+
 /// PostgreSQL UserStats Repository implementation
 pub struct PgUserStatsRepository {
     pool: PgPool,
@@ -54,35 +63,44 @@ impl UserStatsRepository for PgUserStatsRepository {
     async fn update_after_review(
         &self,
         user_id: Uuid,
+        deck_id: Option<Uuid>,
         is_correct: bool,
         review_date: chrono::NaiveDate,
     ) -> AppResult<()> {
-        let current_stats = self.get_or_create(user_id).await?;
-
-        let is_new_day = current_stats
-            .last_active_date
-            .map(|last_date| last_date != review_date)
-            .unwrap_or(true);
-
-        let days_increment = if is_new_day { 1 } else { 0 };
-        let correct_increment = if is_correct { 1 } else { 0 };
+        let correct_increment = i32::from(is_correct);
+        let mut tx = self.pool.begin().await?;
 
         sqlx::query(
-            "UPDATE user_stats 
+            "INSERT INTO user_stats (user_id) VALUES ($1) ON CONFLICT (user_id) DO NOTHING",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "UPDATE user_stats
              SET total_reviews = total_reviews + 1,
                  correct_reviews = correct_reviews + $1,
-                 days_studied = days_studied + $2,
-                 last_active_date = $3,
+                 days_studied = days_studied + CASE WHEN last_active_date IS DISTINCT FROM $2 THEN 1 ELSE 0 END,
+                 last_active_date = $2,
                  updated_at = NOW()
-             WHERE user_id = $4",
+             WHERE user_id = $3",
         )
         .bind(correct_increment)
-        .bind(days_increment)
         .bind(review_date)
         .bind(user_id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
+        if let Some(deck_id) = deck_id {
+            sqlx::query(DECK_REVIEW_UPDATE)
+                .bind(correct_increment)
+                .bind(review_date)
+                .bind(deck_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
         Ok(())
     }
 }
@@ -132,47 +150,6 @@ impl DeckStatsRepository for PgDeckStatsRepository {
         }
     }
 
-    async fn update_after_review(
-        &self,
-        deck_id: Uuid,
-        is_correct: bool,
-        review_date: chrono::NaiveDate,
-    ) -> AppResult<()> {
-        let current_stats = sqlx::query_as::<_, DeckStats>(
-            "SELECT deck_id, user_id, total_cards, total_reviews, correct_reviews, days_studied, last_active_date, created_at, updated_at
-             FROM deck_stats WHERE deck_id = $1",
-        )
-        .bind(deck_id)
-        .fetch_optional(&self.pool)
-        .await?;
-
-        let is_new_day = current_stats
-            .and_then(|s| s.last_active_date)
-            .map(|last_date| last_date != review_date)
-            .unwrap_or(true);
-
-        let days_increment = if is_new_day { 1 } else { 0 };
-        let correct_increment = if is_correct { 1 } else { 0 };
-
-        sqlx::query(
-            "UPDATE deck_stats 
-             SET total_reviews = total_reviews + 1,
-                 correct_reviews = correct_reviews + $1,
-                 days_studied = days_studied + $2,
-                 last_active_date = $3,
-                 updated_at = NOW()
-             WHERE deck_id = $4",
-        )
-        .bind(correct_increment)
-        .bind(days_increment)
-        .bind(review_date)
-        .bind(deck_id)
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
-    }
-
     async fn increment_card_count(&self, deck_id: Uuid) -> AppResult<()> {
         sqlx::query(
             "UPDATE deck_stats 
@@ -20,12 +20,16 @@ impl PgUserRepository {
 impl UserRepository for PgUserRepository {
     async fn create(&self, user: &User) -> AppResult<Uuid> {
         sqlx::query_scalar(
-            "INSERT INTO users (id, email, name, password_hash, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+            "INSERT INTO users (id, email, name, password_hash, utc_offset_minutes, day_cutoff_hour, daily_review_goal, ai_validation_enabled, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING id",
         )
         .bind(user.id)
         .bind(&user.email)
         .bind(&user.name)
         .bind(&user.password_hash)
+        .bind(user.preferences.utc_offset_minutes)
+        .bind(user.preferences.day_cutoff_hour)
+        .bind(user.preferences.daily_review_goal)
+        .bind(user.preferences.ai_validation_enabled)
         .bind(user.created_at)
         .bind(user.updated_at)
         .fetch_one(&self.pool)
@@ -35,7 +39,7 @@ impl UserRepository for PgUserRepository {
 
     async fn find_by_id(&self, id: Uuid) -> AppResult<Option<User>> {
         let user = sqlx::query_as::<_, User>(
-            "SELECT id, email, name, password_hash, created_at, updated_at FROM users WHERE id = $1",
+            "SELECT id, email, name, password_hash, utc_offset_minutes, day_cutoff_hour, daily_review_goal, ai_validation_enabled, created_at, updated_at FROM users WHERE id = $1",
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -45,7 +49,7 @@ impl UserRepository for PgUserRepository {
 
     async fn find_by_email(&self, email: &str) -> AppResult<Option<User>> {
         let user = sqlx::query_as::<_, User>(
-            "SELECT id, email, name, password_hash, created_at, updated_at FROM users WHERE email = $1",
+            "SELECT id, email, name, password_hash, utc_offset_minutes, day_cutoff_hour, daily_review_goal, ai_validation_enabled, created_at, updated_at FROM users WHERE email = $1",
         )
         .bind(email)
         .fetch_optional(&self.pool)
@@ -54,10 +58,14 @@ impl UserRepository for PgUserRepository {
     }
 
     async fn update(&self, user: &User) -> AppResult<()> {
-        sqlx::query("UPDATE users SET email = $1, name = $2, password_hash = $3, updated_at = $4 WHERE id = $5")
+        sqlx::query("UPDATE users SET email = $1, name = $2, password_hash = $3, utc_offset_minutes = $4, day_cutoff_hour = $5, daily_review_goal = $6, ai_validation_enabled = $7, updated_at = $8 WHERE id = $9")
             .bind(&user.email)
             .bind(&user.name)
             .bind(&user.password_hash)
+            .bind(user.preferences.utc_offset_minutes)
+            .bind(user.preferences.day_cutoff_hour)
+            .bind(user.preferences.daily_review_goal)
+            .bind(user.preferences.ai_validation_enabled)
             .bind(user.updated_at)
             .bind(user.id)
             .execute(&self.pool)
@@ -0,0 +1,380 @@
+//! Startup selection of the answer validator and embedding service
+
+use anyhow::{bail, Result};
+use std::sync::Arc;
+
+use crate::domain::ports::{AIValidator, EmbeddingService};
+use crate::infrastructure::ai_validator::{
+    BlendWeights, ChainValidator, ClaudeValidator, FallbackValidator, OpenAIValidator,
+};
+use crate::infrastructure::http_embedding_service::{EmbeddingProvider, HttpEmbeddingService};
+
+/// Validator chosen with the `VALIDATOR` (or `AI_PROVIDER`) env var
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidatorKind {
+    /// OpenAI embeddings + LLM grading; requires OPENAI_API_KEY
+    OpenAi,
+    /// Word-overlap scoring, no network and no embeddings
+    Fallback,
+    /// A self-hosted OpenAI-compatible server; requires LOCAL_AI_BASE_URL
+    Local,
+    /// OpenAI, degrading to word-overlap scoring when a call fails
+    Chain,
+    /// Anthropic Claude LLM grading; requires ANTHROPIC_API_KEY
+    Anthropic,
+}
+
+impl ValidatorKind {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "openai" => Ok(Self::OpenAi),
+            "fallback" => Ok(Self::Fallback),
+            "local" => Ok(Self::Local),
+            "chain" => Ok(Self::Chain),
+            "anthropic" => Ok(Self::Anthropic),
+            other => bail!(
+                "Unknown VALIDATOR {:?} (expected openai, anthropic, fallback, local or chain)",
+                other
+            ),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::Fallback => "fallback",
+            Self::Local => "local",
+            Self::Chain => "chain",
+            Self::Anthropic => "anthropic",
+        }
+    }
+}
+
+/// Everything `build_validator` needs, read from the environment
+#[derive(Debug, Clone, Default)]
+pub struct ValidatorSettings {
+    /// Explicit choice; `None` picks OpenAI when a key is set, fallback otherwise
+    pub kind: Option<ValidatorKind>,
+    pub openai_api_key: Option<String>,
+    pub anthropic_api_key: Option<String>,
+    pub anthropic_model: Option<String>,
+    pub local_base_url: Option<String>,
+    pub local_api_key: Option<String>,
+    pub local_chat_model: Option<String>,
+    pub local_embedding_model: Option<String>,
+    /// Embedding similarity accepted without asking the LLM
+    pub embedding_threshold: Option<f32>,
+    /// Blend borderline embedding and LLM scores instead of taking the LLM's
+    pub blend_weights: Option<BlendWeights>,
+    /// Characters of each answer sent to the LLM or embedded; `Some(0)`
+    /// lifts the limit, `None` keeps the default
+    pub max_answer_chars: Option<usize>,
+    /// Card and search embeddings come from this provider instead of the
+    /// validator's; answer grading is unaffected
+    pub embedding_provider: Option<EmbeddingProvider>,
+}
+
+impl ValidatorSettings {
+    /// Reads VALIDATOR (or its alias AI_PROVIDER), OPENAI_API_KEY,
+    /// EMBEDDING_MATCH_THRESHOLD, BLENDED_SCORING_WEIGHTS,
+    /// LLM_MAX_ANSWER_CHARS and the
+    /// ANTHROPIC_*, LOCAL_AI_* and EMBEDDING_* variables
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        Ok(Self {
+            kind: var("VALIDATOR")
+                .or_else(|| var("AI_PROVIDER"))
+                .map(|v| ValidatorKind::parse(&v))
+                .transpose()?,
+            openai_api_key: var("OPENAI_API_KEY"),
+            anthropic_api_key: var("ANTHROPIC_API_KEY"),
+            anthropic_model: var("ANTHROPIC_MODEL"),
+            local_base_url: var("LOCAL_AI_BASE_URL"),
+            local_api_key: var("LOCAL_AI_API_KEY"),
+            local_chat_model: var("LOCAL_AI_CHAT_MODEL"),
+            local_embedding_model: var("LOCAL_AI_EMBEDDING_MODEL"),
+            embedding_threshold: var("EMBEDDING_MATCH_THRESHOLD")
+                .map(|v| parse_threshold(&v))
+                .transpose()?,
+            blend_weights: var("BLENDED_SCORING_WEIGHTS")
+                .map(|v| parse_blend_weights(&v))
+                .transpose()?,
+            max_answer_chars: var("LLM_MAX_ANSWER_CHARS")
+                .map(|v| {
+                    v.trim().parse::<usize>().map_err(|_| {
+                        anyhow::anyhow!("LLM_MAX_ANSWER_CHARS must be a whole number, got {:?}", v)
+                    })
+                })
+                .transpose()?,
+            embedding_provider: EmbeddingProvider::from_env()?,
+        })
+    }
+
+    /// The validator to build, or an error when the selection can't work
+    /// with the rest of the settings
+    pub fn resolve(&self) -> Result<ValidatorKind> {
+        match self.kind {
+            None if self.openai_api_key.is_some() => Ok(ValidatorKind::OpenAi),
+            None => Ok(ValidatorKind::Fallback),
+            Some(kind @ (ValidatorKind::OpenAi | ValidatorKind::Chain))
+                if self.openai_api_key.is_none() =>
+            {
+                bail!(
+                    "VALIDATOR={} requires OPENAI_API_KEY to be set",
+                    kind.as_str()
+                )
+            }
+            Some(ValidatorKind::Anthropic) if self.anthropic_api_key.is_none() => {
+                bail!("VALIDATOR=anthropic requires ANTHROPIC_API_KEY to be set")
+            }
+            Some(ValidatorKind::Local) if self.local_base_url.is_none() => {
+                bail!("VALIDATOR=local requires LOCAL_AI_BASE_URL to be set")
+            }
+            Some(kind) => Ok(kind),
+        }
+    }
+}
+
+fn parse_threshold(value: &str) -> Result<f32> {
+    match value.trim().parse::<f32>() {
+        Ok(threshold) if (0.0..=1.0).contains(&threshold) => Ok(threshold),
+        _ => bail!(
+            "EMBEDDING_MATCH_THRESHOLD must be a number between 0 and 1, got {:?}",
+            value
+        ),
+    }
+}
+
+/// Parses `<embedding weight>,<llm weight>`, e.g. `0.4,0.6`
+fn parse_blend_weights(value: &str) -> Result<BlendWeights> {
+    let weights: Vec<f32> = value
+        .split(',')
+        .map(|w| w.trim().parse::<f32>())
+        .collect::<Result<_, _>>()
+        .unwrap_or_default();
+    match weights[..] {
+        [embedding, llm] if embedding >= 0.0 && llm >= 0.0 && embedding + llm > 0.0 => {
+            Ok(BlendWeights { embedding, llm })
+        }
+        _ => bail!(
+            "BLENDED_SCORING_WEIGHTS must be two non-negative weights like 0.4,0.6, got {:?}",
+            value
+        ),
+    }
+}
+
+/// Build the validator and embedding service selected by `settings`
+pub fn build_validator(
+    settings: &ValidatorSettings,
+) -> Result<(Arc<dyn AIValidator>, Arc<dyn EmbeddingService>)> {
+    let kind = settings.resolve()?;
+    let tuned = |mut validator: OpenAIValidator| {
+        if let Some(threshold) = settings.embedding_threshold {
+            validator = validator.with_embedding_threshold(threshold);
+        }
+        if let Some(weights) = settings.blend_weights {
+            validator = validator.with_blended_scoring(weights);
+        }
+        if let Some(max_chars) = settings.max_answer_chars {
+            validator = validator.with_max_answer_chars((max_chars > 0).then_some(max_chars));
+        }
+        validator
+    };
+    let openai = || {
+        tuned(OpenAIValidator::new(
+            settings.openai_api_key.clone().unwrap_or_default(),
+        ))
+    };
+
+    let (validator, embedding): (Arc<dyn AIValidator>, Arc<dyn EmbeddingService>) = match kind {
+        ValidatorKind::OpenAi => {
+            let validator = Arc::new(openai());
+            (validator.clone(), validator)
+        }
+        ValidatorKind::Fallback => (Arc::new(FallbackValidator), Arc::new(FallbackValidator)),
+        ValidatorKind::Anthropic => {
+            let mut validator = ClaudeValidator::new(
+                settings.anthropic_api_key.clone().unwrap_or_default(),
+                settings.anthropic_model.clone(),
+            );
+            if let Some(max_chars) = settings.max_answer_chars {
+                validator = validator.with_max_answer_chars((max_chars > 0).then_some(max_chars));
+            }
+            let validator = Arc::new(validator);
+            // Claude has no embeddings; keep OpenAI's when a key is set
+            let embedding: Arc<dyn EmbeddingService> = match settings.openai_api_key {
+                Some(_) => Arc::new(openai()),
+                None => Arc::new(FallbackValidator),
+            };
+            (validator, embedding)
+        }
+        ValidatorKind::Local => {
+            let mut validator = OpenAIValidator::local(
+                settings.local_base_url.clone().unwrap_or_default(),
+                settings.local_api_key.clone(),
+            );
+            if let Some(model) = settings.local_chat_model.clone() {
+                validator = validator.with_chat_model(model);
+            }
+            if let Some(model) = settings.local_embedding_model.clone() {
+                validator = validator.with_embedding_model(model);
+            }
+            let validator = Arc::new(tuned(validator));
+            (validator.clone(), validator)
+        }
+        ValidatorKind::Chain => {
+            let openai = Arc::new(openai());
+            let validator = Arc::new(ChainValidator::new(
+                openai.clone(),
+                Arc::new(FallbackValidator),
+            ));
+            (validator, openai)
+        }
+    };
+
+    Ok(match settings.embedding_provider.clone() {
+        Some(provider) => {
+            let mut embedding = HttpEmbeddingService::new(provider);
+            if let Some(max_chars) = settings.max_answer_chars {
+                embedding = embedding.with_max_answer_chars((max_chars > 0).then_some(max_chars));
+            }
+            (validator, Arc::new(embedding))
+        }
+        None => (validator, embedding),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(kind: Option<ValidatorKind>) -> ValidatorSettings {
+        ValidatorSettings {
+            kind,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_validator_kind() {
+        assert_eq!(
+            ValidatorKind::parse("OpenAI").unwrap(),
+            ValidatorKind::OpenAi
+        );
+        assert_eq!(ValidatorKind::parse("chain").unwrap(), ValidatorKind::Chain);
+        assert_eq!(
+            ValidatorKind::parse("anthropic").unwrap(),
+            ValidatorKind::Anthropic
+        );
+        assert!(ValidatorKind::parse("claude").is_err());
+    }
+
+    #[test]
+    fn test_anthropic_requires_key() {
+        let err = build_validator(&settings(Some(ValidatorKind::Anthropic)))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("ANTHROPIC_API_KEY"));
+
+        let with_key = ValidatorSettings {
+            anthropic_api_key: Some("sk-ant-test".to_string()),
+            ..settings(Some(ValidatorKind::Anthropic))
+        };
+        assert!(build_validator(&with_key).is_ok());
+    }
+
+    #[test]
+    fn test_threshold_must_be_between_zero_and_one() {
+        assert_eq!(parse_threshold("0.9").unwrap(), 0.9);
+        assert!(parse_threshold("1.5").is_err());
+        assert!(parse_threshold("high").is_err());
+    }
+
+    #[test]
+    fn test_blend_weights_parse() {
+        assert_eq!(
+            parse_blend_weights("0.4, 0.6").unwrap(),
+            BlendWeights {
+                embedding: 0.4,
+                llm: 0.6,
+            }
+        );
+        assert!(parse_blend_weights("0.4").is_err());
+        assert!(parse_blend_weights("-1,2").is_err());
+        assert!(parse_blend_weights("0,0").is_err());
+    }
+
+    #[test]
+    fn test_unset_kind_follows_openai_key() {
+        assert_eq!(settings(None).resolve().unwrap(), ValidatorKind::Fallback);
+
+        let with_key = ValidatorSettings {
+            openai_api_key: Some("sk-test".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(with_key.resolve().unwrap(), ValidatorKind::OpenAi);
+        assert!(build_validator(&with_key).is_ok());
+    }
+
+    #[test]
+    fn test_openai_and_chain_require_key() {
+        for kind in [ValidatorKind::OpenAi, ValidatorKind::Chain] {
+            let err = build_validator(&settings(Some(kind))).err().unwrap();
+            assert!(err.to_string().contains("OPENAI_API_KEY"));
+
+            let with_key = ValidatorSettings {
+                openai_api_key: Some("sk-test".to_string()),
+                ..settings(Some(kind))
+            };
+            assert!(build_validator(&with_key).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_local_requires_base_url() {
+        let err = build_validator(&settings(Some(ValidatorKind::Local)))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("LOCAL_AI_BASE_URL"));
+
+        let local = ValidatorSettings {
+            local_base_url: Some("http://localhost:11434/v1".to_string()),
+            local_chat_model: Some("llama3".to_string()),
+            local_embedding_model: Some("nomic-embed-text".to_string()),
+            ..settings(Some(ValidatorKind::Local))
+        };
+        assert!(build_validator(&local).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_ignores_openai_key() {
+        let explicit = ValidatorSettings {
+            openai_api_key: Some("sk-test".to_string()),
+            ..settings(Some(ValidatorKind::Fallback))
+        };
+        let (validator, embedding) = build_validator(&explicit).unwrap();
+
+        let result = validator
+            .validate("hola", "hola", "Translate")
+            .await
+            .unwrap();
+        assert_eq!(result.score, 1.0);
+        assert!(embedding.generate_embedding("hola").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_embedding_provider_replaces_validator_embeddings() {
+        // Nothing listens on port 9, so the request itself fails
+        let with_provider = ValidatorSettings {
+            embedding_provider: Some(EmbeddingProvider::new(
+                "http://127.0.0.1:9/v1".to_string(),
+                "embed".to_string(),
+            )),
+            ..settings(Some(ValidatorKind::Fallback))
+        };
+        let (_, embedding) = build_validator(&with_provider).unwrap();
+
+        let err = embedding.generate_embedding("hola").await.unwrap_err();
+        assert!(err.to_string().contains("Embedding request failed"));
+    }
+}
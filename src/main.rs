@@ -1,24 +1,45 @@
 use re_mem::{
     application::services::{AuthService, CardService, DeckService, ReviewService, UserService},
     application::use_cases::{
-        GetDeckStatsUseCase, GetUserStatsUseCase, ImportAnkiUseCase, ImportTsvUseCase,
-        ReviewCardUseCase,
+        BackfillEmbeddingsUseCase, DeadLettersUseCase, DeckTrashUseCase, ExportUserDataUseCase,
+        GetActivityHeatmapUseCase, GetCardHistoryUseCase, GetDeckStatsUseCase,
+        GetDetailedHealthUseCase, GetDueCountUseCase, GetGoalProgressUseCase,
+        GetSchedulerConfigUseCase, GetScoreHistogramUseCase, GetUserStatsUseCase,
+        ImportAnkiUseCase, ImportCsvUseCase, ImportJsonUseCase, ImportTsvUseCase, ImportXmlUseCase,
+        InspectImportUseCase, OptimizeFsrsUseCase, PruneUseCase, RecommendRelatedDecksUseCase,
+        ReviewCardUseCase, SearchCardsUseCase, StudySessionUseCase, UpdateCardUseCase,
+        ValidateAnswersUseCase,
     },
     domain::{
-        ports::EmbeddingService,
-        repositories::{CardRepository, DeckRepository, DeckStatsRepository},
+        ports::{EmbeddingService, HealthCheck},
+        repositories::{
+            CardRepository, DeckRepository, DeckStatsRepository, ReviewLogRepository,
+            UserStatsRepository,
+        },
+        scheduler_config::SchedulerConfig,
+        value_objects::argon2_params_from_env,
     },
     infrastructure::{
-        ai_validator::{FallbackValidator, OpenAIValidator},
-        database::{init_db_pool, DbConfig},
+        build_validator,
+        caching_embedding_service::CachingEmbeddingService,
+        database::{detect_capabilities, init_db_pool, DbConfig},
+        media_proxy::MediaProxy,
         repositories::{
-            PgCardRepository, PgDeckRepository, PgDeckStatsRepository, PgReviewLogRepository,
-            PgReviewRepository, PgUserRepository, PgUserStatsRepository,
+            CachedUserStatsRepository, PgCardRepository, PgDeadLetterRepository, PgDeckRepository,
+            PgDeckStatsRepository, PgReviewLogRepository, PgReviewRepository, PgUserRepository,
+            PgUserStatsRepository,
         },
-        StatisticsEventHandler,
+        DatabaseHealthCheck, MigrationHealthCheck, OnboardingEventHandler, OpenAIHealthCheck,
+        StatisticsEventHandler, ValidatorSettings,
+    },
+    presentation::router::{create_router, AppServices, ReviewCardUseCaseTrait, RouterConfig},
+    shared::{
+        access::OwnershipPolicy,
+        content_policy::ContentPolicy,
+        event_bus::{EventBus, EventKind, RetryPolicy},
+        quota::UserQuotas,
+        rate_limit::AiRateLimiter,
     },
-    presentation::router::{create_router, AppServices, ReviewCardUseCaseTrait},
-    shared::event_bus::EventBus,
 };
 use std::sync::Arc;
 
@@ -33,6 +54,11 @@ async fn main() {
     // Load configuration
     dotenv::dotenv().ok();
     let db_config = DbConfig::from_env();
+    // Fail at startup rather than on the first registration
+    if let Err(e) = argon2_params_from_env() {
+        tracing::error!("{}", e);
+        std::process::exit(1);
+    }
 
     // Initialize database
     let db_pool = match init_db_pool(&db_config).await {
@@ -45,101 +71,290 @@ async fn main() {
             std::process::exit(1);
         }
     };
+    // Without pgvector, cards are stored without answer embeddings
+    let db_capabilities = match detect_capabilities(&db_pool, &db_config).await {
+        Ok(capabilities) => capabilities,
+        Err(e) => {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
     // Initialize repositories
     let user_repo = Arc::new(PgUserRepository::new(db_pool.clone()));
-    let card_repo = Arc::new(PgCardRepository::new(db_pool.clone()));
+    let card_repo =
+        Arc::new(PgCardRepository::new(db_pool.clone()).with_embeddings(db_capabilities.pgvector));
     let deck_repo = Arc::new(PgDeckRepository::new(db_pool.clone()));
     let review_repo = Arc::new(PgReviewRepository::new(db_pool.clone()));
     let review_log_repo = Arc::new(PgReviewLogRepository::new(db_pool.clone()));
-    let user_stats_repo = Arc::new(PgUserStatsRepository::new(db_pool.clone()));
+    let review_log_repo_dyn: Arc<dyn ReviewLogRepository> = review_log_repo.clone();
+    let user_stats_repo: Arc<dyn UserStatsRepository> =
+        match CachedUserStatsRepository::ttl_from_env() {
+            Some(ttl) => Arc::new(CachedUserStatsRepository::new(
+                Arc::new(PgUserStatsRepository::new(db_pool.clone())),
+                ttl,
+            )),
+            None => Arc::new(PgUserStatsRepository::new(db_pool.clone())),
+        };
     let deck_stats_repo = Arc::new(PgDeckStatsRepository::new(db_pool.clone()));
 
-    // Initialize Event Bus and register handlers
-    let mut event_bus = EventBus::new();
+    let dead_letter_repo = Arc::new(PgDeadLetterRepository::new(db_pool.clone()));
+
+    // Initialize Event Bus and register handlers; events whose handler keeps
+    // failing are dead-lettered for replay
+    let mut event_bus = EventBus::new()
+        .with_retry_policy(RetryPolicy::from_env())
+        .with_dead_letter_repository(dead_letter_repo.clone());
 
     // Initialize Statistics Event Handler
-    let stats_handler = Arc::new(StatisticsEventHandler::new(
-        user_stats_repo.clone(),
-        deck_stats_repo.clone(),
-        card_repo.clone(),
-    ));
+    let stats_handler = Arc::new(
+        StatisticsEventHandler::new(
+            user_stats_repo.clone(),
+            deck_stats_repo.clone(),
+            card_repo.clone(),
+        )
+        .with_user_repository(user_repo.clone()),
+    );
 
     // Register the statistics handler
-    event_bus.register_handler(stats_handler);
+    event_bus.register_for(EventKind::CardReviewed, stats_handler.clone());
+    event_bus.register_for(EventKind::CardCreated, stats_handler);
+
+    // Onboarding handler creates the default Inbox deck for new users
+    event_bus.register_for(
+        EventKind::UserRegistered,
+        Arc::new(OnboardingEventHandler::new(deck_repo.clone())),
+    );
 
     let event_bus = Arc::new(event_bus);
 
+    // 404 vs 403 for resources owned by another user
+    let ownership_policy = OwnershipPolicy::from_env();
+    // Per-user deck/card limits (off unless configured)
+    let quotas = UserQuotas::from_env();
+    // Card content rules for creation and imports
+    let content_policy = ContentPolicy::from_env();
+
     // Initialize application services (legacy)
-    let user_service = Arc::new(UserService::new(user_repo));
-    let card_service = Arc::new(CardService::new(card_repo.clone(), event_bus.clone()));
-    let deck_service = Arc::new(DeckService::new(deck_repo.clone()));
+    let user_service = Arc::new(UserService::new(user_repo.clone()));
+    let card_service = Arc::new(
+        CardService::new(card_repo.clone(), deck_repo.clone(), event_bus.clone())
+            .with_user_repository(user_repo.clone())
+            .with_ownership_policy(ownership_policy)
+            .with_quotas(quotas)
+            .with_content_policy(content_policy),
+    );
+    let deck_service = Arc::new(
+        DeckService::new(deck_repo.clone())
+            .with_ownership_policy(ownership_policy)
+            .with_quotas(quotas),
+    );
+    let deck_trash_use_case =
+        Arc::new(DeckTrashUseCase::new(deck_repo.clone()).with_quotas(quotas, card_repo.clone()));
     let review_service = Arc::new(ReviewService::new(review_repo));
 
     // Initialize statistics use cases
     let get_user_stats_use_case = Arc::new(GetUserStatsUseCase::new(user_stats_repo.clone()));
-    let get_deck_stats_use_case =
-        Arc::new(GetDeckStatsUseCase::new(deck_stats_repo.clone(), deck_repo));
+    let get_deck_stats_use_case = Arc::new(
+        GetDeckStatsUseCase::new(deck_stats_repo.clone(), deck_repo.clone())
+            .with_ownership_policy(ownership_policy),
+    );
+
+    // Scheduler configuration: global defaults merged with per-deck overrides
+    let deck_repo_dyn: Arc<dyn DeckRepository> = deck_repo;
+    let scheduler_config = SchedulerConfig::from_env();
+    let get_scheduler_config_use_case = Arc::new(
+        GetSchedulerConfigUseCase::new(deck_repo_dyn.clone(), scheduler_config.clone())
+            .with_user_repository(user_repo.clone())
+            .with_ownership_policy(ownership_policy),
+    );
 
     // Initialize AI Validator and Review Card Use Case
-    let (review_card_use_case, embedding_service): (
-        Arc<dyn ReviewCardUseCaseTrait>,
-        Arc<dyn EmbeddingService>,
-    ) = match std::env::var("OPENAI_API_KEY") {
-        Ok(api_key) => {
-            tracing::info!("Using OpenAI validator");
-            let validator = Arc::new(OpenAIValidator::new(api_key));
-            let embedding: Arc<dyn EmbeddingService> = validator.clone();
-            let uc = Arc::new(ReviewCardUseCase::new(
-                card_repo.clone(),
-                review_log_repo,
-                validator,
-                event_bus,
-            )) as Arc<dyn ReviewCardUseCaseTrait>;
-            (uc, embedding)
-        }
-        Err(_) => {
-            tracing::warn!(
-                "OPENAI_API_KEY not set — using FallbackValidator (word-overlap scoring)"
-            );
-            let validator = Arc::new(FallbackValidator);
-            let embedding: Arc<dyn EmbeddingService> = Arc::new(FallbackValidator);
-            let uc = Arc::new(ReviewCardUseCase::new(
-                card_repo.clone(),
-                review_log_repo,
-                validator,
-                event_bus,
-            )) as Arc<dyn ReviewCardUseCaseTrait>;
-            (uc, embedding)
-        }
-    };
+    let (ai_validator, embedding_service) =
+        match ValidatorSettings::from_env().and_then(|settings| build_validator(&settings)) {
+            Ok(built) => built,
+            Err(e) => {
+                tracing::error!("Invalid validator configuration: {}", e);
+                std::process::exit(1);
+            }
+        };
+    // Repeated answers (e.g. "yes" in every beginner deck) are embedded once
+    let embedding_service: Arc<dyn EmbeddingService> =
+        match CachingEmbeddingService::capacity_from_env() {
+            Some(capacity) => Arc::new(CachingEmbeddingService::new(embedding_service, capacity)),
+            None => embedding_service,
+        };
+    let review_card_use_case: Arc<dyn ReviewCardUseCaseTrait> = Arc::new(
+        ReviewCardUseCase::new(
+            card_repo.clone(),
+            review_log_repo,
+            ai_validator.clone(),
+            event_bus.clone(),
+        )
+        .with_deck_repository(deck_repo_dyn.clone())
+        .with_user_repository(user_repo.clone())
+        .with_scheduler_config(scheduler_config.clone())
+        .with_ownership_policy(ownership_policy),
+    );
+
+    // Grading preview: validator only, no persistence
+    let validate_answers_use_case = Arc::new(
+        ValidateAnswersUseCase::new(ai_validator)
+            .with_rate_limiter(AiRateLimiter::from_env().map(Arc::new))
+            .with_user_repository(user_repo.clone()),
+    );
 
     // Import use cases (cast concrete repos to trait objects)
     let card_repo_dyn: Arc<dyn CardRepository> = card_repo.clone();
-    let deck_repo_dyn: Arc<dyn DeckRepository> = Arc::new(PgDeckRepository::new(db_pool.clone()));
     let deck_stats_repo_dyn: Arc<dyn DeckStatsRepository> = deck_stats_repo.clone();
 
-    let import_tsv_use_case = Arc::new(ImportTsvUseCase::new(
-        card_repo_dyn.clone(),
-        deck_stats_repo_dyn.clone(),
-        embedding_service.clone(),
+    let import_tsv_use_case = Arc::new(
+        ImportTsvUseCase::new(
+            card_repo_dyn.clone(),
+            deck_repo_dyn.clone(),
+            deck_stats_repo_dyn.clone(),
+            embedding_service.clone(),
+        )
+        .with_ownership_policy(ownership_policy)
+        .with_quotas(quotas)
+        .with_content_policy(content_policy)
+        .with_user_repository(user_repo.clone()),
+    );
+    let import_json_use_case = Arc::new(
+        ImportJsonUseCase::new(
+            card_repo_dyn.clone(),
+            deck_repo_dyn.clone(),
+            deck_stats_repo_dyn.clone(),
+            review_log_repo_dyn.clone(),
+            embedding_service.clone(),
+        )
+        .with_ownership_policy(ownership_policy)
+        .with_quotas(quotas)
+        .with_content_policy(content_policy)
+        .with_user_repository(user_repo.clone()),
+    );
+    let import_xml_use_case = Arc::new(
+        ImportXmlUseCase::new(
+            card_repo_dyn.clone(),
+            deck_repo_dyn.clone(),
+            deck_stats_repo_dyn.clone(),
+            embedding_service.clone(),
+        )
+        .with_ownership_policy(ownership_policy)
+        .with_quotas(quotas)
+        .with_content_policy(content_policy)
+        .with_user_repository(user_repo.clone()),
+    );
+    let import_csv_use_case = Arc::new(
+        ImportCsvUseCase::new(
+            card_repo_dyn.clone(),
+            deck_repo_dyn.clone(),
+            deck_stats_repo_dyn.clone(),
+            embedding_service.clone(),
+        )
+        .with_ownership_policy(ownership_policy)
+        .with_quotas(quotas)
+        .with_content_policy(content_policy)
+        .with_user_repository(user_repo.clone()),
+    );
+    let get_card_history_use_case = Arc::new(
+        GetCardHistoryUseCase::new(card_repo_dyn.clone(), review_log_repo_dyn.clone())
+            .with_ownership_policy(ownership_policy),
+    );
+    let get_activity_heatmap_use_case = Arc::new(GetActivityHeatmapUseCase::new(
+        review_log_repo_dyn.clone(),
+        user_repo.clone(),
     ));
-    let import_anki_use_case = Arc::new(ImportAnkiUseCase::new(
-        card_repo_dyn,
-        deck_repo_dyn,
-        deck_stats_repo_dyn,
-        embedding_service,
+    let get_goal_progress_use_case = Arc::new(GetGoalProgressUseCase::new(
+        review_log_repo_dyn.clone(),
+        user_repo.clone(),
     ));
+    let export_user_data_use_case = Arc::new(
+        ExportUserDataUseCase::new(
+            deck_repo_dyn.clone(),
+            card_repo_dyn.clone(),
+            review_log_repo_dyn.clone(),
+        )
+        .with_ownership_policy(ownership_policy),
+    );
+    let get_due_count_use_case = Arc::new(
+        GetDueCountUseCase::new(card_repo_dyn.clone())
+            .with_stream_interval(GetDueCountUseCase::stream_interval_from_env()),
+    );
+    let recommend_related_decks_use_case = Arc::new(
+        RecommendRelatedDecksUseCase::new(deck_repo_dyn.clone(), card_repo_dyn.clone())
+            .with_ownership_policy(ownership_policy),
+    );
+    let get_score_histogram_use_case = Arc::new(
+        GetScoreHistogramUseCase::new(
+            card_repo_dyn.clone(),
+            deck_repo_dyn.clone(),
+            review_log_repo_dyn.clone(),
+        )
+        .with_ownership_policy(ownership_policy),
+    );
+    let study_session_use_case = Arc::new(
+        StudySessionUseCase::new(card_repo_dyn.clone(), deck_repo_dyn.clone())
+            .with_user_repository(user_repo.clone())
+            .with_ownership_policy(ownership_policy),
+    );
+    let optimize_fsrs_use_case = Arc::new(OptimizeFsrsUseCase::new(review_log_repo_dyn.clone()));
+    let prune_use_case = Arc::new(
+        PruneUseCase::new(deck_repo_dyn.clone(), study_session_use_case.clone())
+            .with_trash_retention(PruneUseCase::trash_retention_from_env()),
+    );
+    // Expired sessions and old trash are removed in the background
+    if let Some(interval) = PruneUseCase::interval_from_env() {
+        prune_use_case.clone().spawn(interval);
+    }
+    let backfill_embeddings_use_case = Arc::new(
+        BackfillEmbeddingsUseCase::new(card_repo_dyn.clone(), embedding_service.clone())
+            .with_user_repository(user_repo.clone()),
+    );
+    let search_cards_use_case = Arc::new(
+        SearchCardsUseCase::new(card_repo_dyn.clone(), embedding_service.clone())
+            .with_user_repository(user_repo.clone()),
+    );
+    let update_card_use_case = Arc::new(
+        UpdateCardUseCase::new(card_repo_dyn.clone(), embedding_service.clone())
+            .with_user_repository(user_repo.clone())
+            .with_ownership_policy(ownership_policy),
+    );
+    let import_anki_use_case = Arc::new(
+        ImportAnkiUseCase::new(
+            card_repo_dyn,
+            deck_repo_dyn,
+            deck_stats_repo_dyn,
+            embedding_service,
+        )
+        .with_quotas(quotas)
+        .with_content_policy(content_policy)
+        .with_user_repository(user_repo.clone())
+        .with_tmp_dir(std::env::var_os("IMPORT_TMP_DIR").map(Into::into)),
+    );
+
+    // Component checks for /health/detailed
+    let mut health_checks: Vec<Arc<dyn HealthCheck>> = vec![
+        Arc::new(DatabaseHealthCheck::new(db_pool.clone())),
+        Arc::new(MigrationHealthCheck::new(db_pool.clone())),
+    ];
+    if let Ok(api_key) = std::env::var("OPENAI_API_KEY") {
+        health_checks.push(Arc::new(OpenAIHealthCheck::new(api_key)));
+    }
+    let get_detailed_health_use_case = Arc::new(GetDetailedHealthUseCase::new(health_checks));
 
     // Initialize auth service
-    let auth_service = Arc::new(AuthService::new(Arc::new(PgUserRepository::new(
-        db_pool.clone(),
-    ))));
+    let auth_service = Arc::new(AuthService::new(
+        Arc::new(PgUserRepository::new(db_pool.clone())),
+        event_bus.clone(),
+    ));
+    let dead_letters_use_case = Arc::new(DeadLettersUseCase::new(dead_letter_repo, event_bus));
 
     let app_services = AppServices {
         user_service,
         card_service,
         deck_service,
+        deck_trash_use_case,
         review_service,
         review_card_use_case,
         get_user_stats_use_case,
@@ -147,10 +362,33 @@ async fn main() {
         auth_service,
         import_tsv_use_case,
         import_anki_use_case,
+        import_json_use_case,
+        get_scheduler_config_use_case,
+        inspect_import_use_case: Arc::new(InspectImportUseCase::new()),
+        export_user_data_use_case,
+        get_due_count_use_case,
+        recommend_related_decks_use_case,
+        get_card_history_use_case,
+        get_score_histogram_use_case,
+        validate_answers_use_case,
+        study_session_use_case,
+        get_detailed_health_use_case,
+        import_xml_use_case,
+        import_csv_use_case,
+        dead_letters_use_case,
+        get_activity_heatmap_use_case,
+        get_goal_progress_use_case,
+        backfill_embeddings_use_case,
+        search_cards_use_case,
+        update_card_use_case,
+        prune_use_case,
+        optimize_fsrs_use_case,
+        // Signed image/audio proxy (off unless MEDIA_PROXY_SECRET is set)
+        media_proxy: MediaProxy::from_env().map(Arc::new),
     };
 
     // Create router
-    let app = create_router(app_services);
+    let app = create_router(app_services, RouterConfig::from_env());
 
     // Run server
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000")
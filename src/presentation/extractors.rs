@@ -0,0 +1,120 @@
+//! Request extractors shared across handlers.
+
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use serde::Deserialize;
+
+use crate::shared::error::{AppError, AppResult};
+
+/// Page size used when the client does not send `limit`
+const DEFAULT_PAGE_SIZE: i64 = 50;
+/// Hard upper bound for `limit`; larger values are clamped
+const MAX_PAGE_SIZE: i64 = 200;
+
+fn default_page_size() -> i64 {
+    std::env::var("DEFAULT_PAGE_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+}
+
+fn max_page_size() -> i64 {
+    std::env::var("MAX_PAGE_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(MAX_PAGE_SIZE)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawPagination {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// Extractor for `limit`/`offset` query parameters.
+///
+/// Missing `limit` falls back to the default page size, values above the
+/// maximum are clamped, and negative values are rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaginationParams {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl PaginationParams {
+    fn from_raw(raw: RawPagination, default_limit: i64, max_limit: i64) -> AppResult<Self> {
+        let limit = raw.limit.unwrap_or(default_limit);
+        let offset = raw.offset.unwrap_or(0);
+
+        if limit < 0 {
+            return Err(AppError::ValidationError(
+                "limit must not be negative".to_string(),
+            ));
+        }
+        if offset < 0 {
+            return Err(AppError::ValidationError(
+                "offset must not be negative".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            limit: limit.min(max_limit),
+            offset,
+        })
+    }
+}
+
+impl<S> FromRequestParts<S> for PaginationParams
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> AppResult<Self> {
+        let Query(raw) = Query::<RawPagination>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| AppError::ValidationError(format!("Invalid pagination: {}", e)))?;
+        Self::from_raw(raw, default_page_size(), max_page_size())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    async fn extract(uri: &str) -> AppResult<PaginationParams> {
+        let (mut parts, _) = Request::builder().uri(uri).body(()).unwrap().into_parts();
+        PaginationParams::from_request_parts(&mut parts, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_defaults_when_missing() {
+        let params = extract("/cards").await.unwrap();
+        assert_eq!(params.limit, DEFAULT_PAGE_SIZE);
+        assert_eq!(params.offset, 0);
+    }
+
+    #[tokio::test]
+    async fn test_over_max_limit_is_clamped() {
+        let params = extract("/cards?limit=1000000&offset=10").await.unwrap();
+        assert_eq!(params.limit, MAX_PAGE_SIZE);
+        assert_eq!(params.offset, 10);
+    }
+
+    #[tokio::test]
+    async fn test_negative_offset_is_rejected() {
+        let result = extract("/cards?offset=-1").await;
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_negative_limit_is_rejected() {
+        let result = extract("/cards?limit=-5").await;
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+}
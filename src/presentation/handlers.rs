@@ -1,21 +1,85 @@
 use axum::{
     extract::{Multipart, Path, Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Response},
+    http::{header, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     Json,
 };
+use std::{collections::HashMap, convert::Infallible};
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 use uuid::Uuid;
 
 use crate::application::dtos::*;
+use crate::application::use_cases::{AnkiFieldMapping, ScoreHistogramQuery};
+use crate::domain::answer_diff::diff_words;
+use crate::domain::entities::CardState;
+use crate::domain::ports::ComponentStatus;
+use crate::domain::repositories::{CardListOptions, CardOrder};
+use crate::presentation::extractors::PaginationParams;
 use crate::presentation::middleware::auth::AuthenticatedUser;
 use crate::presentation::router::AppServices;
-use crate::shared::error::AppError;
+use crate::shared::deadline;
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::jwt::decode_jwt_with_expiry;
 
 /// Health check endpoint
 pub async fn health_check() -> Json<serde_json::Value> {
     Json(serde_json::json!({ "status": "ok" }))
 }
 
+/// Detailed health handler — GET /health/detailed
+///
+/// 200 while the service is `ok` or `degraded`, 503 when it is `down`.
+pub async fn detailed_health_check(State(services): State<AppServices>) -> Response {
+    let health = services.get_detailed_health_use_case.execute().await;
+    let status = match health.status {
+        ComponentStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::OK,
+    };
+    (status, Json(health)).into_response()
+}
+
+/// List dead letters handler — GET /api/v1/admin/dead-letters
+///
+/// Events whose handler failed every retry and that were not replayed yet,
+/// newest first. Supports `limit`/`offset`.
+pub async fn list_dead_letters(
+    pagination: PaginationParams,
+    State(services): State<AppServices>,
+) -> Response {
+    match services
+        .dead_letters_use_case
+        .list(pagination.limit, pagination.offset)
+        .await
+    {
+        Ok(dead_letters) => Json(dead_letters).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Replay dead letter handler — POST /api/v1/admin/dead-letters/{id}/replay
+pub async fn replay_dead_letter(
+    Path(id): Path<Uuid>,
+    State(services): State<AppServices>,
+) -> Response {
+    match services.dead_letters_use_case.replay(id).await {
+        Ok(dead_letter) => Json(dead_letter).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Run prune handler — POST /api/v1/admin/maintenance/prune
+///
+/// Runs the periodic cleanup now instead of waiting for the next tick.
+pub async fn run_prune(State(services): State<AppServices>) -> Response {
+    match services.prune_use_case.execute().await {
+        Ok(result) => Json(result).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
 /// Create user handler
 pub async fn create_user(
     State(services): State<AppServices>,
@@ -36,11 +100,31 @@ pub async fn get_user(Path(user_id): Path<Uuid>, State(services): State<AppServi
 }
 
 /// Create card handler
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/cards",
+    tag = "cards",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource")),
+    request_body = CreateCardRequest,
+    responses(
+        (status = 201, description = "Card created", body = CardDto),
+        (status = 400, description = "Invalid card"),
+        (status = 403, description = "deck_id belongs to another user"),
+        (status = 404, description = "deck_id not found")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn create_card(
     Path(user_id): Path<Uuid>,
     State(services): State<AppServices>,
+    auth: AuthenticatedUser,
     Json(req): Json<CreateCardRequest>,
 ) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot create cards for another user".to_string())
+            .into_response();
+    }
+
     match services.card_service.create_card(user_id, req).await {
         Ok(card) => (StatusCode::CREATED, Json(card)).into_response(),
         Err(err) => err.into_response(),
@@ -48,27 +132,295 @@ pub async fn create_card(
 }
 
 /// Get user cards handler
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/cards",
+    tag = "cards",
+    params(
+        ("user_id" = Uuid, Path, description = "Owner of the resource"),
+        ("limit" = Option<i64>, Query, description = "Page size"),
+        ("offset" = Option<i64>, Query, description = "Cards to skip"),
+        ("order" = Option<String>, Query, description = "`due` (default) or `difficulty`"),
+        ("state" = Option<String>, Query, description = "Only cards in this FSRS state"),
+        ("exclude_card_ids" = Option<String>, Query, description = "Comma-separated card ids to leave out")
+    ),
+    responses((status = 200, description = "One page of the user's cards", body = Page<CardDto>)),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_user_cards(
     Path(user_id): Path<Uuid>,
+    pagination: PaginationParams,
     Query(query): Query<CardListQuery>,
     State(services): State<AppServices>,
+    auth: AuthenticatedUser,
 ) -> Response {
-    let exclude_card_ids = match parse_excluded_card_ids(&query) {
-        Ok(ids) => ids,
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot view another user's cards".to_string())
+            .into_response();
+    }
+
+    let options = match card_list_options(&query, pagination) {
+        Ok(options) => options,
         Err(err) => return err.into_response(),
     };
 
+    match services.card_service.get_user_cards(user_id, options).await {
+        Ok(page) => Json(Page {
+            items: with_signed_media(&services, page.items),
+            ..page
+        })
+        .into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Due cards handler — GET /users/{user_id}/cards/due
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/cards/due",
+    tag = "cards",
+    params(
+        ("user_id" = Uuid, Path, description = "Owner of the resource"),
+        ("limit" = Option<i64>, Query, description = "Page size"),
+        ("offset" = Option<i64>, Query, description = "Cards to skip")
+    ),
+    responses((status = 200, description = "Cards due for review now, most overdue first", body = [CardDto])),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_due_cards(
+    Path(user_id): Path<Uuid>,
+    pagination: PaginationParams,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot view another user's cards".to_string())
+            .into_response();
+    }
+
     match services
         .card_service
-        .get_user_cards(
+        .get_due_cards(
             user_id,
-            query.limit.map(i64::from),
-            query.offset.map(i64::from),
-            exclude_card_ids,
+            chrono::Utc::now(),
+            pagination.limit,
+            pagination.offset,
+        )
+        .await
+    {
+        Ok(cards) => Json(with_signed_media(&services, cards)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Semantic card search handler
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/cards/search",
+    tag = "cards",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource")),
+    request_body = SearchCardsRequest,
+    responses(
+        (status = 200, description = "Cards whose answers are closest in meaning to the query, nearest first", body = [CardSearchHitDto]),
+        (status = 400, description = "Empty query or limit outside 1-50"),
+        (status = 503, description = "No embedding service configured")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn search_cards(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    Json(req): Json<SearchCardsRequest>,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot search another user's cards".to_string())
+            .into_response();
+    }
+
+    match services.search_cards_use_case.execute(user_id, req).await {
+        Ok(mut hits) => {
+            if let Some(proxy) = &services.media_proxy {
+                for hit in &mut hits {
+                    hit.card = hit.card.clone().with_signed_media(proxy.signer());
+                }
+            }
+            Json(hits).into_response()
+        }
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Get card handler
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/cards/{card_id}",
+    tag = "cards",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource"), ("card_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The card", body = CardDto),
+        (status = 404, description = "Card not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_card(
+    Path((user_id, card_id)): Path<(Uuid, Uuid)>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot view another user's cards".to_string())
+            .into_response();
+    }
+
+    match services.card_service.get_card(card_id, user_id).await {
+        Ok(card) => Json(with_signed_media(&services, vec![card]).remove(0)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Update card handler — PATCH /users/{user_id}/cards/{card_id}
+#[utoipa::path(
+    patch,
+    path = "/users/{user_id}/cards/{card_id}",
+    tag = "cards",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource"), ("card_id" = Uuid, Path)),
+    request_body = UpdateCardRequest,
+    responses(
+        (status = 200, description = "The updated card", body = CardDto),
+        (status = 404, description = "Card not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_card(
+    Path((user_id, card_id)): Path<(Uuid, Uuid)>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    Json(req): Json<UpdateCardRequest>,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot modify another user's cards".to_string())
+            .into_response();
+    }
+
+    match services
+        .card_service
+        .update_card(card_id, user_id, req)
+        .await
+    {
+        Ok(card) => Json(card).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Edit card handler — PUT /users/{user_id}/cards/{card_id}
+#[utoipa::path(
+    put,
+    path = "/users/{user_id}/cards/{card_id}",
+    tag = "cards",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource"), ("card_id" = Uuid, Path)),
+    request_body = EditCardRequest,
+    responses(
+        (status = 200, description = "The edited card; its review schedule is unchanged", body = CardDto),
+        (status = 400, description = "Blank question or answer"),
+        (status = 403, description = "Card belongs to another user"),
+        (status = 404, description = "Card not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn edit_card(
+    Path((user_id, card_id)): Path<(Uuid, Uuid)>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    Json(req): Json<EditCardRequest>,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot edit another user's cards".to_string())
+            .into_response();
+    }
+
+    match services
+        .update_card_use_case
+        .execute(card_id, user_id, req)
+        .await
+    {
+        Ok(card) => Json(with_signed_media(&services, vec![card]).remove(0)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct RandomCardQuery {
+    pub deck_id: Option<Uuid>,
+}
+
+/// Random card for free practice — GET /api/v1/users/{user_id}/cards/random
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{user_id}/cards/random",
+    tag = "cards",
+    params(
+        ("user_id" = Uuid, Path, description = "Owner of the resource"),
+        ("deck_id" = Option<Uuid>, Query, description = "Only pick from this deck")
+    ),
+    responses(
+        (status = 200, description = "A random card", body = CardDto),
+        (status = 404, description = "No cards available to practice")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_random_card(
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<RandomCardQuery>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot view another user's cards".to_string())
+            .into_response();
+    }
+
+    match services
+        .card_service
+        .get_random_card(user_id, query.deck_id)
+        .await
+    {
+        Ok(card) => Json(with_signed_media(&services, vec![card]).remove(0)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Card review history handler — GET /users/{user_id}/cards/{card_id}/history
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/cards/{card_id}/history",
+    tag = "cards",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource"), ("card_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "Past gradings of the card, newest first", body = [ReviewHistoryEntryDto]),
+        (status = 403, description = "Card belongs to another user"),
+        (status = 404, description = "Card not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_card_history(
+    Path((user_id, card_id)): Path<(Uuid, Uuid)>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError(
+            "Cannot view another user's review history".to_string(),
         )
+        .into_response();
+    }
+
+    match services
+        .get_card_history_use_case
+        .execute(card_id, user_id)
         .await
     {
-        Ok(cards) => Json(cards).into_response(),
+        Ok(history) => Json(history).into_response(),
         Err(err) => err.into_response(),
     }
 }
@@ -77,8 +429,14 @@ pub async fn get_user_cards(
 pub async fn submit_review(
     Path((user_id, card_id)): Path<(Uuid, Uuid)>,
     State(services): State<AppServices>,
+    auth: AuthenticatedUser,
     Json(req): Json<LegacyReviewCardRequest>,
 ) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot review as another user".to_string())
+            .into_response();
+    }
+
     match services
         .review_service
         .submit_review(card_id, user_id, req)
@@ -91,26 +449,88 @@ pub async fn submit_review(
 
 /// Submit intelligent review with AI validation (API v1)
 /// POST /api/v1/reviews
-/// Body: { "card_id": "uuid", "user_id": "uuid", "user_answer": "string" }
+/// Body: { "card_id": "uuid", "user_answer": "string" }
+/// or, for self-graded decks, `"rating": 1-4` instead of `user_answer`.
+/// The review is recorded for the authenticated user.
+#[utoipa::path(
+    post,
+    path = "/api/v1/reviews",
+    tag = "reviews",
+    request_body = SubmitReviewRequest,
+    responses(
+        (status = 201, description = "Review graded and the card rescheduled", body = ReviewResponseDto),
+        (status = 400, description = "Answer or rating does not match the deck's review mode"),
+        (status = 403, description = "The card belongs to another user"),
+        (status = 404, description = "Card not found"),
+        (status = 409, description = "The card was changed concurrently"),
+        (status = 504, description = "AI validation did not finish before the request deadline")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn submit_intelligent_review(
     State(services): State<AppServices>,
+    auth: AuthenticatedUser,
     Json(req): Json<SubmitReviewRequest>,
 ) -> Response {
     match services
         .review_card_use_case
-        .execute(req.card_id, req.user_id, req.user_answer)
+        .execute_with_rating(
+            req.card_id,
+            auth.user_id,
+            req.user_answer.clone(),
+            req.rating,
+        )
         .await
     {
         Ok(result) => {
+            if let Some(session_id) = req.session_id {
+                if let Err(err) = services.study_session_use_case.record_review(
+                    auth.user_id,
+                    session_id,
+                    req.card_id,
+                ) {
+                    tracing::warn!(
+                        "Review not counted in study session {}: {}",
+                        session_id,
+                        err
+                    );
+                }
+            }
             let response = ReviewResponseDto {
                 card_id: result.card_id,
                 ai_score: result.ai_score,
                 fsrs_rating: result.fsrs_rating,
                 validation_method: result.validation_method.as_str().to_string(),
                 next_review_in_days: result.next_review_in_days,
+                threshold: result.threshold,
+                diff: req
+                    .include_diff
+                    .then(|| diff_words(&result.expected_answer, &req.user_answer))
+                    .flatten(),
             };
             (StatusCode::CREATED, Json(response)).into_response()
         }
+        Err(err) if deadline::is_exceeded(&err) => {
+            AppError::Timeout("AI validation did not finish before the request deadline".into())
+                .into_response()
+        }
+        // Missing, unowned or suspended card, rating/answer not matching the
+        // deck's review mode, or the card saved concurrently elsewhere
+        Err(err)
+            if matches!(
+                err.downcast_ref::<AppError>(),
+                Some(
+                    AppError::ValidationError(_)
+                        | AppError::NotFound(_)
+                        | AppError::AuthorizationError(_)
+                        | AppError::Conflict(_)
+                )
+            ) =>
+        {
+            err.downcast::<AppError>()
+                .unwrap_or_else(|err| AppError::InternalError(err.to_string()))
+                .into_response()
+        }
         Err(err) => {
             tracing::error!("Review failed: {:?}", err);
             (
@@ -125,19 +545,51 @@ pub async fn submit_intelligent_review(
 }
 
 /// Submit review request for API v1
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct SubmitReviewRequest {
     pub card_id: Uuid,
-    pub user_id: Uuid,
+    /// Ignored; reviews are always recorded for the authenticated user
+    #[serde(default)]
+    pub user_id: Option<Uuid>,
+    /// Typed answer; optional on self-graded decks
+    #[serde(default)]
     pub user_answer: String,
+    /// Self-grade rating (1 = again, 2 = hard, 3 = good, 4 = easy); only
+    /// accepted for cards in self-graded decks
+    #[serde(default)]
+    pub rating: Option<i32>,
+    /// Study session the reviewed card was served from, for progress tracking
+    #[serde(default)]
+    pub session_id: Option<Uuid>,
+    /// Return a word diff against the expected answer in the response
+    #[serde(default)]
+    pub include_diff: bool,
 }
 
 /// Create deck handler
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/decks",
+    tag = "decks",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource")),
+    request_body = CreateDeckRequest,
+    responses(
+        (status = 201, description = "Deck created", body = DeckDto),
+        (status = 400, description = "Invalid deck settings")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn create_deck(
     Path(user_id): Path<Uuid>,
     State(services): State<AppServices>,
+    auth: AuthenticatedUser,
     Json(req): Json<CreateDeckRequest>,
 ) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot create decks for another user".to_string())
+            .into_response();
+    }
+
     match services.deck_service.create_deck(user_id, req).await {
         Ok(deck) => (StatusCode::CREATED, Json(deck)).into_response(),
         Err(err) => err.into_response(),
@@ -145,47 +597,126 @@ pub async fn create_deck(
 }
 
 /// Get user decks handler
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/decks",
+    tag = "decks",
+    params(
+        ("user_id" = Uuid, Path, description = "Owner of the resource"),
+        ("limit" = Option<i64>, Query, description = "Page size"),
+        ("offset" = Option<i64>, Query, description = "Decks to skip")
+    ),
+    responses((status = 200, description = "One page of the user's decks", body = Page<DeckDto>)),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_user_decks(
     Path(user_id): Path<Uuid>,
+    pagination: PaginationParams,
     State(services): State<AppServices>,
+    auth: AuthenticatedUser,
 ) -> Response {
-    match services.deck_service.get_user_decks(user_id).await {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot view another user's decks".to_string())
+            .into_response();
+    }
+
+    match services
+        .deck_service
+        .get_user_decks(user_id, pagination.limit, pagination.offset)
+        .await
+    {
         Ok(decks) => Json(decks).into_response(),
         Err(err) => err.into_response(),
     }
 }
 
 /// Get cards by deck handler
+#[utoipa::path(
+    get,
+    path = "/decks/{deck_id}/cards",
+    tag = "decks",
+    params(
+        ("deck_id" = Uuid, Path),
+        ("limit" = Option<i64>, Query, description = "Page size"),
+        ("offset" = Option<i64>, Query, description = "Cards to skip"),
+        ("order" = Option<String>, Query, description = "`due` (default) or `difficulty`"),
+        ("state" = Option<String>, Query, description = "Only cards in this FSRS state"),
+        ("exclude_card_ids" = Option<String>, Query, description = "Comma-separated card ids to leave out")
+    ),
+    responses(
+        (status = 200, description = "One page of the deck's cards", body = Page<CardDto>),
+        (status = 404, description = "Deck not found")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_deck_cards(
     Path(deck_id): Path<Uuid>,
+    pagination: PaginationParams,
     Query(query): Query<CardListQuery>,
     State(services): State<AppServices>,
+    auth: AuthenticatedUser,
 ) -> Response {
-    let exclude_card_ids = match parse_excluded_card_ids(&query) {
-        Ok(ids) => ids,
+    let options = match card_list_options(&query, pagination) {
+        Ok(options) => options,
         Err(err) => return err.into_response(),
     };
 
     match services
         .card_service
-        .get_deck_cards(
-            deck_id,
-            query.limit.map(i64::from),
-            query.offset.map(i64::from),
-            exclude_card_ids,
-        )
+        .get_deck_cards(deck_id, auth.user_id, options)
         .await
     {
-        Ok(cards) => Json(cards).into_response(),
+        Ok(page) => Json(Page {
+            items: with_signed_media(&services, page.items),
+            ..page
+        })
+        .into_response(),
         Err(err) => err.into_response(),
     }
 }
 
+/// Add signed media proxy links to cards when the proxy is enabled
+fn with_signed_media(services: &AppServices, cards: Vec<CardDto>) -> Vec<CardDto> {
+    match &services.media_proxy {
+        Some(proxy) => cards
+            .into_iter()
+            .map(|card| card.with_signed_media(proxy.signer()))
+            .collect(),
+        None => cards,
+    }
+}
+
 #[derive(Debug, Clone, Default, serde::Deserialize)]
 pub struct CardListQuery {
-    pub limit: Option<u32>,
-    pub offset: Option<u32>,
     pub exclude_card_ids: Option<String>,
+    pub order: Option<String>,
+    pub state: Option<String>,
+}
+
+fn card_list_options(
+    query: &CardListQuery,
+    pagination: PaginationParams,
+) -> Result<CardListOptions, AppError> {
+    let order = match query.order.as_deref() {
+        Some(order) => order
+            .parse::<CardOrder>()
+            .map_err(AppError::ValidationError)?,
+        None => CardOrder::default(),
+    };
+    let state = query
+        .state
+        .as_deref()
+        .map(str::parse::<CardState>)
+        .transpose()?;
+
+    Ok(CardListOptions {
+        limit: Some(pagination.limit),
+        offset: Some(pagination.offset),
+        exclude_card_ids: parse_excluded_card_ids(query)?,
+        order,
+        state,
+        ..Default::default()
+    })
 }
 
 fn parse_excluded_card_ids(query: &CardListQuery) -> Result<Option<Vec<Uuid>>, AppError> {
@@ -204,143 +735,1190 @@ fn parse_excluded_card_ids(query: &CardListQuery) -> Result<Option<Vec<Uuid>>, A
     Ok(Some(parsed_ids))
 }
 
-/// Delete deck handler
-pub async fn delete_deck(
+/// Update deck handler
+#[utoipa::path(
+    patch,
+    path = "/users/{user_id}/decks/{deck_id}",
+    tag = "decks",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource"), ("deck_id" = Uuid, Path)),
+    request_body = UpdateDeckRequest,
+    responses(
+        (status = 200, description = "The updated deck", body = DeckDto),
+        (status = 400, description = "Invalid deck settings"),
+        (status = 404, description = "Deck not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_deck(
     Path((user_id, deck_id)): Path<(Uuid, Uuid)>,
     State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    Json(req): Json<UpdateDeckRequest>,
 ) -> Response {
-    match services.deck_service.delete_deck(deck_id, user_id).await {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot modify another user's decks".to_string())
+            .into_response();
+    }
+
+    match services
+        .deck_service
+        .update_deck(deck_id, user_id, req)
+        .await
+    {
+        Ok(deck) => Json(deck).into_response(),
         Err(err) => err.into_response(),
     }
 }
 
-/// Delete card handler
-pub async fn delete_card(
-    Path((user_id, card_id)): Path<(Uuid, Uuid)>,
+/// Deck options handler — GET /api/v1/decks/{deck_id}/options
+#[utoipa::path(
+    get,
+    path = "/api/v1/decks/{deck_id}/options",
+    tag = "decks",
+    params(("deck_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The deck's study options", body = DeckOptions),
+        (status = 404, description = "Deck not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_deck_options(
+    Path(deck_id): Path<Uuid>,
     State(services): State<AppServices>,
+    auth: AuthenticatedUser,
 ) -> Response {
-    match services.card_service.delete_card(card_id, user_id).await {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+    match services
+        .deck_service
+        .get_deck_options(deck_id, auth.user_id)
+        .await
+    {
+        Ok(options) => Json(options).into_response(),
         Err(err) => err.into_response(),
     }
 }
 
-/// Get user statistics handler
-pub async fn get_user_stats(
-    Path(user_id): Path<Uuid>,
+/// Replace deck options handler — PUT /api/v1/decks/{deck_id}/options
+#[utoipa::path(
+    put,
+    path = "/api/v1/decks/{deck_id}/options",
+    tag = "decks",
+    params(("deck_id" = Uuid, Path)),
+    request_body = DeckOptions,
+    responses(
+        (status = 200, description = "The saved study options", body = DeckOptions),
+        (status = 400, description = "An option is invalid; nothing was saved"),
+        (status = 404, description = "Deck not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn update_deck_options(
+    Path(deck_id): Path<Uuid>,
     State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    Json(options): Json<DeckOptions>,
 ) -> Response {
-    match services.get_user_stats_use_case.execute(user_id).await {
-        Ok(stats) => Json(stats).into_response(),
+    match services
+        .deck_service
+        .update_deck_options(deck_id, auth.user_id, options)
+        .await
+    {
+        Ok(options) => Json(options).into_response(),
         Err(err) => err.into_response(),
     }
 }
 
-/// Get deck statistics handler
-pub async fn get_deck_stats(
-    Path(deck_id): Path<Uuid>,
+/// Delete deck handler
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/decks/{deck_id}",
+    tag = "decks",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource"), ("deck_id" = Uuid, Path)),
+    responses(
+        (status = 204, description = "Deck moved to the trash"),
+        (status = 404, description = "Deck not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_deck(
+    Path((user_id, deck_id)): Path<(Uuid, Uuid)>,
     State(services): State<AppServices>,
+    auth: AuthenticatedUser,
 ) -> Response {
-    match services.get_deck_stats_use_case.execute(deck_id).await {
-        Ok(stats) => Json(stats).into_response(),
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot delete another user's decks".to_string())
+            .into_response();
+    }
+
+    match services.deck_service.delete_deck(deck_id, user_id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
         Err(err) => err.into_response(),
     }
 }
 
-/// Register handler — POST /api/v1/auth/register
-pub async fn register(
+/// Trashed decks handler — GET /api/v1/users/{user_id}/decks/trash
+pub async fn list_deck_trash(
+    Path(user_id): Path<Uuid>,
     State(services): State<AppServices>,
-    Json(req): Json<crate::application::dtos::RegisterRequest>,
+    auth: AuthenticatedUser,
 ) -> Response {
-    match services.auth_service.register.execute(req).await {
-        Ok(res) => (StatusCode::CREATED, Json(res)).into_response(),
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot read another user's trash".to_string())
+            .into_response();
+    }
+
+    match services.deck_trash_use_case.list(user_id).await {
+        Ok(decks) => Json(decks).into_response(),
         Err(err) => err.into_response(),
     }
 }
 
-/// Login handler — POST /api/v1/auth/login
-pub async fn login(
+/// Restore deck handler — POST /api/v1/users/{user_id}/decks/trash/{deck_id}/restore
+pub async fn restore_deck(
+    Path((user_id, deck_id)): Path<(Uuid, Uuid)>,
     State(services): State<AppServices>,
-    Json(req): Json<crate::application::dtos::LoginRequest>,
+    auth: AuthenticatedUser,
 ) -> Response {
-    match services.auth_service.login.execute(req).await {
-        Ok(res) => Json(res).into_response(),
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot restore another user's deck".to_string())
+            .into_response();
+    }
+
+    match services.deck_trash_use_case.restore(user_id, deck_id).await {
+        Ok(deck) => Json(deck).into_response(),
         Err(err) => err.into_response(),
     }
 }
 
-/// Import TSV handler — POST /api/v1/decks/{deck_id}/import/tsv
-///
-/// Accepts `multipart/form-data` with a single `file` field containing a UTF-8
-/// TSV file (lines of `front\tback`). Returns an `ImportResult` JSON.
-pub async fn import_tsv(
-    Path(deck_id): Path<Uuid>,
+/// Delete card handler
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}/cards/{card_id}",
+    tag = "cards",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource"), ("card_id" = Uuid, Path)),
+    responses(
+        (status = 204, description = "Card deleted"),
+        (status = 404, description = "Card not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_card(
+    Path((user_id, card_id)): Path<(Uuid, Uuid)>,
     State(services): State<AppServices>,
     auth: AuthenticatedUser,
-    mut multipart: Multipart,
 ) -> Response {
-    let file_bytes = match read_multipart_file(&mut multipart).await {
-        Ok(Some(b)) => b,
-        Ok(None) => {
-            return AppError::ValidationError("No 'file' field found in request".to_string())
-                .into_response()
-        }
-        Err(e) => return AppError::into_response(e),
-    };
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot delete another user's cards".to_string())
+            .into_response();
+    }
 
-    match services
-        .import_tsv_use_case
-        .execute(auth.user_id, deck_id, file_bytes)
-        .await
-    {
-        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    match services.card_service.delete_card(card_id, user_id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
         Err(err) => err.into_response(),
     }
 }
 
-/// Import Anki handler — POST /api/v1/decks/import/anki
+/// Batch card action handler — POST /users/{user_id}/cards/batch-action
 ///
-/// Accepts `multipart/form-data` with a single `file` field containing a `.apkg`
-/// archive. Creates a new deck from the Anki deck name and returns `AnkiImportResult`.
-pub async fn import_anki(
+/// Suspends, unsuspends, resets or reschedules every card of the user
+/// matching the filter and returns how many were affected.
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/cards/batch-action",
+    tag = "cards",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource")),
+    request_body = BatchCardActionRequest,
+    responses(
+        (status = 200, description = "Number of cards changed", body = BatchCardActionResponse),
+        (status = 400, description = "Empty filter or invalid action"),
+        (status = 409, description = "A matching card changed while the action ran")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn batch_card_action(
+    Path(user_id): Path<Uuid>,
     State(services): State<AppServices>,
     auth: AuthenticatedUser,
-    mut multipart: Multipart,
+    Json(req): Json<BatchCardActionRequest>,
 ) -> Response {
-    let file_bytes = match read_multipart_file(&mut multipart).await {
-        Ok(Some(b)) => b,
-        Ok(None) => {
-            return AppError::ValidationError("No 'file' field found in request".to_string())
-                .into_response()
-        }
-        Err(e) => return AppError::into_response(e),
-    };
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot modify another user's cards".to_string())
+            .into_response();
+    }
+
+    match services.card_service.batch_action(user_id, req).await {
+        Ok(result) => Json(result).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
 
+/// Grading preview handler — POST /api/v1/validate. Scores answers without
+/// storing anything or touching any schedule.
+pub async fn validate_answers(
+    auth: AuthenticatedUser,
+    State(services): State<AppServices>,
+    Json(items): Json<Vec<ValidateAnswerItem>>,
+) -> Response {
     match services
-        .import_anki_use_case
-        .execute(auth.user_id, file_bytes)
+        .validate_answers_use_case
+        .execute(auth.user_id, items)
         .await
     {
-        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Ok(results) => Json(results).into_response(),
         Err(err) => err.into_response(),
     }
 }
 
-/// Reads the first `file` field from a multipart form, enforcing a 10 MB size limit.
-async fn read_multipart_file(multipart: &mut Multipart) -> Result<Option<bytes::Bytes>, AppError> {
-    while let Ok(Some(field)) = multipart.next_field().await {
-        if field.name() == Some("file") {
-            let bytes = field.bytes().await.map_err(|e| {
-                AppError::ValidationError(format!("Failed to read uploaded file: {}", e))
-            })?;
-            if bytes.len() > 10 * 1024 * 1024 {
-                return Err(AppError::ValidationError(
-                    "File exceeds the 10 MB size limit".to_string(),
-                ));
-            }
-            return Ok(Some(bytes));
-        }
+/// Start study session handler — POST /api/v1/decks/{deck_id}/sessions
+///
+/// Freezes the deck's due cards into a queue that `next` walks through.
+pub async fn start_study_session(
+    Path(deck_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    match services
+        .study_session_use_case
+        .start(auth.user_id, deck_id)
+        .await
+    {
+        Ok(session) => (StatusCode::CREATED, Json(session)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Next session card handler — GET /api/v1/decks/{deck_id}/sessions/{session_id}/next
+pub async fn next_session_card(
+    Path((deck_id, session_id)): Path<(Uuid, Uuid)>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    match services
+        .study_session_use_case
+        .next(auth.user_id, deck_id, session_id)
+        .await
+    {
+        Ok(next) => Json(next).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Get user statistics handler
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{user_id}/stats",
+    tag = "stats",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource")),
+    responses((status = 200, description = "Review statistics", body = UserStatsDto)),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_user_stats(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot view another user's stats".to_string())
+            .into_response();
+    }
+
+    match services.get_user_stats_use_case.execute(user_id).await {
+        Ok(stats) => Json(stats).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct ScoreHistogramParams {
+    pub deck_id: Option<Uuid>,
+    pub buckets: Option<usize>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// AI score distribution handler — GET /api/v1/users/{user_id}/score-histogram
+pub async fn get_score_histogram(
+    Path(user_id): Path<Uuid>,
+    Query(params): Query<ScoreHistogramParams>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot view another user's stats".to_string())
+            .into_response();
+    }
+
+    let query = ScoreHistogramQuery {
+        deck_id: params.deck_id,
+        buckets: params.buckets,
+        from: params.from,
+        to: params.to,
+    };
+    match services
+        .get_score_histogram_use_case
+        .execute(user_id, query)
+        .await
+    {
+        Ok(histogram) => Json(histogram).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Activity heatmap handler — GET /api/v1/users/{user_id}/activity-heatmap
+///
+/// Review counts per local day of week (Monday first) and hour, in the user's
+/// timezone preference.
+pub async fn get_activity_heatmap(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot read another user's activity".to_string())
+            .into_response();
+    }
+
+    match services
+        .get_activity_heatmap_use_case
+        .execute(user_id)
+        .await
+    {
+        Ok(heatmap) => Json(heatmap).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Today's reviews against the daily goal — GET /api/v1/users/{user_id}/goal-progress
+pub async fn get_goal_progress(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot read another user's progress".to_string())
+            .into_response();
+    }
+
+    match services.get_goal_progress_use_case.execute(user_id).await {
+        Ok(progress) => Json(progress).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Queue embeddings for cards without one — POST /api/v1/users/{user_id}/embeddings/backfill
+pub async fn backfill_embeddings(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot backfill another user's cards".to_string())
+            .into_response();
+    }
+
+    match services.backfill_embeddings_use_case.execute(user_id).await {
+        Ok(result) => (StatusCode::ACCEPTED, Json(result)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Suggest FSRS weights from the user's reviews — POST /api/v1/users/{user_id}/fsrs/optimize
+pub async fn optimize_fsrs(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError(
+            "Cannot optimize another user's FSRS weights".to_string(),
+        )
+        .into_response();
+    }
+
+    match services.optimize_fsrs_use_case.execute(user_id).await {
+        Ok(result) => Json(result).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Get user tags handler — GET /api/v1/users/{user_id}/tags
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{user_id}/tags",
+    tag = "cards",
+    params(("user_id" = Uuid, Path, description = "Owner of the resource")),
+    responses((status = 200, description = "Tags with usage counts, most used first", body = Vec<TagCountDto>)),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_user_tags(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot view another user's tags".to_string())
+            .into_response();
+    }
+
+    match services.card_service.get_user_tags(user_id).await {
+        Ok(tags) => Json(tags).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct StreamAuthQuery {
+    pub token: Option<String>,
+}
+
+/// Due count stream handler — GET /api/v1/users/{user_id}/due-count/stream
+///
+/// Server-sent events with the number of due cards, pushed on connect and then
+/// periodically. Browsers' `EventSource` cannot set headers, so the JWT is
+/// passed as the `token` query parameter; the stream closes when it expires.
+pub async fn stream_due_count(
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<StreamAuthQuery>,
+    State(services): State<AppServices>,
+) -> Response {
+    let (auth_user_id, token_expires_at) = match query.token.as_deref() {
+        Some(token) => match decode_jwt_with_expiry(token) {
+            Ok(decoded) => decoded,
+            Err(err) => return err.into_response(),
+        },
+        None => {
+            return AppError::AuthenticationError("Missing token query parameter".to_string())
+                .into_response()
+        }
+    };
+    if auth_user_id != user_id {
+        return AppError::AuthorizationError("Cannot watch another user's cards".to_string())
+            .into_response();
+    }
+
+    // The token is only checked here, so the stream ends when it expires
+    let updates = services
+        .get_due_count_use_case
+        .watch(user_id, token_expires_at);
+    let events =
+        ReceiverStream::new(updates).map(|update| Ok::<_, Infallible>(due_count_event(update)));
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+fn due_count_event(update: AppResult<DueCountDto>) -> Event {
+    let error = match update.map(|count| Event::default().event("due_count").json_data(count)) {
+        Ok(Ok(event)) => return event,
+        Ok(Err(err)) => err.to_string(),
+        Err(err) => err.to_string(),
+    };
+    tracing::warn!("Due count stream update failed: {}", error);
+    Event::default().event("error").data(error)
+}
+
+/// Get deck statistics handler
+#[utoipa::path(
+    get,
+    path = "/api/v1/decks/{deck_id}/stats",
+    tag = "stats",
+    params(("deck_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "Review statistics of the deck", body = DeckStatsDto),
+        (status = 404, description = "Deck not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_deck_stats(
+    Path(deck_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    match services
+        .get_deck_stats_use_case
+        .execute(deck_id, auth.user_id)
+        .await
+    {
+        Ok(stats) => Json(stats).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Scheduler config handler — GET /api/v1/decks/{deck_id}/scheduler-config
+///
+/// Returns the configuration the scheduler resolves for the deck: global
+/// defaults with the deck's overrides and FSRS weights applied, and the review
+/// mode the caller's preferences leave in effect.
+pub async fn get_scheduler_config(
+    Path(deck_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    match services
+        .get_scheduler_config_use_case
+        .execute(deck_id, auth.user_id)
+        .await
+    {
+        Ok(config) => Json(config).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Related decks handler — GET /api/v1/decks/{deck_id}/related
+///
+/// The authenticated user's other decks ranked by semantic overlap with the
+/// given deck.
+pub async fn get_related_decks(
+    Path(deck_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    match services
+        .recommend_related_decks_use_case
+        .execute(deck_id, auth.user_id)
+        .await
+    {
+        Ok(decks) => Json(decks).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Register handler — POST /api/v1/auth/register
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "Account created", body = AuthResponse),
+        (status = 400, description = "Invalid email, name or password"),
+        (status = 409, description = "Email already registered")
+    )
+)]
+pub async fn register(
+    State(services): State<AppServices>,
+    Json(req): Json<crate::application::dtos::RegisterRequest>,
+) -> Response {
+    match services.auth_service.register.execute(req).await {
+        Ok(res) => (StatusCode::CREATED, Json(res)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Change email handler — PUT /api/v1/users/{user_id}/email
+///
+/// Requires the current password. Only the authenticated user can change
+/// their own email.
+pub async fn change_email(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    Json(req): Json<crate::application::dtos::ChangeEmailRequest>,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot change another user's email".to_string())
+            .into_response();
+    }
+
+    match services
+        .auth_service
+        .change_email
+        .execute(user_id, req)
+        .await
+    {
+        Ok(user) => Json(user).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Change password handler — PUT /api/v1/users/{user_id}/password
+///
+/// Requires the current password. Only the authenticated user can change
+/// their own password.
+pub async fn change_password(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    Json(req): Json<crate::application::dtos::ChangePasswordRequest>,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot change another user's password".to_string())
+            .into_response();
+    }
+
+    match services
+        .auth_service
+        .change_password
+        .execute(user_id, req)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Preferences handler — GET /api/v1/users/{user_id}/preferences
+pub async fn get_user_preferences(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot read another user's preferences".to_string())
+            .into_response();
+    }
+
+    match services.user_service.get_preferences(user_id).await {
+        Ok(preferences) => Json(preferences).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Update preferences handler — PUT /api/v1/users/{user_id}/preferences
+///
+/// Changes the study day settings present in the body; omitted fields keep
+/// their stored values.
+pub async fn update_user_preferences(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    Json(update): Json<UpdatePreferencesRequest>,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError(
+            "Cannot change another user's preferences".to_string(),
+        )
+        .into_response();
+    }
+
+    match services
+        .user_service
+        .update_preferences(user_id, update)
+        .await
+    {
+        Ok(preferences) => Json(preferences).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Login handler — POST /api/v1/auth/login
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Logged in", body = AuthResponse),
+        (status = 401, description = "Wrong email or password")
+    )
+)]
+pub async fn login(
+    State(services): State<AppServices>,
+    Json(req): Json<crate::application::dtos::LoginRequest>,
+) -> Response {
+    match services.auth_service.login.execute(req).await {
+        Ok(res) => Json(res).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Import TSV handler — POST /api/v1/decks/{deck_id}/import/tsv
+///
+/// Accepts `multipart/form-data` with a single `file` field containing a UTF-8
+/// TSV file (lines of `front\tback`). Returns an `ImportResult` JSON.
+#[utoipa::path(
+    post,
+    path = "/api/v1/decks/{deck_id}/import/tsv",
+    tag = "import",
+    params(("deck_id" = Uuid, Path)),
+    request_body(content_type = "multipart/form-data", description = "`file`: UTF-8 TSV of `front<TAB>back` lines"),
+    responses(
+        (status = 200, description = "Import summary", body = ImportResult),
+        (status = 400, description = "Missing or unreadable file")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn import_tsv(
+    Path(deck_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    multipart: Multipart,
+) -> Response {
+    run_tsv_import(services, auth.user_id, Some(deck_id), multipart).await
+}
+
+/// Import TSV by directive handler — POST /api/v1/decks/import/tsv
+///
+/// Like `import_tsv`, but the target deck comes from the file's `#deck:Name`
+/// line; the deck is created when the user has none by that name.
+#[utoipa::path(
+    post,
+    path = "/api/v1/decks/import/tsv",
+    tag = "import",
+    request_body(content_type = "multipart/form-data", description = "`file`: UTF-8 TSV with a `#deck:Name` line and `front<TAB>back` lines"),
+    responses(
+        (status = 200, description = "Import summary", body = ImportResult),
+        (status = 400, description = "Missing file or `#deck:` line")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn import_tsv_by_directive(
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    multipart: Multipart,
+) -> Response {
+    run_tsv_import(services, auth.user_id, None, multipart).await
+}
+
+async fn run_tsv_import(
+    services: AppServices,
+    user_id: Uuid,
+    deck_id: Option<Uuid>,
+    mut multipart: Multipart,
+) -> Response {
+    let (file_bytes, fields) = match read_multipart_form(&mut multipart).await {
+        Ok((Some(b), fields)) => (b, fields),
+        Ok((None, _)) => {
+            return AppError::ValidationError("No 'file' field found in request".to_string())
+                .into_response()
+        }
+        Err(e) => return AppError::into_response(e),
+    };
+    let options = match import_options(&fields) {
+        Ok(options) => options,
+        Err(e) => return e.into_response(),
+    };
+
+    match services
+        .import_tsv_use_case
+        .execute(user_id, deck_id, file_bytes, options)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Import XML handler — POST /api/v1/decks/{deck_id}/import/xml
+///
+/// Accepts `multipart/form-data` with a `file` field containing a Mnemosyne XML
+/// export. Returns an `ImportResult` JSON.
+#[utoipa::path(
+    post,
+    path = "/api/v1/decks/{deck_id}/import/xml",
+    tag = "import",
+    params(("deck_id" = Uuid, Path)),
+    request_body(content_type = "multipart/form-data", description = "`file`: Mnemosyne XML export"),
+    responses(
+        (status = 200, description = "Import summary", body = ImportResult),
+        (status = 400, description = "Missing or invalid file")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn import_xml(
+    Path(deck_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    mut multipart: Multipart,
+) -> Response {
+    let (file_bytes, fields) = match read_multipart_form(&mut multipart).await {
+        Ok((Some(b), fields)) => (b, fields),
+        Ok((None, _)) => {
+            return AppError::ValidationError("No 'file' field found in request".to_string())
+                .into_response()
+        }
+        Err(e) => return AppError::into_response(e),
+    };
+    let options = match import_options(&fields) {
+        Ok(options) => options,
+        Err(e) => return e.into_response(),
+    };
+
+    match services
+        .import_xml_use_case
+        .execute(auth.user_id, deck_id, file_bytes, options)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Import CSV handler — POST /api/v1/decks/{deck_id}/import/csv
+///
+/// Accepts `multipart/form-data` with a `file` field containing a CSV of
+/// `front,back` rows, plus optional `delimiter` (one character, or `tab`;
+/// default `,`) and `header` (skip the first row) fields. Returns an
+/// `ImportResult` JSON.
+#[utoipa::path(
+    post,
+    path = "/api/v1/decks/{deck_id}/import/csv",
+    tag = "import",
+    params(("deck_id" = Uuid, Path)),
+    request_body(content_type = "multipart/form-data", description = "`file`: CSV of `front,back` rows; optional `delimiter` and `header`"),
+    responses(
+        (status = 200, description = "Import summary", body = ImportResult),
+        (status = 400, description = "Missing file or invalid format fields")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn import_csv(
+    Path(deck_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    mut multipart: Multipart,
+) -> Response {
+    let (file_bytes, fields) = match read_multipart_form(&mut multipart).await {
+        Ok((Some(b), fields)) => (b, fields),
+        Ok((None, _)) => {
+            return AppError::ValidationError("No 'file' field found in request".to_string())
+                .into_response()
+        }
+        Err(e) => return AppError::into_response(e),
+    };
+    let format = match csv_format(&fields) {
+        Ok(format) => format,
+        Err(e) => return e.into_response(),
+    };
+    let options = match import_options(&fields) {
+        Ok(options) => options,
+        Err(e) => return e.into_response(),
+    };
+
+    match services
+        .import_csv_use_case
+        .execute(auth.user_id, deck_id, file_bytes, format, options)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Import JSON handler — POST /api/v1/decks/{deck_id}/import/json
+///
+/// Accepts `multipart/form-data` with a `file` field containing a JSON document
+/// of cards, each optionally carrying its review history. Returns
+/// `JsonImportResult`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/decks/{deck_id}/import/json",
+    tag = "import",
+    params(("deck_id" = Uuid, Path)),
+    request_body(content_type = "multipart/form-data", description = "`file`: JSON document of cards with optional review history"),
+    responses(
+        (status = 200, description = "Import summary", body = JsonImportResult),
+        (status = 400, description = "Missing or invalid file")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn import_json(
+    Path(deck_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    mut multipart: Multipart,
+) -> Response {
+    let (file_bytes, fields) = match read_multipart_form(&mut multipart).await {
+        Ok((Some(b), fields)) => (b, fields),
+        Ok((None, _)) => {
+            return AppError::ValidationError("No 'file' field found in request".to_string())
+                .into_response()
+        }
+        Err(e) => return AppError::into_response(e),
+    };
+    let options = match import_options(&fields) {
+        Ok(options) => options,
+        Err(e) => return e.into_response(),
+    };
+
+    match services
+        .import_json_use_case
+        .execute(auth.user_id, deck_id, file_bytes, options)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Import Anki handler — POST /api/v1/decks/import/anki
+///
+/// Accepts `multipart/form-data` with a `file` field containing a `.apkg`
+/// archive and optional `front_index` / `back_index` fields selecting which note
+/// fields become the card front and back. Creates a new deck from the Anki deck
+/// name and returns `AnkiImportResult`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/decks/import/anki",
+    tag = "import",
+    request_body(content_type = "multipart/form-data", description = "`file`: `.apkg` archive; optional `front_index` and `back_index`"),
+    responses(
+        (status = 200, description = "Import summary", body = AnkiImportResult),
+        (status = 400, description = "Missing or invalid archive")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn import_anki(
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+    mut multipart: Multipart,
+) -> Response {
+    let (file_bytes, fields) = match read_multipart_form(&mut multipart).await {
+        Ok((Some(b), fields)) => (b, fields),
+        Ok((None, _)) => {
+            return AppError::ValidationError("No 'file' field found in request".to_string())
+                .into_response()
+        }
+        Err(e) => return AppError::into_response(e),
+    };
+
+    let mapping = match (fields.get("front_index"), fields.get("back_index")) {
+        (None, None) => None,
+        (front, back) => {
+            let parse = |v: Option<&String>, default: usize| match v {
+                Some(v) => v
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| AppError::ValidationError(format!("Invalid field index: {}", v))),
+                None => Ok(default),
+            };
+            let defaults = AnkiFieldMapping::default();
+            match (
+                parse(front, defaults.front_index),
+                parse(back, defaults.back_index),
+            ) {
+                (Ok(front_index), Ok(back_index)) => Some(AnkiFieldMapping {
+                    front_index,
+                    back_index,
+                }),
+                (Err(e), _) | (_, Err(e)) => return e.into_response(),
+            }
+        }
+    };
+    let options = match import_options(&fields) {
+        Ok(options) => options,
+        Err(e) => return e.into_response(),
+    };
+
+    match services
+        .import_anki_use_case
+        .execute(auth.user_id, file_bytes, mapping, options)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Import inspect handler — POST /api/v1/import/inspect
+///
+/// Accepts `multipart/form-data` with a `file` field and an optional `delimiter`
+/// field. Returns the detected column layout and a front/back guess without
+/// importing anything.
+#[utoipa::path(
+    post,
+    path = "/api/v1/import/inspect",
+    tag = "import",
+    request_body(content_type = "multipart/form-data", description = "`file`: delimited file to inspect; optional `delimiter`"),
+    responses(
+        (status = 200, description = "Detected column layout", body = ImportInspection),
+        (status = 400, description = "Missing or unreadable file")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn inspect_import(
+    State(services): State<AppServices>,
+    mut multipart: Multipart,
+) -> Response {
+    let (file_bytes, fields) = match read_multipart_form(&mut multipart).await {
+        Ok((Some(b), fields)) => (b, fields),
+        Ok((None, _)) => {
+            return AppError::ValidationError("No 'file' field found in request".to_string())
+                .into_response()
+        }
+        Err(e) => return AppError::into_response(e),
+    };
+
+    match services
+        .inspect_import_use_case
+        .execute(file_bytes, fields.get("delimiter").map(String::as_str))
+    {
+        Ok(inspection) => Json(inspection).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Import options from multipart form fields (`skip_duplicates` and
+/// `deck_column`, each `true|false`)
+fn import_options(fields: &HashMap<String, String>) -> Result<ImportOptions, AppError> {
+    let flag = |name: &str| match fields.get(name).map(|v| v.trim()) {
+        None | Some("") | Some("false") | Some("0") => Ok(false),
+        Some("true") | Some("1") => Ok(true),
+        Some(other) => Err(AppError::ValidationError(format!(
+            "Invalid {} value: {}",
+            name, other
+        ))),
+    };
+    Ok(ImportOptions {
+        skip_duplicates: flag("skip_duplicates")?,
+        deck_column: flag("deck_column")?,
+    })
+}
+
+/// CSV layout from the `delimiter` and `header` form fields
+fn csv_format(fields: &HashMap<String, String>) -> Result<CsvFormat, AppError> {
+    let delimiter = match fields.get("delimiter").map(String::as_str) {
+        None | Some("") => b',',
+        Some("tab") | Some("\t") => b'\t',
+        Some(value) => match value.as_bytes() {
+            [byte] if byte.is_ascii_punctuation() && *byte != b'"' => *byte,
+            _ => {
+                return Err(AppError::ValidationError(format!(
+                    "Invalid delimiter value: {}",
+                    value
+                )))
+            }
+        },
+    };
+    let has_header = match fields.get("header").map(|v| v.trim()) {
+        None | Some("") | Some("false") | Some("0") => false,
+        Some("true") | Some("1") => true,
+        Some(other) => {
+            return Err(AppError::ValidationError(format!(
+                "Invalid header value: {}",
+                other
+            )))
+        }
+    };
+    Ok(CsvFormat {
+        delimiter,
+        has_header,
+    })
+}
+
+/// Reads a multipart form: the `file` field (10 MB limit) plus every other
+/// field as text.
+async fn read_multipart_form(
+    multipart: &mut Multipart,
+) -> Result<(Option<bytes::Bytes>, HashMap<String, String>), AppError> {
+    let mut file = None;
+    let mut fields = HashMap::new();
+
+    while let Ok(Some(field)) = multipart.next_field().await {
+        match field.name().map(str::to_string) {
+            Some(name) if name == "file" => file = Some(read_file_field(field).await?),
+            Some(name) => {
+                if let Ok(text) = field.text().await {
+                    fields.insert(name, text);
+                }
+            }
+            None => {}
+        }
+    }
+
+    Ok((file, fields))
+}
+
+async fn read_file_field(
+    field: axum::extract::multipart::Field<'_>,
+) -> Result<bytes::Bytes, AppError> {
+    let bytes = field
+        .bytes()
+        .await
+        .map_err(|e| AppError::ValidationError(format!("Failed to read uploaded file: {}", e)))?;
+    if bytes.len() > 10 * 1024 * 1024 {
+        return Err(AppError::ValidationError(
+            "File exceeds the 10 MB size limit".to_string(),
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Start export handler — POST /users/{user_id}/export
+///
+/// Enqueues a background export of the user's decks, cards and review logs.
+/// Returns 202 with the job; 409 if an export is already pending.
+pub async fn start_export(
+    Path(user_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    if auth.user_id != user_id {
+        return AppError::AuthorizationError("Cannot export another user's data".to_string())
+            .into_response();
+    }
+
+    match services.export_user_data_use_case.start(user_id) {
+        Ok(job) => (StatusCode::ACCEPTED, Json(job)).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Export job status handler — GET /api/v1/export-jobs/{job_id}
+pub async fn get_export_job(
+    Path(job_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    match services
+        .export_user_data_use_case
+        .status(job_id, auth.user_id)
+    {
+        Ok(job) => Json(job).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Export download handler — GET /api/v1/export-jobs/{job_id}/download
+pub async fn download_export(
+    Path(job_id): Path<Uuid>,
+    State(services): State<AppServices>,
+    auth: AuthenticatedUser,
+) -> Response {
+    match services
+        .export_user_data_use_case
+        .download(job_id, auth.user_id)
+    {
+        Ok(payload) => (
+            [
+                (header::CONTENT_TYPE, "application/json".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"re-mem-export-{}.json\"", job_id),
+                ),
+            ],
+            payload,
+        )
+            .into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct MediaQuery {
+    pub url: String,
+    pub sig: Option<String>,
+}
+
+/// Signed media proxy — GET /api/v1/media?url=&sig=
+pub async fn get_media(
+    Query(query): Query<MediaQuery>,
+    State(services): State<AppServices>,
+) -> Response {
+    let Some(proxy) = &services.media_proxy else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match proxy.fetch(&query.url, query.sig.as_deref()).await {
+        Ok(media) => (
+            [
+                (header::CONTENT_TYPE, media.content_type),
+                (header::CACHE_CONTROL, "private, max-age=300".to_string()),
+                (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+                (header::CONTENT_SECURITY_POLICY, "sandbox".to_string()),
+            ],
+            media.body,
+        )
+            .into_response(),
+        Err(err) => err.into_response(),
     }
-    Ok(None)
 }
@@ -6,6 +6,7 @@ use axum::{
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::shared::{
@@ -41,6 +42,16 @@ where
     }
 }
 
+/// Middleware function that rejects requests whose bearer token is not
+/// `expected`, for internal endpoints guarded by a shared secret rather than a
+/// user JWT.
+pub async fn require_static_token(expected: Arc<str>, request: Request, next: Next) -> Response {
+    match extract_bearer_token(request.headers()) {
+        Some(token) if token == &*expected => next.run(request).await,
+        _ => AppError::AuthenticationError("Missing or invalid token".to_string()).into_response(),
+    }
+}
+
 /// Middleware function that rejects requests without a valid JWT.
 /// Apply to protected route groups via `Router::layer(middleware::from_fn(require_auth))`.
 pub async fn require_auth(request: Request, next: Next) -> Response {
@@ -0,0 +1,29 @@
+//! Request deadline middleware
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::shared::deadline;
+
+/// Header a client can use to ask for a shorter deadline than the server's
+pub const REQUEST_TIMEOUT_HEADER: &str = "x-request-timeout-ms";
+
+/// Middleware function that sets the request deadline used to cancel upstream
+/// AI calls: the shorter of `default` and the client's `X-Request-Timeout-Ms`.
+/// Requests with neither run without one.
+pub async fn request_deadline(default: Option<Duration>, request: Request, next: Next) -> Response {
+    let requested = request
+        .headers()
+        .get(REQUEST_TIMEOUT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_millis);
+    let timeout = match (default, requested) {
+        (Some(default), Some(requested)) => Some(default.min(requested)),
+        (default, requested) => default.or(requested),
+    };
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+    deadline::scope(deadline, next.run(request)).await
+}
@@ -10,8 +10,10 @@
 //! - S: Each handler focuses on one endpoint
 //! - I: Handlers use specific DTOs
 
+pub mod extractors;
 pub mod handlers;
 pub mod middleware;
+pub mod openapi;
 pub mod router;
 
 pub use router::create_router;
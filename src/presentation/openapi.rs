@@ -0,0 +1,103 @@
+//! OpenAPI contract of the main endpoints, served at `GET /api/openapi.json`
+//! with a Swagger UI at `/docs`
+
+use axum::{response::Html, Json};
+use utoipa::{
+    openapi::security::{Http, HttpAuthScheme, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use super::handlers;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Re-Mem API",
+        description = "Flashcards scheduled with FSRS and graded by AI answer validation"
+    ),
+    paths(
+        handlers::register,
+        handlers::login,
+        handlers::create_card,
+        handlers::get_user_cards,
+        handlers::get_random_card,
+        handlers::get_user_tags,
+        handlers::get_due_cards,
+        handlers::search_cards,
+        handlers::get_card,
+        handlers::update_card,
+        handlers::edit_card,
+        handlers::get_card_history,
+        handlers::delete_card,
+        handlers::batch_card_action,
+        handlers::create_deck,
+        handlers::get_user_decks,
+        handlers::update_deck,
+        handlers::delete_deck,
+        handlers::get_deck_cards,
+        handlers::get_deck_options,
+        handlers::update_deck_options,
+        handlers::submit_intelligent_review,
+        handlers::get_user_stats,
+        handlers::get_deck_stats,
+        handlers::import_tsv,
+        handlers::import_tsv_by_directive,
+        handlers::import_xml,
+        handlers::import_csv,
+        handlers::import_json,
+        handlers::import_anki,
+        handlers::inspect_import,
+    ),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "auth", description = "Registration and login"),
+        (name = "cards", description = "Flashcards"),
+        (name = "decks", description = "Decks of cards"),
+        (name = "reviews", description = "Answer grading and scheduling"),
+        (name = "stats", description = "Review statistics"),
+        (name = "import", description = "Deck imports")
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the JWT bearer scheme the protected paths refer to
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+    }
+}
+
+/// OpenAPI spec handler — GET /api/openapi.json
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Swagger UI handler — GET /docs
+///
+/// The UI assets are loaded from a CDN; only the page is served from here.
+pub async fn swagger_ui() -> Html<&'static str> {
+    Html(SWAGGER_UI_PAGE)
+}
+
+const SWAGGER_UI_PAGE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <title>Re-Mem API</title>
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+    <script>
+        window.ui = SwaggerUIBundle({ url: "/api/openapi.json", dom_id: "#swagger-ui" });
+    </script>
+</body>
+</html>
+"##;
@@ -0,0 +1,137 @@
+//! Ownership checks for user-owned resources.
+//!
+//! Reporting "exists but belongs to someone else" as 403 and "does not exist"
+//! as 404 lets a caller probe which ids exist. `OwnershipPolicy` decides
+//! whether unowned resources are reported as 404 instead.
+
+use uuid::Uuid;
+
+use crate::shared::error::{AppError, AppResult};
+
+/// Kind of access being checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+/// How a request for a resource owned by another user is reported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OwnershipPolicy {
+    /// 404 when missing, 403 when owned by another user
+    #[default]
+    Explicit,
+    /// 404 for unowned resources on reads; mutations keep 403
+    HideReads,
+    /// 404 for unowned resources on reads and mutations
+    HideAll,
+}
+
+impl OwnershipPolicy {
+    /// Policy from `OWNERSHIP_POLICY` (`explicit`, `hide_reads` or `hide_all`).
+    /// When unset, defaults to `hide_reads` if `ENVIRONMENT=production` and
+    /// `explicit` otherwise.
+    pub fn from_env() -> Self {
+        match std::env::var("OWNERSHIP_POLICY").ok().as_deref() {
+            Some("explicit") => Self::Explicit,
+            Some("hide_reads") => Self::HideReads,
+            Some("hide_all") => Self::HideAll,
+            other => {
+                if let Some(value) = other {
+                    tracing::warn!("Unknown OWNERSHIP_POLICY {:?}, using default", value);
+                }
+                match std::env::var("ENVIRONMENT").as_deref() {
+                    Ok("production") => Self::HideReads,
+                    _ => Self::Explicit,
+                }
+            }
+        }
+    }
+
+    fn hides(self, access: Access) -> bool {
+        match self {
+            Self::Explicit => false,
+            Self::HideReads => access == Access::Read,
+            Self::HideAll => true,
+        }
+    }
+
+    /// Return `resource` if it exists and `owner_id` of it is `user_id`.
+    ///
+    /// `kind` names the resource in error messages (e.g. `"Card"`).
+    pub fn authorize<T>(
+        self,
+        resource: Option<T>,
+        owner_id: impl FnOnce(&T) -> Uuid,
+        user_id: Uuid,
+        access: Access,
+        kind: &str,
+        id: Uuid,
+    ) -> AppResult<T> {
+        let not_found = || AppError::NotFound(format!("{} with id {} not found", kind, id));
+
+        let resource = resource.ok_or_else(not_found)?;
+        if owner_id(&resource) == user_id {
+            return Ok(resource);
+        }
+
+        if self.hides(access) {
+            Err(not_found())
+        } else {
+            Err(AppError::AuthorizationError(format!(
+                "Cannot access {} belonging to another user",
+                kind.to_lowercase()
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(policy: OwnershipPolicy, owner: Uuid, user: Uuid, access: Access) -> AppResult<Uuid> {
+        policy.authorize(Some(owner), |o| *o, user, access, "Card", Uuid::new_v4())
+    }
+
+    #[test]
+    fn test_owner_is_allowed_under_every_policy() {
+        let user = Uuid::new_v4();
+        for policy in [
+            OwnershipPolicy::Explicit,
+            OwnershipPolicy::HideReads,
+            OwnershipPolicy::HideAll,
+        ] {
+            assert!(check(policy, user, user, Access::Write).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_hide_reads_returns_not_found_for_unowned_reads_only() {
+        let (owner, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let policy = OwnershipPolicy::HideReads;
+
+        assert!(matches!(
+            check(policy, owner, other, Access::Read),
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            check(policy, owner, other, Access::Write),
+            Err(AppError::AuthorizationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_explicit_policy_distinguishes_missing_and_unowned() {
+        let (owner, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let policy = OwnershipPolicy::Explicit;
+
+        assert!(matches!(
+            check(policy, owner, other, Access::Read),
+            Err(AppError::AuthorizationError(_))
+        ));
+        let missing: AppResult<Uuid> =
+            policy.authorize(None, |o| *o, other, Access::Read, "Card", Uuid::new_v4());
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+    }
+}
@@ -0,0 +1,50 @@
+//! Checks on card content applied when cards are created or imported.
+
+/// Rules for card content; everything is allowed by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContentPolicy {
+    /// Refuse cards whose front and back are the same text (ignoring case
+    /// and whitespace) — usually a data error that makes a trivial card
+    pub reject_identical_sides: bool,
+}
+
+impl ContentPolicy {
+    /// Policy from `REJECT_IDENTICAL_CARD_SIDES` (`true`/`1` to enable)
+    pub fn from_env() -> Self {
+        Self {
+            reject_identical_sides: std::env::var("REJECT_IDENTICAL_CARD_SIDES")
+                .is_ok_and(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1")),
+        }
+    }
+
+    /// Why a card with this front and back is not allowed, if it isn't
+    pub fn check(&self, front: &str, back: &str) -> Result<(), &'static str> {
+        if self.reject_identical_sides && normalize(front) == normalize(back) {
+            return Err("Card front and back must differ");
+        }
+        Ok(())
+    }
+}
+
+/// Lowercase with whitespace runs collapsed to single spaces
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_sides_rejected_only_when_enabled() {
+        let strict = ContentPolicy {
+            reject_identical_sides: true,
+        };
+        assert!(strict.check("Word ", "word").is_err());
+        assert!(strict.check("hola", "hello").is_ok());
+        assert!(ContentPolicy::default().check("word", "word").is_ok());
+    }
+}
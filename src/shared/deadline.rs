@@ -0,0 +1,85 @@
+//! Per-request deadlines for slow upstream calls.
+//!
+//! The deadline lives in a task-local set by the request middleware, so
+//! adapters deep in the call chain (e.g. the OpenAI validator) can bound their
+//! calls without every port threading it through.
+
+use std::future::Future;
+use thiserror::Error;
+use tokio::time::Instant;
+
+tokio::task_local! {
+    static REQUEST_DEADLINE: Option<Instant>;
+}
+
+/// Returned (inside `anyhow::Error`) when a call was cancelled because the
+/// request deadline passed
+#[derive(Debug, Error)]
+#[error("Request deadline exceeded")]
+pub struct DeadlineExceeded;
+
+/// Run `future` with `deadline` as the current request deadline
+pub async fn scope<F: Future>(deadline: Option<Instant>, future: F) -> F::Output {
+    REQUEST_DEADLINE.scope(deadline, future).await
+}
+
+/// The deadline of the request being served, if any. Spawned tasks don't
+/// inherit it; pass it on with [`scope`].
+pub fn current() -> Option<Instant> {
+    REQUEST_DEADLINE
+        .try_with(|deadline| *deadline)
+        .ok()
+        .flatten()
+}
+
+/// Await `future`, cancelling it with [`DeadlineExceeded`] once the current
+/// request deadline passes
+pub async fn run<T, F>(future: F) -> anyhow::Result<T>
+where
+    F: Future<Output = anyhow::Result<T>>,
+{
+    match current() {
+        Some(deadline) => tokio::time::timeout_at(deadline, future)
+            .await
+            .unwrap_or_else(|_| Err(DeadlineExceeded.into())),
+        None => future.await,
+    }
+}
+
+/// Whether `err` was caused by a passed request deadline
+pub fn is_exceeded(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<DeadlineExceeded>().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_run_without_deadline_waits() {
+        let result = run(async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(1)
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_cancels_after_deadline() {
+        let deadline = Instant::now() + Duration::from_millis(10);
+
+        let result: anyhow::Result<()> = scope(Some(deadline), async {
+            run(async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(())
+            })
+            .await
+        })
+        .await;
+
+        assert!(is_exceeded(&result.unwrap_err()));
+    }
+}
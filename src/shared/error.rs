@@ -26,7 +26,7 @@ pub enum AppError {
     InternalError(String),
 
     #[error("Database error: {0}")]
-    DatabaseError(#[from] sqlx::Error),
+    DatabaseError(#[source] sqlx::Error),
 
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
@@ -37,8 +37,20 @@ pub enum AppError {
     #[error("Authorization failed: {0}")]
     AuthorizationError(String),
 
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
     #[error("External API error: {0}")]
     ExternalApiError(String),
+
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
 }
 
 #[derive(Serialize)]
@@ -48,14 +60,39 @@ pub struct ErrorResponse {
     pub status: u16,
 }
 
+/// SQLSTATE for `unique_violation`
+const UNIQUE_VIOLATION: &str = "23505";
+
+impl From<sqlx::Error> for AppError {
+    /// Unique constraint violations become `Conflict`, so races that slip past
+    /// an application-level uniqueness check still surface as 409
+    fn from(err: sqlx::Error) -> Self {
+        if let Some(db_err) = err.as_database_error() {
+            if db_err.code().as_deref() == Some(UNIQUE_VIOLATION) {
+                let message = match db_err.constraint() {
+                    Some(constraint) if constraint.contains("email") => {
+                        "An account with this email already exists".to_string()
+                    }
+                    _ => "Resource already exists".to_string(),
+                };
+                return AppError::Conflict(message);
+            }
+        }
+        AppError::DatabaseError(err)
+    }
+}
+
 impl AppError {
     pub fn status_code(&self) -> StatusCode {
         match self {
             AppError::ValidationError(_) => StatusCode::BAD_REQUEST,
             AppError::NotFound(_) => StatusCode::NOT_FOUND,
-            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Conflict(_) | AppError::QuotaExceeded(_) => StatusCode::CONFLICT,
+            AppError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
             AppError::AuthenticationError(_) => StatusCode::UNAUTHORIZED,
             AppError::AuthorizationError(_) => StatusCode::FORBIDDEN,
+            AppError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
             AppError::DatabaseError(_)
             | AppError::InternalError(_)
             | AppError::ExternalApiError(_)
@@ -83,3 +120,77 @@ impl IntoResponse for AppError {
         (status, Json(error_response)).into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::error::{DatabaseError, ErrorKind};
+    use std::borrow::Cow;
+
+    #[derive(Debug)]
+    struct FakeDbError {
+        code: &'static str,
+        constraint: Option<&'static str>,
+    }
+
+    impl std::fmt::Display for FakeDbError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "fake database error {}", self.code)
+        }
+    }
+
+    impl std::error::Error for FakeDbError {}
+
+    impl DatabaseError for FakeDbError {
+        fn message(&self) -> &str {
+            "duplicate key value violates unique constraint"
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.code))
+        }
+
+        fn constraint(&self) -> Option<&str> {
+            self.constraint
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::UniqueViolation
+        }
+    }
+
+    fn db_error(code: &'static str, constraint: Option<&'static str>) -> sqlx::Error {
+        sqlx::Error::Database(Box::new(FakeDbError { code, constraint }))
+    }
+
+    #[test]
+    fn test_unique_violation_maps_to_conflict() {
+        let err = AppError::from(db_error("23505", Some("users_email_key")));
+
+        assert!(
+            matches!(err, AppError::Conflict(ref msg) if msg == "An account with this email already exists")
+        );
+        assert_eq!(err.status_code(), StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn test_other_database_errors_stay_internal() {
+        let err = AppError::from(db_error("23503", None));
+        assert!(matches!(err, AppError::DatabaseError(_)));
+
+        let err = AppError::from(sqlx::Error::RowNotFound);
+        assert_eq!(err.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
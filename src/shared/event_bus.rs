@@ -1,8 +1,16 @@
-use std::sync::Arc;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Duration};
 use uuid::Uuid;
 
+use crate::{
+    domain::{entities::DeadLetterEvent, repositories::DeadLetterRepository},
+    shared::error::{AppError, AppResult},
+};
+
 /// Simple domain events enum for basic event-driven architecture
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum DomainEvent {
     CardReviewed {
         card_id: Uuid,
@@ -15,41 +23,246 @@ pub enum DomainEvent {
         user_id: Uuid,
         deck_id: Option<Uuid>,
     },
+    UserRegistered {
+        user_id: Uuid,
+        email: String,
+    },
+}
+
+/// Variant of a [`DomainEvent`], without its data; handlers subscribe to
+/// these with [`EventBus::register_for`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    CardReviewed,
+    CardCreated,
+    UserRegistered,
+}
+
+impl EventKind {
+    /// Name used for the `type` tag when an event is serialized
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CardReviewed => "card_reviewed",
+            Self::CardCreated => "card_created",
+            Self::UserRegistered => "user_registered",
+        }
+    }
+}
+
+impl DomainEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Self::CardReviewed { .. } => EventKind::CardReviewed,
+            Self::CardCreated { .. } => EventKind::CardCreated,
+            Self::UserRegistered { .. } => EventKind::UserRegistered,
+        }
+    }
+
+    /// Name of the variant, as used for the `type` tag when serialized
+    pub fn event_name(&self) -> &'static str {
+        self.kind().as_str()
+    }
+
+    /// Id of the entity the event is about: the card for card events, the
+    /// user for user events
+    pub fn event_id(&self) -> Uuid {
+        match self {
+            Self::CardReviewed { card_id, .. } | Self::CardCreated { card_id, .. } => *card_id,
+            Self::UserRegistered { user_id, .. } => *user_id,
+        }
+    }
 }
 
 /// Event handler trait for processing domain events
 #[async_trait::async_trait]
 pub trait EventHandler: Send + Sync {
     async fn handle(&self, event: DomainEvent) -> crate::AppResult<()>;
+
+    /// Name recorded on dead-lettered events and used to find the handler
+    /// again on replay
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// How often a failing handler is retried before its event is dead-lettered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first one
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled for each further retry
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy from `EVENT_HANDLER_MAX_ATTEMPTS` and
+    /// `EVENT_HANDLER_RETRY_BACKOFF_MS`; unset or unparsable values keep the
+    /// defaults (3 attempts, 200 ms)
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            max_attempts: var("EVENT_HANDLER_MAX_ATTEMPTS").map_or(defaults.max_attempts, |n| {
+                n.clamp(1, u32::MAX as u64) as u32
+            }),
+            initial_backoff: var("EVENT_HANDLER_RETRY_BACKOFF_MS")
+                .map_or(defaults.initial_backoff, Duration::from_millis),
+        }
+    }
+
+    /// Wait after failed attempt number `attempt` (1-based)
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
 }
 
 /// In-memory event bus for handling domain events
 /// This will evolve into a proper event sourcing system for DDD migration
 pub struct EventBus {
-    handlers: Vec<Arc<dyn EventHandler>>,
+    /// Handlers in registration order, each with the event kind it wants
+    /// (`None` for every event)
+    handlers: Vec<(Option<EventKind>, Arc<dyn EventHandler>)>,
+    retry_policy: RetryPolicy,
+    dead_letters: Option<Arc<dyn DeadLetterRepository>>,
 }
 
 impl EventBus {
     pub fn new() -> Self {
         Self {
             handlers: Vec::new(),
+            retry_policy: RetryPolicy::default(),
+            dead_letters: None,
         }
     }
 
-    /// Register an event handler
+    /// Retries for handlers that fail
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Store events whose handler failed every attempt, so they can be
+    /// replayed; without one they are only logged
+    pub fn with_dead_letter_repository(
+        mut self,
+        dead_letters: Arc<dyn DeadLetterRepository>,
+    ) -> Self {
+        self.dead_letters = Some(dead_letters);
+        self
+    }
+
+    /// Register an event handler for every event
     pub fn register_handler(&mut self, handler: Arc<dyn EventHandler>) {
-        self.handlers.push(handler);
+        self.handlers.push((None, handler));
+    }
+
+    /// Register an event handler for events of `kind` only. Call once per
+    /// kind for a handler interested in several.
+    pub fn register_for(&mut self, kind: EventKind, handler: Arc<dyn EventHandler>) {
+        self.handlers.push((Some(kind), handler));
     }
 
-    /// Publish a domain event to all registered handlers
+    /// Publish a domain event to the handlers registered for its kind
     pub async fn publish(&self, event: DomainEvent) {
-        tracing::info!("Event published: {:?}", event);
-        for handler in &self.handlers {
-            if let Err(e) = handler.handle(event.clone()).await {
-                tracing::error!("Event handler error: {:?}", e);
+        tracing::info!(
+            "Event published: {} {}",
+            event.event_name(),
+            event.event_id()
+        );
+        let kind = event.kind();
+        let interested = self
+            .handlers
+            .iter()
+            .filter(|(wanted, _)| wanted.is_none_or(|wanted| wanted == kind))
+            .map(|(_, handler)| handler);
+        for handler in interested {
+            if let Err((attempts, e)) = self.dispatch(handler.as_ref(), &event).await {
+                tracing::error!(
+                    "Event handler {} failed after {} attempts: {:?}",
+                    handler.name(),
+                    attempts,
+                    e
+                );
+                self.dead_letter(handler.name(), &event, attempts, &e).await;
+            }
+        }
+    }
+
+    /// Run the handler called `handler_name` on `event` again, with the
+    /// usual retries. A failure is returned, not dead-lettered.
+    pub async fn replay(&self, handler_name: &str, event: DomainEvent) -> AppResult<()> {
+        let (_, handler) = self
+            .handlers
+            .iter()
+            .find(|(_, handler)| handler.name() == handler_name)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Event handler {} is not registered", handler_name))
+            })?;
+        self.dispatch(handler.as_ref(), &event)
+            .await
+            .map_err(|(_, e)| e)
+    }
+
+    /// Hand `event` to `handler`, retrying with backoff. On failure, returns
+    /// the number of attempts made and the last error.
+    async fn dispatch(
+        &self,
+        handler: &dyn EventHandler,
+        event: &DomainEvent,
+    ) -> Result<(), (u32, AppError)> {
+        let mut attempt = 1;
+        loop {
+            match handler.handle(event.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt >= self.retry_policy.max_attempts => return Err((attempt, e)),
+                Err(e) => {
+                    tracing::warn!(
+                        "Event handler {} failed (attempt {}/{}), retrying: {:?}",
+                        handler.name(),
+                        attempt,
+                        self.retry_policy.max_attempts,
+                        e
+                    );
+                    tokio::time::sleep(self.retry_policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
             }
         }
     }
+
+    async fn dead_letter(
+        &self,
+        handler: &str,
+        event: &DomainEvent,
+        attempts: u32,
+        error: &AppError,
+    ) {
+        let Some(dead_letters) = &self.dead_letters else {
+            return;
+        };
+        let dead_letter = DeadLetterEvent {
+            id: Uuid::new_v4(),
+            handler: handler.to_string(),
+            event: event.clone(),
+            error: error.to_string(),
+            attempts: attempts as i32,
+            created_at: Utc::now(),
+            replayed_at: None,
+        };
+        if let Err(e) = dead_letters.create(&dead_letter).await {
+            tracing::error!("Failed to dead-letter event for {}: {:?}", handler, e);
+        }
+    }
 }
 
 impl Default for EventBus {
@@ -57,3 +270,231 @@ impl Default for EventBus {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    };
+
+    /// Fails the first `failures` calls, then succeeds
+    struct FlakyHandler {
+        failures: u32,
+        calls: AtomicU32,
+    }
+
+    impl FlakyHandler {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures,
+                calls: AtomicU32::new(0),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EventHandler for FlakyHandler {
+        async fn handle(&self, _event: DomainEvent) -> AppResult<()> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if call <= self.failures {
+                return Err(AppError::InternalError("connection reset".to_string()));
+            }
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "flaky"
+        }
+    }
+
+    #[derive(Default)]
+    struct StubDeadLetterRepo(Mutex<Vec<DeadLetterEvent>>);
+
+    #[async_trait::async_trait]
+    impl DeadLetterRepository for StubDeadLetterRepo {
+        async fn create(&self, dead_letter: &DeadLetterEvent) -> AppResult<Uuid> {
+            self.0.lock().unwrap().push(dead_letter.clone());
+            Ok(dead_letter.id)
+        }
+        async fn find_by_id(&self, id: Uuid) -> AppResult<Option<DeadLetterEvent>> {
+            Ok(self.0.lock().unwrap().iter().find(|d| d.id == id).cloned())
+        }
+        async fn list_pending(&self, limit: i64, offset: i64) -> AppResult<Vec<DeadLetterEvent>> {
+            let stored = self.0.lock().unwrap();
+            Ok(stored
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+        async fn mark_replayed(&self, _id: Uuid, _at: chrono::DateTime<Utc>) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    fn event() -> DomainEvent {
+        DomainEvent::CardReviewed {
+            card_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            score: 0.9,
+            rating: 3,
+        }
+    }
+
+    fn bus(handler: Arc<FlakyHandler>, dead_letters: Arc<StubDeadLetterRepo>) -> EventBus {
+        let mut bus = EventBus::new()
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 3,
+                initial_backoff: Duration::ZERO,
+            })
+            .with_dead_letter_repository(dead_letters);
+        bus.register_handler(handler);
+        bus
+    }
+
+    #[tokio::test]
+    async fn test_handler_failing_twice_is_retried() {
+        let handler = Arc::new(FlakyHandler::new(2));
+        let dead_letters = Arc::new(StubDeadLetterRepo::default());
+
+        bus(handler.clone(), dead_letters.clone())
+            .publish(event())
+            .await;
+
+        assert_eq!(handler.calls.load(Ordering::SeqCst), 3);
+        assert!(dead_letters.0.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_handler_always_failing_is_dead_lettered() {
+        let handler = Arc::new(FlakyHandler::new(u32::MAX));
+        let dead_letters = Arc::new(StubDeadLetterRepo::default());
+        let event = event();
+
+        bus(handler.clone(), dead_letters.clone())
+            .publish(event.clone())
+            .await;
+
+        assert_eq!(handler.calls.load(Ordering::SeqCst), 3);
+        let stored = dead_letters.0.lock().unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].handler, "flaky");
+        assert_eq!(stored[0].attempts, 3);
+        assert_eq!(stored[0].event, event);
+    }
+
+    /// Keeps every event it is handed
+    #[derive(Default)]
+    struct RecordingHandler(Mutex<Vec<DomainEvent>>);
+
+    #[async_trait::async_trait]
+    impl EventHandler for RecordingHandler {
+        async fn handle(&self, event: DomainEvent) -> AppResult<()> {
+            self.0.lock().unwrap().push(event);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_handler_receives_event_despite_failing_one() {
+        let first = Arc::new(RecordingHandler::default());
+        let second = Arc::new(RecordingHandler::default());
+        let failing = Arc::new(FlakyHandler::new(u32::MAX));
+        let mut bus = EventBus::new().with_retry_policy(RetryPolicy {
+            max_attempts: 1,
+            initial_backoff: Duration::ZERO,
+        });
+        bus.register_handler(first.clone());
+        bus.register_handler(failing.clone());
+        bus.register_handler(second.clone());
+        let event = event();
+
+        bus.publish(event.clone()).await;
+
+        assert_eq!(*first.0.lock().unwrap(), vec![event.clone()]);
+        assert_eq!(*second.0.lock().unwrap(), vec![event]);
+        assert_eq!(failing.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replay_runs_named_handler() {
+        let handler = Arc::new(FlakyHandler::new(0));
+        let bus = bus(handler.clone(), Arc::new(StubDeadLetterRepo::default()));
+
+        bus.replay("flaky", event()).await.unwrap();
+        assert_eq!(handler.calls.load(Ordering::SeqCst), 1);
+
+        let unknown = bus.replay("missing", event()).await;
+        assert!(matches!(unknown, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_handler_only_receives_kinds_it_registered_for() {
+        let created_only = Arc::new(RecordingHandler::default());
+        let everything = Arc::new(RecordingHandler::default());
+        let mut bus = EventBus::new();
+        bus.register_for(EventKind::CardCreated, created_only.clone());
+        bus.register_handler(everything.clone());
+        let created = DomainEvent::CardCreated {
+            card_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            deck_id: None,
+        };
+
+        bus.publish(event()).await;
+        bus.publish(created.clone()).await;
+
+        assert_eq!(*created_only.0.lock().unwrap(), vec![created]);
+        assert_eq!(everything.0.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_event_name_matches_serialized_tag() {
+        let user_id = Uuid::new_v4();
+        let card_id = Uuid::new_v4();
+        for (event, id) in [
+            (
+                DomainEvent::CardReviewed {
+                    card_id,
+                    user_id,
+                    score: 0.9,
+                    rating: 3,
+                },
+                card_id,
+            ),
+            (
+                DomainEvent::CardCreated {
+                    card_id,
+                    user_id,
+                    deck_id: None,
+                },
+                card_id,
+            ),
+            (
+                DomainEvent::UserRegistered {
+                    user_id,
+                    email: "a@example.com".to_string(),
+                },
+                user_id,
+            ),
+        ] {
+            let json = serde_json::to_value(&event).unwrap();
+            assert_eq!(json["type"], event.event_name());
+            assert_eq!(event.event_id(), id);
+            assert_eq!(serde_json::from_value::<DomainEvent>(json).unwrap(), event);
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(100),
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    }
+}
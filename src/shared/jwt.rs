@@ -1,7 +1,7 @@
 //! JWT utilities for encoding and decoding authentication tokens.
 
 use crate::shared::error::{AppError, AppResult};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -41,6 +41,11 @@ pub fn encode_jwt(user_id: Uuid) -> AppResult<String> {
 }
 
 pub fn decode_jwt(token: &str) -> AppResult<Uuid> {
+    decode_jwt_with_expiry(token).map(|(user_id, _)| user_id)
+}
+
+/// User id of a valid token together with when the token expires
+pub fn decode_jwt_with_expiry(token: &str) -> AppResult<(Uuid, DateTime<Utc>)> {
     let data = decode::<Claims>(
         token,
         &DecodingKey::from_secret(jwt_secret().as_bytes()),
@@ -48,8 +53,11 @@ pub fn decode_jwt(token: &str) -> AppResult<Uuid> {
     )
     .map_err(|e| AppError::AuthenticationError(format!("Invalid token: {e}")))?;
 
-    Uuid::parse_str(&data.claims.sub)
-        .map_err(|_| AppError::AuthenticationError("Invalid user id in token".to_string()))
+    let user_id = Uuid::parse_str(&data.claims.sub)
+        .map_err(|_| AppError::AuthenticationError("Invalid user id in token".to_string()))?;
+    let expires_at = DateTime::from_timestamp(data.claims.exp as i64, 0)
+        .ok_or_else(|| AppError::AuthenticationError("Invalid expiry in token".to_string()))?;
+    Ok((user_id, expires_at))
 }
 
 #[cfg(test)]
@@ -64,6 +72,14 @@ mod tests {
         assert_eq!(user_id, decoded);
     }
 
+    #[test]
+    fn test_decode_reports_expiry() {
+        let token = encode_jwt(Uuid::new_v4()).expect("encode should succeed");
+        let (_, expires_at) = decode_jwt_with_expiry(&token).expect("decode should succeed");
+        let expected = Utc::now() + chrono::Duration::days(expiration_days());
+        assert!((expected - expires_at).num_seconds().abs() <= 2);
+    }
+
     #[test]
     fn test_decode_invalid_token_returns_error() {
         let result = decode_jwt("not.a.valid.token");
@@ -0,0 +1,82 @@
+//! Keyed async locks: one mutex per key, created on first use and dropped
+//! once nobody holds or waits for it
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex, Weak},
+};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Serializes work per key while letting different keys proceed in parallel
+pub struct KeyedLocks<K> {
+    locks: Mutex<HashMap<K, Weak<AsyncMutex<()>>>>,
+}
+
+impl<K> Default for KeyedLocks<K> {
+    fn default() -> Self {
+        Self {
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone> KeyedLocks<K> {
+    /// Wait for the lock of `key`; it is released when the guard drops
+    pub async fn lock(&self, key: K) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            // Forget keys whose lock is no longer held or awaited
+            locks.retain(|_, lock| lock.strong_count() > 0);
+            match locks.get(&key).and_then(Weak::upgrade) {
+                Some(lock) => lock,
+                None => {
+                    let lock = Arc::new(AsyncMutex::new(()));
+                    locks.insert(key, Arc::downgrade(&lock));
+                    lock
+                }
+            }
+        };
+        lock.lock_owned().await
+    }
+
+    /// Keys with a live lock
+    pub fn len(&self) -> usize {
+        self.locks
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|lock| lock.strong_count() > 0)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_same_key_waits_other_keys_do_not() {
+        let locks = Arc::new(KeyedLocks::default());
+        let guard = locks.lock("a").await;
+
+        let waiting = tokio::spawn({
+            let locks = locks.clone();
+            async move { locks.lock("a").await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+
+        // Another key is not blocked
+        drop(locks.lock("b").await);
+
+        drop(guard);
+        drop(waiting.await.unwrap());
+        assert!(locks.is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+//! HMAC signatures for media proxy URLs.
+//!
+//! Card responses link external images/audio through `/api/v1/media`; the
+//! signature ties each proxied URL to this server so the endpoint can't be
+//! used as an open proxy.
+
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Path of the media proxy endpoint
+pub const MEDIA_PROXY_PATH: &str = "/api/v1/media";
+
+/// Signs and verifies media URLs with a server-side secret
+#[derive(Clone)]
+pub struct MediaSigner {
+    key: Vec<u8>,
+}
+
+impl std::fmt::Debug for MediaSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MediaSigner(***)")
+    }
+}
+
+impl MediaSigner {
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self { key: secret.into() }
+    }
+
+    /// Signer keyed with `MEDIA_PROXY_SECRET`, or `None` (proxy disabled)
+    /// when unset or empty
+    pub fn from_env() -> Option<Self> {
+        std::env::var("MEDIA_PROXY_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .map(Self::new)
+    }
+
+    fn mac(&self, url: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(url.as_bytes());
+        mac
+    }
+
+    /// Hex-encoded HMAC-SHA256 of `url`
+    pub fn sign(&self, url: &str) -> String {
+        hex::encode(self.mac(url).finalize().into_bytes())
+    }
+
+    /// Whether `signature` was produced by `sign(url)`; compared in constant time
+    pub fn verify(&self, url: &str, signature: &str) -> bool {
+        match hex::decode(signature) {
+            Ok(bytes) => self.mac(url).verify_slice(&bytes).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    /// Relative proxy URL serving `url`
+    pub fn proxy_url(&self, url: &str) -> String {
+        format!(
+            "{}?url={}&sig={}",
+            MEDIA_PROXY_PATH,
+            utf8_percent_encode(url, NON_ALPHANUMERIC),
+            self.sign(url)
+        )
+    }
+}
+
+/// http(s) URLs in `text` that point at an image or audio file, judged by
+/// their extension
+pub fn media_urls(text: &str) -> Vec<&str> {
+    const EXTENSIONS: [&str; 9] = [
+        ".png", ".jpg", ".jpeg", ".gif", ".webp", ".svg", ".mp3", ".ogg", ".wav",
+    ];
+    text.split(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | '(' | ')'))
+        .filter(|word| word.starts_with("http://") || word.starts_with("https://"))
+        .filter(|url| {
+            let path = url.split(['?', '#']).next().unwrap_or_default();
+            let path = path.to_ascii_lowercase();
+            EXTENSIONS.iter().any(|ext| path.ends_with(ext))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signer = MediaSigner::new("secret");
+        let url = "https://example.com/cat.png";
+        let sig = signer.sign(url);
+
+        assert!(signer.verify(url, &sig));
+        assert!(!signer.verify("https://example.com/dog.png", &sig));
+        assert!(!signer.verify(url, "not-hex"));
+        assert!(!MediaSigner::new("other").verify(url, &sig));
+        assert!(signer
+            .proxy_url(url)
+            .starts_with("/api/v1/media?url=https%3A%2F%2Fexample%2Ecom%2Fcat%2Epng&sig="));
+    }
+
+    #[test]
+    fn test_media_urls_finds_images_and_audio() {
+        let text = r#"<img src="https://example.com/a.PNG?v=2"> listen: http://cdn.test/b.mp3 see https://example.com/page"#;
+        assert_eq!(
+            media_urls(text),
+            vec!["https://example.com/a.PNG?v=2", "http://cdn.test/b.mp3"]
+        );
+    }
+}
@@ -1,9 +1,16 @@
 //! Shared module containing cross-cutting concerns
 //! Including event bus, error handling, and utilities
 
+pub mod access;
+pub mod content_policy;
+pub mod deadline;
 pub mod error;
 pub mod event_bus;
 pub mod jwt;
+pub mod keyed_lock;
+pub mod media_signature;
+pub mod quota;
+pub mod rate_limit;
 
 pub use error::{AppError, AppResult};
 pub use event_bus::{DomainEvent, EventBus, EventHandler};
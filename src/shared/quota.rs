@@ -0,0 +1,86 @@
+//! Per-user limits on the number of decks and cards, for multi-tenant
+//! deployments.
+
+use crate::shared::error::{AppError, AppResult};
+
+/// Per-user deck and card limits; `None` means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UserQuotas {
+    pub max_decks: Option<i64>,
+    pub max_cards: Option<i64>,
+}
+
+impl UserQuotas {
+    /// Quotas from `MAX_DECKS_PER_USER` and `MAX_CARDS_PER_USER`. Unset, zero
+    /// or unparsable values leave the limit off.
+    pub fn from_env() -> Self {
+        Self {
+            max_decks: limit_from_env("MAX_DECKS_PER_USER"),
+            max_cards: limit_from_env("MAX_CARDS_PER_USER"),
+        }
+    }
+
+    /// Fail with `QuotaExceeded` if a user who owns `current` decks may not
+    /// create another one
+    pub fn check_new_deck(&self, current: i64) -> AppResult<()> {
+        match self.max_decks {
+            Some(max) if current >= max => Err(AppError::QuotaExceeded(format!(
+                "Deck limit of {} per user reached",
+                max
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fail with `QuotaExceeded` if a user who owns `current` cards may not
+    /// create another one
+    pub fn check_new_card(&self, current: i64) -> AppResult<()> {
+        match self.remaining_cards(current) {
+            Some(0) => Err(AppError::QuotaExceeded(format!(
+                "Card limit of {} per user reached",
+                self.max_cards.unwrap_or_default()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// How many more cards a user who owns `current` cards may create, or
+    /// `None` when unlimited
+    pub fn remaining_cards(&self, current: i64) -> Option<i64> {
+        self.max_cards.map(|max| (max - current).max(0))
+    }
+}
+
+fn limit_from_env(name: &str) -> Option<i64> {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|limit| *limit > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlimited_by_default() {
+        let quotas = UserQuotas::default();
+        assert!(quotas.check_new_deck(1_000).is_ok());
+        assert!(quotas.check_new_card(1_000).is_ok());
+        assert_eq!(quotas.remaining_cards(1_000), None);
+    }
+
+    #[test]
+    fn test_remaining_cards_never_negative() {
+        let quotas = UserQuotas {
+            max_decks: None,
+            max_cards: Some(10),
+        };
+        assert_eq!(quotas.remaining_cards(4), Some(6));
+        assert_eq!(quotas.remaining_cards(12), Some(0));
+        assert!(matches!(
+            quotas.check_new_card(10),
+            Err(AppError::QuotaExceeded(_))
+        ));
+    }
+}
@@ -0,0 +1,75 @@
+//! Per-user limit on paid AI calls.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+use crate::shared::error::{AppError, AppResult};
+
+/// Fixed-window limiter: each user may spend `limit` AI calls per `window`
+pub struct AiRateLimiter {
+    limit: u32,
+    window: Duration,
+    usage: Mutex<HashMap<Uuid, (Instant, u32)>>,
+}
+
+impl AiRateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Limiter allowing `AI_REQUESTS_PER_MINUTE` calls per user, or `None`
+    /// when unset or zero
+    pub fn from_env() -> Option<Self> {
+        std::env::var("AI_REQUESTS_PER_MINUTE")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|limit| *limit > 0)
+            .map(|limit| Self::new(limit, Duration::from_secs(60)))
+    }
+
+    /// Record `cost` calls for `user_id`, or fail with `RateLimited` without
+    /// recording anything if that would exceed the limit
+    pub fn acquire(&self, user_id: Uuid, cost: u32) -> AppResult<()> {
+        let now = Instant::now();
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        usage.retain(|_, (start, _)| now.duration_since(*start) < self.window);
+
+        let (_, used) = usage.entry(user_id).or_insert((now, 0));
+        if used.saturating_add(cost) > self.limit {
+            return Err(AppError::RateLimited(format!(
+                "AI request limit of {} per {}s reached",
+                self.limit,
+                self.window.as_secs()
+            )));
+        }
+        *used += cost;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_is_per_user_and_rejects_whole_batch() {
+        let limiter = AiRateLimiter::new(5, Duration::from_secs(60));
+        let user = Uuid::new_v4();
+
+        assert!(limiter.acquire(user, 3).is_ok());
+        assert!(matches!(
+            limiter.acquire(user, 3),
+            Err(AppError::RateLimited(_))
+        ));
+        assert!(limiter.acquire(user, 2).is_ok());
+        assert!(limiter.acquire(Uuid::new_v4(), 5).is_ok());
+    }
+}
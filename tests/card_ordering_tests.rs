@@ -0,0 +1,146 @@
+//! Database-backed tests for card list ordering
+//!
+//! These run against the database in `TEST_DATABASE_URL` (initialized with
+//! `scripts/init.sql`) and are skipped when it is not set.
+
+use bytes::Bytes;
+use chrono::Utc;
+use re_mem::application::dtos::ImportOptions;
+use re_mem::application::use_cases::ImportTsvUseCase;
+use re_mem::domain::entities::{Card, Deck, User};
+use re_mem::domain::repositories::{CardRepository, DeckRepository, UserRepository};
+use re_mem::infrastructure::database::ExtensionCatalog;
+use re_mem::infrastructure::repositories::{
+    PgCardRepository, PgDeckRepository, PgDeckStatsRepository, PgUserRepository,
+};
+use re_mem::infrastructure::FallbackValidator;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+async fn test_pool() -> Option<PgPool> {
+    let url = std::env::var("TEST_DATABASE_URL").ok()?;
+    Some(
+        PgPool::connect(&url)
+            .await
+            .expect("TEST_DATABASE_URL is unreachable"),
+    )
+}
+
+/// Card repository for the test database; without pgvector it has no
+/// `answer_embedding` column, so embeddings are left out
+async fn card_repository(pool: &PgPool) -> PgCardRepository {
+    let pgvector = pool
+        .has_extension("vector")
+        .await
+        .expect("Cannot list installed extensions");
+    PgCardRepository::new(pool.clone()).with_embeddings(pgvector)
+}
+
+#[tokio::test]
+async fn test_find_by_deck_and_user_order_is_stable() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("TEST_DATABASE_URL not set, skipping");
+        return;
+    };
+    let users = PgUserRepository::new(pool.clone());
+    let decks = PgDeckRepository::new(pool.clone());
+    let cards = card_repository(&pool).await;
+
+    let user = User::new(
+        format!("{}@example.com", uuid::Uuid::new_v4()),
+        "Order".to_string(),
+    );
+    users.create(&user).await.unwrap();
+    let deck = Deck::new(user.id, "Order".to_string(), None);
+    decks.create(&deck).await.unwrap();
+
+    // Equal creation times, so only the id tiebreak decides their order
+    let created_at = Utc::now();
+    let batch: Vec<Card> = (0..5)
+        .map(|i| {
+            let mut card =
+                Card::new(user.id, format!("Q{}", i), "A".to_string()).with_deck(deck.id);
+            card.created_at = created_at;
+            card
+        })
+        .collect();
+    cards.bulk_create(&batch).await.unwrap();
+
+    let first = cards.find_by_deck(deck.id).await.unwrap();
+    let second = cards.find_by_deck(deck.id).await.unwrap();
+    let by_user = cards.find_by_user(user.id).await.unwrap();
+    users.delete(user.id).await.unwrap();
+
+    let ids = |cards: &[Card]| cards.iter().map(|card| card.id).collect::<Vec<_>>();
+    let mut expected = ids(&batch);
+    expected.sort();
+    assert_eq!(ids(&first), expected);
+    assert_eq!(ids(&second), expected);
+    assert_eq!(ids(&by_user), expected);
+}
+
+#[tokio::test]
+async fn test_tsv_import_keeps_file_order() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("TEST_DATABASE_URL not set, skipping");
+        return;
+    };
+    let users = PgUserRepository::new(pool.clone());
+    let decks = PgDeckRepository::new(pool.clone());
+    let cards = Arc::new(card_repository(&pool).await);
+
+    let user = User::new(
+        format!("{}@example.com", uuid::Uuid::new_v4()),
+        "Import order".to_string(),
+    );
+    users.create(&user).await.unwrap();
+    let deck = Deck::new(user.id, "Import order".to_string(), None);
+    decks.create(&deck).await.unwrap();
+
+    let fronts: Vec<String> = (0..30).map(|i| format!("Card {:02}", 29 - i)).collect();
+    let tsv: String = fronts
+        .iter()
+        .map(|front| format!("{}\tback\n", front))
+        .collect();
+    let import = ImportTsvUseCase::new(
+        cards.clone(),
+        Arc::new(PgDeckRepository::new(pool.clone())),
+        Arc::new(PgDeckStatsRepository::new(pool.clone())),
+        Arc::new(FallbackValidator),
+    );
+    import
+        .execute(
+            user.id,
+            Some(deck.id),
+            Bytes::from(tsv),
+            ImportOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let listed: Vec<String> = cards
+        .find_by_deck(deck.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|card| card.question)
+        .collect();
+    assert_eq!(listed, fronts);
+
+    // Cards created in the same instant still come back as the file listed them
+    sqlx::query("UPDATE cards SET created_at = NOW() WHERE deck_id = $1")
+        .bind(deck.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let tied: Vec<String> = cards
+        .find_by_deck(deck.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|card| card.question)
+        .collect();
+    users.delete(user.id).await.unwrap();
+
+    assert_eq!(tied, fronts);
+}
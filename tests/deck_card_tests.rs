@@ -195,10 +195,11 @@ mod card_repository_tests {
     #[tokio::test]
     async fn test_card_with_embedding() {
         let user_id = Uuid::new_v4();
-        let embedding = vec![0.1, 0.2, 0.3, 0.4, 0.5];
+        let embedding = vec![0.1; re_mem::domain::embedding::EMBEDDING_DIMENSIONS];
 
         let card = Card::new(user_id, "Test".to_string(), "Answer".to_string())
-            .with_embedding(embedding.clone());
+            .with_embedding(embedding.clone())
+            .unwrap();
 
         assert_eq!(card.answer_embedding, Some(embedding));
     }
@@ -0,0 +1,123 @@
+//! Database-backed tests for the deck trash
+//!
+//! These run against the database in `TEST_DATABASE_URL` (initialized with
+//! `scripts/init.sql`) and are skipped when it is not set.
+
+use chrono::Utc;
+use re_mem::application::services::CardService;
+use re_mem::domain::entities::{Card, Deck, User};
+use re_mem::domain::repositories::{
+    CardListOptions, CardRepository, DeckRepository, UserRepository,
+};
+use re_mem::infrastructure::database::ExtensionCatalog;
+use re_mem::infrastructure::repositories::{PgCardRepository, PgDeckRepository, PgUserRepository};
+use re_mem::shared::event_bus::EventBus;
+use re_mem::AppError;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+async fn test_pool() -> Option<PgPool> {
+    let url = std::env::var("TEST_DATABASE_URL").ok()?;
+    Some(
+        PgPool::connect(&url)
+            .await
+            .expect("TEST_DATABASE_URL is unreachable"),
+    )
+}
+
+/// Card repository for the test database; without pgvector it has no
+/// `answer_embedding` column, so embeddings are left out
+async fn card_repository(pool: &PgPool) -> PgCardRepository {
+    let pgvector = pool
+        .has_extension("vector")
+        .await
+        .expect("Cannot list installed extensions");
+    PgCardRepository::new(pool.clone()).with_embeddings(pgvector)
+}
+
+#[tokio::test]
+async fn test_trashed_deck_hides_deck_and_cards_until_restored() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("TEST_DATABASE_URL not set, skipping");
+        return;
+    };
+    let users = PgUserRepository::new(pool.clone());
+    let decks = Arc::new(PgDeckRepository::new(pool.clone()));
+    let cards = Arc::new(card_repository(&pool).await);
+    let card_service = CardService::new(cards.clone(), decks.clone(), Arc::new(EventBus::new()));
+
+    let user = User::new(
+        format!("{}@example.com", uuid::Uuid::new_v4()),
+        "Trash".to_string(),
+    );
+    users.create(&user).await.unwrap();
+    let mut deck = Deck::new(user.id, "Trash".to_string(), None);
+    decks.create(&deck).await.unwrap();
+    let card = Card::new(user.id, "Hola".to_string(), "Hello".to_string())
+        .with_deck(deck.id)
+        .with_tags(["greetings"]);
+    cards.create(&card).await.unwrap();
+
+    deck.deleted_at = Some(Utc::now());
+    decks.update(&deck).await.unwrap();
+    let hidden_deck = decks.find_by_id(deck.id).await.unwrap();
+    let listed_decks = decks.find_by_user(user.id).await.unwrap();
+    let trashed = decks.find_deleted_by_user(user.id).await.unwrap();
+    let hidden_cards = cards.find_by_user(user.id).await.unwrap();
+    let due_while_trashed = cards.count_due_by_user(user.id, Utc::now()).await.unwrap();
+    let due_in_trashed_deck = cards
+        .find_due_by_deck(deck.id, Utc::now(), 50, 0)
+        .await
+        .unwrap();
+    let tags_while_trashed = cards.count_tags_by_user(user.id).await.unwrap();
+    let deck_cards_while_trashed = cards.find_by_deck(deck.id).await.unwrap();
+    let deck_page_while_trashed = cards
+        .find_by_deck_paginated(deck.id, CardListOptions::default())
+        .await
+        .unwrap();
+    let deck_listing_while_trashed = card_service
+        .get_deck_cards(deck.id, user.id, CardListOptions::default())
+        .await;
+    let kept = cards.find_by_id(card.id).await.unwrap();
+    let quota_cards_while_trashed = cards.count_by_user(user.id).await.unwrap();
+    let quota_decks_while_trashed = decks.count_by_user(user.id).await.unwrap();
+    let cards_in_trashed_deck = cards.count_by_deck(deck.id).await.unwrap();
+
+    deck.deleted_at = None;
+    decks.update(&deck).await.unwrap();
+    let restored_deck = decks.find_by_id(deck.id).await.unwrap();
+    let restored_cards = cards.find_by_user(user.id).await.unwrap();
+    let quota_cards_restored = cards.count_by_user(user.id).await.unwrap();
+    let restored_deck_listing = card_service
+        .get_deck_cards(deck.id, user.id, CardListOptions::default())
+        .await
+        .unwrap();
+    users.delete(user.id).await.unwrap();
+
+    assert!(hidden_deck.is_none());
+    assert!(listed_decks.is_empty());
+    assert_eq!(trashed.len(), 1);
+    assert!(hidden_cards.is_empty());
+    assert_eq!(due_while_trashed, 0);
+    assert!(due_in_trashed_deck.is_empty());
+    assert!(tags_while_trashed.is_empty());
+    assert!(deck_cards_while_trashed.is_empty());
+    assert!(deck_page_while_trashed.is_empty());
+    assert!(matches!(
+        deck_listing_while_trashed,
+        Err(AppError::NotFound(_))
+    ));
+    // Trashing a deck never deletes its cards
+    assert!(kept.is_some());
+    // Quotas leave out trashed decks and their cards alike
+    assert_eq!(
+        (quota_decks_while_trashed, quota_cards_while_trashed),
+        (0, 0)
+    );
+    assert_eq!(cards_in_trashed_deck, 1);
+    assert_eq!(quota_cards_restored, 1);
+    assert!(restored_deck.is_some());
+    assert_eq!(restored_cards.len(), 1);
+    assert_eq!(restored_deck_listing.items.len(), 1);
+    assert_eq!(restored_deck_listing.total, 1);
+}
@@ -1,5 +1,6 @@
 //! Integration tests for ReMem
 
+use re_mem::domain::embedding::EMBEDDING_DIMENSIONS;
 use re_mem::domain::entities::{Card, Deck, Review, User};
 
 #[test]
@@ -62,22 +63,28 @@ fn test_card_with_deck() {
 #[test]
 fn test_card_with_embedding() {
     let user_id = uuid::Uuid::new_v4();
-    let embedding = vec![0.1, 0.2, 0.3, 0.4, 0.5];
+    let embedding = vec![0.1; EMBEDDING_DIMENSIONS];
     let card = Card::new(user_id, "Test".to_string(), "Answer".to_string())
-        .with_embedding(embedding.clone());
+        .with_embedding(embedding.clone())
+        .unwrap();
 
     assert_eq!(card.answer_embedding, Some(embedding));
+
+    let short = Card::new(user_id, "Test".to_string(), "Answer".to_string())
+        .with_embedding(vec![0.1, 0.2, 0.3]);
+    assert!(short.is_err());
 }
 
 #[test]
 fn test_card_builder_pattern() {
     let user_id = uuid::Uuid::new_v4();
     let deck_id = uuid::Uuid::new_v4();
-    let embedding = vec![0.1, 0.2, 0.3];
+    let embedding = vec![0.3; EMBEDDING_DIMENSIONS];
 
     let card = Card::new(user_id, "Q".to_string(), "A".to_string())
         .with_deck(deck_id)
-        .with_embedding(embedding.clone());
+        .with_embedding(embedding.clone())
+        .unwrap();
 
     assert_eq!(card.deck_id, Some(deck_id));
     assert_eq!(card.answer_embedding, Some(embedding));
@@ -0,0 +1,106 @@
+//! Database-backed tests for paginated card and deck listings
+//!
+//! These run against the database in `TEST_DATABASE_URL` (initialized with
+//! `scripts/init.sql`) and are skipped when it is not set.
+
+use re_mem::domain::entities::{Card, CardState, Deck, User};
+use re_mem::domain::repositories::{
+    CardListOptions, CardRepository, DeckRepository, UserRepository,
+};
+use re_mem::infrastructure::database::ExtensionCatalog;
+use re_mem::infrastructure::repositories::{PgCardRepository, PgDeckRepository, PgUserRepository};
+use sqlx::PgPool;
+
+async fn test_pool() -> Option<PgPool> {
+    let url = std::env::var("TEST_DATABASE_URL").ok()?;
+    Some(
+        PgPool::connect(&url)
+            .await
+            .expect("TEST_DATABASE_URL is unreachable"),
+    )
+}
+
+/// Card repository for the test database; without pgvector it has no
+/// `answer_embedding` column, so embeddings are left out
+async fn card_repository(pool: &PgPool) -> PgCardRepository {
+    let pgvector = pool
+        .has_extension("vector")
+        .await
+        .expect("Cannot list installed extensions");
+    PgCardRepository::new(pool.clone()).with_embeddings(pgvector)
+}
+
+#[tokio::test]
+async fn test_limit_and_offset_slice_listings_and_count_all_rows() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("TEST_DATABASE_URL not set, skipping");
+        return;
+    };
+    let users = PgUserRepository::new(pool.clone());
+    let decks = PgDeckRepository::new(pool.clone());
+    let cards = card_repository(&pool).await;
+
+    let user = User::new(
+        format!("{}@example.com", uuid::Uuid::new_v4()),
+        "Pages".to_string(),
+    );
+    users.create(&user).await.unwrap();
+    for i in 0..3 {
+        decks
+            .create(&Deck::new(user.id, format!("Deck {}", i), None))
+            .await
+            .unwrap();
+    }
+    let batch: Vec<Card> = (0..7)
+        .map(|i| Card::new(user.id, format!("Q{}", i), "A".to_string()))
+        .collect();
+    cards.bulk_create(&batch).await.unwrap();
+
+    let all = cards
+        .find_by_user_paginated(user.id, CardListOptions::default())
+        .await
+        .unwrap();
+    let page = cards
+        .find_by_user_paginated(
+            user.id,
+            CardListOptions {
+                limit: Some(3),
+                offset: Some(5),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let total = cards
+        .count_by_user_matching(
+            user.id,
+            CardListOptions {
+                limit: Some(3),
+                offset: Some(5),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let reviewed = cards
+        .count_by_user_matching(
+            user.id,
+            CardListOptions {
+                state: Some(CardState::Review),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let deck_page = decks.find_by_user_paginated(user.id, 2, 1).await.unwrap();
+    let all_decks = decks.find_by_user(user.id).await.unwrap();
+    users.delete(user.id).await.unwrap();
+
+    let ids = |cards: &[Card]| cards.iter().map(|card| card.id).collect::<Vec<_>>();
+    assert_eq!(ids(&page), ids(&all[5..]));
+    assert_eq!(total, 7);
+    assert_eq!(reviewed, 0);
+    let deck_ids: Vec<_> = deck_page.iter().map(|deck| deck.id).collect();
+    let expected: Vec<_> = all_decks[1..].iter().map(|deck| deck.id).collect();
+    assert_eq!(deck_ids, expected);
+}
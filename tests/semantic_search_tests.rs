@@ -0,0 +1,117 @@
+//! Database-backed tests for semantic card search over answer embeddings
+//!
+//! These run against the database in `TEST_DATABASE_URL` (initialized with
+//! `scripts/init.sql`) and are skipped when it is not set or has no pgvector.
+
+use re_mem::domain::embedding::EMBEDDING_DIMENSIONS;
+use re_mem::domain::entities::{Card, EmbeddingStatus, User};
+use re_mem::domain::repositories::{CardRepository, UserRepository};
+use re_mem::infrastructure::database::ExtensionCatalog;
+use re_mem::infrastructure::repositories::{PgCardRepository, PgUserRepository};
+use sqlx::PgPool;
+
+/// Pool for the test database, or `None` when it is not set or has no pgvector
+async fn test_pool() -> Option<PgPool> {
+    let url = std::env::var("TEST_DATABASE_URL").ok()?;
+    let pool = PgPool::connect(&url)
+        .await
+        .expect("TEST_DATABASE_URL is unreachable");
+    let pgvector = pool
+        .has_extension("vector")
+        .await
+        .expect("Cannot list installed extensions");
+    pgvector.then_some(pool)
+}
+
+/// Unit vector at `angle` radians in the plane of the first two axes
+fn embedding(angle: f32) -> Vec<f32> {
+    let mut vector = vec![0.0; EMBEDDING_DIMENSIONS];
+    vector[0] = angle.cos();
+    vector[1] = angle.sin();
+    vector
+}
+
+#[tokio::test]
+async fn test_search_by_embedding_orders_by_cosine_distance() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("TEST_DATABASE_URL not set or without pgvector, skipping");
+        return;
+    };
+    let users = PgUserRepository::new(pool.clone());
+    let cards = PgCardRepository::new(pool.clone());
+
+    let user = User::new(
+        format!("{}@example.com", uuid::Uuid::new_v4()),
+        "Search".to_string(),
+    );
+    users.create(&user).await.unwrap();
+    let other = User::new(
+        format!("{}@example.com", uuid::Uuid::new_v4()),
+        "Other".to_string(),
+    );
+    users.create(&other).await.unwrap();
+
+    for (owner, answer, angle) in [
+        (user.id, "far", Some(1.5)),
+        (user.id, "nearest", Some(0.1)),
+        (user.id, "middle", Some(0.8)),
+        (user.id, "not embedded", None),
+        (other.id, "someone else's", Some(0.0)),
+    ] {
+        let mut card = Card::new(owner, "Q".to_string(), answer.to_string());
+        card.answer_embedding = angle.map(embedding);
+        cards.create(&card).await.unwrap();
+    }
+
+    let hits = cards
+        .search_by_embedding(user.id, &embedding(0.0), 10)
+        .await
+        .unwrap();
+    let answers: Vec<&str> = hits.iter().map(|(card, _)| card.answer.as_str()).collect();
+    assert_eq!(answers, ["nearest", "middle", "far"]);
+    assert!(hits.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    assert!((hits[0].1 - (1.0 - 0.1f32.cos())).abs() < 1e-4);
+
+    let limited = cards
+        .search_by_embedding(user.id, &embedding(0.0), 2)
+        .await
+        .unwrap();
+    assert_eq!(limited.len(), 2);
+}
+
+#[tokio::test]
+async fn test_storing_an_embedding_does_not_conflict_with_a_loaded_review() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("TEST_DATABASE_URL not set or without pgvector, skipping");
+        return;
+    };
+    let users = PgUserRepository::new(pool.clone());
+    let cards = PgCardRepository::new(pool.clone());
+
+    let user = User::new(
+        format!("{}@example.com", uuid::Uuid::new_v4()),
+        "Embedding".to_string(),
+    );
+    users.create(&user).await.unwrap();
+    let card = Card::new(user.id, "Q".to_string(), "A".to_string()).with_embedding_pending();
+    cards.create(&card).await.unwrap();
+
+    // A review loads the card, then the embedding worker finishes
+    let mut loaded = cards.find_by_id(card.id).await.unwrap().unwrap();
+    let loaded_at = loaded.updated_at;
+    cards
+        .set_embedding_status(card.id, EmbeddingStatus::Failed)
+        .await
+        .unwrap();
+    cards
+        .update_embedding(card.id, embedding(0.0))
+        .await
+        .unwrap();
+
+    loaded.fsrs_state.reps += 1;
+    loaded.updated_at = chrono::Utc::now();
+    cards.update_if_unchanged(&loaded, loaded_at).await.unwrap();
+    let stored = cards.find_by_id(card.id).await.unwrap().unwrap();
+    assert_eq!(stored.fsrs_state.reps, 1);
+    assert_eq!(stored.embedding_status, EmbeddingStatus::Ready);
+}
@@ -0,0 +1,67 @@
+//! Database-backed tests for review statistics
+//!
+//! These run against the database in `TEST_DATABASE_URL` (initialized with
+//! `scripts/init.sql`) and are skipped when it is not set.
+
+use chrono::NaiveDate;
+use re_mem::domain::entities::{Deck, User};
+use re_mem::domain::repositories::{
+    DeckRepository, DeckStatsRepository, UserRepository, UserStatsRepository,
+};
+use re_mem::infrastructure::repositories::{
+    PgDeckRepository, PgDeckStatsRepository, PgUserRepository, PgUserStatsRepository,
+};
+use sqlx::PgPool;
+
+async fn test_pool() -> Option<PgPool> {
+    let url = std::env::var("TEST_DATABASE_URL").ok()?;
+    Some(
+        PgPool::connect(&url)
+            .await
+            .expect("TEST_DATABASE_URL is unreachable"),
+    )
+}
+
+#[tokio::test]
+async fn test_review_counts_for_user_and_deck_together() {
+    let Some(pool) = test_pool().await else {
+        eprintln!("TEST_DATABASE_URL not set, skipping");
+        return;
+    };
+    let users = PgUserRepository::new(pool.clone());
+    let decks = PgDeckRepository::new(pool.clone());
+    let user_stats = PgUserStatsRepository::new(pool.clone());
+    let deck_stats = PgDeckStatsRepository::new(pool.clone());
+
+    let user = User::new(
+        format!("{}@example.com", uuid::Uuid::new_v4()),
+        "Stats".to_string(),
+    );
+    users.create(&user).await.unwrap();
+    let deck = Deck::new(user.id, "Stats".to_string(), None);
+    decks.create(&deck).await.unwrap();
+    deck_stats.get_or_create(deck.id, user.id).await.unwrap();
+
+    let day = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+    for is_correct in [true, false] {
+        user_stats
+            .update_after_review(user.id, Some(deck.id), is_correct, day)
+            .await
+            .unwrap();
+    }
+    user_stats
+        .update_after_review(user.id, None, true, day.succ_opt().unwrap())
+        .await
+        .unwrap();
+
+    let user_totals = user_stats.get_or_create(user.id).await.unwrap();
+    let deck_totals = deck_stats.get_or_create(deck.id, user.id).await.unwrap();
+    users.delete(user.id).await.unwrap();
+
+    assert_eq!(user_totals.total_reviews, 3);
+    assert_eq!(user_totals.correct_reviews, 2);
+    assert_eq!(user_totals.days_studied, 2);
+    assert_eq!(deck_totals.total_reviews, 2);
+    assert_eq!(deck_totals.correct_reviews, 1);
+    assert_eq!(deck_totals.days_studied, 1);
+}